@@ -0,0 +1,27 @@
+use std::{env, fs, path::Path, process::Command};
+
+fn main() {
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|hash| hash.trim().to_string())
+        .filter(|hash| !hash.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+    let timestamp = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+
+    let out_dir = env::var("OUT_DIR").expect("cargo sets OUT_DIR");
+    fs::write(
+        Path::new(&out_dir).join("build_info.rs"),
+        format!(
+            "pub const GIT_COMMIT: &str = {commit:?};\npub const BUILD_TIMESTAMP: &str = {timestamp:?};\n"
+        ),
+    )
+    .expect("write build_info.rs");
+
+    // Re-run when the checked-out commit changes.
+    println!("cargo:rerun-if-changed=../.git/HEAD");
+    println!("cargo:rerun-if-changed=../.git/refs/heads");
+}
@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use sha2::{Digest, Sha256};
+
+use super::client::{get_ai_response, AiError, AiRequest};
+use crate::{config::Config, resilience::CircuitBreaker, storage::KvStore};
+
+const AI_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    Hit,
+    Miss,
+}
+
+impl CacheStatus {
+    /// Value of the `X-Cache` response header.
+    pub fn as_header_value(self) -> &'static str {
+        match self {
+            CacheStatus::Hit => "HIT",
+            CacheStatus::Miss => "MISS",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CachedAiResponse {
+    pub text: String,
+    pub prompt_hash: String,
+    pub cache: CacheStatus,
+}
+
+/// Hex-encoded SHA-256 of the prompt, used both as the cache key and the ETag.
+pub fn prompt_hash(prompt: &str) -> String {
+    hex::encode(Sha256::digest(prompt.as_bytes()))
+}
+
+fn cache_key(hash: &str) -> String {
+    format!("ai-cache:{hash}")
+}
+
+/// Wraps [`get_ai_response`] with a 24h KV cache keyed by the prompt hash.
+///
+/// KV failures are treated as a cache miss so that a storage hiccup never
+/// blocks the AI call itself. With `use_cache` set to `false` the cache is
+/// neither read nor written.
+///
+/// Cache misses go through `breaker`: while it is open the API is not called
+/// and [`AiError::Unavailable`] is returned instead.
+pub async fn get_cached_ai_response(
+    kv: &KvStore,
+    breaker: &CircuitBreaker,
+    config: &Config,
+    request: &AiRequest,
+    use_cache: bool,
+) -> Result<CachedAiResponse, AiError> {
+    let hash = prompt_hash(&request.prompt);
+    let key = cache_key(&hash);
+
+    if use_cache {
+        if let Ok(Some(text)) = kv.get(&key).await {
+            return Ok(CachedAiResponse {
+                text,
+                prompt_hash: hash,
+                cache: CacheStatus::Hit,
+            });
+        }
+    }
+
+    breaker.try_acquire().map_err(|open| AiError::Unavailable {
+        retry_after_secs: open.retry_after.as_secs().max(1),
+    })?;
+    let text = match get_ai_response(config, request).await {
+        Ok(text) => {
+            breaker.record_success();
+            text
+        }
+        Err(err) => {
+            if err.is_outage() {
+                breaker.record_failure();
+            }
+            return Err(err);
+        }
+    };
+
+    if use_cache {
+        let _ = kv.put(&key, text.clone(), Some(AI_CACHE_TTL)).await;
+    }
+
+    Ok(CachedAiResponse {
+        text,
+        prompt_hash: hash,
+        cache: CacheStatus::Miss,
+    })
+}
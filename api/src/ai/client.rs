@@ -0,0 +1,123 @@
+use std::{fmt, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+
+const AI_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Body sent to the Workers AI `run` endpoint.
+#[derive(Debug, Clone, Serialize)]
+pub struct AiRequest {
+    pub prompt: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+}
+
+impl AiRequest {
+    pub fn new(prompt: impl Into<String>) -> Self {
+        Self {
+            prompt: prompt.into(),
+            max_tokens: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum AiError {
+    NotConfigured,
+    Request(String),
+    Api {
+        status: u16,
+        body: String,
+    },
+    InvalidResponse(String),
+    /// Recent calls kept failing, so the API is not being called for now.
+    Unavailable {
+        retry_after_secs: u64,
+    },
+}
+
+impl AiError {
+    /// Whether the error suggests the AI service itself is unhealthy, as
+    /// opposed to a problem with configuration or the request.
+    pub fn is_outage(&self) -> bool {
+        match self {
+            AiError::Request(_) | AiError::InvalidResponse(_) => true,
+            AiError::Api { status, .. } => *status >= 500 || *status == 429,
+            AiError::NotConfigured | AiError::Unavailable { .. } => false,
+        }
+    }
+}
+
+impl fmt::Display for AiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AiError::NotConfigured => write!(
+                f,
+                "AI is not configured: set CLOUDFLARE_ACCOUNT_ID and CLOUDFLARE_API_TOKEN"
+            ),
+            AiError::Request(err) => write!(f, "AI request failed: {err}"),
+            AiError::Api { status, body } => write!(f, "AI API returned {status}: {body}"),
+            AiError::InvalidResponse(reason) => write!(f, "AI API response was invalid: {reason}"),
+            AiError::Unavailable { retry_after_secs } => write!(
+                f,
+                "AI service temporarily unavailable, retry in {retry_after_secs}s"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AiError {}
+
+#[derive(Deserialize)]
+struct RunResponse {
+    result: Option<RunResult>,
+}
+
+#[derive(Deserialize)]
+struct RunResult {
+    response: Option<String>,
+}
+
+/// Runs `request` against the configured Cloudflare Workers AI model and
+/// returns the generated text.
+pub async fn get_ai_response(config: &Config, request: &AiRequest) -> Result<String, AiError> {
+    let (Some(account_id), Some(api_token)) = (
+        config.cloudflare_account_id.as_deref(),
+        config.cloudflare_api_token.as_deref(),
+    ) else {
+        return Err(AiError::NotConfigured);
+    };
+
+    let url = format!(
+        "https://api.cloudflare.com/client/v4/accounts/{account_id}/ai/run/{}",
+        config.ai_model
+    );
+
+    let mut response = awc::Client::default()
+        .post(url)
+        .timeout(AI_REQUEST_TIMEOUT)
+        .bearer_auth(api_token)
+        .send_json(request)
+        .await
+        .map_err(|err| AiError::Request(err.to_string()))?;
+
+    if !response.status().is_success() {
+        let body = response.body().await.unwrap_or_default();
+        return Err(AiError::Api {
+            status: response.status().as_u16(),
+            body: String::from_utf8_lossy(&body).into_owned(),
+        });
+    }
+
+    let payload: RunResponse = response
+        .json()
+        .await
+        .map_err(|err| AiError::InvalidResponse(err.to_string()))?;
+
+    payload
+        .result
+        .and_then(|result| result.response)
+        .ok_or_else(|| AiError::InvalidResponse("missing `result.response`".to_string()))
+}
@@ -0,0 +1,11 @@
+mod cache;
+mod client;
+mod nudge;
+mod payer;
+
+pub use cache::{get_cached_ai_response, prompt_hash, CacheStatus, CachedAiResponse};
+pub use client::{get_ai_response, AiError, AiRequest};
+pub use nudge::{nudge_prompt, Channel, NudgeMessage, SMS_MAX_CHARS};
+pub use payer::{
+    match_participant, parse_payer_guess, payer_prompt, Confidence, PayerGuess, MAX_NAME_DISTANCE,
+};
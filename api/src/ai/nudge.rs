@@ -0,0 +1,73 @@
+use serde::Serialize;
+
+use crate::models::Money;
+
+/// SMS messages must fit in a single segment.
+pub const SMS_MAX_CHARS: usize = 160;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    Sms,
+    Email,
+    Chat,
+}
+
+impl Channel {
+    pub const ALL: [Channel; 3] = [Channel::Sms, Channel::Email, Channel::Chat];
+
+    pub fn tone(self) -> &'static str {
+        match self {
+            Channel::Sms => "friendly",
+            Channel::Email => "polite",
+            Channel::Chat => "casual",
+        }
+    }
+
+    fn instructions(self) -> String {
+        match self {
+            Channel::Sms => format!(
+                "a text message of at most {SMS_MAX_CHARS} characters, with no greeting line"
+            ),
+            Channel::Email => {
+                "a short email body of two or three sentences, without a subject line".to_string()
+            }
+            Channel::Chat => "a one-line group chat message, emoji allowed".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NudgeMessage {
+    pub channel: Channel,
+    pub text: String,
+    pub tone: String,
+}
+
+/// Prompt for a reminder to `name` that they still owe `amount` on the bill
+/// called `title`.
+pub fn nudge_prompt(channel: Channel, name: &str, title: &str, amount: Money) -> String {
+    format!(
+        "Write a {tone} reminder to {name} that they still owe ${amount} for \"{title}\". \
+         Write it as {instructions}. Reply with the message text only.",
+        tone = channel.tone(),
+        instructions = channel.instructions(),
+    )
+}
+
+impl NudgeMessage {
+    /// Tidies the model's `text` for `channel`, cutting SMS messages down to
+    /// [`SMS_MAX_CHARS`] if the model ignored the limit.
+    pub fn new(channel: Channel, text: &str) -> Self {
+        let mut text = text.trim().trim_matches('"').trim().to_string();
+        if channel == Channel::Sms && text.chars().count() > SMS_MAX_CHARS {
+            text = text.chars().take(SMS_MAX_CHARS - 1).collect::<String>();
+            text.push('…');
+        }
+        Self {
+            channel,
+            text,
+            tone: channel.tone().to_string(),
+        }
+    }
+}
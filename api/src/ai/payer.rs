@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+
+use super::AiError;
+use crate::models::{Bill, Participant};
+
+/// Largest edit distance at which a name from the AI still counts as a
+/// participant's name, so "Sara" or "jon" still match.
+pub const MAX_NAME_DISTANCE: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    High,
+    Medium,
+    Low,
+}
+
+/// What the model answered when asked who paid.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PayerGuess {
+    /// `None` when the model could not tell.
+    pub payer: Option<String>,
+    pub confidence: Confidence,
+    pub reasoning: String,
+}
+
+pub fn payer_prompt(bill: &Bill, participants: &[Participant]) -> String {
+    let names: Vec<&str> = participants.iter().map(|p| p.name.as_str()).collect();
+    format!(
+        "A group split a bill and one person paid it. Work out who most likely paid.\n\
+         Bill title: {title}\n\
+         Notes: {notes}\n\
+         Participants: {names}\n\n\
+         Answer with a single JSON object and nothing else, in the form \
+         {{\"payer\": \"<participant name or null>\", \"confidence\": \"high|medium|low\", \
+         \"reasoning\": \"<one sentence>\"}}.",
+        title = bill.title,
+        notes = bill.notes.as_deref().unwrap_or("(none)"),
+        names = names.join(", "),
+    )
+}
+
+/// Parses the model's answer, tolerating prose or code fences around the
+/// JSON object.
+pub fn parse_payer_guess(text: &str) -> Result<PayerGuess, AiError> {
+    let (Some(start), Some(end)) = (text.find('{'), text.rfind('}')) else {
+        return Err(AiError::InvalidResponse(
+            "no JSON object in the answer".to_string(),
+        ));
+    };
+    if end < start {
+        return Err(AiError::InvalidResponse(
+            "no JSON object in the answer".to_string(),
+        ));
+    }
+    serde_json::from_str(&text[start..=end])
+        .map_err(|err| AiError::InvalidResponse(format!("unexpected answer: {err}")))
+}
+
+/// The participant whose name is closest to `name`, ignoring case, if any is
+/// within [`MAX_NAME_DISTANCE`].
+pub fn match_participant<'a>(
+    name: &str,
+    participants: &'a [Participant],
+) -> Option<&'a Participant> {
+    let name = name.trim().to_lowercase();
+    participants
+        .iter()
+        .map(|participant| {
+            (
+                levenshtein(&name, &participant.name.to_lowercase()),
+                participant,
+            )
+        })
+        .filter(|(distance, _)| *distance <= MAX_NAME_DISTANCE)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, participant)| participant)
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
@@ -0,0 +1,126 @@
+//! Line items that look out of place on their bill, by simple statistics.
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::models::{Bill, LineItem};
+
+/// Prices this many standard deviations above the bill's mean are flagged.
+pub const PRICE_Z_THRESHOLD: f64 = 2.0;
+
+/// Above this many standard deviations a price is a high-severity alert.
+pub const HIGH_PRICE_Z_THRESHOLD: f64 = 3.0;
+
+/// Fewer items than this say too little about what a normal price is.
+pub const MIN_ITEMS_FOR_PRICE_CHECK: usize = 3;
+
+/// Descriptions of charges that usually belong in a category of their own,
+/// matched ignoring case.
+pub const FEE_PATTERNS: &[&str] = &[
+    "service charge",
+    "service fee",
+    "surcharge",
+    "cover charge",
+    "gratuity",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnomalySeverity {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AnomalyAlert {
+    pub line_item_id: Uuid,
+    pub description: String,
+    pub reason: String,
+    pub severity: AnomalySeverity,
+}
+
+impl AnomalyAlert {
+    fn new(item: &LineItem, reason: String, severity: AnomalySeverity) -> Self {
+        Self {
+            line_item_id: item.id,
+            description: item.description.clone(),
+            reason,
+            severity,
+        }
+    }
+}
+
+/// Mean and population standard deviation of `values`.
+pub fn mean_and_std_dev(values: &[f64]) -> Option<(f64, f64)> {
+    if values.is_empty() {
+        return None;
+    }
+    let count = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / count;
+    let variance = values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / count;
+    Some((mean, variance.sqrt()))
+}
+
+/// Every alert for `bill`'s line items, in line item order. An item can have
+/// more than one.
+pub fn detect_anomalies(bill: &Bill) -> Vec<AnomalyAlert> {
+    let prices: Vec<f64> = bill
+        .line_items
+        .iter()
+        .map(|item| item.unit_price.to_decimal().try_into().unwrap_or(0.0))
+        .collect();
+    let price_stats = (bill.line_items.len() >= MIN_ITEMS_FOR_PRICE_CHECK)
+        .then(|| mean_and_std_dev(&prices))
+        .flatten()
+        .filter(|(_, std_dev)| *std_dev > 0.0);
+
+    let mut alerts = Vec::new();
+    for (item, price) in bill.line_items.iter().zip(&prices) {
+        if item.quantity == 0 {
+            alerts.push(AnomalyAlert::new(
+                item,
+                "Quantity is zero, so the item adds nothing to the bill".to_string(),
+                AnomalySeverity::High,
+            ));
+        }
+
+        if let Some((mean, std_dev)) = price_stats {
+            let z = (price - mean) / std_dev;
+            if z > PRICE_Z_THRESHOLD {
+                let severity = if z > HIGH_PRICE_Z_THRESHOLD {
+                    AnomalySeverity::High
+                } else {
+                    AnomalySeverity::Medium
+                };
+                alerts.push(AnomalyAlert::new(
+                    item,
+                    format!(
+                        "Price of {} is {z:.1} standard deviations above the bill's average of {mean:.2}",
+                        item.unit_price
+                    ),
+                    severity,
+                ));
+            }
+        }
+
+        if item.category.is_none() {
+            let description = item.description.to_lowercase();
+            if let Some(pattern) = FEE_PATTERNS
+                .iter()
+                .find(|pattern| description.contains(*pattern))
+            {
+                alerts.push(AnomalyAlert::new(
+                    item,
+                    format!("Looks like a {pattern} but has no category"),
+                    AnomalySeverity::Low,
+                ));
+            }
+        }
+    }
+    alerts
+}
@@ -0,0 +1,87 @@
+//! How far a bill is from being paid off.
+
+use chrono::{DateTime, Days, Duration, NaiveDate, Utc};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Bill, Money, Payment};
+
+/// Payments made this many days back set the pace for
+/// [`Completion::projected_settlement_date`].
+pub const VELOCITY_WINDOW_DAYS: i64 = 7;
+
+/// How far a participant's payments may be from their share for them to
+/// count as settled.
+pub const SETTLED_TOLERANCE: Money = Money::from_cents(1);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Completion {
+    /// The shares of the latest split added up.
+    pub total: Money,
+    pub paid: Money,
+    /// `total - paid`, never below zero.
+    pub outstanding: Money,
+    /// `paid / total * 100`, rounded to two decimals; 100 for a bill with
+    /// nothing to pay.
+    pub completion_pct: f64,
+    pub fully_settled_participants: usize,
+    pub total_participants: usize,
+    /// When `outstanding` reaches zero if payments keep coming in at the
+    /// pace of the last [`VELOCITY_WINDOW_DAYS`] days. `None` once settled
+    /// or when nothing was paid in that time.
+    pub projected_settlement_date: Option<NaiveDate>,
+}
+
+/// `bill`'s progress towards being paid off, or `None` before it has been
+/// split.
+pub fn completion(bill: &Bill, now: DateTime<Utc>) -> Option<Completion> {
+    let split = bill.latest_split()?;
+    let total: Money = split.shares.iter().map(|share| share.amount_owed).sum();
+    let paid: Money = bill.payments.iter().map(Payment::base_amount).sum();
+    let outstanding = (total - paid).max(Money::ZERO);
+
+    let completion_pct = if total.is_zero() {
+        100.0
+    } else {
+        (paid.to_decimal() / total.to_decimal() * Decimal::ONE_HUNDRED)
+            .round_dp(2)
+            .to_f64()
+            .unwrap_or(0.0)
+    };
+
+    let fully_settled_participants = bill
+        .participant_ids()
+        .into_iter()
+        .filter(|participant_id| {
+            bill.outstanding(*participant_id)
+                .is_some_and(|left| left.abs() <= SETTLED_TOLERANCE)
+        })
+        .count();
+
+    let window_start = now - Duration::days(VELOCITY_WINDOW_DAYS);
+    let recent: Money = bill
+        .payments
+        .iter()
+        .filter(|payment| payment.paid_at > window_start && payment.paid_at <= now)
+        .map(Payment::base_amount)
+        .sum();
+    let projected_settlement_date = if outstanding.is_zero() || recent <= Money::ZERO {
+        None
+    } else {
+        let per_day = recent.to_decimal() / Decimal::from(VELOCITY_WINDOW_DAYS);
+        (outstanding.to_decimal() / per_day)
+            .ceil()
+            .to_u64()
+            .and_then(|days| now.date_naive().checked_add_days(Days::new(days)))
+    };
+
+    Some(Completion {
+        total,
+        paid,
+        outstanding,
+        completion_pct,
+        fully_settled_participants,
+        total_participants: bill.participants.len(),
+        projected_settlement_date,
+    })
+}
@@ -0,0 +1,8 @@
+//! Insights drawn from bills and from each participant's history across
+//! them.
+
+pub mod anomaly;
+pub mod completion;
+pub mod monthly;
+pub mod stats;
+pub mod turn;
@@ -0,0 +1,118 @@
+//! One participant's bills from a single calendar month.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{Bill, BillStatus, CurrencyCode, Money};
+
+/// Earliest year a summary can be asked for.
+pub const MIN_SUMMARY_YEAR: i32 = 2020;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BillSummary {
+    pub id: Uuid,
+    pub title: String,
+    pub status: BillStatus,
+    pub currency: CurrencyCode,
+    pub total: Money,
+    /// Their share of the latest split; `None` until one is computed.
+    pub your_share: Option<Money>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What one participant spent, paid and owes across the month's bills in
+/// one currency.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CurrencyTotals {
+    pub currency: CurrencyCode,
+    /// Their shares of the latest split on each bill.
+    pub total_spent: Money,
+    /// Everything they have paid towards the bills.
+    pub you_paid: Money,
+    /// What they still owe, leaving out bills they have overpaid.
+    pub you_owe: Money,
+}
+
+impl CurrencyTotals {
+    fn new(currency: CurrencyCode) -> Self {
+        Self {
+            currency,
+            total_spent: Money::ZERO,
+            you_paid: Money::ZERO,
+            you_owe: Money::ZERO,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonthSummary {
+    pub year: i32,
+    pub month: u32,
+    /// Oldest first.
+    pub bills: Vec<BillSummary>,
+    /// One entry per bill currency, in currency order. Amounts are never
+    /// converted or added across currencies.
+    pub totals: Vec<CurrencyTotals>,
+}
+
+/// Whether the bill is one of `participant_id`'s: they are on it or created
+/// it.
+pub fn is_own_bill(bill: &Bill, participant_id: Uuid) -> bool {
+    bill.has_participant(participant_id) || bill.creator_id == Some(participant_id)
+}
+
+/// Summarises the bills in `bills` that `participant_id` is on or created
+/// and that were created in `month` of `year`, with totals per currency.
+pub fn month_summary(participant_id: Uuid, year: i32, month: u32, bills: &[Bill]) -> MonthSummary {
+    let mut bills: Vec<&Bill> = bills
+        .iter()
+        .filter(|bill| {
+            is_own_bill(bill, participant_id)
+                && bill.created_at.year() == year
+                && bill.created_at.month() == month
+        })
+        .collect();
+    bills.sort_by_key(|bill| (bill.created_at, bill.id));
+
+    let mut summary = MonthSummary {
+        year,
+        month,
+        bills: Vec::with_capacity(bills.len()),
+        totals: Vec::new(),
+    };
+    let mut totals: BTreeMap<CurrencyCode, CurrencyTotals> = BTreeMap::new();
+    for bill in bills {
+        let your_share = bill.latest_split().and_then(|snapshot| {
+            snapshot
+                .shares
+                .iter()
+                .find(|share| share.participant_id == participant_id)
+                .map(|share| share.amount_owed)
+        });
+        let currency_totals = totals
+            .entry(bill.base_currency.clone())
+            .or_insert_with(|| CurrencyTotals::new(bill.base_currency.clone()));
+        currency_totals.total_spent += your_share.unwrap_or(Money::ZERO);
+        currency_totals.you_paid += bill
+            .payments_by(participant_id)
+            .map(|payment| payment.base_amount())
+            .sum();
+        if let Some(outstanding) = bill.outstanding(participant_id) {
+            currency_totals.you_owe += outstanding.max(Money::ZERO);
+        }
+        summary.bills.push(BillSummary {
+            id: bill.id,
+            title: bill.title.clone(),
+            status: bill.status,
+            currency: bill.base_currency.clone(),
+            total: bill.total(),
+            your_share,
+            created_at: bill.created_at,
+        });
+    }
+    summary.totals = totals.into_values().collect();
+    summary
+}
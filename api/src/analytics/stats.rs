@@ -0,0 +1,186 @@
+//! One participant's spending across every bill they are on.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{Bill, Money};
+
+/// Months covered by [`ParticipantStats::monthly_spend`], including the
+/// current one.
+pub const STATS_MONTHS: u32 = 12;
+
+/// Entries kept in each of the ranked lists.
+pub const STATS_TOP_N: usize = 5;
+
+/// Shown for line items without a category.
+pub const UNCATEGORISED: &str = "uncategorised";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonthlySpend {
+    /// `YYYY-MM`.
+    pub month: String,
+    pub amount: Money,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryTotal {
+    pub category: String,
+    pub amount: Money,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoParticipant {
+    pub participant_id: Uuid,
+    pub name: String,
+    pub shared_bills_count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParticipantStats {
+    pub participant_id: Uuid,
+    pub bill_count: u32,
+    /// Their shares of the latest split on each bill.
+    pub total_owed: Money,
+    /// Everything they have paid, in each bill's currency.
+    pub total_paid: Money,
+    /// Shares by the month the bill was created, oldest first. Months without
+    /// bills are included with zero.
+    pub monthly_spend: Vec<MonthlySpend>,
+    /// Their part of each line item they share, by category, largest first.
+    pub top_categories: Vec<CategoryTotal>,
+    /// Who they share the most bills with.
+    pub most_frequent_co_participants: Vec<CoParticipant>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Summarises `participant_id`'s part in `bills`; bills they are not on are
+/// ignored. `names` maps participant ids to names for the co-participant
+/// ranking.
+pub fn participant_stats(
+    participant_id: Uuid,
+    bills: &[Bill],
+    names: &HashMap<Uuid, String>,
+    now: DateTime<Utc>,
+) -> ParticipantStats {
+    let bills: Vec<&Bill> = bills
+        .iter()
+        .filter(|bill| bill.has_participant(participant_id))
+        .collect();
+
+    let months = recent_months(now.date_naive(), STATS_MONTHS);
+    let mut monthly: BTreeMap<String, Money> = months
+        .iter()
+        .map(|month| (month.clone(), Money::ZERO))
+        .collect();
+    let mut categories: HashMap<String, Decimal> = HashMap::new();
+    let mut co_participants: HashMap<Uuid, u32> = HashMap::new();
+    let mut total_owed = Money::ZERO;
+    let mut total_paid = Money::ZERO;
+
+    for bill in &bills {
+        let share = bill.latest_split().and_then(|snapshot| {
+            snapshot
+                .shares
+                .iter()
+                .find(|share| share.participant_id == participant_id)
+                .map(|share| share.amount_owed)
+        });
+        if let Some(share) = share {
+            total_owed += share;
+            if let Some(amount) = monthly.get_mut(&month_of(bill.created_at.date_naive())) {
+                *amount += share;
+            }
+        }
+        total_paid += bill
+            .payments_by(participant_id)
+            .map(|payment| payment.base_amount())
+            .sum();
+
+        for item in &bill.line_items {
+            let sharers = bill.item_sharers(item);
+            if !sharers.contains(&participant_id) {
+                continue;
+            }
+            let category = item
+                .category
+                .clone()
+                .unwrap_or_else(|| UNCATEGORISED.to_string());
+            *categories.entry(category).or_default() +=
+                item.total().to_decimal() / Decimal::from(sharers.len());
+        }
+
+        for other in bill.participant_ids() {
+            if other != participant_id {
+                *co_participants.entry(other).or_default() += 1;
+            }
+        }
+    }
+
+    let mut top_categories: Vec<CategoryTotal> = categories
+        .into_iter()
+        .map(|(category, amount)| CategoryTotal {
+            category,
+            amount: Money::from_decimal(amount),
+        })
+        .collect();
+    top_categories.sort_by(|a, b| {
+        b.amount
+            .cmp(&a.amount)
+            .then_with(|| a.category.cmp(&b.category))
+    });
+    top_categories.truncate(STATS_TOP_N);
+
+    let mut most_frequent_co_participants: Vec<CoParticipant> = co_participants
+        .into_iter()
+        .map(|(id, count)| CoParticipant {
+            participant_id: id,
+            name: names.get(&id).cloned().unwrap_or_default(),
+            shared_bills_count: count,
+        })
+        .collect();
+    most_frequent_co_participants.sort_by(|a, b| {
+        b.shared_bills_count
+            .cmp(&a.shared_bills_count)
+            .then_with(|| a.name.cmp(&b.name))
+            .then_with(|| a.participant_id.cmp(&b.participant_id))
+    });
+    most_frequent_co_participants.truncate(STATS_TOP_N);
+
+    ParticipantStats {
+        participant_id,
+        bill_count: bills.len() as u32,
+        total_owed,
+        total_paid,
+        monthly_spend: monthly
+            .into_iter()
+            .map(|(month, amount)| MonthlySpend { month, amount })
+            .collect(),
+        top_categories,
+        most_frequent_co_participants,
+        generated_at: now,
+    }
+}
+
+fn month_of(date: NaiveDate) -> String {
+    format!("{:04}-{:02}", date.year(), date.month())
+}
+
+/// The `count` months up to and including `today`'s, oldest first.
+fn recent_months(today: NaiveDate, count: u32) -> Vec<String> {
+    let current = today.year() * 12 + today.month0() as i32;
+    (0..count as i32)
+        .rev()
+        .map(|back| {
+            let month = current - back;
+            format!(
+                "{:04}-{:02}",
+                month.div_euclid(12),
+                month.rem_euclid(12) + 1
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,81 @@
+//! Whose turn it is to pay.
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::models::{Bill, Participant};
+
+/// How many of a group's most recent bills are considered by default.
+pub const DEFAULT_LOOKBACK: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SuggestedPayer {
+    pub participant_id: Uuid,
+    pub name: String,
+    /// Times they were the payer of one of `recent_bills`.
+    pub times_paid_recently: u32,
+    pub reason: String,
+}
+
+/// Suggests the participant who has paid the fewest of `recent_bills`,
+/// breaking ties by name, then id. `None` when there is no one to suggest.
+pub fn suggest_payer(
+    participants: &[Participant],
+    recent_bills: &[Bill],
+) -> Option<SuggestedPayer> {
+    let times_paid = |participant: &Participant| {
+        recent_bills
+            .iter()
+            .filter(|bill| bill.payer_id == Some(participant.id))
+            .count() as u32
+    };
+    let mut counts: Vec<(&Participant, u32)> = participants
+        .iter()
+        .map(|participant| (participant, times_paid(participant)))
+        .collect();
+    counts.sort_by(|(a, a_times), (b, b_times)| {
+        a_times
+            .cmp(b_times)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+
+    let (payer, times) = *counts.first()?;
+    let tied = counts.iter().filter(|(_, other)| *other == times).count();
+    let bills = match recent_bills.len() {
+        1 => "bill".to_string(),
+        n => format!("{n} bills"),
+    };
+    let paid = match times {
+        0 => "hasn't paid".to_string(),
+        1 => "has paid once".to_string(),
+        n => format!("has paid {n} times"),
+    };
+    let reason = if recent_bills.is_empty() {
+        format!(
+            "No recent bills to go on, so {} was picked by name",
+            payer.name
+        )
+    } else if tied > 1 {
+        let others = match tied - 1 {
+            1 => "1 other".to_string(),
+            n => format!("{n} others"),
+        };
+        format!(
+            "{} {paid} in the last {bills}, tied with {others} and first by name",
+            payer.name
+        )
+    } else {
+        format!(
+            "{} {paid} in the last {bills}, less than anyone else",
+            payer.name
+        )
+    };
+
+    Some(SuggestedPayer {
+        participant_id: payer.id,
+        name: payer.name.clone(),
+        times_paid_recently: times,
+        reason,
+    })
+}
@@ -0,0 +1,88 @@
+//! Bearer-token authentication. Tokens are HS256 JWTs signed with
+//! `JWT_SECRET`; their `sub` claim is the caller's participant id.
+
+use actix_web::{http::header, HttpRequest};
+use jsonwebtoken::{decode, DecodingKey, Validation};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{config::Config, error::ApiError, models::Bill};
+
+#[derive(Deserialize)]
+struct Claims {
+    sub: Uuid,
+    #[serde(default)]
+    name: Option<String>,
+}
+
+/// The caller identified by a valid token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthUser {
+    pub id: Uuid,
+    /// From the token's optional `name` claim.
+    pub name: Option<String>,
+}
+
+impl AuthUser {
+    /// How the caller is shown to others: their name, or their id.
+    pub fn display_name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| self.id.to_string())
+    }
+}
+
+/// The caller behind the request's `Authorization: Bearer` token. `None` when
+/// the request carries no token or authentication is not configured; an
+/// invalid or expired token is an error.
+pub fn authenticate(req: &HttpRequest, config: &Config) -> Result<Option<AuthUser>, ApiError> {
+    let Some(secret) = config.jwt_secret.as_deref() else {
+        return Ok(None);
+    };
+    let Some(value) = req.headers().get(header::AUTHORIZATION) else {
+        return Ok(None);
+    };
+    let token = value
+        .to_str()
+        .ok()
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or_else(|| {
+            ApiError::Unauthorized("`Authorization` must be a Bearer token".to_string())
+        })?;
+
+    let claims = decode::<Claims>(
+        token.trim(),
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &Validation::default(),
+    )
+    .map_err(|err| ApiError::Unauthorized(format!("Invalid token: {err}")))?
+    .claims;
+    Ok(Some(AuthUser {
+        id: claims.sub,
+        name: claims.name,
+    }))
+}
+
+/// Fails unless the caller created `bill`. Anonymous bills have no creator
+/// and can be changed by anyone.
+pub fn require_creator(req: &HttpRequest, config: &Config, bill: &Bill) -> Result<(), ApiError> {
+    let Some(creator_id) = bill.creator_id else {
+        return Ok(());
+    };
+    if require_user(req, config)?.id == creator_id {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden(
+            "Only the bill's creator can do this".to_string(),
+        ))
+    }
+}
+
+/// Like [`authenticate`], but a token is required.
+pub fn require_user(req: &HttpRequest, config: &Config) -> Result<AuthUser, ApiError> {
+    if config.jwt_secret.is_none() {
+        return Err(ApiError::ServiceUnavailable(
+            "Authentication is not configured: set JWT_SECRET".to_string(),
+        ));
+    }
+    authenticate(req, config)?
+        .ok_or_else(|| ApiError::Unauthorized("Authentication is required".to_string()))
+}
@@ -0,0 +1,27 @@
+//! Version details baked in at compile time by `build.rs`.
+
+use std::sync::OnceLock;
+
+include!(concat!(env!("OUT_DIR"), "/build_info.rs"));
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Endpoints clients should move off, as `METHOD /path`.
+pub const DEPRECATED_ENDPOINTS: &[&str] = &[];
+
+/// Feature flags clients can check for, from `features.json`.
+pub fn features() -> &'static [String] {
+    static FEATURES: OnceLock<Vec<String>> = OnceLock::new();
+    FEATURES.get_or_init(|| {
+        serde_json::from_str(include_str!("features.json"))
+            .expect("features.json is a list of strings")
+    })
+}
+
+/// Response header carrying [`header_value`] on every response.
+pub const HEADER_NAME: &str = "X-Build-Info";
+
+/// `{version}+{commit}`, e.g. `0.1.0+a1b2c3d`.
+pub fn header_value() -> String {
+    format!("{VERSION}+{GIT_COMMIT}")
+}
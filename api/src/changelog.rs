@@ -0,0 +1,302 @@
+//! Human-readable history of a bill, one entry per saved change.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    explain::dollars,
+    models::{Bill, LineItem, Payment},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangelogEntry {
+    pub timestamp: DateTime<Utc>,
+    /// Who made the change, `None` for anonymous requests.
+    pub actor_name: Option<String>,
+    pub changes: Vec<String>,
+}
+
+/// Describes what changed between two saved versions of a bill, one line per
+/// change. `before` is `None` when the bill is new. `names` maps participant
+/// ids to names; unknown ids are shown as they are.
+///
+/// Splits are derived from the rest of the bill and are not described.
+pub fn describe_changes(
+    before: Option<&Bill>,
+    after: &Bill,
+    names: &HashMap<Uuid, String>,
+) -> Vec<String> {
+    let Some(before) = before else {
+        return vec![format!("Bill '{}' created", after.title)];
+    };
+    let name = |id: &Uuid| names.get(id).cloned().unwrap_or_else(|| id.to_string());
+    let mut changes = Vec::new();
+
+    if before.title != after.title {
+        changes.push(format!(
+            "Title changed from '{}' to '{}'",
+            before.title, after.title
+        ));
+    }
+    match (&before.notes, &after.notes) {
+        (None, Some(_)) => changes.push("Notes added".to_string()),
+        (Some(_), None) => changes.push("Notes removed".to_string()),
+        (Some(old), Some(new)) if old != new => changes.push("Notes updated".to_string()),
+        _ => {}
+    }
+    if before.status != after.status {
+        changes.push(format!(
+            "Status changed from {} to {}",
+            before.status, after.status
+        ));
+    }
+    if before.base_currency != after.base_currency {
+        changes.push(format!(
+            "Currency changed from {} to {}",
+            before.base_currency, after.base_currency
+        ));
+    }
+    if before.payer_id != after.payer_id {
+        changes.push(match &after.payer_id {
+            Some(payer) => format!("Payer set to {}", name(payer)),
+            None => "Payer cleared".to_string(),
+        });
+    }
+    if before.discount != after.discount {
+        changes.push(format!(
+            "Discount changed from {} to {}",
+            dollars(before.discount),
+            dollars(after.discount)
+        ));
+    }
+
+    for rate in &after.manual_rates {
+        if !before.manual_rates.contains(rate) {
+            changes.push(format!(
+                "Manual {} to {} rate set to {}",
+                rate.from,
+                rate.to,
+                rate.rate.normalize()
+            ));
+        }
+    }
+
+    if before.split_config != after.split_config {
+        changes.push(match &after.split_config {
+            Some(config) => format!("Split method set to {}", config.method),
+            None => "Split method cleared".to_string(),
+        });
+    }
+
+    let before_participants: HashSet<Uuid> = before.participant_ids().into_iter().collect();
+    let after_participants: HashSet<Uuid> = after.participant_ids().into_iter().collect();
+    for id in after.participant_ids() {
+        if !before_participants.contains(&id) {
+            changes.push(format!("Participant {} added", name(&id)));
+        }
+    }
+    for id in before.participant_ids() {
+        if !after_participants.contains(&id) {
+            changes.push(format!("Participant {} removed", name(&id)));
+        }
+    }
+    for member in &after.participants {
+        let accepted_before = before
+            .participants
+            .iter()
+            .any(|old| old.participant_id == member.participant_id && old.accepted_at.is_some());
+        if member.accepted_at.is_some() && !accepted_before {
+            changes.push(format!(
+                "Participant {} accepted",
+                name(&member.participant_id)
+            ));
+        }
+        let exemption_before = before.exemption(member.participant_id);
+        match (&member.exemption, exemption_before) {
+            (Some(exemption), previous)
+                if previous.map(|previous| &previous.reason) != Some(&exemption.reason) =>
+            {
+                changes.push(format!(
+                    "Participant {} exempted: {}",
+                    name(&member.participant_id),
+                    exemption.reason
+                ));
+            }
+            (None, Some(_)) => changes.push(format!(
+                "Participant {} no longer exempt",
+                name(&member.participant_id)
+            )),
+            _ => {}
+        }
+    }
+
+    describe_line_items(&mut changes, before, after, &name);
+    describe_payments(&mut changes, before, after, &name);
+
+    for (id, due_date) in &after.due_dates {
+        if before.due_dates.get(id) != Some(due_date) {
+            changes.push(format!("Due date for {} set to {due_date}", name(id)));
+        }
+    }
+    for id in before.due_dates.keys() {
+        if !after.due_dates.contains_key(id) {
+            changes.push(format!("Due date for {} reset to the default", name(id)));
+        }
+    }
+
+    for tag in &after.tags {
+        if !before.tags.iter().any(|old| old.id == tag.id) {
+            changes.push(format!("Tagged '{}'", tag.name));
+        }
+    }
+    for tag in &before.tags {
+        if !after.tags.iter().any(|new| new.id == tag.id) {
+            changes.push(format!("Tag '{}' removed", tag.name));
+        }
+    }
+    changes
+}
+
+fn describe_line_items(
+    changes: &mut Vec<String>,
+    before: &Bill,
+    after: &Bill,
+    name: &impl Fn(&Uuid) -> String,
+) {
+    // Merged items are reported as merges rather than removals.
+    let mut merged = HashSet::new();
+    for audit in after.merge_audit.iter().skip(before.merge_audit.len()) {
+        merged.insert(audit.source.id);
+        changes.push(format!(
+            "Line item '{}' merged into '{}'",
+            audit.source.description, audit.target.description
+        ));
+    }
+
+    for item in &after.line_items {
+        match before.line_items.iter().find(|old| old.id == item.id) {
+            Some(old) => describe_line_item(changes, old, item, name),
+            None => changes.push(format!(
+                "Line item '{}' added at {}",
+                item.description,
+                price(item)
+            )),
+        }
+    }
+    for item in &before.line_items {
+        if !merged.contains(&item.id) && !after.line_items.iter().any(|new| new.id == item.id) {
+            changes.push(format!("Line item '{}' removed", item.description));
+        }
+    }
+
+    let kept = |bill: &Bill, other: &Bill| -> Vec<Uuid> {
+        bill.line_items
+            .iter()
+            .filter(|item| other.line_items.iter().any(|kept| kept.id == item.id))
+            .map(|item| item.id)
+            .collect()
+    };
+    if kept(before, after) != kept(after, before) {
+        changes.push("Line items reordered".to_string());
+    }
+}
+
+fn describe_line_item(
+    changes: &mut Vec<String>,
+    old: &LineItem,
+    new: &LineItem,
+    name: &impl Fn(&Uuid) -> String,
+) {
+    let label = &new.description;
+    if old.description != new.description {
+        changes.push(format!(
+            "Line item '{}' renamed to '{}'",
+            old.description, new.description
+        ));
+    }
+    if old.unit_price != new.unit_price {
+        changes.push(format!(
+            "Line item '{label}' price changed from {} to {}",
+            dollars(old.unit_price),
+            dollars(new.unit_price)
+        ));
+    }
+    if old.quantity != new.quantity {
+        changes.push(format!(
+            "Line item '{label}' quantity changed from {} to {}",
+            old.quantity, new.quantity
+        ));
+    }
+    if old.participant_ids != new.participant_ids {
+        if new.participant_ids.is_empty() {
+            changes.push(format!("Line item '{label}' unassigned"));
+        } else {
+            let sharers: Vec<String> = new.participant_ids.iter().map(name).collect();
+            changes.push(format!(
+                "Line item '{label}' now shared by {}",
+                sharers.join(", ")
+            ));
+        }
+    }
+    if old.tax_rate != new.tax_rate {
+        changes.push(match new.tax_rate {
+            Some(rate) => format!(
+                "Line item '{label}' tax rate set to {}%",
+                (rate * Decimal::from(100)).normalize()
+            ),
+            None => format!("Line item '{label}' tax rate removed"),
+        });
+    }
+    if old.category != new.category {
+        changes.push(match &new.category {
+            Some(category) => format!("Line item '{label}' filed under '{category}'"),
+            None => format!("Line item '{label}' category removed"),
+        });
+    }
+}
+
+fn price(item: &LineItem) -> String {
+    if item.quantity == 1 {
+        dollars(item.unit_price)
+    } else {
+        format!("{} × {}", item.quantity, dollars(item.unit_price))
+    }
+}
+
+fn describe_payments(
+    changes: &mut Vec<String>,
+    before: &Bill,
+    after: &Bill,
+    name: &impl Fn(&Uuid) -> String,
+) {
+    let described = |payment: &Payment| match &payment.currency {
+        Some(currency) => format!(
+            "{} {currency} ({})",
+            payment.amount,
+            dollars(payment.base_amount())
+        ),
+        None => dollars(payment.amount),
+    };
+    for payment in &after.payments {
+        if !before.payments.iter().any(|old| old.id == payment.id) {
+            changes.push(format!(
+                "{} paid {}",
+                name(&payment.participant_id),
+                described(payment)
+            ));
+        }
+    }
+    for payment in &before.payments {
+        if !after.payments.iter().any(|new| new.id == payment.id) {
+            changes.push(format!(
+                "Payment of {} by {} removed",
+                described(payment),
+                name(&payment.participant_id)
+            ));
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use std::env;
+
+use uuid::Uuid;
+
+const DEFAULT_AI_MODEL: &str = "@cf/meta/llama-3.1-8b-instruct";
+const DEFAULT_APP_BASE_URL: &str = "http://localhost:3000";
+const DEFAULT_EXCHANGE_RATE_API_URL: &str = "https://open.er-api.com/v6/latest";
+const DEFAULT_WKHTMLTOPDF_PATH: &str = "wkhtmltopdf";
+const DEFAULT_PAYMENT_DAYS: u32 = 7;
+const DEFAULT_DRAFT_EXPIRY_DAYS: u32 = 7;
+const DEFAULT_REMINDER_INTERVAL_DAYS: u32 = 3;
+
+/// Runtime configuration, read once from the environment at startup.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub cloudflare_account_id: Option<String>,
+    pub cloudflare_api_token: Option<String>,
+    pub ai_model: String,
+    pub email: EmailConfig,
+    pub sms: SmsConfig,
+    /// Base URL of the frontend, used to build links sent to participants.
+    pub app_base_url: String,
+    pub allowed_origins: AllowedOrigins,
+    /// Secret expected in `X-Admin-Key` by the admin endpoints. They are
+    /// disabled when unset.
+    pub admin_key: Option<String>,
+    /// HS256 secret bearer tokens are verified with. Requests are
+    /// anonymous when unset.
+    pub jwt_secret: Option<String>,
+    /// Endpoint returning `{ result, rates }` for `{url}/{base_currency}`.
+    pub exchange_rate_api_url: String,
+    /// `wkhtmltopdf` binary used to render PDF receipts.
+    pub wkhtmltopdf_path: String,
+    /// Days after a bill is created that participants are expected to pay
+    /// by, unless the creator sets a due date.
+    pub default_payment_days: u32,
+    /// Days a bill can stay a draft before the nightly job deletes it.
+    pub draft_expiry_days: u32,
+    /// Days after `POST /bills/:id/notify` that a reminder is sent to anyone
+    /// who still owes.
+    pub reminder_interval_days: u32,
+}
+
+/// Origins allowed to call the API from a browser, from `ALLOWED_ORIGINS`
+/// (`*` or a comma-separated list). Defaults to any origin.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum AllowedOrigins {
+    #[default]
+    Any,
+    List(Vec<String>),
+}
+
+impl AllowedOrigins {
+    pub fn parse(value: &str) -> Self {
+        let origins: Vec<String> = value
+            .split(',')
+            .map(str::trim)
+            .filter(|origin| !origin.is_empty())
+            .map(str::to_string)
+            .collect();
+        if origins.is_empty() || origins.iter().any(|origin| origin == "*") {
+            AllowedOrigins::Any
+        } else {
+            AllowedOrigins::List(origins)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailProvider {
+    Mailgun,
+    SendGrid,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EmailConfig {
+    /// Selected by `EMAIL_PROVIDER=mailgun|sendgrid`.
+    pub provider: Option<EmailProvider>,
+    pub from: Option<String>,
+    pub mailgun_api_key: Option<String>,
+    pub mailgun_domain: Option<String>,
+    pub sendgrid_api_key: Option<String>,
+    /// Verifies Mailgun delivery webhooks; they are rejected when unset.
+    pub mailgun_webhook_signing_key: Option<String>,
+}
+
+impl EmailConfig {
+    fn from_env() -> Self {
+        let provider = env::var("EMAIL_PROVIDER").ok().and_then(|value| {
+            match value.to_ascii_lowercase().as_str() {
+                "mailgun" => Some(EmailProvider::Mailgun),
+                "sendgrid" => Some(EmailProvider::SendGrid),
+                _ => None,
+            }
+        });
+
+        Self {
+            provider,
+            from: env::var("EMAIL_FROM").ok(),
+            mailgun_api_key: env::var("MAILGUN_API_KEY").ok(),
+            mailgun_domain: env::var("MAILGUN_DOMAIN").ok(),
+            sendgrid_api_key: env::var("SENDGRID_API_KEY").ok(),
+            mailgun_webhook_signing_key: env::var("MAILGUN_WEBHOOK_SIGNING_KEY")
+                .ok()
+                .filter(|key| !key.is_empty()),
+        }
+    }
+}
+
+/// Twilio credentials. SMS is enabled only when all three are set.
+#[derive(Debug, Clone, Default)]
+pub struct SmsConfig {
+    pub twilio_account_sid: Option<String>,
+    pub twilio_auth_token: Option<String>,
+    pub twilio_from_number: Option<String>,
+}
+
+impl SmsConfig {
+    fn from_env() -> Self {
+        Self {
+            twilio_account_sid: env::var("TWILIO_ACCOUNT_SID").ok(),
+            twilio_auth_token: env::var("TWILIO_AUTH_TOKEN").ok(),
+            twilio_from_number: env::var("TWILIO_FROM_NUMBER").ok(),
+        }
+    }
+
+    pub fn is_configured(&self) -> bool {
+        self.twilio_account_sid.is_some()
+            && self.twilio_auth_token.is_some()
+            && self.twilio_from_number.is_some()
+    }
+}
+
+impl Config {
+    pub fn from_env() -> Self {
+        Self {
+            cloudflare_account_id: env::var("CLOUDFLARE_ACCOUNT_ID").ok(),
+            cloudflare_api_token: env::var("CLOUDFLARE_API_TOKEN").ok(),
+            ai_model: env::var("AI_MODEL").unwrap_or_else(|_| DEFAULT_AI_MODEL.to_string()),
+            email: EmailConfig::from_env(),
+            sms: SmsConfig::from_env(),
+            app_base_url: env::var("APP_BASE_URL")
+                .unwrap_or_else(|_| DEFAULT_APP_BASE_URL.to_string()),
+            allowed_origins: env::var("ALLOWED_ORIGINS")
+                .map(|value| AllowedOrigins::parse(&value))
+                .unwrap_or_default(),
+            admin_key: env::var("ADMIN_KEY").ok().filter(|key| !key.is_empty()),
+            jwt_secret: env::var("JWT_SECRET")
+                .ok()
+                .filter(|secret| !secret.is_empty()),
+            exchange_rate_api_url: env::var("EXCHANGE_RATE_API_URL")
+                .unwrap_or_else(|_| DEFAULT_EXCHANGE_RATE_API_URL.to_string()),
+            wkhtmltopdf_path: env::var("WKHTMLTOPDF_PATH")
+                .unwrap_or_else(|_| DEFAULT_WKHTMLTOPDF_PATH.to_string()),
+            default_payment_days: env::var("DEFAULT_PAYMENT_DAYS")
+                .ok()
+                .and_then(|days| days.trim().parse().ok())
+                .unwrap_or(DEFAULT_PAYMENT_DAYS),
+            draft_expiry_days: env::var("DRAFT_EXPIRY_DAYS")
+                .ok()
+                .and_then(|days| days.trim().parse().ok())
+                .unwrap_or(DEFAULT_DRAFT_EXPIRY_DAYS),
+            reminder_interval_days: env::var("REMINDER_INTERVAL_DAYS")
+                .ok()
+                .and_then(|days| days.trim().parse().ok())
+                .unwrap_or(DEFAULT_REMINDER_INTERVAL_DAYS),
+        }
+    }
+
+    /// Link a participant can follow to view `bill_id`.
+    pub fn bill_link(&self, bill_id: Uuid) -> String {
+        format!(
+            "{}/bills/{bill_id}",
+            self.app_base_url.trim_end_matches('/')
+        )
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            cloudflare_account_id: None,
+            cloudflare_api_token: None,
+            ai_model: DEFAULT_AI_MODEL.to_string(),
+            email: EmailConfig::default(),
+            sms: SmsConfig::default(),
+            app_base_url: DEFAULT_APP_BASE_URL.to_string(),
+            allowed_origins: AllowedOrigins::Any,
+            admin_key: None,
+            jwt_secret: None,
+            exchange_rate_api_url: DEFAULT_EXCHANGE_RATE_API_URL.to_string(),
+            wkhtmltopdf_path: DEFAULT_WKHTMLTOPDF_PATH.to_string(),
+            default_payment_days: DEFAULT_PAYMENT_DAYS,
+            draft_expiry_days: DEFAULT_DRAFT_EXPIRY_DAYS,
+            reminder_interval_days: DEFAULT_REMINDER_INTERVAL_DAYS,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Footer text for bills used as expense reports or shared between
+//! businesses, per jurisdiction, in the local language.
+
+use crate::models::Bill;
+
+struct Disclaimer {
+    jurisdiction: &'static str,
+    /// `chrono` format for the bill date, as written locally.
+    date_format: &'static str,
+    /// `{amount}`, `{date}` and `{participants}` are filled in.
+    template: &'static str,
+}
+
+const DISCLAIMERS: &[Disclaimer] = &[
+    Disclaimer {
+        jurisdiction: "US-CA",
+        date_format: "%m/%d/%Y",
+        template: "This statement summarizes a shared expense of {amount} dated {date}, \
+                   split among {participants} participants. It is not a formal invoice or \
+                   receipt and does not by itself create a debt enforceable under California \
+                   law. Keep the original receipts for tax and reimbursement purposes.",
+    },
+    Disclaimer {
+        jurisdiction: "UK",
+        date_format: "%d/%m/%Y",
+        template: "This statement summarises a shared expense of {amount} dated {date}, \
+                   split between {participants} participants. It is not a VAT invoice or a \
+                   formal invoice and cannot be used to reclaim VAT. Keep the original \
+                   receipts for your records.",
+    },
+    Disclaimer {
+        jurisdiction: "DE",
+        date_format: "%d.%m.%Y",
+        template: "Diese Aufstellung fasst gemeinsame Ausgaben in Höhe von {amount} vom {date} \
+                   zusammen, aufgeteilt auf {participants} Beteiligte. Sie ist keine Rechnung \
+                   im Sinne des § 14 UStG und berechtigt nicht zum Vorsteuerabzug. Bewahren \
+                   Sie die Originalbelege auf.",
+    },
+    Disclaimer {
+        jurisdiction: "FR",
+        date_format: "%d/%m/%Y",
+        template: "Ce relevé récapitule une dépense commune de {amount} du {date}, répartie \
+                   entre {participants} participants. Il ne constitue pas une facture au sens \
+                   du Code général des impôts et ne permet pas de déduire la TVA. Conservez \
+                   les justificatifs originaux.",
+    },
+];
+
+/// Jurisdiction codes [`disclaimer`] knows.
+pub fn jurisdictions() -> impl Iterator<Item = &'static str> {
+    DISCLAIMERS.iter().map(|disclaimer| disclaimer.jurisdiction)
+}
+
+/// The disclaimer for `bill` in `jurisdiction`, or `None` for an unknown
+/// code.
+pub fn disclaimer(bill: &Bill, jurisdiction: &str) -> Option<String> {
+    let disclaimer = DISCLAIMERS
+        .iter()
+        .find(|disclaimer| disclaimer.jurisdiction.eq_ignore_ascii_case(jurisdiction))?;
+    let amount = format!("{} {}", bill.total(), bill.base_currency);
+    let date = bill.created_at.format(disclaimer.date_format).to_string();
+    Some(
+        disclaimer
+            .template
+            .replace("{amount}", &amount)
+            .replace("{date}", &date)
+            .replace("{participants}", &bill.participants.len().to_string()),
+    )
+}
@@ -0,0 +1,60 @@
+//! Detection of line items that look like the same thing entered twice.
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{models::Bill, util::similarity::jaro_winkler};
+
+/// Pairs scoring above this are reported as likely duplicates.
+pub const DUPLICATE_THRESHOLD: f64 = 0.85;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateCandidate {
+    pub item_a_id: Uuid,
+    pub item_b_id: Uuid,
+    /// Jaro-Winkler similarity of the descriptions, ignoring case.
+    pub similarity_score: f64,
+    pub reason: String,
+}
+
+/// Every pair of line items whose descriptions are more similar than
+/// [`DUPLICATE_THRESHOLD`], most similar first. `item_a` is always the item
+/// added earlier.
+pub fn find_duplicates(bill: &Bill) -> Vec<DuplicateCandidate> {
+    let names: Vec<String> = bill
+        .line_items
+        .iter()
+        .map(|item| item.description.trim().to_lowercase())
+        .collect();
+
+    let mut candidates = Vec::new();
+    for (i, a) in bill.line_items.iter().enumerate() {
+        for (j, b) in bill.line_items.iter().enumerate().skip(i + 1) {
+            let score = jaro_winkler(&names[i], &names[j]);
+            if score <= DUPLICATE_THRESHOLD {
+                continue;
+            }
+            let mut reason = if names[i] == names[j] {
+                "Descriptions are the same apart from case or spacing".to_string()
+            } else {
+                format!(
+                    "\"{}\" and \"{}\" are {:.0}% similar",
+                    a.description,
+                    b.description,
+                    score * 100.0
+                )
+            };
+            if a.unit_price == b.unit_price {
+                reason.push_str(&format!(", and both cost {}", a.unit_price));
+            }
+            candidates.push(DuplicateCandidate {
+                item_a_id: a.id,
+                item_b_id: b.id,
+                similarity_score: (score * 1000.0).round() / 1000.0,
+                reason,
+            });
+        }
+    }
+    candidates.sort_by(|x, y| y.similarity_score.total_cmp(&x.similarity_score));
+    candidates
+}
@@ -0,0 +1,93 @@
+use std::fmt;
+
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use serde_json::json;
+
+use crate::{ai::AiError, storage::KvError};
+
+/// Error returned by API handlers. Renders the same `{ error, message, status }`
+/// body as the generic error handler so clients only deal with one shape.
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Unauthorized(String),
+    /// The caller is known but not allowed to do this.
+    Forbidden(String),
+    /// The stored bill lacks what the requested operation needs.
+    InsufficientData(String),
+    NotFound(String),
+    /// The request conflicts with the current state of the resource.
+    Conflict(String),
+    /// The request's `If-Match` does not name the resource's current version.
+    PreconditionFailed(String),
+    BadGateway(String),
+    ServiceUnavailable(String),
+    Internal(String),
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::BadRequest(message)
+            | ApiError::Unauthorized(message)
+            | ApiError::Forbidden(message)
+            | ApiError::InsufficientData(message)
+            | ApiError::NotFound(message)
+            | ApiError::Conflict(message)
+            | ApiError::PreconditionFailed(message)
+            | ApiError::BadGateway(message)
+            | ApiError::ServiceUnavailable(message)
+            | ApiError::Internal(message) => f.write_str(message),
+        }
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::BadRequest(_) | ApiError::InsufficientData(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
+            ApiError::BadGateway(_) => StatusCode::BAD_GATEWAY,
+            ApiError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let status = self.status_code();
+        if let ApiError::InsufficientData(reason) = self {
+            return HttpResponse::build(status).json(json!({
+                "error": "InsufficientData",
+                "reason": reason,
+                "status": status.as_u16()
+            }));
+        }
+
+        HttpResponse::build(status).json(json!({
+            "error": status.canonical_reason().unwrap_or("Error"),
+            "message": self.to_string(),
+            "status": status.as_u16()
+        }))
+    }
+}
+
+impl From<KvError> for ApiError {
+    fn from(err: KvError) -> Self {
+        ApiError::Internal(err.to_string())
+    }
+}
+
+impl From<AiError> for ApiError {
+    fn from(err: AiError) -> Self {
+        match err {
+            AiError::NotConfigured | AiError::Unavailable { .. } => {
+                ApiError::ServiceUnavailable(err.to_string())
+            }
+            _ => ApiError::BadGateway(err.to_string()),
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! Live exchange rates from an open.er-api.com compatible service.
+
+use std::{fmt, str::FromStr, time::Duration};
+
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    config::Config,
+    error::ApiError,
+    models::{Bill, CurrencyCode, ExchangeRate},
+};
+
+const RATE_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug)]
+pub enum ExchangeError {
+    Request(String),
+    Api {
+        status: u16,
+    },
+    /// The service answered but does not quote the requested pair.
+    UnknownCurrency(CurrencyCode),
+    InvalidResponse(String),
+}
+
+impl fmt::Display for ExchangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExchangeError::Request(err) => write!(f, "exchange rate request failed: {err}"),
+            ExchangeError::Api { status } => write!(f, "exchange rate service returned {status}"),
+            ExchangeError::UnknownCurrency(code) => {
+                write!(f, "no exchange rate is available for {code}")
+            }
+            ExchangeError::InvalidResponse(reason) => {
+                write!(f, "exchange rate response was invalid: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExchangeError {}
+
+impl From<ExchangeError> for ApiError {
+    fn from(err: ExchangeError) -> Self {
+        match err {
+            ExchangeError::UnknownCurrency(_) => ApiError::BadRequest(err.to_string()),
+            _ => ApiError::BadGateway(err.to_string()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LatestRates {
+    result: String,
+    #[serde(default)]
+    rates: serde_json::Map<String, Value>,
+}
+
+/// How many units of `to` one unit of `from` buys right now.
+pub async fn fetch_rate(
+    config: &Config,
+    from: &CurrencyCode,
+    to: &CurrencyCode,
+) -> Result<Decimal, ExchangeError> {
+    if from == to {
+        return Ok(Decimal::ONE);
+    }
+
+    let url = format!(
+        "{}/{from}",
+        config.exchange_rate_api_url.trim_end_matches('/')
+    );
+    let mut response = awc::Client::default()
+        .get(url)
+        .timeout(RATE_REQUEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|err| ExchangeError::Request(err.to_string()))?;
+    if !response.status().is_success() {
+        return Err(ExchangeError::Api {
+            status: response.status().as_u16(),
+        });
+    }
+
+    let latest: LatestRates = response
+        .json()
+        .await
+        .map_err(|err| ExchangeError::InvalidResponse(err.to_string()))?;
+    if latest.result != "success" {
+        return Err(ExchangeError::UnknownCurrency(from.clone()));
+    }
+    let rate = latest
+        .rates
+        .get(to.as_str())
+        .ok_or_else(|| ExchangeError::UnknownCurrency(to.clone()))?;
+    Decimal::from_str(&rate.to_string())
+        .ok()
+        .filter(|rate| *rate > Decimal::ZERO)
+        .ok_or_else(|| ExchangeError::InvalidResponse(format!("rate `{rate}` is not usable")))
+}
+
+/// The rate converting `from` into `bill`'s currency: the bill's manual rate
+/// when one is set, otherwise the live rate.
+pub async fn rate_into_bill_currency(
+    config: &Config,
+    bill: &Bill,
+    from: &CurrencyCode,
+) -> Result<ExchangeRate, ExchangeError> {
+    if let Some(manual) = bill.manual_rate(from) {
+        return Ok(manual.clone());
+    }
+    let rate = fetch_rate(config, from, &bill.base_currency).await?;
+    Ok(ExchangeRate::new(
+        from.clone(),
+        bill.base_currency.clone(),
+        rate,
+    ))
+}
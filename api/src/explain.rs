@@ -0,0 +1,407 @@
+//! Plain-language walk-through of how a split was arrived at.
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    models::{Bill, Money, Participant, ParticipantShare},
+    split::{SplitMethod, SplitSpec},
+};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExplanationStep {
+    pub step: usize,
+    pub description: String,
+    pub running_total: Money,
+}
+
+pub(crate) fn dollars(amount: Money) -> String {
+    if amount.is_negative() {
+        format!("-${}", amount.abs())
+    } else {
+        format!("${amount}")
+    }
+}
+
+/// Explains how `shares` follow from `bill` under `method`, one step per
+/// sentence. `running_total` is the amount accounted for so far.
+pub fn explain_split(
+    bill: &Bill,
+    method: SplitMethod,
+    shares: &[ParticipantShare],
+) -> Vec<ExplanationStep> {
+    let mut steps = Steps::default();
+    let total = bill.total();
+
+    steps.push(
+        format!(
+            "Added up {} line item(s) for a subtotal of {}.",
+            bill.line_items.len(),
+            dollars(bill.subtotal())
+        ),
+        bill.subtotal(),
+    );
+    if !bill.discount.is_zero() {
+        steps.push(
+            format!(
+                "Took off a {} discount, leaving {} to split.",
+                dollars(bill.discount),
+                dollars(total)
+            ),
+            total,
+        );
+    }
+
+    match method {
+        SplitMethod::Equal => explain_equal(&mut steps, bill, total, shares),
+        SplitMethod::Itemised => explain_itemised(&mut steps, bill, shares),
+        SplitMethod::Proportional => explain_proportional(&mut steps, total, shares),
+        SplitMethod::Custom => {
+            let mut running = Money::ZERO;
+            for share in shares {
+                running += share.amount_owed;
+                steps.push(
+                    format!(
+                        "{} was assigned {}.",
+                        share.name,
+                        dollars(share.amount_owed)
+                    ),
+                    running,
+                );
+            }
+        }
+    }
+    steps.0
+}
+
+#[derive(Default)]
+struct Steps(Vec<ExplanationStep>);
+
+impl Steps {
+    fn push(&mut self, description: String, running_total: Money) {
+        self.0.push(ExplanationStep {
+            step: self.0.len() + 1,
+            description,
+            running_total,
+        });
+    }
+}
+
+fn explain_equal(steps: &mut Steps, bill: &Bill, total: Money, shares: &[ParticipantShare]) {
+    // Exempt participants' zero shares are not part of the division.
+    let shares: Vec<&ParticipantShare> = shares
+        .iter()
+        .filter(|share| !bill.is_exempt(share.participant_id))
+        .collect();
+    if shares.is_empty() {
+        return;
+    }
+    let each = Money::from_cents(total.cents().div_euclid(shares.len() as i64));
+    steps.push(
+        format!(
+            "Divided {} total by {} participants = {} each.",
+            dollars(total),
+            shares.len(),
+            dollars(each)
+        ),
+        each * shares.len() as u32,
+    );
+
+    for share in shares.iter().filter(|share| share.amount_owed != each) {
+        steps.push(
+            format!(
+                "{} takes the {} rounding remainder and pays {}.",
+                share.name,
+                dollars(share.amount_owed - each),
+                dollars(share.amount_owed)
+            ),
+            total,
+        );
+    }
+}
+
+fn explain_itemised(steps: &mut Steps, bill: &Bill, shares: &[ParticipantShare]) {
+    let mut running = Money::ZERO;
+    for share in shares {
+        let mut parts = Vec::new();
+        let mut listed = Money::ZERO;
+        for item in &bill.line_items {
+            let sharers = bill.item_sharers(item);
+            if !sharers.contains(&share.participant_id) {
+                continue;
+            }
+            let portion =
+                Money::from_decimal(item.total().to_decimal() / Decimal::from(sharers.len()));
+            listed += portion;
+            if sharers.len() == 1 {
+                parts.push(format!("{} {}", item.description, dollars(portion)));
+            } else {
+                parts.push(format!(
+                    "{} {} (1/{})",
+                    item.description,
+                    dollars(portion),
+                    sharers.len()
+                ));
+            }
+        }
+
+        let mut breakdown = if parts.is_empty() {
+            "no items".to_string()
+        } else {
+            parts.join(" + ")
+        };
+        // Discount share and rounding, folded into one figure.
+        let adjustment = share.amount_owed - listed;
+        if !adjustment.is_zero() {
+            let label = if bill.discount.is_zero() {
+                "rounding"
+            } else {
+                "share of discount and rounding"
+            };
+            let sign = if adjustment.is_negative() { '-' } else { '+' };
+            breakdown.push_str(&format!(" {sign} {label} {}", dollars(adjustment.abs())));
+        }
+
+        running += share.amount_owed;
+        steps.push(
+            format!(
+                "{}: {} = {}.",
+                share.name,
+                breakdown,
+                dollars(share.amount_owed)
+            ),
+            running,
+        );
+    }
+}
+
+fn explain_proportional(steps: &mut Steps, total: Money, shares: &[ParticipantShare]) {
+    let mut running = Money::ZERO;
+    for share in shares {
+        running += share.amount_owed;
+        let percent = if total.is_zero() {
+            Decimal::ZERO
+        } else {
+            (share.amount_owed.to_decimal() / total.to_decimal() * Decimal::from(100)).round_dp(1)
+        };
+        steps.push(
+            format!(
+                "{} pays {}% of {} = {}.",
+                share.name,
+                percent.normalize(),
+                dollars(total),
+                dollars(share.amount_owed)
+            ),
+            running,
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum DebtStepKind {
+    LineItem,
+    /// Bills do not record tips yet, so no step has this kind.
+    Tip,
+    Discount,
+    Payment,
+    Rounding,
+}
+
+/// One step in how a participant's debt adds up.
+#[derive(Debug, Clone, Serialize)]
+pub struct DebtStep {
+    #[serde(rename = "type")]
+    pub kind: DebtStepKind,
+    pub description: String,
+    pub delta: Money,
+    /// What the participant owes after this step.
+    pub running_total: Money,
+}
+
+/// Traces `share` back through `bill`: each line item's contribution under
+/// `spec`, the participant's part of the discount, the rounding to whole
+/// cents and finally their payments. The last `running_total` is what they
+/// still owe (negative when they are owed money).
+pub fn debt_chain(
+    bill: &Bill,
+    participants: &[Participant],
+    spec: &SplitSpec,
+    share: &ParticipantShare,
+) -> Vec<DebtStep> {
+    let mut chain = DebtChain::default();
+    let you = share.participant_id;
+    let fraction = flat_fraction(bill, participants, spec, share);
+
+    let mut items_exact = Decimal::ZERO;
+    for item in &bill.line_items {
+        let sharers = bill.item_sharers(item);
+        let (portion, how) = match fraction {
+            None if sharers.contains(&you) => (
+                item.total().to_decimal() / Decimal::from(sharers.len()),
+                format!("shared by {}", shared_by(&sharers, participants)),
+            ),
+            None => (Decimal::ZERO, "not assigned to you".to_string()),
+            Some(fraction) => (
+                item.total().to_decimal() * fraction,
+                format!(
+                    "{} split, your part is {}%",
+                    method_name(spec),
+                    percent(fraction)
+                ),
+            ),
+        };
+        items_exact += portion;
+        chain.push(
+            DebtStepKind::LineItem,
+            format!(
+                "{} ({}): {}, so you owe {}.",
+                item.description,
+                dollars(item.total()),
+                how,
+                dollars(Money::from_decimal(portion))
+            ),
+            Money::from_decimal(portion),
+        );
+    }
+
+    let subtotal = bill.subtotal().to_decimal();
+    if !bill.discount.is_zero() && !subtotal.is_zero() {
+        let exact = items_exact * bill.discount.to_decimal() / subtotal;
+        let delta = -Money::from_decimal(exact);
+        chain.push(
+            DebtStepKind::Discount,
+            format!(
+                "Your part of the {} discount takes off {}.",
+                dollars(bill.discount),
+                dollars(delta.abs())
+            ),
+            delta,
+        );
+    }
+
+    let rounding = share.amount_owed - chain.running;
+    if !rounding.is_zero() {
+        chain.push(
+            DebtStepKind::Rounding,
+            format!(
+                "Rounding to whole cents adjusts your share by {}, to {}.",
+                dollars(rounding),
+                dollars(share.amount_owed)
+            ),
+            rounding,
+        );
+    }
+
+    for payment in bill
+        .payments
+        .iter()
+        .filter(|payment| payment.participant_id == you)
+    {
+        let paid = payment.base_amount();
+        let mut description = format!(
+            "You paid {} on {}",
+            dollars(paid),
+            payment.paid_at.format("%Y-%m-%d")
+        );
+        if let Some(currency) = &payment.currency {
+            description.push_str(&format!(" ({} {currency})", payment.amount));
+        }
+        if let Some(note) = &payment.note {
+            description.push_str(&format!(" for \"{note}\""));
+        }
+        description.push('.');
+        chain.push(DebtStepKind::Payment, description, -paid);
+    }
+
+    chain.steps
+}
+
+#[derive(Default)]
+struct DebtChain {
+    steps: Vec<DebtStep>,
+    running: Money,
+}
+
+impl DebtChain {
+    fn push(&mut self, kind: DebtStepKind, description: String, delta: Money) {
+        self.running += delta;
+        self.steps.push(DebtStep {
+            kind,
+            description,
+            delta,
+            running_total: self.running,
+        });
+    }
+}
+
+/// The part of every line item `share` covers, or `None` for itemised splits
+/// where it depends on the item.
+fn flat_fraction(
+    bill: &Bill,
+    participants: &[Participant],
+    spec: &SplitSpec,
+    share: &ParticipantShare,
+) -> Option<Decimal> {
+    if bill.is_exempt(share.participant_id) && !matches!(spec, SplitSpec::Itemised) {
+        return Some(Decimal::ZERO);
+    }
+    match spec {
+        SplitSpec::Itemised => None,
+        SplitSpec::Equal => {
+            let liable = participants
+                .iter()
+                .filter(|participant| !bill.is_exempt(participant.id))
+                .count();
+            Some(Decimal::ONE / Decimal::from(liable.max(1)))
+        }
+        SplitSpec::Proportional { weights } => {
+            let total_weight: Decimal = weights
+                .iter()
+                .filter(|(id, _)| !bill.is_exempt(**id))
+                .map(|(_, weight)| *weight)
+                .sum();
+            let weight = weights
+                .get(&share.participant_id)
+                .copied()
+                .unwrap_or_default();
+            Some(if total_weight.is_zero() {
+                Decimal::ZERO
+            } else {
+                weight / total_weight
+            })
+        }
+        SplitSpec::Custom { .. } => {
+            let total = bill.total().to_decimal();
+            Some(if total.is_zero() {
+                Decimal::ZERO
+            } else {
+                share.amount_owed.to_decimal() / total
+            })
+        }
+    }
+}
+
+fn shared_by(sharers: &[Uuid], participants: &[Participant]) -> String {
+    let names: Vec<&str> = participants
+        .iter()
+        .filter(|participant| sharers.contains(&participant.id))
+        .map(|participant| participant.name.as_str())
+        .collect();
+    if names.len() == 1 {
+        "you alone".to_string()
+    } else {
+        format!("{} ({} ways)", names.join(", "), names.len())
+    }
+}
+
+fn method_name(spec: &SplitSpec) -> String {
+    spec.method().to_string()
+}
+
+fn percent(fraction: Decimal) -> String {
+    (fraction * Decimal::from(100))
+        .round_dp(1)
+        .normalize()
+        .to_string()
+}
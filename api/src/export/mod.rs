@@ -0,0 +1,2 @@
+pub mod quickbooks;
+pub mod spreadsheet;
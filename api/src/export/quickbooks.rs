@@ -0,0 +1,109 @@
+//! QuickBooks Desktop IIF (Intuit Interchange Format) export.
+//!
+//! IIF is tab-separated. A `!TRNS`/`!SPL`/`!ENDTRNS` header block declares the
+//! columns, then each transaction is a `TRNS` row, its `SPL` rows and an
+//! `ENDTRNS` terminator. The `TRNS` amount and its splits must sum to zero.
+
+use crate::models::{Bill, LineItem};
+
+/// Account used when a line item has no category.
+pub const DEFAULT_EXPENSE_ACCOUNT: &str = "Meals & Entertainment";
+/// Account the bill was paid from.
+pub const PAYMENT_ACCOUNT: &str = "Checking";
+
+const TRANSACTION_TYPE: &str = "CHECK";
+
+/// QuickBooks account for a line item category. Common categories map to the
+/// standard chart of accounts; anything else is assumed to already name an
+/// account in the company file.
+pub fn account_for(category: Option<&str>) -> String {
+    let Some(category) = category.map(str::trim).filter(|c| !c.is_empty()) else {
+        return DEFAULT_EXPENSE_ACCOUNT.to_string();
+    };
+    match category.to_ascii_lowercase().as_str() {
+        "food" | "meals" | "drinks" | "entertainment" => DEFAULT_EXPENSE_ACCOUNT.to_string(),
+        "travel" | "transport" | "taxi" => "Travel Expense".to_string(),
+        "lodging" | "hotel" | "accommodation" => "Travel Expense:Lodging".to_string(),
+        "office" | "supplies" => "Office Supplies".to_string(),
+        "utilities" => "Utilities".to_string(),
+        _ => category.to_string(),
+    }
+}
+
+/// Renders `bill` as an IIF file with one transaction per line item.
+/// Bill-wide discounts are not exported.
+pub fn bill_to_iif(bill: &Bill) -> String {
+    let mut out = String::new();
+    push_row(
+        &mut out,
+        &[
+            "!TRNS", "TRNSID", "TRNSTYPE", "DATE", "ACCNT", "NAME", "AMOUNT", "MEMO",
+        ],
+    );
+    push_row(
+        &mut out,
+        &[
+            "!SPL", "SPLID", "TRNSTYPE", "DATE", "ACCNT", "NAME", "AMOUNT", "MEMO",
+        ],
+    );
+    push_row(&mut out, &["!ENDTRNS"]);
+
+    let date = bill.created_at.format("%m/%d/%Y").to_string();
+    for item in &bill.line_items {
+        push_transaction(&mut out, bill, item, &date);
+    }
+    out
+}
+
+fn push_transaction(out: &mut String, bill: &Bill, item: &LineItem, date: &str) {
+    let total = item.total();
+    let memo = memo(item);
+    let account = account_for(item.category.as_deref());
+
+    push_row(
+        out,
+        &[
+            "TRNS",
+            "",
+            TRANSACTION_TYPE,
+            date,
+            PAYMENT_ACCOUNT,
+            &bill.title,
+            &(-total).to_string(),
+            &memo,
+        ],
+    );
+    push_row(
+        out,
+        &[
+            "SPL",
+            "",
+            TRANSACTION_TYPE,
+            date,
+            &account,
+            &bill.title,
+            &total.to_string(),
+            &memo,
+        ],
+    );
+    push_row(out, &["ENDTRNS"]);
+}
+
+fn memo(item: &LineItem) -> String {
+    if item.quantity == 1 {
+        item.description.clone()
+    } else {
+        format!("{} x{}", item.description, item.quantity)
+    }
+}
+
+/// Appends one tab-separated row. Tabs and line breaks inside a field would
+/// break the format, so they are replaced with spaces.
+fn push_row(out: &mut String, fields: &[&str]) {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|field| field.replace(['\t', '\r', '\n'], " "))
+        .collect();
+    out.push_str(&fields.join("\t"));
+    out.push_str("\r\n");
+}
@@ -0,0 +1,125 @@
+//! CSV export for Excel and Google Sheets that keeps the arithmetic live.
+//!
+//! Columns are A description, B quantity, C unit price, D total and E tax
+//! rate. Line item totals, the subtotal, the total and the tax included in it
+//! are formulas over those cells, so the sheet recalculates when an item is
+//! edited. Row 1 holds the headers; CSV cannot mark it frozen, so that is
+//! left to the spreadsheet.
+
+use std::collections::BTreeSet;
+
+use rust_decimal::Decimal;
+
+use crate::models::Bill;
+
+pub const HEADERS: [&str; 5] = ["Description", "Quantity", "Unit price", "Total", "Tax rate"];
+
+/// Renders `bill` as CSV with formulas for every computed cell.
+pub fn bill_to_formula_csv(bill: &Bill) -> String {
+    let mut out = String::new();
+    push_row(&mut out, &HEADERS.map(str::to_string));
+
+    let first = 2;
+    let last = first + bill.line_items.len() - 1;
+    for (row, item) in (first..).zip(&bill.line_items) {
+        push_row(
+            &mut out,
+            &[
+                text(&item.description),
+                item.quantity.to_string(),
+                item.unit_price.to_string(),
+                format!("=B{row}*C{row}"),
+                item.tax_rate
+                    .map(|rate| rate.normalize().to_string())
+                    .unwrap_or_default(),
+            ],
+        );
+    }
+    let items = if bill.line_items.is_empty() {
+        None
+    } else {
+        Some((format!("D{first}:D{last}"), format!("E{first}:E{last}")))
+    };
+
+    let subtotal_row = last + 1;
+    let subtotal = match &items {
+        Some((totals, _)) => format!("=SUM({totals})"),
+        None => "0.00".to_string(),
+    };
+    push_row(&mut out, &summary("Subtotal", subtotal));
+    let total_row = if bill.discount.is_zero() {
+        subtotal_row
+    } else {
+        push_row(&mut out, &summary("Discount", bill.discount.to_string()));
+        push_row(
+            &mut out,
+            &summary("Total", format!("=D{subtotal_row}-D{}", subtotal_row + 1)),
+        );
+        subtotal_row + 2
+    };
+
+    // Prices include tax, so each band's tax is rate / (1 + rate) of its
+    // share of the total, the discount spread over items by price.
+    let rates: BTreeSet<Decimal> = bill
+        .line_items
+        .iter()
+        .filter_map(|item| item.tax_rate.map(|rate| rate.normalize()))
+        .collect();
+    if let Some((totals, tax_rates)) = &items {
+        let scale = if bill.discount.is_zero() {
+            String::new()
+        } else {
+            format!("*D{total_row}/D{subtotal_row}")
+        };
+        for rate in rates {
+            push_row(
+                &mut out,
+                &summary(
+                    &format!(
+                        "Tax included at {}%",
+                        (rate * Decimal::ONE_HUNDRED).normalize()
+                    ),
+                    format!("=SUMIF({tax_rates},{rate},{totals}){scale}*{rate}/(1+{rate})"),
+                ),
+            );
+        }
+    }
+    out
+}
+
+fn summary(label: &str, value: String) -> [String; 5] {
+    [
+        label.to_string(),
+        String::new(),
+        String::new(),
+        value,
+        String::new(),
+    ]
+}
+
+/// User-entered text, with a leading `'` if a spreadsheet would otherwise
+/// read it as a formula.
+fn text(value: &str) -> String {
+    if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    }
+}
+
+/// Appends one CSV row, quoting fields that contain a comma, quote or line
+/// break.
+fn push_row(out: &mut String, fields: &[String]) {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\r', '\n']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect();
+    out.push_str(&fields.join(","));
+    out.push_str("\r\n");
+}
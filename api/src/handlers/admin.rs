@@ -0,0 +1,112 @@
+use actix_web::{get, post, web, HttpRequest, HttpResponse};
+use uuid::Uuid;
+
+use chrono::Utc;
+
+use crate::{
+    config::Config,
+    error::ApiError,
+    jobs::{expire_drafts, send_reminders},
+    queues::EmailTask,
+    state::AppState,
+};
+
+pub const ADMIN_KEY_HEADER: &str = "X-Admin-Key";
+
+/// Lets the request through only if it carries the configured admin key.
+pub(crate) fn require_admin(req: &HttpRequest, config: &Config) -> Result<(), ApiError> {
+    let Some(expected) = config.admin_key.as_deref() else {
+        return Err(ApiError::ServiceUnavailable(
+            "Admin endpoints are disabled: set ADMIN_KEY".to_string(),
+        ));
+    };
+    let given = req
+        .headers()
+        .get(ADMIN_KEY_HEADER)
+        .and_then(|value| value.to_str().ok());
+    if given != Some(expected) {
+        return Err(ApiError::Unauthorized(format!(
+            "A valid `{ADMIN_KEY_HEADER}` header is required"
+        )));
+    }
+    Ok(())
+}
+
+/// Current state of every circuit breaker.
+#[get("/admin/circuit-breakers")]
+async fn circuit_breakers(state: web::Data<AppState>) -> HttpResponse {
+    HttpResponse::Ok().json([state.ai_breaker.status()])
+}
+
+/// Key counts per record type and an estimate of the storage they use.
+#[get("/admin/kv-stats")]
+async fn kv_stats(req: HttpRequest, state: web::Data<AppState>) -> Result<HttpResponse, ApiError> {
+    require_admin(&req, &state.config)?;
+    Ok(HttpResponse::Ok().json(state.repo().stats().await?))
+}
+
+/// Notification tasks that failed for good, oldest first.
+#[get("/admin/dead-letter-queue")]
+async fn dead_letter_queue(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+) -> Result<HttpResponse, ApiError> {
+    require_admin(&req, &state.config)?;
+    Ok(HttpResponse::Ok().json(state.repo().list_dead_letters().await?))
+}
+
+/// Takes a task off the dead-letter queue and queues it again with a fresh
+/// set of retries.
+#[post("/admin/dead-letter-queue/{task_id}/replay")]
+async fn replay_dead_letter(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    task_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    require_admin(&req, &state.config)?;
+    let task_id = task_id.into_inner();
+    let repo = state.repo();
+    let letter = repo
+        .get_dead_letter(task_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Dead letter {task_id} not found")))?;
+
+    let task: EmailTask = letter.task;
+    if !state.notification_queue.send(task.clone()) {
+        return Err(ApiError::ServiceUnavailable(
+            "The notification queue is not accepting tasks".to_string(),
+        ));
+    }
+    repo.delete_dead_letter(task_id).await?;
+
+    Ok(HttpResponse::Accepted().json(task))
+}
+
+/// Runs the nightly draft expiry now.
+#[post("/admin/cron/expire-drafts")]
+async fn run_expire_drafts(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+) -> Result<HttpResponse, ApiError> {
+    require_admin(&req, &state.config)?;
+    Ok(HttpResponse::Ok().json(expire_drafts(&state, Utc::now()).await?))
+}
+
+/// Sends the reminders that are due now, as the reminder check does.
+#[post("/admin/cron/send-reminders")]
+async fn run_send_reminders(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+) -> Result<HttpResponse, ApiError> {
+    require_admin(&req, &state.config)?;
+    Ok(HttpResponse::Ok().json(send_reminders(&state, Utc::now()).await?))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(circuit_breakers)
+        .service(kv_stats)
+        .service(dead_letter_queue)
+        .service(replay_dead_letter)
+        .service(run_expire_drafts)
+        .service(run_send_reminders);
+}
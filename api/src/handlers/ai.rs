@@ -0,0 +1,173 @@
+use actix_web::{get, http::header, post, web, HttpRequest, HttpResponse};
+use futures::future::try_join_all;
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use super::{ensure_not_archived, load_bill};
+use crate::{
+    ai::{
+        get_cached_ai_response, match_participant, nudge_prompt, parse_payer_guess, payer_prompt,
+        AiRequest, Channel, NudgeMessage,
+    },
+    error::ApiError,
+    i18n::{t, t_with},
+    models::Money,
+    split::net_balances,
+    state::AppState,
+};
+
+#[derive(Deserialize)]
+struct PromptBody {
+    prompt: String,
+    max_tokens: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct CacheQuery {
+    #[serde(default = "default_use_cache")]
+    use_cache: bool,
+}
+
+fn default_use_cache() -> bool {
+    true
+}
+
+#[post("/ai/prompt")]
+async fn prompt(
+    state: web::Data<AppState>,
+    query: web::Query<CacheQuery>,
+    body: web::Json<PromptBody>,
+) -> Result<HttpResponse, ApiError> {
+    let body = body.into_inner();
+    if body.prompt.trim().is_empty() {
+        return Err(ApiError::BadRequest(t_with(
+            "must_not_be_empty",
+            &[("field", &"prompt")],
+        )));
+    }
+
+    let request = AiRequest {
+        prompt: body.prompt,
+        max_tokens: body.max_tokens,
+    };
+    let response = get_cached_ai_response(
+        &state.kv,
+        &state.ai_breaker,
+        &state.config,
+        &request,
+        query.use_cache,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(("X-Cache", response.cache.as_header_value()))
+        .insert_header((header::ETAG, format!("\"{}\"", response.prompt_hash)))
+        .json(json!({ "response": response.text })))
+}
+
+/// Asks the model who paid, based on the bill's title and notes, and records
+/// the answer as the bill's payer when it names one of the participants.
+#[post("/bills/{id}/assign-payer-from-ai")]
+async fn assign_payer_from_ai(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    query: web::Query<CacheQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo_for(&req);
+    let mut bill = load_bill(&repo, id.into_inner()).await?;
+    ensure_not_archived(&bill)?;
+    let participants = repo.get_bill_participants(&bill).await?;
+    if participants.is_empty() {
+        return Err(ApiError::InsufficientData(t("no_participants")));
+    }
+
+    let request = AiRequest::new(payer_prompt(&bill, &participants));
+    let response = get_cached_ai_response(
+        &state.kv,
+        &state.ai_breaker,
+        &state.config,
+        &request,
+        query.use_cache,
+    )
+    .await?;
+    let guess = parse_payer_guess(&response.text)?;
+
+    let payer = guess
+        .payer
+        .as_deref()
+        .and_then(|name| match_participant(name, &participants))
+        .ok_or_else(|| {
+            ApiError::InsufficientData(format!("Could not tell who paid: {}", guess.reasoning))
+        })?;
+
+    bill.payer_id = Some(payer.id);
+    bill.touch();
+    repo.put_bill(&bill).await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(("X-Cache", response.cache.as_header_value()))
+        .json(json!({
+            "inferred_payer": payer,
+            "confidence": guess.confidence,
+            "reasoning": guess.reasoning
+        })))
+}
+
+/// Reminders for a participant who still owes money on the latest split, one
+/// per channel. Generated in parallel and cached like any other prompt, so
+/// repeat requests within a day are served from KV. Participants who owe
+/// nothing get an empty list.
+#[get("/bills/{id}/participants/{participant_id}/messages")]
+async fn nudge_messages(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, participant_id) = path.into_inner();
+    let repo = state.repo();
+    let bill = load_bill(&repo, id).await?;
+    if !bill.has_participant(participant_id) {
+        return Err(ApiError::NotFound(t_with(
+            "participant_not_on_bill",
+            &[("id", &participant_id)],
+        )));
+    }
+    let snapshot = bill
+        .latest_split()
+        .ok_or_else(|| ApiError::InsufficientData(t("no_split_yet")))?;
+    let participants = repo.get_bill_participants(&bill).await?;
+
+    let Some(balance) = net_balances(&bill, snapshot, &participants)
+        .into_iter()
+        .find(|balance| balance.participant_id == participant_id)
+        .filter(|balance| balance.net_balance < Money::ZERO)
+    else {
+        return Ok(HttpResponse::Ok().json(Vec::<NudgeMessage>::new()));
+    };
+    let outstanding = balance.net_balance.abs();
+
+    let generate = Channel::ALL.map(|channel| {
+        let request = AiRequest::new(nudge_prompt(
+            channel,
+            &balance.name,
+            &bill.title,
+            outstanding,
+        ));
+        let state = &state;
+        async move {
+            let response =
+                get_cached_ai_response(&state.kv, &state.ai_breaker, &state.config, &request, true)
+                    .await?;
+            Ok::<_, ApiError>(NudgeMessage::new(channel, &response.text))
+        }
+    });
+
+    Ok(HttpResponse::Ok().json(try_join_all(generate).await?))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(prompt)
+        .service(assign_payer_from_ai)
+        .service(nudge_messages);
+}
@@ -0,0 +1,214 @@
+use std::{cell::RefCell, future::Future, pin::Pin, rc::Rc};
+
+use actix_http::Request;
+use actix_service::IntoServiceFactory;
+use actix_web::{
+    body::{self, BoxBody},
+    dev::{AppConfig, Payload, Service, ServiceFactory, ServiceResponse},
+    http::{header, Method, Uri},
+    post, web, Error, HttpRequest, HttpResponse, ResponseError,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{app, error::ApiError, i18n::t_with, state::AppState, storage::KvStore};
+
+/// Most operations one batch may contain.
+pub const MAX_BATCH_OPERATIONS: usize = 25;
+
+/// `true` when every operation succeeded and its writes were kept, `false`
+/// when they were rolled back.
+pub const COMMITTED_HEADER: &str = "X-Batch-Committed";
+
+/// Headers describing the outer request's own body, which each operation
+/// replaces with its own.
+const BODY_HEADERS: [header::HeaderName; 4] = [
+    header::CONTENT_LENGTH,
+    header::CONTENT_TYPE,
+    header::CONTENT_ENCODING,
+    header::TRANSFER_ENCODING,
+];
+
+#[derive(Debug, Deserialize)]
+pub struct BatchOperation {
+    pub method: String,
+    pub path: String,
+    pub body: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchResponse {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl BatchResponse {
+    async fn from_response(response: HttpResponse) -> Self {
+        let status = response.status().as_u16();
+        let bytes = body::to_bytes(response.into_body())
+            .await
+            .unwrap_or_default();
+        Self {
+            status,
+            body: parse_body(&bytes),
+        }
+    }
+}
+
+type ResponseFuture = Pin<Box<dyn Future<Output = Result<ServiceResponse<BoxBody>, Error>>>>;
+
+/// The application, as a function operations are dispatched to.
+type BatchApp = Rc<dyn Fn(Request) -> ResponseFuture>;
+
+thread_local! {
+    /// Each worker builds the application for batched operations once, on
+    /// its first batch, and reuses it for as long as the state is the same.
+    static BATCH_APP: RefCell<Option<(*const AppState, BatchApp)>> = const { RefCell::new(None) };
+}
+
+async fn batch_app(state: &web::Data<AppState>) -> Result<BatchApp, ApiError> {
+    let key: *const AppState = &***state;
+    let cached = BATCH_APP.with_borrow(|cached| {
+        cached
+            .as_ref()
+            .filter(|(built_for, _)| *built_for == key)
+            .map(|(_, app)| app.clone())
+    });
+    if let Some(app) = cached {
+        return Ok(app);
+    }
+
+    let service = app(state.clone())
+        .into_factory()
+        .new_service(AppConfig::default())
+        .await
+        .map_err(|()| ApiError::Internal("Could not start the batch service".to_string()))?;
+    let service = Rc::new(service);
+    let dispatch: BatchApp = Rc::new(move |req| {
+        let response = service.call(req);
+        Box::pin(async move { Ok(response.await?.map_into_boxed_body()) })
+    });
+    BATCH_APP.set(Some((key, dispatch.clone())));
+    Ok(dispatch)
+}
+
+#[derive(Deserialize)]
+struct BatchQuery {
+    #[serde(default)]
+    stop_on_error: bool,
+}
+
+/// Runs each operation against the full application, in order, with the
+/// caller's headers, so it is authenticated as if it had been sent on its own.
+/// The batch is atomic: if any operation fails, every write the batch made
+/// is rolled back and [`COMMITTED_HEADER`] is `false`. Queued notifications
+/// and other side effects outside the store are not undone.
+#[post("/batch")]
+async fn batch(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<BatchQuery>,
+    operations: web::Json<Vec<BatchOperation>>,
+) -> Result<HttpResponse, ApiError> {
+    let operations = operations.into_inner();
+    if operations.is_empty() {
+        return Err(ApiError::BadRequest(t_with(
+            "must_not_be_empty",
+            &[("field", &"operations")],
+        )));
+    }
+    if operations.len() > MAX_BATCH_OPERATIONS {
+        return Err(ApiError::BadRequest(format!(
+            "A batch can have at most {MAX_BATCH_OPERATIONS} operations"
+        )));
+    }
+    let dispatch = batch_app(&state).await?;
+
+    let run = async {
+        let mut responses = Vec::with_capacity(operations.len());
+        let mut failed = false;
+        for operation in &operations {
+            let response = match prepare(&req, operation) {
+                Ok(inner) => match dispatch(inner).await {
+                    Ok(res) => res.into_parts().1,
+                    Err(err) => err.error_response(),
+                },
+                Err(error) => error.error_response(),
+            };
+            let response = BatchResponse::from_response(response).await;
+
+            failed |= response.status >= 400;
+            responses.push(response);
+            if failed && query.stop_on_error {
+                break;
+            }
+        }
+        (responses, failed)
+    };
+    let ((responses, failed), journal) = KvStore::journaled(run).await;
+    if failed {
+        state.kv.roll_back(journal);
+    }
+
+    Ok(HttpResponse::Ok()
+        .insert_header((COMMITTED_HEADER, (!failed).to_string()))
+        .json(responses))
+}
+
+/// Validates an operation and builds the request for it, carrying the outer
+/// request's headers.
+fn prepare(outer: &HttpRequest, operation: &BatchOperation) -> Result<Request, ApiError> {
+    let method = Method::from_bytes(operation.method.to_ascii_uppercase().as_bytes())
+        .map_err(|_| ApiError::BadRequest(format!("Invalid method `{}`", operation.method)))?;
+    if !operation.path.starts_with('/') {
+        return Err(ApiError::BadRequest(
+            "`path` must start with `/`".to_string(),
+        ));
+    }
+    if operation.path == "/batch" || operation.path.starts_with("/batch?") {
+        return Err(ApiError::BadRequest("Batches cannot be nested".to_string()));
+    }
+    let uri: Uri = operation
+        .path
+        .parse()
+        .map_err(|_| ApiError::BadRequest(format!("Invalid path `{}`", operation.path)))?;
+
+    let body = match &operation.body {
+        Some(body) => {
+            serde_json::to_vec(body).map_err(|err| ApiError::Internal(err.to_string()))?
+        }
+        None => Vec::new(),
+    };
+    let body_len = body.len();
+    let mut request = Request::with_payload(Payload::from(body));
+    let head = request.head_mut();
+    head.method = method;
+    head.uri = uri;
+    for (name, value) in outer.headers() {
+        if !BODY_HEADERS.contains(name) {
+            head.headers.append(name.clone(), value.clone());
+        }
+    }
+    if operation.body.is_some() {
+        head.headers.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("application/json"),
+        );
+        head.headers
+            .insert(header::CONTENT_LENGTH, header::HeaderValue::from(body_len));
+    }
+    Ok(request)
+}
+
+/// JSON bodies are embedded as-is, anything else as a string.
+fn parse_body(bytes: &[u8]) -> Value {
+    if bytes.is_empty() {
+        return Value::Null;
+    }
+    serde_json::from_slice(bytes)
+        .unwrap_or_else(|_| json!(String::from_utf8_lossy(bytes).into_owned()))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(batch);
+}
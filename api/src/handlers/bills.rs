@@ -0,0 +1,1234 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, HashMap, HashSet},
+};
+
+use actix_web::{delete, get, http::header, patch, post, put, web, HttpRequest, HttpResponse};
+use chrono::Utc;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use url::Url;
+use uuid::Uuid;
+
+use super::{
+    admin::require_admin,
+    ensure_editable, ensure_not_archived, ensure_participant, load_bill,
+    util::{prefer_return, ReturnPreference, PREFERENCE_APPLIED},
+};
+use crate::{
+    analytics::{
+        anomaly::detect_anomalies,
+        monthly::{self, MIN_SUMMARY_YEAR},
+        turn::{suggest_payer, DEFAULT_LOOKBACK},
+    },
+    auth::{authenticate, require_user},
+    data::disclaimers::{disclaimer, jurisdictions},
+    duplicates::find_duplicates,
+    error::ApiError,
+    exchange::rate_into_bill_currency,
+    i18n::{t, t_with},
+    import::csv_items::parse_line_items,
+    models::{
+        Bill, BillStatus, BillTag, CurrencyCode, ExchangeRate, LineItem, MergeAudit, Money,
+        Participant, Payment, MAX_LINE_ITEM_TOTAL,
+    },
+    state::AppState,
+    validation::validate,
+};
+
+#[derive(Deserialize)]
+struct CreateBillBody {
+    title: String,
+    notes: Option<String>,
+    base_currency: Option<CurrencyCode>,
+}
+
+#[post("/bills")]
+async fn create_bill(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<CreateBillBody>,
+) -> Result<HttpResponse, ApiError> {
+    let body = body.into_inner();
+    if body.title.trim().is_empty() {
+        return Err(ApiError::BadRequest(t_with(
+            "must_not_be_empty",
+            &[("field", &"title")],
+        )));
+    }
+
+    let mut bill = Bill::new(body.title.trim(), body.notes);
+    if let Some(currency) = body.base_currency {
+        bill.base_currency = currency;
+    }
+    bill.creator_id = authenticate(&req, &state.config)?.map(|user| user.id);
+    state.repo_for(&req).put_bill(&bill).await?;
+
+    let preference = prefer_return(&req);
+    let mut response = HttpResponse::Created();
+    if let Some(applied) = preference.applied() {
+        response.insert_header((PREFERENCE_APPLIED, applied));
+    }
+    if preference == ReturnPreference::Minimal {
+        return Ok(response.json(json!({ "id": bill.id, "status": 201 })));
+    }
+    Ok(response.json(bill))
+}
+
+#[derive(Deserialize)]
+struct UpdateBillBody {
+    title: Option<String>,
+    /// An empty string clears the notes.
+    notes: Option<String>,
+    discount: Option<Money>,
+    status: Option<BillStatus>,
+}
+
+/// Partially updates a bill. Responds with `{ id, status }` unless the client
+/// sends `Prefer: return=representation`.
+#[patch("/bills/{id}")]
+async fn update_bill(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    body: web::Json<UpdateBillBody>,
+) -> Result<HttpResponse, ApiError> {
+    let body = body.into_inner();
+    let repo = state.repo_for(&req);
+    let mut bill = load_bill(&repo, id.into_inner()).await?;
+    ensure_not_archived(&bill)?;
+
+    if let Some(title) = body.title {
+        if title.trim().is_empty() {
+            return Err(ApiError::BadRequest(t_with(
+                "must_not_be_empty",
+                &[("field", &"title")],
+            )));
+        }
+        bill.title = title.trim().to_string();
+    }
+    if let Some(notes) = body.notes {
+        bill.notes = (!notes.trim().is_empty()).then_some(notes);
+    }
+    if let Some(discount) = body.discount {
+        if discount.is_negative() {
+            return Err(ApiError::BadRequest(
+                "`discount` must not be negative".to_string(),
+            ));
+        }
+        if discount > bill.subtotal() {
+            return Err(ApiError::BadRequest(
+                "`discount` must not exceed the bill subtotal".to_string(),
+            ));
+        }
+        bill.discount = discount;
+    }
+    if let Some(status) = body.status {
+        if status == BillStatus::Archived {
+            return Err(ApiError::BadRequest(
+                "Use `POST /bills/:id/archive` to archive a bill".to_string(),
+            ));
+        }
+        bill.status = status;
+    }
+    bill.touch();
+    repo.put_bill(&bill).await?;
+
+    let preference = prefer_return(&req);
+    let mut response = HttpResponse::Ok();
+    if let Some(applied) = preference.applied() {
+        response.insert_header((PREFERENCE_APPLIED, applied));
+    }
+    if preference == ReturnPreference::Representation {
+        return Ok(response.json(bill));
+    }
+    Ok(response.json(json!({ "id": bill.id, "status": 200 })))
+}
+
+const DEFAULT_PAGE_SIZE: usize = 20;
+const MAX_PAGE_SIZE: usize = 100;
+
+#[derive(Deserialize)]
+struct ListBillsQuery {
+    cursor: Option<Uuid>,
+    #[serde(default = "default_page_size")]
+    limit: usize,
+    /// `me`, or another user's id (admin only).
+    created_by: Option<String>,
+    /// Only `me`: bills the caller takes part in but did not create.
+    participant: Option<String>,
+    #[serde(default)]
+    include_archived: bool,
+    /// Name of a tag the bills must carry.
+    tag: Option<String>,
+}
+
+/// The filters of `GET /bills`, resolved against the caller.
+struct BillFilter {
+    creator: Option<Uuid>,
+    participant: Option<Uuid>,
+    include_archived: bool,
+    /// Bills carrying the requested tag, from the tag's index.
+    tagged: Option<HashSet<Uuid>>,
+}
+
+impl BillFilter {
+    async fn from_query(
+        req: &HttpRequest,
+        state: &AppState,
+        query: &ListBillsQuery,
+    ) -> Result<Self, ApiError> {
+        let creator = match query.created_by.as_deref() {
+            None => None,
+            Some("me") => Some(require_user(req, &state.config)?.id),
+            Some(other) => {
+                let id = other.parse::<Uuid>().map_err(|_| {
+                    ApiError::BadRequest("`created_by` must be `me` or a user id".to_string())
+                })?;
+                require_admin(req, &state.config)?;
+                Some(id)
+            }
+        };
+        let participant = match query.participant.as_deref() {
+            None => None,
+            Some("me") => Some(require_user(req, &state.config)?.id),
+            Some(_) => {
+                return Err(ApiError::BadRequest(
+                    "`participant` only supports `me`".to_string(),
+                ))
+            }
+        };
+        let tagged = match query.tag.as_deref() {
+            None => None,
+            Some(tag) => {
+                let name = BillTag::normalise_name(tag).map_err(ApiError::BadRequest)?;
+                let ids = state.repo().tagged_bill_ids(&name).await?;
+                Some(ids.into_iter().collect())
+            }
+        };
+        Ok(Self {
+            creator,
+            participant,
+            include_archived: query.include_archived,
+            tagged,
+        })
+    }
+
+    fn matches(&self, bill: &Bill) -> bool {
+        (self.include_archived || !bill.is_archived())
+            && self
+                .creator
+                .is_none_or(|creator| bill.creator_id == Some(creator))
+            && self.participant.is_none_or(|participant| {
+                bill.has_participant(participant) && bill.creator_id != Some(participant)
+            })
+            && self
+                .tagged
+                .as_ref()
+                .is_none_or(|tagged| tagged.contains(&bill.id))
+    }
+
+    /// The filters as query parameters for pagination links.
+    fn query_string(query: &ListBillsQuery) -> String {
+        [
+            ("created_by", &query.created_by),
+            ("participant", &query.participant),
+            ("tag", &query.tag),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.as_ref().map(|value| format!("&{name}={value}")))
+        .chain(
+            query
+                .include_archived
+                .then(|| "&include_archived=true".to_string()),
+        )
+        .collect()
+    }
+}
+
+#[derive(Deserialize)]
+struct SuggestedPayerQuery {
+    /// How many of the group's most recent other bills to look at.
+    #[serde(default = "default_lookback")]
+    lookback: usize,
+}
+
+fn default_lookback() -> usize {
+    DEFAULT_LOOKBACK
+}
+
+/// Whose turn it is to pay: the participant who was the payer least often on
+/// the most recent other bills they share with this bill's participants.
+#[get("/bills/{id}/suggested-payer")]
+async fn suggested_payer(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    query: web::Query<SuggestedPayerQuery>,
+) -> Result<HttpResponse, ApiError> {
+    if !(1..=MAX_PAGE_SIZE).contains(&query.lookback) {
+        return Err(ApiError::BadRequest(format!(
+            "`lookback` must be between 1 and {MAX_PAGE_SIZE}"
+        )));
+    }
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let participants = repo.get_bill_participants(&bill).await?;
+    if participants.is_empty() {
+        return Err(ApiError::InsufficientData(t("no_participants")));
+    }
+
+    let group: HashSet<Uuid> = participants
+        .iter()
+        .map(|participant| participant.id)
+        .collect();
+    let mut recent = repo
+        .list_bills(None, usize::MAX, |other| {
+            other.id != bill.id
+                && other
+                    .participants
+                    .iter()
+                    .any(|member| group.contains(&member.participant_id))
+        })
+        .await?
+        .items;
+    recent.sort_by_key(|other| Reverse(other.created_at));
+    recent.truncate(query.lookback);
+
+    let suggestion = suggest_payer(&participants, &recent)
+        .ok_or_else(|| ApiError::InsufficientData(t("no_participants")))?;
+    Ok(HttpResponse::Ok().json(suggestion))
+}
+
+#[derive(Deserialize)]
+struct OverdueQuery {
+    /// Only this participant's overdue shares.
+    participant_id: Option<Uuid>,
+}
+
+#[derive(Serialize)]
+struct OverdueParticipant {
+    participant_id: Uuid,
+    name: String,
+    amount_owed: Money,
+    days_overdue: u32,
+}
+
+#[derive(Serialize)]
+struct OverdueBillSummary {
+    bill_id: Uuid,
+    title: String,
+    /// Most days overdue first.
+    overdue_participants: Vec<OverdueParticipant>,
+}
+
+/// The caller's bills with shares still unpaid past their due date, most
+/// overdue first.
+#[get("/bills/overdue")]
+async fn overdue_bills(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<OverdueQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let user = require_user(&req, &state.config)?;
+    let repo = state.repo();
+    let bills = repo
+        .list_bills(None, usize::MAX, |bill| {
+            !bill.is_archived() && bill.creator_id == Some(user.id)
+        })
+        .await?
+        .items;
+
+    let today = Utc::now().date_naive();
+    let default_days = state.config.default_payment_days;
+    let mut summaries = Vec::new();
+    for bill in &bills {
+        let overdue: Vec<(Uuid, u32)> = bill
+            .participant_ids()
+            .into_iter()
+            .filter(|id| *id != user.id && query.participant_id.is_none_or(|only| only == *id))
+            .filter_map(|id| Some((id, bill.days_overdue(id, default_days, today)?)))
+            .collect();
+        if overdue.is_empty() {
+            continue;
+        }
+
+        let participants = repo.get_bill_participants(bill).await?;
+        let mut overdue_participants: Vec<OverdueParticipant> = overdue
+            .into_iter()
+            .map(|(participant_id, days_overdue)| OverdueParticipant {
+                participant_id,
+                name: participants
+                    .iter()
+                    .find(|participant| participant.id == participant_id)
+                    .map(|participant| participant.name.clone())
+                    .unwrap_or_default(),
+                amount_owed: bill.outstanding(participant_id).unwrap_or(Money::ZERO),
+                days_overdue,
+            })
+            .collect();
+        overdue_participants.sort_by_key(|participant| Reverse(participant.days_overdue));
+        summaries.push(OverdueBillSummary {
+            bill_id: bill.id,
+            title: bill.title.clone(),
+            overdue_participants,
+        });
+    }
+    summaries.sort_by_key(|summary| Reverse(summary.overdue_participants[0].days_overdue));
+
+    Ok(HttpResponse::Ok().json(summaries))
+}
+
+#[derive(Serialize)]
+struct SharedBill<'a> {
+    #[serde(flatten)]
+    bill: &'a Bill,
+    /// What the caller still owes on the bill.
+    outstanding_amount: Money,
+    creator_name: Option<String>,
+}
+
+/// Bills someone else created that the caller still owes on, largest
+/// balance first.
+#[get("/bills/shared-with-me")]
+async fn shared_with_me(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+) -> Result<HttpResponse, ApiError> {
+    let user = require_user(&req, &state.config)?;
+    let repo = state.repo();
+
+    let mut owing = Vec::new();
+    for bill_id in repo.participant_bill_ids(user.id).await? {
+        let Some(bill) = repo.get_bill(bill_id).await? else {
+            continue;
+        };
+        if bill.is_archived() || bill.creator_id == Some(user.id) {
+            continue;
+        }
+        match bill.outstanding(user.id) {
+            Some(outstanding) if outstanding > Money::ZERO => owing.push((bill, outstanding)),
+            _ => {}
+        }
+    }
+    owing.sort_by_key(|(_, outstanding)| Reverse(*outstanding));
+
+    let mut creator_names = HashMap::new();
+    for creator_id in owing.iter().filter_map(|(bill, _)| bill.creator_id) {
+        if let Entry::Vacant(entry) = creator_names.entry(creator_id) {
+            entry.insert(
+                repo.get_participant(creator_id)
+                    .await?
+                    .map(|participant| participant.name),
+            );
+        }
+    }
+    let shared: Vec<SharedBill> = owing
+        .iter()
+        .map(|(bill, outstanding)| SharedBill {
+            bill,
+            outstanding_amount: *outstanding,
+            creator_name: bill
+                .creator_id
+                .and_then(|id| creator_names.get(&id).cloned().flatten()),
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(shared))
+}
+
+/// The caller's bills created in one calendar month, those they are on or
+/// created, with what they spent, paid and still owe in each currency.
+#[get("/bills/month/{year}/{month}")]
+async fn month_summary(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<(i32, u32)>,
+) -> Result<HttpResponse, ApiError> {
+    let (year, month) = path.into_inner();
+    if year < MIN_SUMMARY_YEAR {
+        return Err(ApiError::BadRequest(format!(
+            "`year` must be {MIN_SUMMARY_YEAR} or later"
+        )));
+    }
+    if !(1..=12).contains(&month) {
+        return Err(ApiError::BadRequest(
+            "`month` must be between 1 and 12".to_string(),
+        ));
+    }
+    let user = require_user(&req, &state.config)?;
+    let repo = state.repo();
+
+    // Bills are not indexed by creator, so this scans every bill.
+    let bills = repo
+        .list_bills(None, usize::MAX, |bill| monthly::is_own_bill(bill, user.id))
+        .await?
+        .items;
+
+    Ok(HttpResponse::Ok().json(monthly::month_summary(user.id, year, month, &bills)))
+}
+
+fn default_page_size() -> usize {
+    DEFAULT_PAGE_SIZE
+}
+
+#[get("/bills")]
+async fn list_bills(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<ListBillsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    if !(1..=MAX_PAGE_SIZE).contains(&query.limit) {
+        return Err(ApiError::BadRequest(format!(
+            "`limit` must be between 1 and {MAX_PAGE_SIZE}"
+        )));
+    }
+
+    let filter = BillFilter::from_query(&req, &state, &query).await?;
+    let page = state
+        .repo()
+        .list_bills(query.cursor, query.limit, |bill| filter.matches(bill))
+        .await?;
+
+    // RFC 8288 links so generic clients can page without reading the body.
+    let filters = BillFilter::query_string(&query);
+    let mut links = vec![format!(
+        "<{}?limit={}{filters}>; rel=\"first\"",
+        req.path(),
+        query.limit
+    )];
+    if let Some(next) = page.next_cursor {
+        links.push(format!(
+            "<{}?cursor={next}&limit={}{filters}>; rel=\"next\"",
+            req.path(),
+            query.limit
+        ));
+    }
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::LINK, links.join(", ")))
+        .json(json!({
+            "bills": page.items,
+            "next_cursor": page.next_cursor,
+        })))
+}
+
+#[get("/bills/{id}")]
+async fn get_bill(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let bill = load_bill(&state.repo(), id.into_inner()).await?;
+    let etag = format!("\"{}\"", bill.etag());
+
+    if if_none_match(&req, &etag) {
+        return Ok(HttpResponse::NotModified()
+            .insert_header((header::ETAG, etag))
+            .finish());
+    }
+    Ok(HttpResponse::Ok()
+        .insert_header((header::ETAG, etag))
+        .json(bill))
+}
+
+/// What changed on each save of the bill, oldest first.
+#[get("/bills/{id}/changelog")]
+async fn get_changelog(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(repo.get_changelog(bill.id).await?))
+}
+
+/// Whether the request's `If-None-Match` lists `etag` (weak comparison) or `*`.
+fn if_none_match(req: &HttpRequest, etag: &str) -> bool {
+    req.headers()
+        .get_all(header::IF_NONE_MATCH)
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+}
+
+/// Fails unless the request's `If-Match` is absent, `*` or lists `etag`
+/// (strong comparison).
+fn check_if_match(req: &HttpRequest, etag: &str) -> Result<(), ApiError> {
+    let mut candidates = req
+        .headers()
+        .get_all(header::IF_MATCH)
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .peekable();
+    if candidates.peek().is_none()
+        || candidates.any(|candidate| candidate == "*" || candidate == etag)
+    {
+        Ok(())
+    } else {
+        Err(ApiError::PreconditionFailed(
+            "The bill has changed since it was fetched; reload it and try again".to_string(),
+        ))
+    }
+}
+
+#[derive(Deserialize)]
+struct AddParticipantBody {
+    name: String,
+    email: Option<String>,
+    phone: Option<String>,
+    venmo_handle: Option<String>,
+    paypal_email: Option<String>,
+    cashapp_tag: Option<String>,
+    avatar_url: Option<Url>,
+}
+
+#[post("/bills/{id}/participants")]
+async fn add_participant(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    body: web::Json<AddParticipantBody>,
+) -> Result<HttpResponse, ApiError> {
+    let body = body.into_inner();
+    if body.name.trim().is_empty() {
+        return Err(ApiError::BadRequest(t_with(
+            "must_not_be_empty",
+            &[("field", &"name")],
+        )));
+    }
+    if body
+        .avatar_url
+        .as_ref()
+        .is_some_and(|url| url.scheme() != "https")
+    {
+        return Err(ApiError::BadRequest(
+            "`avatar_url` must be an HTTPS URL".to_string(),
+        ));
+    }
+
+    let repo = state.repo_for(&req);
+    let mut bill = load_bill(&repo, id.into_inner()).await?;
+    ensure_not_archived(&bill)?;
+
+    let mut participant = Participant::new(body.name.trim(), body.email);
+    participant.phone = body.phone;
+    participant.venmo_handle = body.venmo_handle;
+    participant.paypal_email = body.paypal_email;
+    participant.cashapp_tag = body.cashapp_tag;
+    participant.avatar_url = body.avatar_url;
+
+    repo.put_participant(&participant).await?;
+
+    bill.add_participant(participant.id);
+    bill.touch();
+    repo.put_bill(&bill).await?;
+
+    Ok(HttpResponse::Created().json(participant))
+}
+
+/// Confirms the participant is on the bill. With authentication configured
+/// only the participant themselves can accept. Accepting again keeps the
+/// original time.
+#[post("/bills/{id}/participants/{participant_id}/accept")]
+async fn accept_participation(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, participant_id) = path.into_inner();
+    if state.config.jwt_secret.is_some() && require_user(&req, &state.config)?.id != participant_id
+    {
+        return Err(ApiError::Forbidden(
+            "Participants can only accept for themselves".to_string(),
+        ));
+    }
+    let repo = state.repo_for(&req);
+    let mut bill = load_bill(&repo, id).await?;
+    ensure_not_archived(&bill)?;
+    ensure_participant(&bill, participant_id)?;
+
+    let now = Utc::now();
+    let accepted_at = bill.accept_participant(participant_id, now);
+    if accepted_at == Some(now) {
+        bill.touch();
+        repo.put_bill(&bill).await?;
+    }
+
+    Ok(HttpResponse::Ok().json(json!({
+        "participant_id": participant_id,
+        "accepted_at": accepted_at,
+    })))
+}
+
+#[derive(Deserialize)]
+struct ExemptBody {
+    reason: String,
+}
+
+/// Exempts the participant from the bill's splits: they owe nothing, and
+/// what they would have owed is shared by everyone else. Exempting them
+/// again replaces the reason.
+#[post("/bills/{id}/participants/{participant_id}/exempt")]
+async fn exempt_participant(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    body: web::Json<ExemptBody>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, participant_id) = path.into_inner();
+    let reason = body.into_inner().reason.trim().to_string();
+    if reason.is_empty() {
+        return Err(ApiError::BadRequest(
+            "`reason` must not be empty".to_string(),
+        ));
+    }
+    let repo = state.repo_for(&req);
+    let mut bill = load_bill(&repo, id).await?;
+    ensure_editable(&bill)?;
+    ensure_participant(&bill, participant_id)?;
+
+    bill.exempt_participant(participant_id, reason, Utc::now());
+    bill.touch();
+    repo.put_bill(&bill).await?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "participant_id": participant_id,
+        "exemption": bill.exemption(participant_id),
+    })))
+}
+
+#[delete("/bills/{id}/participants/{participant_id}/exempt")]
+async fn remove_exemption(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, participant_id) = path.into_inner();
+    let repo = state.repo_for(&req);
+    let mut bill = load_bill(&repo, id).await?;
+    ensure_editable(&bill)?;
+    ensure_participant(&bill, participant_id)?;
+
+    if !bill.remove_exemption(participant_id) {
+        return Err(ApiError::NotFound(format!(
+            "Participant {participant_id} is not exempt"
+        )));
+    }
+    bill.touch();
+    repo.put_bill(&bill).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[derive(Deserialize)]
+struct SetPayerBody {
+    participant_id: Uuid,
+}
+
+#[put("/bills/{id}/payer")]
+async fn set_payer(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    body: web::Json<SetPayerBody>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo_for(&req);
+    let mut bill = load_bill(&repo, id.into_inner()).await?;
+    ensure_not_archived(&bill)?;
+    if !bill.has_participant(body.participant_id) {
+        return Err(ApiError::BadRequest(t_with(
+            "participant_not_on_bill",
+            &[("id", &body.participant_id)],
+        )));
+    }
+
+    bill.payer_id = Some(body.participant_id);
+    bill.touch();
+    repo.put_bill(&bill).await?;
+
+    Ok(HttpResponse::Ok().json(bill))
+}
+
+/// Everyone who has been recorded as the bill's payer, in the order the
+/// payer was changed, to settle who actually paid.
+#[get("/bills/{id}/payer-history")]
+async fn get_payer_history(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(repo.get_payer_history(bill.id).await?))
+}
+
+/// A bill participant alongside where they stand on the latest split.
+#[derive(Debug, Serialize)]
+pub struct BillParticipantView {
+    pub participant_id: Uuid,
+    pub name: String,
+    pub email: Option<String>,
+    /// Share from the latest split, `None` until a split has been computed.
+    pub share_amount: Option<Money>,
+    /// Whether recorded payments cover the share.
+    pub has_paid: bool,
+    /// Total paid so far, `None` if nothing has been paid.
+    pub payment_amount: Option<Money>,
+}
+
+#[get("/bills/{id}/participants")]
+async fn list_participants(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let participants = repo.get_bill_participants(&bill).await?;
+
+    let views: Vec<BillParticipantView> = participants
+        .into_iter()
+        .map(|participant| {
+            let share_amount = bill.latest_split().and_then(|snapshot| {
+                snapshot
+                    .shares
+                    .iter()
+                    .find(|share| share.participant_id == participant.id)
+                    .map(|share| share.amount_owed)
+            });
+            let payments: Vec<Money> = bill
+                .payments_by(participant.id)
+                .map(Payment::base_amount)
+                .collect();
+            let payment_amount = (!payments.is_empty()).then(|| payments.iter().sum::<Money>());
+            let has_paid =
+                share_amount.is_some_and(|share| payment_amount.unwrap_or(Money::ZERO) >= share);
+
+            BillParticipantView {
+                participant_id: participant.id,
+                name: participant.name,
+                email: participant.email,
+                share_amount,
+                has_paid,
+                payment_amount,
+            }
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(views))
+}
+
+#[derive(Deserialize)]
+struct AddLineItemBody {
+    description: String,
+    #[serde(default = "default_quantity")]
+    quantity: u32,
+    unit_price: Money,
+    #[serde(default)]
+    participant_ids: Vec<Uuid>,
+    category: Option<String>,
+    tax_rate: Option<Decimal>,
+    /// Currency `unit_price` is in; defaults to the bill's base currency.
+    currency: Option<CurrencyCode>,
+}
+
+fn default_quantity() -> u32 {
+    1
+}
+
+#[post("/bills/{id}/line-items")]
+async fn add_line_item(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    body: web::Json<AddLineItemBody>,
+) -> Result<HttpResponse, ApiError> {
+    let body = body.into_inner();
+    if body.description.trim().is_empty() {
+        return Err(ApiError::BadRequest(t_with(
+            "must_not_be_empty",
+            &[("field", &"description")],
+        )));
+    }
+    if body.quantity == 0 {
+        return Err(ApiError::BadRequest(
+            "`quantity` must be at least 1".to_string(),
+        ));
+    }
+    if body.unit_price.is_negative() {
+        return Err(ApiError::BadRequest(
+            "`unit_price` must not be negative".to_string(),
+        ));
+    }
+    if body
+        .tax_rate
+        .is_some_and(|rate| rate.is_sign_negative() || rate > Decimal::ONE)
+    {
+        return Err(ApiError::BadRequest(
+            "`tax_rate` must be between 0 and 1".to_string(),
+        ));
+    }
+
+    let repo = state.repo_for(&req);
+    let mut bill = load_bill(&repo, id.into_inner()).await?;
+    ensure_not_archived(&bill)?;
+
+    if let Some(unknown) = body
+        .participant_ids
+        .iter()
+        .find(|participant_id| !bill.has_participant(**participant_id))
+    {
+        return Err(ApiError::BadRequest(t_with(
+            "participant_not_on_bill",
+            &[("id", unknown)],
+        )));
+    }
+
+    let mut item = LineItem::new(body.description.trim(), body.quantity, body.unit_price);
+    item.set_participants(body.participant_ids);
+    item.category = body
+        .category
+        .map(|category| category.trim().to_string())
+        .filter(|category| !category.is_empty());
+    item.tax_rate = body.tax_rate;
+    if let Some(currency) = body
+        .currency
+        .filter(|currency| *currency != bill.base_currency)
+    {
+        let rate = rate_into_bill_currency(&state.config, &bill, &currency).await?;
+        item = item.converted(currency, rate.rate);
+        bill.exchange_rates_used.push(rate);
+    }
+    if !item.is_within_limit() {
+        return Err(ApiError::BadRequest(format!(
+            "`quantity` times `unit_price` must not exceed {MAX_LINE_ITEM_TOTAL}"
+        )));
+    }
+
+    let item = bill.add_line_item(item).clone();
+    bill.touch();
+    repo.put_bill(&bill).await?;
+
+    Ok(HttpResponse::Created().json(item))
+}
+
+/// Adds every valid row of a CSV body as a line item. Rows that fail
+/// validation are skipped and reported; send the bill's `ETag` as
+/// `If-Match` to make sure nobody changed the bill in the meantime.
+#[post("/bills/{id}/line-items/import/csv")]
+async fn import_line_items_csv(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApiError> {
+    let csv = std::str::from_utf8(&body)
+        .map_err(|_| ApiError::BadRequest("The CSV must be UTF-8".to_string()))?;
+    let parsed = parse_line_items(csv).map_err(|err| ApiError::BadRequest(err.to_string()))?;
+
+    let repo = state.repo_for(&req);
+    let mut bill = load_bill(&repo, id.into_inner()).await?;
+    ensure_not_archived(&bill)?;
+    check_if_match(&req, &format!("\"{}\"", bill.etag()))?;
+
+    let result = parsed.result();
+    if !parsed.items.is_empty() {
+        for item in parsed.items {
+            bill.add_line_item(item);
+        }
+        bill.touch();
+        repo.put_bill(&bill).await?;
+    }
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::ETAG, format!("\"{}\"", bill.etag())))
+        .json(result))
+}
+
+#[derive(Deserialize)]
+struct ManualRateBody {
+    from: CurrencyCode,
+    to: CurrencyCode,
+    rate: Decimal,
+}
+
+/// Overrides the live rates with hand-picked ones, e.g. month-end rates. Line
+/// items and payments already in a `from` currency are repriced; responds
+/// with the bill's updated `exchange_rates_used`.
+#[post("/bills/{id}/set-currency-conversion-rates")]
+async fn set_conversion_rates(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    body: web::Json<Vec<ManualRateBody>>,
+) -> Result<HttpResponse, ApiError> {
+    let rates = body.into_inner();
+    if rates.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one rate is required".to_string(),
+        ));
+    }
+    let repo = state.repo_for(&req);
+    let mut bill = load_bill(&repo, id.into_inner()).await?;
+    ensure_editable(&bill)?;
+
+    for rate in &rates {
+        if rate.rate <= Decimal::ZERO {
+            return Err(ApiError::BadRequest(format!(
+                "The {} to {} rate must be positive",
+                rate.from, rate.to
+            )));
+        }
+        if rate.to != bill.base_currency || rate.from == rate.to {
+            return Err(ApiError::BadRequest(format!(
+                "Rates must convert another currency into the bill's currency, {}",
+                bill.base_currency
+            )));
+        }
+    }
+    for rate in rates {
+        bill.set_manual_rate(ExchangeRate::manual(rate.from, rate.to, rate.rate));
+    }
+    bill.touch();
+    repo.put_bill(&bill).await?;
+
+    Ok(HttpResponse::Ok().json(bill.exchange_rates_used))
+}
+
+/// Merges line item `item_id` into `other_id`: the source item is removed and
+/// the target replaced by the combination of both. Both originals are kept in
+/// the bill's merge audit.
+#[post("/bills/{id}/line-items/{item_id}/merge-into/{other_id}")]
+async fn merge_line_items(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, item_id, other_id) = path.into_inner();
+    if item_id == other_id {
+        return Err(ApiError::BadRequest(
+            "A line item cannot be merged into itself".to_string(),
+        ));
+    }
+    let repo = state.repo_for(&req);
+    let mut bill = load_bill(&repo, id).await?;
+    ensure_editable(&bill)?;
+
+    let line_item_not_found =
+        |item_id: Uuid| ApiError::NotFound(format!("Line item {item_id} not found"));
+    let source_index = bill
+        .line_items
+        .iter()
+        .position(|item| item.id == item_id)
+        .ok_or_else(|| line_item_not_found(item_id))?;
+    let target_index = bill
+        .line_items
+        .iter()
+        .position(|item| item.id == other_id)
+        .ok_or_else(|| line_item_not_found(other_id))?;
+
+    let target = bill.line_items[target_index].clone();
+    let merged = target.merged_with(&bill.line_items[source_index]);
+    bill.line_items[target_index] = merged.clone();
+    let source = bill.line_items.remove(source_index);
+    bill.merge_audit.push(MergeAudit::new(source, target));
+    bill.touch();
+    repo.put_bill(&bill).await?;
+
+    Ok(HttpResponse::Ok().json(merged))
+}
+
+#[derive(Deserialize)]
+struct LineItemPosition {
+    line_item_id: Uuid,
+    position: u32,
+}
+
+#[derive(Deserialize)]
+struct ReorderBody {
+    reorder: Vec<LineItemPosition>,
+}
+
+/// Sets the display position of every line item; responds with the line
+/// items in their new order.
+#[post("/bills/{id}/line-items/reorder")]
+async fn reorder_line_items(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    body: web::Json<ReorderBody>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo_for(&req);
+    let mut bill = load_bill(&repo, id.into_inner()).await?;
+    ensure_editable(&bill)?;
+
+    let mut positions = HashMap::new();
+    let mut taken = HashSet::new();
+    for entry in &body.reorder {
+        if !bill
+            .line_items
+            .iter()
+            .any(|item| item.id == entry.line_item_id)
+        {
+            return Err(ApiError::BadRequest(format!(
+                "Line item {} is not on this bill",
+                entry.line_item_id
+            )));
+        }
+        if positions
+            .insert(entry.line_item_id, entry.position)
+            .is_some()
+        {
+            return Err(ApiError::BadRequest(format!(
+                "Line item {} is listed more than once",
+                entry.line_item_id
+            )));
+        }
+        if !taken.insert(entry.position) {
+            return Err(ApiError::BadRequest(format!(
+                "Position {} is given to more than one line item",
+                entry.position
+            )));
+        }
+    }
+    if let Some(missing) = bill
+        .line_items
+        .iter()
+        .find(|item| !positions.contains_key(&item.id))
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Line item {} needs a position",
+            missing.id
+        )));
+    }
+
+    bill.reorder_line_items(&positions);
+    bill.touch();
+    repo.put_bill(&bill).await?;
+
+    Ok(HttpResponse::Ok().json(bill.line_items))
+}
+
+/// Hides a bill from `GET /bills` and makes it read-only.
+#[post("/bills/{id}/archive")]
+async fn archive_bill(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo_for(&req);
+    let mut bill = load_bill(&repo, id.into_inner()).await?;
+    if !bill.is_archived() {
+        bill.archive();
+        bill.touch();
+        repo.put_bill(&bill).await?;
+    }
+    Ok(HttpResponse::Ok().json(bill))
+}
+
+/// Puts an archived bill back in the status it had before.
+#[post("/bills/{id}/unarchive")]
+async fn unarchive_bill(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo_for(&req);
+    let mut bill = load_bill(&repo, id.into_inner()).await?;
+    if bill.is_archived() {
+        bill.unarchive();
+        bill.touch();
+        repo.put_bill(&bill).await?;
+    }
+    Ok(HttpResponse::Ok().json(bill))
+}
+
+/// Pairs of line items that look like the same thing entered twice.
+#[post("/bills/{id}/duplicate-check")]
+async fn duplicate_check(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let bill = load_bill(&state.repo(), id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(find_duplicates(&bill)))
+}
+
+/// Line items with unusual prices or quantities, or fees without a category.
+#[get("/bills/{id}/anomaly-detection")]
+async fn anomaly_detection(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let bill = load_bill(&state.repo(), id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(detect_anomalies(&bill)))
+}
+
+/// Every consistency problem with the bill, errors first, so they can be
+/// fixed together before it is locked. An empty list means the bill is
+/// ready.
+#[get("/bills/{id}/validation-errors")]
+async fn validation_errors(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let bill = load_bill(&state.repo(), id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(validate(&bill)))
+}
+
+#[derive(Deserialize)]
+struct DisclaimerQuery {
+    jurisdiction: String,
+}
+
+/// Footer text stating what the bill is, and that it is not a formal
+/// invoice, in the wording of the given jurisdiction.
+#[get("/bills/{id}/legal-disclaimer")]
+async fn legal_disclaimer(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    query: web::Query<DisclaimerQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let bill = load_bill(&state.repo(), id.into_inner()).await?;
+    let text = disclaimer(&bill, &query.jurisdiction).ok_or_else(|| {
+        ApiError::BadRequest(format!(
+            "Unknown jurisdiction `{}`; expected one of {}",
+            query.jurisdiction,
+            jurisdictions().collect::<Vec<_>>().join(", ")
+        ))
+    })?;
+    Ok(HttpResponse::Ok().json(json!({
+        "jurisdiction": query.jurisdiction.to_ascii_uppercase(),
+        "disclaimer": text
+    })))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(create_bill)
+        .service(list_bills)
+        // Before `get_bill`, which would otherwise take these as ids.
+        .service(overdue_bills)
+        .service(shared_with_me)
+        .service(month_summary)
+        .service(get_bill)
+        .service(get_changelog)
+        .service(update_bill)
+        .service(set_payer)
+        .service(get_payer_history)
+        .service(suggested_payer)
+        .service(add_participant)
+        .service(list_participants)
+        .service(accept_participation)
+        .service(exempt_participant)
+        .service(remove_exemption)
+        .service(add_line_item)
+        .service(import_line_items_csv)
+        .service(set_conversion_rates)
+        .service(merge_line_items)
+        .service(reorder_line_items)
+        .service(duplicate_check)
+        .service(anomaly_detection)
+        .service(validation_errors)
+        .service(legal_disclaimer)
+        .service(archive_bill)
+        .service(unarchive_bill);
+}
@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+use actix_web::{get, web, Error, HttpResponse, Responder};
+use futures::{future::ok, stream::once};
+
+#[get("/hello/{name}")]
+async fn greet(name: web::Path<String>) -> impl Responder {
+    format!("Hello {name}!")
+}
+
+#[get("/stream-delay")]
+async fn stream_delay() -> HttpResponse {
+    let tick_duration = Duration::from_millis(10);
+
+    let body = async_stream::stream! {
+        for i in 0..1000 {
+            actix_web::rt::time::sleep(tick_duration).await;
+            yield Ok::<_, Error>(web::Bytes::from(format!("data: {}\n\n", i)));
+        }
+    };
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .streaming(body)
+}
+
+#[get("/stream")]
+async fn my_stream() -> HttpResponse {
+    let body = once(ok::<_, Error>(web::Bytes::from_static(b"test")));
+
+    HttpResponse::Ok()
+        .content_type("application/json")
+        .streaming(body)
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(greet).service(my_stream).service(stream_delay);
+}
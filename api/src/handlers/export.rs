@@ -0,0 +1,55 @@
+use actix_web::{
+    get,
+    http::header::{ContentDisposition, DispositionParam, DispositionType},
+    post, web, HttpResponse,
+};
+use uuid::Uuid;
+
+use super::load_bill;
+use crate::{
+    error::ApiError,
+    export::{quickbooks::bill_to_iif, spreadsheet::bill_to_formula_csv},
+    state::AppState,
+};
+
+/// Downloads the bill as a QuickBooks IIF file.
+#[post("/bills/{id}/send-to-accounting")]
+async fn send_to_accounting(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let bill = load_bill(&state.repo(), id.into_inner()).await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/x-iif")
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(format!("bill-{}.iif", bill.id))],
+        })
+        .body(bill_to_iif(&bill)))
+}
+
+/// Downloads the bill as CSV whose totals are spreadsheet formulas.
+#[get("/bills/{id}/export/spreadsheet-formulas")]
+async fn spreadsheet_formulas(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let bill = load_bill(&state.repo(), id.into_inner()).await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(format!(
+                "bill-{}-formulas.csv",
+                bill.id
+            ))],
+        })
+        .body(bill_to_formula_csv(&bill)))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(send_to_accounting)
+        .service(spreadsheet_formulas);
+}
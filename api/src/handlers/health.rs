@@ -0,0 +1,38 @@
+use actix_web::{get, web, HttpResponse};
+use serde_json::json;
+
+use crate::{
+    build_info::{features, BUILD_TIMESTAMP, DEPRECATED_ENDPOINTS, GIT_COMMIT, VERSION},
+    openapi,
+};
+
+#[get("/health")]
+async fn health() -> HttpResponse {
+    HttpResponse::Ok().json(json!({
+        "status": "ok",
+        "version": VERSION,
+        "git_commit": GIT_COMMIT,
+        "build_timestamp": BUILD_TIMESTAMP,
+    }))
+}
+
+/// What this build supports, so clients can check compatibility.
+#[get("/version")]
+async fn version() -> HttpResponse {
+    HttpResponse::Ok().json(json!({
+        "api_version": VERSION,
+        "git_commit": GIT_COMMIT,
+        "build_timestamp": BUILD_TIMESTAMP,
+        "features": features(),
+        "deprecated_endpoints": DEPRECATED_ENDPOINTS,
+    }))
+}
+
+#[get("/openapi.json")]
+async fn openapi_spec() -> HttpResponse {
+    HttpResponse::Ok().json(openapi::spec())
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(health).service(version).service(openapi_spec);
+}
@@ -0,0 +1,95 @@
+use actix_web::web;
+use uuid::Uuid;
+
+use crate::{error::ApiError, i18n::t_with, models::Bill, storage::KvRepository};
+
+pub mod admin;
+pub mod ai;
+pub mod batch;
+pub mod bills;
+pub mod demo;
+pub mod export;
+pub mod health;
+pub mod notifications;
+pub mod options;
+pub mod participants;
+pub mod payments;
+pub mod receipts;
+pub mod settlements;
+pub mod split;
+pub mod tags;
+pub mod taxes;
+pub mod util;
+
+/// Registers every API route on the application.
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.app_data(
+        web::JsonConfig::default()
+            .error_handler(|err, _| ApiError::BadRequest(err.to_string()).into()),
+    )
+    .app_data(
+        web::QueryConfig::default()
+            .error_handler(|err, _| ApiError::BadRequest(err.to_string()).into()),
+    );
+
+    admin::configure(cfg);
+    ai::configure(cfg);
+    batch::configure(cfg);
+    bills::configure(cfg);
+    demo::configure(cfg);
+    export::configure(cfg);
+    health::configure(cfg);
+    notifications::configure(cfg);
+    participants::configure(cfg);
+    payments::configure(cfg);
+    receipts::configure(cfg);
+    settlements::configure(cfg);
+    split::configure(cfg);
+    tags::configure(cfg);
+    taxes::configure(cfg);
+    // Catch-all, keep last.
+    options::configure(cfg);
+}
+
+/// Fails with a 409 once the bill is settled or archived.
+pub(crate) fn ensure_editable(bill: &Bill) -> Result<(), ApiError> {
+    if bill.is_editable() {
+        Ok(())
+    } else {
+        Err(ApiError::Conflict(format!(
+            "Bill {} is {} and can no longer be changed",
+            bill.id, bill.status
+        )))
+    }
+}
+
+/// Fails with a 409 if the bill is archived. Archived bills stay readable.
+pub(crate) fn ensure_not_archived(bill: &Bill) -> Result<(), ApiError> {
+    if bill.is_archived() {
+        Err(ApiError::Conflict(format!(
+            "Bill {} is archived; unarchive it before making changes",
+            bill.id
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// Fails with a 404 unless the participant is on the bill.
+pub(crate) fn ensure_participant(bill: &Bill, participant_id: Uuid) -> Result<(), ApiError> {
+    if bill.has_participant(participant_id) {
+        Ok(())
+    } else {
+        Err(ApiError::NotFound(t_with(
+            "participant_not_on_bill",
+            &[("id", &participant_id)],
+        )))
+    }
+}
+
+/// Fetches a bill or fails with a 404.
+pub(crate) async fn load_bill(repo: &KvRepository<'_>, id: Uuid) -> Result<Bill, ApiError> {
+    repo.get_bill(id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(t_with("bill_not_found", &[("id", &id)])))
+}
@@ -0,0 +1,301 @@
+use actix_web::{delete, get, post, web, HttpResponse};
+use chrono::{Duration, Utc};
+use futures::{future::join_all, FutureExt};
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::{ensure_participant, load_bill};
+use crate::{
+    error::ApiError,
+    i18n::t,
+    models::{Bill, Money, SplitSnapshot},
+    notifications::{
+        email::MailgunWebhook,
+        reminders::{self, ScheduledReminder},
+        sms::{fetch_delivery_report, send_share_sms},
+        DeliveryReport, DeliverySummary, NotificationChannel, NotificationError,
+        NotificationStatus, NotifySummary,
+    },
+    queues::EmailTask,
+    state::AppState,
+    storage::KvRepository,
+};
+
+#[derive(Serialize)]
+struct QueuedNotifications {
+    queued: usize,
+    /// Reminders scheduled for those notified; participants who already have
+    /// [`reminders::MAX_PENDING_REMINDERS`] pending get none.
+    reminders_scheduled: usize,
+}
+
+/// Queues an email to every participant with an outstanding share of the
+/// latest split. Delivery happens in the background; outcomes show up in
+/// `GET /bills/:id/notifications/status`. Each participant notified is
+/// reminded again after `reminder_interval_days` if they still owe.
+#[post("/bills/{id}/notify")]
+async fn notify(state: web::Data<AppState>, id: web::Path<Uuid>) -> Result<HttpResponse, ApiError> {
+    let email = &state.config.email;
+    if email.provider.is_none() {
+        return Err(ApiError::ServiceUnavailable(
+            "Email is not configured: set EMAIL_PROVIDER".to_string(),
+        ));
+    }
+
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let snapshot = bill
+        .latest_split()
+        .ok_or_else(|| ApiError::InsufficientData(t("no_split_yet")))?;
+
+    let remind_at = Utc::now() + Duration::days(state.config.reminder_interval_days.into());
+    let mut queued = 0;
+    let mut reminders_scheduled = 0;
+    for (participant_id, outstanding) in outstanding_shares(&bill, snapshot) {
+        let task = EmailTask::new(participant_id, bill.id, NotificationChannel::Email);
+        if !state.notification_queue.send(task) {
+            return Err(ApiError::ServiceUnavailable(
+                "The notification queue is not accepting tasks".to_string(),
+            ));
+        }
+        queued += 1;
+
+        let mut reminders = repo.get_reminders(bill.id, participant_id).await?;
+        let reminder =
+            ScheduledReminder::new(NotificationChannel::Email, remind_at, &bill, outstanding);
+        if reminders::schedule(&mut reminders, reminder) {
+            repo.put_reminders(bill.id, participant_id, &reminders)
+                .await?;
+            reminders_scheduled += 1;
+        }
+    }
+
+    Ok(HttpResponse::Accepted().json(QueuedNotifications {
+        queued,
+        reminders_scheduled,
+    }))
+}
+
+/// Each participant in `snapshot` who still owes something once their
+/// payments are taken off, with the amount left to pay.
+fn outstanding_shares<'a>(
+    bill: &'a Bill,
+    snapshot: &'a SplitSnapshot,
+) -> impl Iterator<Item = (Uuid, Money)> + 'a {
+    snapshot.shares.iter().filter_map(|share| {
+        bill.outstanding(share.participant_id)
+            .filter(|outstanding| *outstanding > Money::ZERO)
+            .map(|outstanding| (share.participant_id, outstanding))
+    })
+}
+
+/// Appends a status row per send to the bill's notification history.
+async fn record_statuses(
+    repo: &KvRepository<'_>,
+    bill_id: Uuid,
+    channel: NotificationChannel,
+    results: &[(Uuid, Result<Option<String>, NotificationError>)],
+) -> Result<(), ApiError> {
+    let mut statuses = repo.get_notification_statuses(bill_id).await?;
+    statuses.extend(results.iter().map(|(participant_id, result)| {
+        NotificationStatus::from_result(*participant_id, channel, result)
+    }));
+    repo.put_notification_statuses(bill_id, &statuses).await?;
+    Ok(())
+}
+
+/// Texts every participant with a phone number and an outstanding share.
+#[post("/bills/{id}/notify/sms")]
+async fn notify_sms(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let sms = &state.config.sms;
+    if !sms.is_configured() {
+        return Err(ApiError::ServiceUnavailable(
+            "SMS is not configured: set TWILIO_ACCOUNT_SID, TWILIO_AUTH_TOKEN and TWILIO_FROM_NUMBER"
+                .to_string(),
+        ));
+    }
+
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let participants = repo.get_bill_participants(&bill).await?;
+    if participants
+        .iter()
+        .all(|participant| participant.phone.is_none())
+    {
+        return Err(ApiError::BadRequest(
+            "No participants on this bill have a phone number registered".to_string(),
+        ));
+    }
+
+    let snapshot = bill
+        .latest_split()
+        .ok_or_else(|| ApiError::InsufficientData(t("no_split_yet")))?;
+    let share_link = state.config.bill_link(bill.id);
+
+    let sends = outstanding_shares(&bill, snapshot).filter_map(|(participant_id, outstanding)| {
+        participants
+            .iter()
+            .find(|participant| participant.id == participant_id && participant.phone.is_some())
+            .map(|participant| {
+                send_share_sms(sms, participant, &bill, outstanding, &share_link)
+                    .map(|result| (participant.id, result))
+            })
+    });
+
+    let results = join_all(sends).await;
+    record_statuses(&repo, bill.id, NotificationChannel::Sms, &results).await?;
+
+    let mut summary = NotifySummary::default();
+    for (_, result) in results {
+        summary.record(result);
+    }
+
+    Ok(HttpResponse::Ok().json(summary))
+}
+
+#[derive(Serialize)]
+struct NotificationStatusResponse {
+    summary: DeliverySummary,
+    notifications: Vec<NotificationStatus>,
+}
+
+/// Every notification sent for the bill and whether it arrived. Pending SMS
+/// are checked with Twilio first; email delivery arrives via the Mailgun
+/// webhook.
+#[get("/bills/{id}/notifications/status")]
+async fn notification_status(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let mut statuses = repo.get_notification_statuses(bill.id).await?;
+
+    let sms = &state.config.sms;
+    if sms.is_configured() {
+        let polls = statuses
+            .iter()
+            .enumerate()
+            .filter(|(_, status)| status.channel == NotificationChannel::Sms && status.is_pending())
+            .filter_map(|(index, status)| {
+                let sid = status.provider_message_id.as_deref()?;
+                let participant_id = status.participant_id;
+                Some(async move { (index, fetch_delivery_report(sms, participant_id, sid).await) })
+            });
+        // A failed poll leaves the row pending for the next request.
+        let reports: Vec<(usize, DeliveryReport)> = join_all(polls)
+            .await
+            .into_iter()
+            .filter_map(|(index, report)| report.ok().map(|report| (index, report)))
+            .collect();
+
+        let mut changed = false;
+        for (index, report) in reports {
+            changed |= statuses[index].apply(report);
+        }
+        if changed {
+            repo.put_notification_statuses(bill.id, &statuses).await?;
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(NotificationStatusResponse {
+        summary: DeliverySummary::of(&statuses),
+        notifications: statuses,
+    }))
+}
+
+/// Receives Mailgun delivery events for notification emails.
+#[post("/webhooks/mailgun")]
+async fn mailgun_webhook(
+    state: web::Data<AppState>,
+    body: web::Json<MailgunWebhook>,
+) -> Result<HttpResponse, ApiError> {
+    let Some(signing_key) = state.config.email.mailgun_webhook_signing_key.as_deref() else {
+        return Err(ApiError::ServiceUnavailable(
+            "Mailgun webhooks are not configured: set MAILGUN_WEBHOOK_SIGNING_KEY".to_string(),
+        ));
+    };
+    if !body.is_authentic(signing_key, Utc::now().timestamp()) {
+        return Err(ApiError::Unauthorized(
+            "Invalid or expired Mailgun signature".to_string(),
+        ));
+    }
+
+    let event = &body.event_data;
+    let report = event.report();
+    if report == DeliveryReport::Pending {
+        return Ok(HttpResponse::Ok().finish());
+    }
+
+    // Events for mail we did not send are acknowledged so Mailgun stops
+    // retrying them.
+    let message_id = &event.message.headers.message_id;
+    let repo = state.repo();
+    let Some(bill_id) = repo.find_notification_bill(message_id).await? else {
+        return Ok(HttpResponse::Ok().finish());
+    };
+
+    let mut statuses = repo.get_notification_statuses(bill_id).await?;
+    let changed = statuses
+        .iter_mut()
+        .find(|status| {
+            status.channel == NotificationChannel::Email
+                && status.provider_message_id.as_deref() == Some(message_id.as_str())
+        })
+        .is_some_and(|status| status.apply(report));
+    if changed {
+        repo.put_notification_statuses(bill_id, &statuses).await?;
+    }
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// `participant_id`'s reminders on the bill that have yet to fire, soonest
+/// first.
+#[get("/bills/{id}/participants/{participant_id}/reminders")]
+async fn list_reminders(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, participant_id) = path.into_inner();
+    let repo = state.repo();
+    let bill = load_bill(&repo, id).await?;
+    ensure_participant(&bill, participant_id)?;
+    Ok(HttpResponse::Ok().json(repo.get_reminders(bill.id, participant_id).await?))
+}
+
+/// Cancels one pending reminder.
+#[delete("/bills/{id}/participants/{participant_id}/reminders/{reminder_id}")]
+async fn cancel_reminder(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, participant_id, reminder_id) = path.into_inner();
+    let repo = state.repo();
+    let bill = load_bill(&repo, id).await?;
+    ensure_participant(&bill, participant_id)?;
+
+    let mut reminders = repo.get_reminders(bill.id, participant_id).await?;
+    let count = reminders.len();
+    reminders.retain(|reminder| reminder.id != reminder_id);
+    if reminders.len() == count {
+        return Err(ApiError::NotFound(format!(
+            "Reminder {reminder_id} not found"
+        )));
+    }
+    repo.put_reminders(bill.id, participant_id, &reminders)
+        .await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(notify)
+        .service(notify_sms)
+        .service(notification_status)
+        .service(mailgun_webhook)
+        .service(list_reminders)
+        .service(cancel_reminder);
+}
@@ -0,0 +1,53 @@
+use actix_web::{
+    guard,
+    http::header::{self, HeaderValue},
+    web, HttpRequest, HttpResponse,
+};
+
+use crate::{
+    error::ApiError,
+    i18n::t_with,
+    routes::{allow_header_value, allowed_methods},
+};
+
+const PREFLIGHT_MAX_AGE_SECS: &str = "86400";
+
+/// Answers `OPTIONS` for any known path with the methods it supports, plus
+/// the CORS preflight headers when the request is a preflight.
+async fn options(req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    let methods = allowed_methods(req.path())
+        .ok_or_else(|| ApiError::NotFound(t_with("route_not_found", &[("path", &req.path())])))?;
+    let allow = allow_header_value(&methods);
+
+    let mut response = HttpResponse::NoContent();
+    response.insert_header((header::ALLOW, allow.clone()));
+
+    if req
+        .headers()
+        .contains_key(header::ACCESS_CONTROL_REQUEST_METHOD)
+    {
+        response
+            .insert_header((header::ACCESS_CONTROL_ALLOW_METHODS, allow))
+            .insert_header((header::ACCESS_CONTROL_MAX_AGE, PREFLIGHT_MAX_AGE_SECS));
+        if let Some(headers) = req.headers().get(header::ACCESS_CONTROL_REQUEST_HEADERS) {
+            response.insert_header((header::ACCESS_CONTROL_ALLOW_HEADERS, headers.clone()));
+        } else {
+            response.insert_header((
+                header::ACCESS_CONTROL_ALLOW_HEADERS,
+                HeaderValue::from_static("content-type"),
+            ));
+        }
+    }
+
+    Ok(response.finish())
+}
+
+/// Must be registered after every other route so it only sees `OPTIONS`
+/// requests that no handler claimed.
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/{tail:.*}")
+            .guard(guard::Options())
+            .to(options),
+    );
+}
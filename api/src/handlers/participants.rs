@@ -0,0 +1,255 @@
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    time::Duration,
+};
+
+use actix_web::{get, http::header, post, web, HttpRequest, HttpResponse};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    analytics::stats::participant_stats,
+    error::ApiError,
+    i18n::t_with,
+    search::{email_matches, name_score, rank, score_participant, ParticipantMatch},
+    state::AppState,
+};
+
+fn default_search_limit() -> usize {
+    10
+}
+
+#[derive(Deserialize)]
+struct SearchQuery {
+    q: String,
+    #[serde(default = "default_search_limit")]
+    limit: usize,
+}
+
+/// Participants whose name is close to `q`, or whose email starts with it,
+/// best match first. Names are matched through the name index so only the
+/// participants that match are loaded for them.
+#[get("/participants/search")]
+async fn search_participants(
+    state: web::Data<AppState>,
+    query: web::Query<SearchQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let q = query.q.trim();
+    if q.is_empty() {
+        return Err(ApiError::BadRequest("`q` must not be empty".to_string()));
+    }
+    let repo = state.repo();
+
+    let mut seen = HashSet::new();
+    let mut matches = Vec::new();
+    for name in repo.participant_names().await? {
+        if name_score(q, &name).is_none() {
+            continue;
+        }
+        for id in repo.participant_ids_named(&name).await? {
+            if let Some(participant) = repo.get_participant(id).await? {
+                if let Some(score) = score_participant(q, &participant) {
+                    seen.insert(participant.id);
+                    matches.push(ParticipantMatch { participant, score });
+                }
+            }
+        }
+    }
+    for participant in repo.list_participants().await? {
+        let by_email = participant
+            .email
+            .as_deref()
+            .is_some_and(|email| email_matches(q, email));
+        if by_email && seen.insert(participant.id) {
+            matches.push(ParticipantMatch {
+                participant,
+                score: 1.0,
+            });
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(rank(matches, query.limit)))
+}
+
+/// A participant's spending across every bill they are on. Computed by
+/// scanning all bills, so results are cached for a few minutes.
+#[get("/participants/{id}/stats")]
+async fn get_participant_stats(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let participant_id = id.into_inner();
+    let repo = state.repo();
+    if let Some(stats) = repo.get_participant_stats(participant_id).await? {
+        return Ok(HttpResponse::Ok()
+            .insert_header(("X-Cache", "HIT"))
+            .json(stats));
+    }
+    if repo.get_participant(participant_id).await?.is_none() {
+        return Err(ApiError::NotFound(t_with(
+            "participant_not_found",
+            &[("id", &participant_id)],
+        )));
+    }
+
+    let bills = repo
+        .list_bills(None, usize::MAX, |bill| {
+            bill.has_participant(participant_id)
+        })
+        .await?
+        .items;
+    let mut names = HashMap::new();
+    for other in bills.iter().flat_map(|bill| bill.participant_ids()) {
+        if let Entry::Vacant(entry) = names.entry(other) {
+            if let Some(participant) = repo.get_participant(other).await? {
+                entry.insert(participant.name);
+            }
+        }
+    }
+
+    let stats = participant_stats(participant_id, &bills, &names, Utc::now());
+    repo.put_participant_stats(&stats).await?;
+    Ok(HttpResponse::Ok()
+        .insert_header(("X-Cache", "MISS"))
+        .json(stats))
+}
+
+const AVATAR_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Browsers and CDNs may keep avatars for a day.
+const AVATAR_CACHE_CONTROL: &str = "public, max-age=86400";
+
+/// Streams the participant's avatar from their `avatar_url`. The upstream
+/// request carries none of the caller's headers, so cookies and credentials
+/// never leave this server, and only the image's `Content-Type` is passed
+/// back. Redirects are not followed: only the stored URL was checked to be
+/// HTTPS, and a redirect could point anywhere, internal hosts included.
+#[get("/participants/{id}/avatar")]
+async fn get_avatar(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let participant_id = id.into_inner();
+    let participant = state
+        .repo()
+        .get_participant(participant_id)
+        .await?
+        .ok_or_else(|| {
+            ApiError::NotFound(t_with("participant_not_found", &[("id", &participant_id)]))
+        })?;
+    let url = participant
+        .avatar_url
+        .ok_or_else(|| ApiError::NotFound(format!("Participant {participant_id} has no avatar")))?;
+
+    let response = awc::Client::builder()
+        .disable_redirects()
+        .finish()
+        .get(url.as_str())
+        .insert_header((header::ACCEPT, "image/*"))
+        .timeout(AVATAR_REQUEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|err| ApiError::BadGateway(format!("Could not fetch the avatar: {err}")))?;
+    if !response.status().is_success() {
+        return Err(ApiError::BadGateway(format!(
+            "The avatar host responded with {}",
+            response.status()
+        )));
+    }
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| value.starts_with("image/"))
+        .map(str::to_string)
+        .ok_or_else(|| ApiError::BadGateway("The avatar URL is not an image".to_string()))?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(content_type)
+        .insert_header((header::CACHE_CONTROL, AVATAR_CACHE_CONTROL))
+        .streaming(response))
+}
+
+pub const CONFIRM_HEADER: &str = "X-Confirm";
+
+#[derive(Deserialize)]
+struct MergeParticipantRequest {
+    merge_into: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+struct MergeParticipantResult {
+    bills_updated: u32,
+    payments_transferred: u32,
+}
+
+/// Folds a duplicate participant into `merge_into` on every bill, archived
+/// ones included, then deletes the duplicate. The two must share at least
+/// one bill, and the caller must send `X-Confirm: merge` since this cannot
+/// be undone.
+#[post("/participants/{id}/merge")]
+async fn merge_participant(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    body: web::Json<MergeParticipantRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let confirmed = req
+        .headers()
+        .get(CONFIRM_HEADER)
+        .and_then(|value| value.to_str().ok())
+        == Some("merge");
+    if !confirmed {
+        return Err(ApiError::BadRequest(format!(
+            "Merging participants cannot be undone: send `{CONFIRM_HEADER}: merge` to confirm"
+        )));
+    }
+    let source_id = id.into_inner();
+    let target_id = body.merge_into;
+    if source_id == target_id {
+        return Err(ApiError::BadRequest(
+            "A participant cannot be merged into itself".to_string(),
+        ));
+    }
+
+    let repo = state.repo_for(&req);
+    let not_found = |id: Uuid| ApiError::NotFound(t_with("participant_not_found", &[("id", &id)]));
+    if repo.get_participant(source_id).await?.is_none() {
+        return Err(not_found(source_id));
+    }
+    let target = repo
+        .get_participant(target_id)
+        .await?
+        .ok_or_else(|| not_found(target_id))?;
+
+    let mut bills = repo
+        .list_bills(None, usize::MAX, |bill| bill.has_participant(source_id))
+        .await?
+        .items;
+    if !bills.iter().any(|bill| bill.has_participant(target_id)) {
+        return Err(ApiError::BadRequest(format!(
+            "Participants {source_id} and {target_id} are not on any bill together"
+        )));
+    }
+
+    let mut payments_transferred = 0;
+    for bill in &mut bills {
+        payments_transferred += bill.merge_participant(source_id, &target);
+        bill.touch();
+        repo.put_bill(bill).await?;
+    }
+    repo.delete_participant(source_id).await?;
+
+    Ok(HttpResponse::Ok().json(MergeParticipantResult {
+        bills_updated: bills.len() as u32,
+        payments_transferred,
+    }))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(search_participants)
+        .service(get_participant_stats)
+        .service(get_avatar)
+        .service(merge_participant);
+}
@@ -0,0 +1,403 @@
+use actix_web::{get, post, put, web, HttpRequest, HttpResponse};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{ensure_not_archived, ensure_participant, load_bill};
+use crate::{
+    analytics::completion::completion,
+    auth::require_creator,
+    error::ApiError,
+    exchange::rate_into_bill_currency,
+    i18n::{t, t_with},
+    models::{Bill, CurrencyCode, Money, Payment, MAX_PAYMENT_AMOUNT},
+    payment_links::{
+        co_payment_link, optimal_payment_route, suggest_payment_method, CoPaymentLink,
+        PaymentDetails,
+    },
+    qr::{to_png, to_svg, QrCode},
+    state::AppState,
+};
+
+#[derive(Deserialize)]
+struct RecordPaymentBody {
+    participant_id: Uuid,
+    amount: Money,
+    /// Defaults to the bill's base currency.
+    currency: Option<CurrencyCode>,
+    note: Option<String>,
+}
+
+#[post("/bills/{id}/payments")]
+async fn record_payment(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    body: web::Json<RecordPaymentBody>,
+) -> Result<HttpResponse, ApiError> {
+    let body = body.into_inner();
+    if body.amount.is_negative() || body.amount.is_zero() {
+        return Err(ApiError::BadRequest(
+            "`amount` must be greater than zero".to_string(),
+        ));
+    }
+
+    let repo = state.repo_for(&req);
+    let mut bill = load_bill(&repo, id.into_inner()).await?;
+    ensure_not_archived(&bill)?;
+    if !bill.has_participant(body.participant_id) {
+        return Err(ApiError::BadRequest(t_with(
+            "participant_not_on_bill",
+            &[("id", &body.participant_id)],
+        )));
+    }
+
+    let mut payment = Payment::new(body.participant_id, body.amount, body.note);
+    if let Some(currency) = body
+        .currency
+        .filter(|currency| *currency != bill.base_currency)
+    {
+        let rate = rate_into_bill_currency(&state.config, &bill, &currency).await?;
+        payment = payment.converted(currency, rate.rate);
+        bill.exchange_rates_used.push(rate);
+    }
+    if !payment.is_within_limit() {
+        return Err(ApiError::BadRequest(format!(
+            "`amount` must not exceed {MAX_PAYMENT_AMOUNT}"
+        )));
+    }
+    bill.payments.push(payment.clone());
+    bill.touch();
+    repo.put_bill(&bill).await?;
+
+    Ok(HttpResponse::Created().json(payment))
+}
+
+/// A payment as shown in one participant's payment history.
+#[derive(Debug, Serialize)]
+pub struct PaymentView {
+    pub payment_id: Uuid,
+    /// The bill's payer, who the money goes to, if one is recorded.
+    pub counterpart_name: Option<String>,
+    pub amount: Money,
+    pub currency: CurrencyCode,
+    pub paid_at: DateTime<Utc>,
+    /// Payments are only ever recorded by hand for now, so this is always
+    /// `false`.
+    pub is_automated: bool,
+    pub memo: Option<String>,
+    /// Share from the latest split less this and every earlier payment, in
+    /// the bill's currency. `None` until a split has been computed.
+    pub remaining_balance: Option<Money>,
+}
+
+/// Every payment the participant has made towards the bill, newest first.
+#[get("/bills/{id}/participants/{participant_id}/payment-history")]
+async fn payment_history(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, participant_id) = path.into_inner();
+    let repo = state.repo();
+    let bill = load_bill(&repo, id).await?;
+    ensure_participant(&bill, participant_id)?;
+    let counterpart_name = match bill.payer_id {
+        Some(payer_id) => repo
+            .get_participant(payer_id)
+            .await?
+            .map(|payer| payer.name),
+        None => None,
+    };
+    let mut remaining = bill.latest_split().and_then(|snapshot| {
+        snapshot
+            .shares
+            .iter()
+            .find(|share| share.participant_id == participant_id)
+            .map(|share| share.amount_owed)
+    });
+
+    let mut payments: Vec<&Payment> = bill.payments_by(participant_id).collect();
+    payments.sort_by_key(|payment| payment.paid_at);
+    let mut history: Vec<PaymentView> = payments
+        .into_iter()
+        .map(|payment| {
+            if let Some(remaining) = remaining.as_mut() {
+                *remaining -= payment.base_amount();
+            }
+            PaymentView {
+                payment_id: payment.id,
+                counterpart_name: counterpart_name.clone(),
+                amount: payment.amount,
+                currency: payment
+                    .currency
+                    .clone()
+                    .unwrap_or_else(|| bill.base_currency.clone()),
+                paid_at: payment.paid_at,
+                is_automated: false,
+                memo: payment.note.clone(),
+                remaining_balance: remaining,
+            }
+        })
+        .collect();
+    history.reverse();
+
+    Ok(HttpResponse::Ok().json(history))
+}
+
+#[derive(Serialize)]
+struct DueDateView {
+    participant_id: Uuid,
+    due_date: NaiveDate,
+    /// Whether the creator set `due_date` rather than it being the default.
+    is_override: bool,
+    /// `None` until a split has been computed.
+    outstanding: Option<Money>,
+    /// Only once `due_date` has passed with money still owed.
+    is_overdue: bool,
+    days_overdue: u32,
+}
+
+impl DueDateView {
+    fn new(bill: &Bill, participant_id: Uuid, default_payment_days: u32) -> Self {
+        let days_overdue = bill.days_overdue(
+            participant_id,
+            default_payment_days,
+            Utc::now().date_naive(),
+        );
+        Self {
+            participant_id,
+            due_date: bill.due_date(participant_id, default_payment_days),
+            is_override: bill.due_dates.contains_key(&participant_id),
+            outstanding: bill.outstanding(participant_id),
+            is_overdue: days_overdue.is_some(),
+            days_overdue: days_overdue.unwrap_or(0),
+        }
+    }
+}
+
+/// When the participant's share is due and whether it is overdue.
+#[get("/bills/{id}/participants/{participant_id}/due-date")]
+async fn participant_due_date(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, participant_id) = path.into_inner();
+    let bill = load_bill(&state.repo(), id).await?;
+    ensure_participant(&bill, participant_id)?;
+
+    Ok(HttpResponse::Ok().json(DueDateView::new(
+        &bill,
+        participant_id,
+        state.config.default_payment_days,
+    )))
+}
+
+/// Which payment button to show the participant first, from the payment
+/// apps on their profile.
+#[get("/bills/{id}/participants/{participant_id}/suggested-payment-method")]
+async fn suggested_payment_method(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, participant_id) = path.into_inner();
+    let repo = state.repo();
+    let bill = load_bill(&repo, id).await?;
+    ensure_participant(&bill, participant_id)?;
+    let participant = repo.get_participant(participant_id).await?.ok_or_else(|| {
+        ApiError::NotFound(t_with("participant_not_found", &[("id", &participant_id)]))
+    })?;
+
+    Ok(HttpResponse::Ok().json(suggest_payment_method(&participant)))
+}
+
+/// The cheapest way for the participant to pay what they still owe, from
+/// the payment apps on their profile and each app's fees.
+#[get("/bills/{id}/participants/{participant_id}/optimal-payment-route")]
+async fn optimal_payment_route_for(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, participant_id) = path.into_inner();
+    let repo = state.repo();
+    let bill = load_bill(&repo, id).await?;
+    ensure_participant(&bill, participant_id)?;
+    let amount = bill
+        .outstanding(participant_id)
+        .ok_or_else(|| ApiError::InsufficientData(t("no_split_yet")))?;
+    if amount <= Money::ZERO {
+        return Err(ApiError::Conflict(format!(
+            "Participant {participant_id} has nothing left to pay"
+        )));
+    }
+    let participant = repo.get_participant(participant_id).await?.ok_or_else(|| {
+        ApiError::NotFound(t_with("participant_not_found", &[("id", &participant_id)]))
+    })?;
+
+    Ok(HttpResponse::Ok().json(optimal_payment_route(
+        &participant,
+        &bill.base_currency,
+        amount,
+    )))
+}
+
+#[derive(Deserialize)]
+struct SetDueDateBody {
+    /// `null` goes back to the default.
+    due_date: Option<NaiveDate>,
+}
+
+/// Sets an explicit due date for the participant. Only the bill's creator
+/// may do this.
+#[put("/bills/{id}/participants/{participant_id}/due-date")]
+async fn set_due_date(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    body: web::Json<SetDueDateBody>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, participant_id) = path.into_inner();
+    let repo = state.repo_for(&req);
+    let mut bill = load_bill(&repo, id).await?;
+    ensure_not_archived(&bill)?;
+    ensure_participant(&bill, participant_id)?;
+    require_creator(&req, &state.config, &bill)?;
+
+    match body.into_inner().due_date {
+        Some(due_date) => bill.due_dates.insert(participant_id, due_date),
+        None => bill.due_dates.remove(&participant_id),
+    };
+    bill.touch();
+    repo.put_bill(&bill).await?;
+
+    Ok(HttpResponse::Ok().json(DueDateView::new(
+        &bill,
+        participant_id,
+        state.config.default_payment_days,
+    )))
+}
+
+/// Links for everyone still owing on the latest split to pay the bill's
+/// payer in Venmo, PayPal or Cash App. A link is only included when the
+/// payer has registered an account on that app.
+#[get("/bills/{id}/co-payment-links")]
+async fn co_payment_links(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let payer_id = bill.payer_id.ok_or_else(|| {
+        ApiError::InsufficientData("Set the bill's payer to get payment links".to_string())
+    })?;
+    if bill.latest_split().is_none() {
+        return Err(ApiError::InsufficientData(t("no_split_yet")));
+    }
+
+    let participants = repo.get_bill_participants(&bill).await?;
+    let payer = participants
+        .iter()
+        .find(|participant| participant.id == payer_id)
+        .ok_or_else(|| ApiError::NotFound(t_with("participant_not_found", &[("id", &payer_id)])))?;
+    let links: Vec<CoPaymentLink> = participants
+        .iter()
+        .filter(|participant| participant.id != payer_id)
+        .filter_map(|participant| {
+            bill.outstanding(participant.id)
+                .filter(|amount| *amount > Money::ZERO)
+                .map(|amount| co_payment_link(&bill, participant, payer, amount))
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(links))
+}
+
+/// How much of the latest split has been paid, and when the rest should be
+/// in at the current pace.
+#[get("/bills/{id}/completion-percentage")]
+async fn completion_percentage(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let bill = load_bill(&state.repo(), id.into_inner()).await?;
+    let completion = completion(&bill, Utc::now())
+        .ok_or_else(|| ApiError::InsufficientData(t("no_split_yet")))?;
+    Ok(HttpResponse::Ok().json(completion))
+}
+
+/// Pixels per module in PNG QR codes.
+const QR_PNG_SCALE: usize = 8;
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum QrFormat {
+    #[default]
+    Svg,
+    Png,
+}
+
+#[derive(Deserialize)]
+struct PaymentQrQuery {
+    #[serde(default)]
+    format: QrFormat,
+}
+
+/// A QR code of what the participant still owes the bill's payer, encoded
+/// as `{ payee_name, amount, bill_reference, currency }` JSON.
+#[get("/bills/{id}/participants/{participant_id}/payment-qr")]
+async fn payment_qr(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    query: web::Query<PaymentQrQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, participant_id) = path.into_inner();
+    let repo = state.repo();
+    let bill = load_bill(&repo, id).await?;
+    ensure_participant(&bill, participant_id)?;
+    let payer_id = bill.payer_id.ok_or_else(|| {
+        ApiError::InsufficientData("Set the bill's payer to get a payment QR code".to_string())
+    })?;
+    if participant_id == payer_id {
+        return Err(ApiError::BadRequest(
+            "The payer has no one to pay".to_string(),
+        ));
+    }
+    let amount = bill
+        .outstanding(participant_id)
+        .ok_or_else(|| ApiError::InsufficientData(t("no_split_yet")))?;
+    if amount <= Money::ZERO {
+        return Err(ApiError::Conflict(format!(
+            "Participant {participant_id} has nothing left to pay"
+        )));
+    }
+    let payer = repo
+        .get_participant(payer_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(t_with("participant_not_found", &[("id", &payer_id)])))?;
+
+    let details = serde_json::to_vec(&PaymentDetails::new(&bill, &payer, amount))
+        .map_err(|err| ApiError::Internal(err.to_string()))?;
+    let code = QrCode::encode(&details).map_err(|err| {
+        ApiError::BadRequest(format!("Payment details do not fit in a QR code: {err}"))
+    })?;
+
+    Ok(match query.format {
+        QrFormat::Svg => HttpResponse::Ok()
+            .content_type("image/svg+xml")
+            .body(to_svg(&code)),
+        QrFormat::Png => HttpResponse::Ok()
+            .content_type("image/png")
+            .body(to_png(&code, QR_PNG_SCALE)),
+    })
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(record_payment)
+        .service(payment_history)
+        .service(participant_due_date)
+        .service(suggested_payment_method)
+        .service(optimal_payment_route_for)
+        .service(set_due_date)
+        .service(co_payment_links)
+        .service(completion_percentage)
+        .service(payment_qr);
+}
@@ -0,0 +1,186 @@
+use actix_web::{get, http::header, post, web, HttpRequest, HttpResponse};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::load_bill;
+use crate::{
+    error::ApiError,
+    i18n::{t, t_with},
+    invoice::{bill_to_invoice, render_invoice_html},
+    models::Bill,
+    receipt::{render_pdf, render_receipt_html, PersonalReceipt},
+    state::AppState,
+};
+
+/// Whether the client asked for HTML rather than JSON.
+fn wants_html(req: &HttpRequest) -> bool {
+    req.headers()
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/html"))
+}
+
+/// `participant_id`'s receipt for the latest split of `bill`.
+fn personal_receipt(bill: &Bill, participant_id: Uuid) -> Result<PersonalReceipt, ApiError> {
+    if !bill.has_participant(participant_id) {
+        return Err(ApiError::NotFound(t_with(
+            "participant_not_on_bill",
+            &[("id", &participant_id)],
+        )));
+    }
+    let snapshot = bill
+        .latest_split()
+        .ok_or_else(|| ApiError::InsufficientData(t("no_split_yet")))?;
+    let share = snapshot
+        .shares
+        .iter()
+        .find(|share| share.participant_id == participant_id)
+        .ok_or_else(|| {
+            ApiError::InsufficientData(format!(
+                "Participant {participant_id} joined after the latest split"
+            ))
+        })?;
+
+    Ok(PersonalReceipt::new(bill, share))
+}
+
+/// One participant's receipt for the latest split: JSON by default, or a
+/// printable page with `Accept: text/html`.
+#[get("/bills/{id}/participants/{participant_id}/receipt")]
+async fn get_receipt(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, participant_id) = path.into_inner();
+    let bill = load_bill(&state.repo(), id).await?;
+    let receipt = personal_receipt(&bill, participant_id)?;
+    if wants_html(&req) {
+        let html =
+            render_receipt_html(&receipt).map_err(|err| ApiError::Internal(err.to_string()))?;
+        return Ok(HttpResponse::Ok()
+            .content_type("text/html; charset=utf-8")
+            .insert_header((header::VARY, "Accept"))
+            .body(html));
+    }
+    Ok(HttpResponse::Ok()
+        .insert_header((header::VARY, "Accept"))
+        .json(receipt))
+}
+
+#[derive(Deserialize)]
+struct PdfReceiptQuery {
+    participant_id: Uuid,
+}
+
+/// The receipt page printed to PDF. Rendered once per bill version and
+/// participant, then served from KV until the bill changes.
+#[get("/bills/{id}/pdf-receipt")]
+async fn get_pdf_receipt(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    query: web::Query<PdfReceiptQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let receipt = personal_receipt(&bill, query.participant_id)?;
+
+    let (pdf, cache) = match repo.get_receipt_pdf(&bill, query.participant_id).await? {
+        Some(pdf) => (pdf, "HIT"),
+        None => {
+            let html =
+                render_receipt_html(&receipt).map_err(|err| ApiError::Internal(err.to_string()))?;
+            let pdf = render_pdf(&state.config, &html).await?;
+            repo.put_receipt_pdf(&bill, query.participant_id, &pdf)
+                .await?;
+            (pdf, "MISS")
+        }
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/pdf")
+        .insert_header(("X-Cache", cache))
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("inline; filename=\"receipt-{}.pdf\"", bill.id),
+        ))
+        .body(pdf))
+}
+
+#[derive(Deserialize)]
+struct InvoiceRequest {
+    recipient_id: Uuid,
+}
+
+/// Whether the client asked for a PDF rather than JSON.
+fn wants_pdf(req: &HttpRequest) -> bool {
+    req.headers()
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|accept| accept.contains("application/pdf"))
+}
+
+/// A formal invoice from the bill's payer to another participant for the
+/// whole bill: JSON by default, or a PDF with `Accept: application/pdf`.
+/// Invoices are generated on request and not stored.
+#[post("/bills/{id}/convert-to-invoice")]
+async fn convert_to_invoice(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    body: web::Json<InvoiceRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let payer_id = bill.payer_id.ok_or_else(|| {
+        ApiError::InsufficientData("Set the bill's payer before invoicing".to_string())
+    })?;
+    if body.recipient_id == payer_id {
+        return Err(ApiError::BadRequest(
+            "The payer cannot invoice themselves".to_string(),
+        ));
+    }
+    if !bill.has_participant(body.recipient_id) {
+        return Err(ApiError::NotFound(t_with(
+            "participant_not_on_bill",
+            &[("id", &body.recipient_id)],
+        )));
+    }
+
+    let participants = repo.get_bill_participants(&bill).await?;
+    let find = |id: Uuid| {
+        participants
+            .iter()
+            .find(|participant| participant.id == id)
+            .ok_or_else(|| ApiError::NotFound(t_with("participant_not_found", &[("id", &id)])))
+    };
+    let invoice = bill_to_invoice(
+        &bill,
+        find(payer_id)?,
+        find(body.recipient_id)?,
+        state.config.default_payment_days,
+    );
+
+    if wants_pdf(&req) {
+        let html =
+            render_invoice_html(&invoice).map_err(|err| ApiError::Internal(err.to_string()))?;
+        let pdf = render_pdf(&state.config, &html).await?;
+        return Ok(HttpResponse::Ok()
+            .content_type("application/pdf")
+            .insert_header((header::VARY, "Accept"))
+            .insert_header((
+                header::CONTENT_DISPOSITION,
+                format!("inline; filename=\"{}.pdf\"", invoice.invoice_number),
+            ))
+            .body(pdf));
+    }
+    Ok(HttpResponse::Ok()
+        .insert_header((header::VARY, "Accept"))
+        .json(invoice))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_receipt)
+        .service(get_pdf_receipt)
+        .service(convert_to_invoice);
+}
@@ -0,0 +1,66 @@
+use actix_web::{get, web, HttpResponse};
+use uuid::Uuid;
+
+use super::load_bill;
+use crate::{
+    error::ApiError,
+    i18n::t,
+    split::{
+        minimise_settlements, naive_settlements, net_balances, Balance, DebtGraph, Settlement,
+        SettlementSavings,
+    },
+    state::AppState,
+};
+
+/// Balances and minimised transfers for the bill's latest split.
+async fn load_settlements(
+    state: &AppState,
+    bill_id: Uuid,
+) -> Result<(Vec<Balance>, Vec<Settlement>), ApiError> {
+    let repo = state.repo();
+    let bill = load_bill(&repo, bill_id).await?;
+    let snapshot = bill
+        .latest_split()
+        .ok_or_else(|| ApiError::InsufficientData(t("no_split_yet")))?;
+    let participants = repo.get_bill_participants(&bill).await?;
+
+    let balances = net_balances(&bill, snapshot, &participants);
+    let settlements = minimise_settlements(&balances);
+    Ok((balances, settlements))
+}
+
+#[get("/bills/{id}/settlements")]
+async fn get_settlements(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let (_, settlements) = load_settlements(&state, id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(settlements))
+}
+
+#[get("/bills/{id}/split/graph")]
+async fn get_debt_graph(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let (balances, settlements) = load_settlements(&state, id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(DebtGraph::new(balances, settlements)))
+}
+
+/// How many transfers the minimised settlements save over every debtor
+/// paying every creditor directly.
+#[get("/bills/{id}/split/minimised-vs-naive")]
+async fn minimised_vs_naive(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let (balances, settlements) = load_settlements(&state, id.into_inner()).await?;
+    let naive = naive_settlements(&balances);
+    Ok(HttpResponse::Ok().json(SettlementSavings::new(&naive, &settlements)))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_settlements)
+        .service(get_debt_graph)
+        .service(minimised_vs_naive);
+}
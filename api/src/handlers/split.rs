@@ -0,0 +1,1173 @@
+use std::{collections::HashSet, mem};
+
+use actix_web::{delete, get, http::header, post, put, web, HttpRequest, HttpResponse};
+use rust_decimal::Decimal;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use super::{ensure_editable, ensure_participant, load_bill};
+use crate::{
+    error::ApiError,
+    explain::{debt_chain, explain_split},
+    i18n::{t, t_with},
+    models::{Bill, Money, Participant, ParticipantShare, SplitSnapshot, MAX_LINE_ITEM_TOTAL},
+    queues::{SplitJob, SplitJobStatus},
+    split::{
+        allocation_matrix, bar_chart, comparative_spend, compare_methods, compute_split,
+        currency_breakdown, distribute_rounding_remainder, fairness_history, fairness_sparkline,
+        inequality_warning, recommend_method, round_to_nearest, rounding_report, sensitivity,
+        split_tax_exclusive, split_tax_inclusive, InequalityWarning, RoundedShares, SplitConfig,
+        SplitDiff, SplitMethod, SplitPerspective, SplitResult, SplitSpec, TaxExclusiveSplitResult,
+        TaxInclusiveSplitResult, WhatIfPriceResult, DEFAULT_INEQUALITY_THRESHOLD,
+    },
+    state::AppState,
+    storage::KvRepository,
+};
+
+#[derive(Deserialize)]
+struct SplitQuery {
+    /// Defaults to the bill's saved split config, or `equal` without one.
+    method: Option<SplitMethod>,
+    /// JSON-encoded `{ participant_id: weight }`, required for `proportional`.
+    weights: Option<String>,
+    /// JSON-encoded `{ participant_id: amount }`, required for `custom`.
+    amounts: Option<String>,
+    /// Refuse to split while anyone on the bill has not accepted.
+    #[serde(default)]
+    require_acceptance: bool,
+}
+
+impl SplitQuery {
+    fn spec(&self, bill: &Bill) -> Result<SplitSpec, ApiError> {
+        if self.require_acceptance {
+            let unconfirmed = bill.unconfirmed_participants();
+            if !unconfirmed.is_empty() {
+                let ids: Vec<String> = unconfirmed.iter().map(Uuid::to_string).collect();
+                return Err(ApiError::Conflict(format!(
+                    "Participants {} have not accepted the bill yet",
+                    ids.join(", ")
+                )));
+            }
+        }
+
+        let Some(method) = self.method else {
+            return match &bill.split_config {
+                Some(config) => config.spec().map_err(ApiError::BadRequest),
+                None => Ok(SplitSpec::Equal),
+            };
+        };
+        Ok(match method {
+            SplitMethod::Equal => SplitSpec::Equal,
+            SplitMethod::Itemised => SplitSpec::Itemised,
+            SplitMethod::Proportional => SplitSpec::Proportional {
+                weights: required_json_param("weights", self.weights.as_deref())?,
+            },
+            SplitMethod::Custom => SplitSpec::Custom {
+                amounts: required_json_param("amounts", self.amounts.as_deref())?,
+            },
+        })
+    }
+}
+
+fn required_json_param<T: DeserializeOwned>(
+    name: &str,
+    value: Option<&str>,
+) -> Result<T, ApiError> {
+    let value = value.ok_or_else(|| {
+        ApiError::BadRequest(format!("`{name}` is required for this split method"))
+    })?;
+    serde_json::from_str(value)
+        .map_err(|err| ApiError::BadRequest(format!("`{name}` is not valid JSON: {err}")))
+}
+
+#[derive(Deserialize)]
+struct InequalityQuery {
+    /// Ratio of the largest share to the average that triggers a warning.
+    threshold: Option<Decimal>,
+}
+
+impl InequalityQuery {
+    fn threshold(&self) -> Result<Decimal, ApiError> {
+        match self.threshold {
+            Some(threshold) if threshold <= Decimal::ONE => Err(ApiError::BadRequest(
+                "`threshold` must be greater than 1".to_string(),
+            )),
+            Some(threshold) => Ok(threshold),
+            None => Ok(DEFAULT_INEQUALITY_THRESHOLD),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RoundToQuery {
+    /// Multiple each share is rounded up to, e.g. `0.05`.
+    round_to: Option<Decimal>,
+}
+
+impl RoundToQuery {
+    /// The requested granularity, or `None` if the query does not set one.
+    fn granularity(&self) -> Result<Option<Money>, ApiError> {
+        let Some(round_to) = self.round_to else {
+            return Ok(None);
+        };
+        let granularity = Money::from_decimal(round_to);
+        if round_to <= Decimal::ZERO
+            || round_to > Decimal::ONE
+            || granularity.to_decimal() != round_to.normalize()
+        {
+            return Err(ApiError::BadRequest(
+                "`round_to` must be a multiple of 0.01 between 0.01 and 1.00".to_string(),
+            ));
+        }
+        Ok(Some(granularity))
+    }
+}
+
+#[derive(Serialize)]
+struct SplitResponse {
+    #[serde(flatten)]
+    breakdown: SplitBreakdown,
+    inequality_warning: Option<InequalityWarning>,
+    /// Participants included in the split who have not accepted yet.
+    unconfirmed_participants: Vec<Uuid>,
+    /// Participants who owe nothing, and why.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    exemptions: Vec<ExemptParticipant>,
+}
+
+/// The shares in [`SplitResponse`], in the shape the tax mode asks for.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum SplitBreakdown {
+    Shares(SplitResult),
+    TaxExclusive(TaxExclusiveSplitResult),
+    TaxInclusive(TaxInclusiveSplitResult),
+}
+
+#[derive(Serialize)]
+struct ExemptParticipant {
+    participant_id: Uuid,
+    reason: String,
+}
+
+impl ExemptParticipant {
+    fn of(bill: &Bill) -> Vec<Self> {
+        bill.participants
+            .iter()
+            .filter_map(|member| {
+                Some(ExemptParticipant {
+                    participant_id: member.participant_id,
+                    reason: member.exemption.as_ref()?.reason.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// `{ warning: false }`, or `{ warning: true, ... }` with the details.
+#[derive(Serialize)]
+struct InequalityResponse {
+    warning: bool,
+    #[serde(flatten)]
+    details: Option<InequalityWarning>,
+}
+
+/// How `GET /bills/:id/split` presents the tax in each share. Prices are
+/// always tax-inclusive; this only changes the breakdown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum TaxMode {
+    /// Gross shares with their net and tax components.
+    Inclusive,
+    /// Shares as pre-tax amounts plus tax.
+    Exclusive,
+}
+
+#[derive(Deserialize)]
+struct TaxQuery {
+    tax_mode: Option<TaxMode>,
+    /// Shorthand for `tax_mode=exclusive`.
+    #[serde(default)]
+    tax_exclusive: bool,
+}
+
+impl TaxQuery {
+    fn mode(&self) -> Result<Option<TaxMode>, ApiError> {
+        match (self.tax_mode, self.tax_exclusive) {
+            (Some(TaxMode::Inclusive), true) => Err(ApiError::BadRequest(
+                "`tax_exclusive` cannot be combined with `tax_mode=inclusive`".to_string(),
+            )),
+            (None, true) => Ok(Some(TaxMode::Exclusive)),
+            (mode, _) => Ok(mode),
+        }
+    }
+}
+
+/// The bill's own split, resolved from a [`SplitQuery`] and saved to its
+/// split history. Every endpoint that presents the split computes it through
+/// here, then only formats it.
+struct RecordedSplit {
+    bill: Bill,
+    participants: Vec<Participant>,
+    spec: SplitSpec,
+    result: SplitResult,
+}
+
+impl RecordedSplit {
+    async fn compute(
+        repo: &KvRepository<'_>,
+        bill: Bill,
+        query: &SplitQuery,
+    ) -> Result<Self, ApiError> {
+        let (split, ()) = Self::compute_with(repo, bill, query, |_| Ok(())).await?;
+        Ok(split)
+    }
+
+    /// Like [`compute`](Self::compute), letting `adjust` change the result
+    /// before it is recorded, e.g. to round the shares.
+    async fn compute_with<T>(
+        repo: &KvRepository<'_>,
+        mut bill: Bill,
+        query: &SplitQuery,
+        adjust: impl FnOnce(&mut SplitResult) -> Result<T, ApiError>,
+    ) -> Result<(Self, T), ApiError> {
+        let spec = query.spec(&bill)?;
+        let participants = repo.get_bill_participants(&bill).await?;
+        let mut result = compute_split(&bill, &participants, &spec)?;
+        let adjusted = adjust(&mut result)?;
+        // Archived bills are read-only, so nothing is recorded for them.
+        if bill.record_split(&result) {
+            repo.put_bill(&bill).await?;
+        }
+        let split = Self {
+            bill,
+            participants,
+            spec,
+            result,
+        };
+        Ok((split, adjusted))
+    }
+
+    /// The shares of everyone who is not exempt.
+    fn liable_shares(&self) -> Vec<ParticipantShare> {
+        self.result
+            .shares
+            .iter()
+            .filter(|share| !self.bill.is_exempt(share.participant_id))
+            .cloned()
+            .collect()
+    }
+
+    /// [`inequality_warning`] over the liable shares: exempt participants'
+    /// zero shares say nothing about how fair the rest is.
+    fn inequality_warning(&self, threshold: Decimal) -> Option<InequalityWarning> {
+        inequality_warning(&self.liable_shares(), threshold)
+    }
+}
+
+#[get("/bills/{id}/split")]
+async fn get_split(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    query: web::Query<SplitQuery>,
+    inequality: web::Query<InequalityQuery>,
+    tax: web::Query<TaxQuery>,
+    round: web::Query<RoundToQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let threshold = inequality.threshold()?;
+    let tax_mode = tax.mode()?;
+    let round_to = round.granularity()?;
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let (split, ()) = RecordedSplit::compute_with(&repo, bill, &query, |result| {
+        if let Some(round_to) = round_to {
+            let shares = mem::take(&mut result.shares);
+            result.shares = round_to_nearest(shares, result.total, round_to)?.shares;
+        }
+        Ok(())
+    })
+    .await?;
+
+    let inequality_warning = split.inequality_warning(threshold);
+    let RecordedSplit { bill, result, .. } = split;
+    let breakdown = match tax_mode {
+        Some(TaxMode::Exclusive) => {
+            SplitBreakdown::TaxExclusive(split_tax_exclusive(&bill, &result))
+        }
+        Some(TaxMode::Inclusive) => {
+            SplitBreakdown::TaxInclusive(split_tax_inclusive(&bill, &result))
+        }
+        None => SplitBreakdown::Shares(result),
+    };
+    Ok(HttpResponse::Ok().json(SplitResponse {
+        breakdown,
+        inequality_warning,
+        unconfirmed_participants: bill.unconfirmed_participants(),
+        exemptions: ExemptParticipant::of(&bill),
+    }))
+}
+
+/// Queues the split, resolved like `GET /bills/:id/split`, to be computed in
+/// the background, for bills too large to split within a request. Poll
+/// `poll_url` for the result. The split is recorded on the bill once the
+/// job is `done`.
+#[get("/bills/{id}/split/async")]
+async fn queue_split(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    query: web::Query<SplitQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let spec = query.spec(&bill)?;
+
+    let job = SplitJob::new(bill.id, SplitConfig::from(&spec));
+    repo.put_split_job(&SplitJobStatus::pending(&job)).await?;
+    let job_id = job.id;
+    if !state.split_queue.send(job) {
+        return Err(ApiError::ServiceUnavailable(
+            "The split queue is not accepting jobs".to_string(),
+        ));
+    }
+
+    let poll_url = format!("/bills/{}/split/jobs/{job_id}", bill.id);
+    Ok(HttpResponse::Accepted()
+        .insert_header((header::LOCATION, poll_url.clone()))
+        .json(json!({ "job_id": job_id, "poll_url": poll_url })))
+}
+
+/// Status of a split queued with `GET /bills/:id/split/async`, with the
+/// result once it is `done`. Jobs are kept for a day.
+#[get("/bills/{id}/split/jobs/{job_id}")]
+async fn get_split_job(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, job_id) = path.into_inner();
+    let status = state
+        .repo()
+        .get_split_job(job_id)
+        .await?
+        .filter(|status| status.bill_id == id)
+        .ok_or_else(|| ApiError::NotFound(format!("Split job {job_id} not found")))?;
+    Ok(HttpResponse::Ok().json(status))
+}
+
+/// Each share of the split, computed like `GET /bills/:id/split`, as a gross
+/// amount with its net and tax parts.
+#[get("/bills/{id}/split/tax-inclusive")]
+async fn get_tax_inclusive_split(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    query: web::Query<SplitQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let split = RecordedSplit::compute(&repo, bill, &query).await?;
+
+    Ok(HttpResponse::Ok().json(split_tax_inclusive(&split.bill, &split.result)))
+}
+
+/// Whether one participant's share of the split, computed like
+/// `GET /bills/:id/split`, is suspiciously large.
+#[get("/bills/{id}/split-inequality-warning")]
+async fn get_inequality_warning(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    query: web::Query<SplitQuery>,
+    inequality: web::Query<InequalityQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let threshold = inequality.threshold()?;
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let split = RecordedSplit::compute(&repo, bill, &query).await?;
+
+    let details = split.inequality_warning(threshold);
+    Ok(HttpResponse::Ok().json(InequalityResponse {
+        warning: details.is_some(),
+        details,
+    }))
+}
+
+/// Computes the split like `GET /bills/:id/split` and explains it step by
+/// step.
+#[get("/bills/{id}/split/explain")]
+async fn explain(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    query: web::Query<SplitQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let split = RecordedSplit::compute(&repo, bill, &query).await?;
+
+    Ok(HttpResponse::Ok().json(explain_split(
+        &split.bill,
+        split.result.method,
+        &split.result.shares,
+    )))
+}
+
+/// Traces one participant's share of the split, computed like
+/// `GET /bills/:id/split`, through the line items, discount, rounding and
+/// their payments.
+#[get("/bills/{id}/participants/{participant_id}/debt-chain")]
+async fn get_debt_chain(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    query: web::Query<SplitQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, participant_id) = path.into_inner();
+    let repo = state.repo();
+    let bill = load_bill(&repo, id).await?;
+    let split = RecordedSplit::compute(&repo, bill, &query).await?;
+
+    let share = split
+        .result
+        .shares
+        .iter()
+        .find(|share| share.participant_id == participant_id)
+        .ok_or_else(|| {
+            ApiError::NotFound(t_with(
+                "participant_not_on_bill",
+                &[("id", &participant_id)],
+            ))
+        })?;
+    Ok(HttpResponse::Ok().json(debt_chain(
+        &split.bill,
+        &split.participants,
+        &split.spec,
+        share,
+    )))
+}
+
+/// Computes the split like `GET /bills/:id/split` as seen by one
+/// participant.
+#[get("/bills/{id}/split/preview-as/{participant_id}")]
+async fn preview_as(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    query: web::Query<SplitQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, participant_id) = path.into_inner();
+    let repo = state.repo();
+    let bill = load_bill(&repo, id).await?;
+    if !bill.has_participant(participant_id) {
+        return Err(ApiError::NotFound(t_with(
+            "participant_not_on_bill",
+            &[("id", &participant_id)],
+        )));
+    }
+    let split = RecordedSplit::compute(&repo, bill, &query).await?;
+
+    Ok(HttpResponse::Ok().json(SplitPerspective::new(
+        &split.bill,
+        &split.participants,
+        split.result,
+        participant_id,
+    )))
+}
+
+#[derive(Serialize)]
+struct RoundedSplitResponse {
+    method: SplitMethod,
+    total: Money,
+    #[serde(flatten)]
+    rounded: RoundedShares,
+}
+
+/// The split computed like `GET /bills/:id/split` with each share rounded up
+/// to `round_to`, by default the smallest unit of the bill's currency. The
+/// rounded shares are what is recorded.
+#[get("/bills/{id}/split/round-to-nearest")]
+async fn get_split_rounded_to_nearest(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    query: web::Query<SplitQuery>,
+    round: web::Query<RoundToQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let round_to = round.granularity()?;
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let round_to = round_to.unwrap_or_else(|| bill.base_currency.smallest_unit());
+    let (split, rounded) = RecordedSplit::compute_with(&repo, bill, &query, |result| {
+        let rounded = round_to_nearest(mem::take(&mut result.shares), result.total, round_to)?;
+        result.shares = rounded.shares.clone();
+        Ok(rounded)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(RoundedSplitResponse {
+        method: split.result.method,
+        total: split.result.total,
+        rounded,
+    }))
+}
+
+/// How the split's shares were rounded to whole cents.
+#[get("/bills/{id}/split/rounding-report")]
+async fn get_rounding_report(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    query: web::Query<SplitQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let split = RecordedSplit::compute(&repo, bill, &query).await?;
+
+    let report = rounding_report(&split.bill, &split.participants, &split.spec)?;
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// How much of each share, computed like `GET /bills/:id/split`, came from
+/// items in each original currency.
+#[get("/bills/{id}/split/currency-breakdown")]
+async fn get_currency_breakdown(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    query: web::Query<SplitQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let split = RecordedSplit::compute(&repo, bill, &query).await?;
+
+    Ok(HttpResponse::Ok().json(currency_breakdown(&split.bill, &split.result)))
+}
+
+#[derive(Serialize)]
+struct CompactShare {
+    name: String,
+    total: Money,
+    /// Line items assigned to them, plus unassigned ones, which everyone
+    /// shares.
+    items_count: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exempt_reason: Option<String>,
+}
+
+/// Each share of the split, computed like `GET /bills/:id/split`, as just a
+/// name, amount and item count, small enough for a push notification.
+#[get("/bills/{id}/split/breakdown/compact")]
+async fn get_compact_breakdown(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    query: web::Query<SplitQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let RecordedSplit { bill, result, .. } = RecordedSplit::compute(&repo, bill, &query).await?;
+
+    let compact: Vec<CompactShare> = result
+        .shares
+        .into_iter()
+        .map(|share| CompactShare {
+            items_count: bill
+                .line_items
+                .iter()
+                .filter(|item| {
+                    let sharers = bill.item_sharers(item);
+                    if sharers.is_empty() {
+                        !bill.is_exempt(share.participant_id)
+                    } else {
+                        sharers.contains(&share.participant_id)
+                    }
+                })
+                .count() as u32,
+            exempt_reason: bill
+                .exemption(share.participant_id)
+                .map(|exemption| exemption.reason.clone()),
+            name: share.name,
+            total: share.amount_owed,
+        })
+        .collect();
+    Ok(HttpResponse::Ok().json(compact))
+}
+
+/// How the participant's share of the latest split compares with the
+/// average.
+#[get("/bills/{id}/participants/{participant_id}/comparative-spend")]
+async fn get_comparative_spend(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, participant_id) = path.into_inner();
+    let bill = load_bill(&state.repo(), id).await?;
+    ensure_participant(&bill, participant_id)?;
+    let snapshot = bill
+        .latest_split()
+        .ok_or_else(|| ApiError::InsufficientData(t("no_split_yet")))?;
+    let comparison = comparative_spend(&snapshot.shares, participant_id).ok_or_else(|| {
+        ApiError::InsufficientData(format!(
+            "Participant {participant_id} joined after the latest split"
+        ))
+    })?;
+    Ok(HttpResponse::Ok().json(comparison))
+}
+
+/// The fraction of each line item each participant pays in the itemised
+/// split, for tracking down where shares come from.
+#[get("/bills/{id}/split/allocation-matrix")]
+async fn get_allocation_matrix(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let participants = repo.get_bill_participants(&bill).await?;
+    Ok(HttpResponse::Ok().json(allocation_matrix(&bill, &participants)?))
+}
+
+/// Which line items move each sharer's cost the most, per 1% price change.
+#[get("/bills/{id}/split/sensitivity")]
+async fn split_sensitivity(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let bill = load_bill(&state.repo(), id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(sensitivity(&bill)))
+}
+
+/// The bill's shares under equal, equally weighted proportional and itemised
+/// splits side by side. Nothing is recorded.
+#[get("/bills/{id}/split/comparison")]
+async fn split_comparison(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let participants = repo.get_bill_participants(&bill).await?;
+    Ok(HttpResponse::Ok().json(compare_methods(&bill, &participants)?))
+}
+
+/// Which split method suits the bill, from how its items are shared.
+#[get("/bills/{id}/recommended-split-method")]
+async fn recommended_split_method(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let participants = repo.get_bill_participants(&bill).await?;
+    let recommendation = recommend_method(&bill, &participants).ok_or_else(|| {
+        ApiError::InsufficientData(
+            "A recommendation needs at least one participant and one line item".to_string(),
+        )
+    })?;
+    Ok(HttpResponse::Ok().json(recommendation))
+}
+
+#[derive(Deserialize)]
+struct VisualisationQuery {
+    #[serde(default)]
+    method: SplitMethod,
+}
+
+/// One method's shares from the comparison as a plain-text bar chart.
+/// `proportional` uses equal weights; `custom` needs amounts, so it is not
+/// offered.
+#[get("/bills/{id}/split/comparison/visualisation")]
+async fn split_comparison_visualisation(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    query: web::Query<VisualisationQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let participants = repo.get_bill_participants(&bill).await?;
+    let comparison = compare_methods(&bill, &participants)?;
+    let shares = match (query.method, comparison.shares(query.method)) {
+        (_, Some(shares)) => shares,
+        (SplitMethod::Custom, None) => {
+            return Err(ApiError::BadRequest(
+                "the `custom` method needs amounts, so it is not part of the comparison"
+                    .to_string(),
+            ))
+        }
+        (_, None) => {
+            return Err(ApiError::InsufficientData(
+                "every line item must be assigned to someone for an itemised split".to_string(),
+            ))
+        }
+    };
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .body(bar_chart(shares)))
+}
+
+#[derive(Deserialize)]
+struct SimulateQuery {
+    /// Flat amount taken off the bill total.
+    add_discount: Option<Money>,
+    /// Fraction of the bill total taken off, e.g. `0.15` for 15%.
+    add_discount_pct: Option<Decimal>,
+}
+
+/// Previews the split under a hypothetical change without touching the
+/// stored bill's items: line items taken out (repeated `?remove_item=`)
+/// and/or a bill-wide discount (`?add_discount=` or `?add_discount_pct=`).
+/// Only the bill's actual split is recorded, not the hypothetical one.
+#[get("/bills/{id}/split/simulate")]
+async fn simulate_split(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    query: web::Query<SplitQuery>,
+    simulate: web::Query<SimulateQuery>,
+    pairs: web::Query<Vec<(String, String)>>,
+) -> Result<HttpResponse, ApiError> {
+    let remove_items = pairs
+        .iter()
+        .filter(|(key, _)| key == "remove_item")
+        .map(|(_, value)| {
+            value.parse::<Uuid>().map_err(|_| {
+                ApiError::BadRequest(format!("`remove_item` value `{value}` is not a valid id"))
+            })
+        })
+        .collect::<Result<HashSet<Uuid>, ApiError>>()?;
+
+    if simulate.add_discount.is_some() && simulate.add_discount_pct.is_some() {
+        return Err(ApiError::BadRequest(
+            "Pass either `add_discount` or `add_discount_pct`, not both".to_string(),
+        ));
+    }
+    if remove_items.is_empty()
+        && simulate.add_discount.is_none()
+        && simulate.add_discount_pct.is_none()
+    {
+        return Err(ApiError::BadRequest(
+            "Nothing to simulate: pass `remove_item`, `add_discount` or `add_discount_pct`"
+                .to_string(),
+        ));
+    }
+
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    if let Some(unknown) = remove_items
+        .iter()
+        .find(|item_id| !bill.line_items.iter().any(|item| item.id == **item_id))
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Line item {unknown} is not part of this bill"
+        )));
+    }
+    let mut simulated = bill.clone();
+    simulated
+        .line_items
+        .retain(|item| !remove_items.contains(&item.id));
+
+    let discount = match (simulate.add_discount, simulate.add_discount_pct) {
+        (Some(amount), _) => Some(amount),
+        (_, Some(pct)) => {
+            if pct <= Decimal::ZERO || pct > Decimal::ONE {
+                return Err(ApiError::BadRequest(
+                    "`add_discount_pct` must be greater than 0 and at most 1".to_string(),
+                ));
+            }
+            Some(Money::from_decimal(simulated.total().to_decimal() * pct))
+        }
+        (None, None) => None,
+    };
+    if let Some(discount) = discount {
+        if discount <= Money::ZERO {
+            return Err(ApiError::BadRequest(
+                "`add_discount` must be positive".to_string(),
+            ));
+        }
+        if discount > simulated.total() {
+            return Err(ApiError::BadRequest(format!(
+                "Discount of {discount} exceeds the bill total of {}",
+                simulated.total()
+            )));
+        }
+        simulated.discount += discount;
+    }
+
+    let original = RecordedSplit::compute(&repo, bill, &query).await?;
+    let hypothetical = compute_split(&simulated, &original.participants, &original.spec)?;
+
+    Ok(HttpResponse::Ok().json(SplitDiff::new(original.result.shares, hypothetical.shares)))
+}
+
+#[derive(Deserialize)]
+struct WhatIfQuery {
+    /// Line item whose unit price changes.
+    change_price: Uuid,
+    /// Its hypothetical unit price.
+    to: Money,
+}
+
+/// Previews the split with one line item's unit price changed, without
+/// touching the stored bill's items. Only the bill's actual split is
+/// recorded, not the hypothetical one.
+#[get("/bills/{id}/split/what-if")]
+async fn what_if_price(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    query: web::Query<SplitQuery>,
+    what_if: web::Query<WhatIfQuery>,
+) -> Result<HttpResponse, ApiError> {
+    if what_if.to <= Money::ZERO {
+        return Err(ApiError::BadRequest("`to` must be positive".to_string()));
+    }
+
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let Some(index) = bill
+        .line_items
+        .iter()
+        .position(|item| item.id == what_if.change_price)
+    else {
+        return Err(ApiError::BadRequest(format!(
+            "Line item {} is not part of this bill",
+            what_if.change_price
+        )));
+    };
+    let mut hypothetical_bill = bill.clone();
+    hypothetical_bill.line_items[index].unit_price = what_if.to;
+    if !hypothetical_bill.line_items[index].is_within_limit() {
+        return Err(ApiError::BadRequest(format!(
+            "`to` times the item's quantity must not exceed {MAX_LINE_ITEM_TOTAL}"
+        )));
+    }
+
+    let original = RecordedSplit::compute(&repo, bill, &query).await?;
+    let hypothetical = compute_split(&hypothetical_bill, &original.participants, &original.spec)?;
+
+    Ok(HttpResponse::Ok().json(WhatIfPriceResult::new(
+        what_if.change_price,
+        original.bill.line_items[index].unit_price,
+        what_if.to,
+        original.result.shares,
+        hypothetical.shares,
+    )))
+}
+
+/// Saves how the bill is split, used by every split endpoint when the request
+/// does not give a `method`. Itemised assignments are applied to the line
+/// items straight away.
+#[put("/bills/{id}/split-config")]
+async fn put_split_config(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    body: web::Json<SplitConfig>,
+) -> Result<HttpResponse, ApiError> {
+    let config = body.into_inner();
+    config.spec().map_err(ApiError::BadRequest)?;
+    let repo = state.repo_for(&req);
+    let mut bill = load_bill(&repo, id.into_inner()).await?;
+    ensure_editable(&bill)?;
+
+    let participant_ids = config
+        .weights
+        .iter()
+        .flat_map(|weights| weights.keys())
+        .chain(config.amounts.iter().flat_map(|amounts| amounts.keys()))
+        .chain(
+            config
+                .assignments
+                .iter()
+                .flat_map(|assignments| assignments.values().flatten()),
+        );
+    for participant_id in participant_ids {
+        if !bill.has_participant(*participant_id) {
+            return Err(ApiError::BadRequest(t_with(
+                "participant_not_on_bill",
+                &[("id", participant_id)],
+            )));
+        }
+    }
+    if let Some(assignments) = &config.assignments {
+        if config.method != SplitMethod::Itemised {
+            return Err(ApiError::BadRequest(
+                "`assignments` only apply to the `itemised` method".to_string(),
+            ));
+        }
+        for (item_id, participant_ids) in assignments {
+            if participant_ids.is_empty() {
+                return Err(ApiError::BadRequest(format!(
+                    "Line item {item_id} must be assigned to at least one participant"
+                )));
+            }
+            let item = bill
+                .line_items
+                .iter_mut()
+                .find(|item| item.id == *item_id)
+                .ok_or_else(|| {
+                    ApiError::BadRequest(format!("Line item {item_id} is not part of this bill"))
+                })?;
+            item.set_participants(participant_ids.clone());
+        }
+    }
+
+    bill.split_config = Some(config);
+    bill.touch();
+    repo.put_bill(&bill).await?;
+
+    Ok(HttpResponse::Ok().json(&bill.split_config))
+}
+
+/// Every split computed for the bill, oldest first. Each endpoint that
+/// computes the bill's own split records one; comparisons across methods and
+/// replays do not.
+#[get("/bills/{id}/split-history")]
+async fn get_split_history(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let bill = load_bill(&state.repo(), id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(bill.split_history))
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum FairnessFormat {
+    #[default]
+    Json,
+    Text,
+}
+
+#[derive(Deserialize)]
+struct FairnessQuery {
+    #[serde(default)]
+    format: FairnessFormat,
+}
+
+/// The Gini coefficient of each recorded split, to show whether edits made
+/// the bill fairer. `?format=text` gives a sparkline of the trend instead.
+#[get("/bills/{id}/fairness-history")]
+async fn get_fairness_history(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    query: web::Query<FairnessQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let bill = load_bill(&state.repo(), id.into_inner()).await?;
+    let points = fairness_history(&bill.split_history);
+    Ok(match query.format {
+        FairnessFormat::Json => HttpResponse::Ok().json(points),
+        FairnessFormat::Text => HttpResponse::Ok()
+            .content_type("text/plain; charset=utf-8")
+            .body(fairness_sparkline(&points)),
+    })
+}
+
+#[get("/bills/{id}/split-history/{snapshot_id}")]
+async fn get_split_snapshot(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, snapshot_id) = path.into_inner();
+    let bill = load_bill(&state.repo(), id).await?;
+    let snapshot = bill
+        .split_history
+        .into_iter()
+        .find(|snapshot| snapshot.id == snapshot_id)
+        .ok_or_else(|| ApiError::NotFound(format!("Split snapshot {snapshot_id} not found")))?;
+    Ok(HttpResponse::Ok().json(snapshot))
+}
+
+#[derive(Deserialize)]
+struct ReplayQuery {
+    /// Snapshots do not keep the weights or amounts they were computed with,
+    /// so these are required again to replay a `proportional` or `custom` split.
+    weights: Option<String>,
+    amounts: Option<String>,
+}
+
+/// Applies a recorded snapshot's split method to the bill as it is now, so
+/// the effect of later corrections can be compared. Nothing is recorded.
+#[get("/bills/{id}/split/history/{snapshot_id}/replay")]
+async fn replay_split_snapshot(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    query: web::Query<ReplayQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, snapshot_id) = path.into_inner();
+    let repo = state.repo();
+    let bill = load_bill(&repo, id).await?;
+    let original = bill
+        .split_history
+        .iter()
+        .find(|snapshot| snapshot.id == snapshot_id)
+        .ok_or_else(|| ApiError::NotFound(format!("Split snapshot {snapshot_id} not found")))?;
+    let query = query.into_inner();
+    let spec = SplitQuery {
+        method: Some(original.method),
+        weights: query.weights,
+        amounts: query.amounts,
+        require_acceptance: false,
+    }
+    .spec(&bill)?;
+    let participants = repo.get_bill_participants(&bill).await?;
+
+    let result = compute_split(&bill, &participants, &spec)?;
+    Ok(HttpResponse::Ok().json(json!({
+        "original_snapshot": original,
+        "replayed_snapshot": SplitSnapshot::from_result(&result)
+    })))
+}
+
+#[derive(Deserialize)]
+struct ClearHistoryQuery {
+    /// How many of the most recent snapshots to keep.
+    #[serde(default = "default_keep_last")]
+    keep_last: usize,
+}
+
+fn default_keep_last() -> usize {
+    1
+}
+
+/// Drops all but the `keep_last` most recent snapshots.
+#[delete("/bills/{id}/split-history")]
+async fn clear_split_history(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    query: web::Query<ClearHistoryQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo_for(&req);
+    let mut bill = load_bill(&repo, id.into_inner()).await?;
+    ensure_editable(&bill)?;
+
+    let deleted = bill.split_history.len().saturating_sub(query.keep_last);
+    if deleted > 0 {
+        bill.split_history.drain(..deleted);
+        bill.touch();
+        repo.put_bill(&bill).await?;
+    }
+
+    Ok(HttpResponse::Ok().json(json!({
+        "deleted": deleted,
+        "remaining": bill.split_history.len()
+    })))
+}
+
+#[delete("/bills/{id}/split-history/{snapshot_id}")]
+async fn delete_split_snapshot(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, snapshot_id) = path.into_inner();
+    let repo = state.repo_for(&req);
+    let mut bill = load_bill(&repo, id).await?;
+    ensure_editable(&bill)?;
+
+    let position = bill
+        .split_history
+        .iter()
+        .position(|snapshot| snapshot.id == snapshot_id)
+        .ok_or_else(|| ApiError::NotFound(format!("Split snapshot {snapshot_id} not found")))?;
+    bill.split_history.remove(position);
+    bill.touch();
+    repo.put_bill(&bill).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[derive(Deserialize)]
+struct ShareInput {
+    participant_id: Uuid,
+    amount_owed: Money,
+}
+
+#[derive(Deserialize)]
+struct AdjustRoundingBody {
+    shares: Vec<ShareInput>,
+}
+
+/// Takes shares computed elsewhere (e.g. by a client working in floats) and
+/// returns them corrected so they sum to the bill total. Only rounding-sized
+/// differences — at most one cent per share — are accepted.
+#[post("/bills/{id}/split/adjust-rounding")]
+async fn adjust_rounding(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    body: web::Json<AdjustRoundingBody>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo();
+    let bill = load_bill(&repo, id.into_inner()).await?;
+    let body = body.into_inner();
+
+    if body.shares.is_empty() {
+        return Err(ApiError::BadRequest(t_with(
+            "must_not_be_empty",
+            &[("field", &"shares")],
+        )));
+    }
+
+    let mut seen = HashSet::new();
+    let mut shares = Vec::with_capacity(body.shares.len());
+    for input in body.shares {
+        if !bill.has_participant(input.participant_id) {
+            return Err(ApiError::BadRequest(t_with(
+                "participant_not_on_bill",
+                &[("id", &input.participant_id)],
+            )));
+        }
+        if !seen.insert(input.participant_id) {
+            return Err(ApiError::BadRequest(format!(
+                "Participant {} appears more than once",
+                input.participant_id
+            )));
+        }
+        let participant = repo
+            .get_participant(input.participant_id)
+            .await?
+            .ok_or_else(|| {
+                ApiError::NotFound(t_with(
+                    "participant_not_found",
+                    &[("id", &input.participant_id)],
+                ))
+            })?;
+        shares.push(ParticipantShare {
+            participant_id: participant.id,
+            name: participant.name,
+            amount_owed: input.amount_owed,
+        });
+    }
+
+    let total = bill.total();
+    let allocated: Money = shares.iter().map(|share| share.amount_owed).sum();
+    let max_remainder = Money::from_cents(shares.len() as i64);
+    if (total - allocated).abs() > max_remainder {
+        return Err(ApiError::BadRequest(format!(
+            "Shares add up to {allocated} but the bill total is {total}; only rounding differences can be adjusted"
+        )));
+    }
+
+    Ok(HttpResponse::Ok().json(distribute_rounding_remainder(shares, total)))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_split)
+        .service(simulate_split)
+        .service(what_if_price)
+        .service(get_inequality_warning)
+        .service(explain)
+        .service(get_debt_chain)
+        .service(preview_as)
+        .service(get_rounding_report)
+        .service(get_split_rounded_to_nearest)
+        .service(get_currency_breakdown)
+        .service(get_compact_breakdown)
+        .service(split_sensitivity)
+        .service(get_tax_inclusive_split)
+        .service(queue_split)
+        .service(get_comparative_spend)
+        .service(get_allocation_matrix)
+        .service(get_split_job)
+        .service(split_comparison)
+        .service(split_comparison_visualisation)
+        .service(recommended_split_method)
+        .service(adjust_rounding)
+        .service(put_split_config)
+        .service(get_split_history)
+        .service(get_fairness_history)
+        .service(get_split_snapshot)
+        .service(replay_split_snapshot)
+        .service(clear_split_history)
+        .service(delete_split_snapshot);
+}
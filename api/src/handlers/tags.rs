@@ -0,0 +1,99 @@
+use actix_web::{delete, post, web, HttpRequest, HttpResponse};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::{ensure_not_archived, load_bill};
+use crate::{
+    error::ApiError,
+    models::{BillTag, HexColor, MAX_TAGS_PER_BILL},
+    state::AppState,
+    storage::KvRepository,
+};
+
+/// Either an existing tag's id, or a name to reuse or create the tag by.
+#[derive(Deserialize)]
+struct AttachTagBody {
+    tag_id: Option<Uuid>,
+    name: Option<String>,
+    /// Only used when a new tag is created.
+    color: Option<HexColor>,
+}
+
+async fn resolve_tag(repo: &KvRepository<'_>, body: AttachTagBody) -> Result<BillTag, ApiError> {
+    match (body.tag_id, body.name) {
+        (Some(id), None) => repo
+            .get_tag(id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Tag {id} not found"))),
+        (None, Some(name)) => {
+            let name = BillTag::normalise_name(&name).map_err(ApiError::BadRequest)?;
+            if let Some(tag) = repo.find_tag(&name).await? {
+                return Ok(tag);
+            }
+            let tag = BillTag::new(&name, body.color).map_err(ApiError::BadRequest)?;
+            repo.put_tag(&tag).await?;
+            Ok(tag)
+        }
+        _ => Err(ApiError::BadRequest(
+            "Pass either `tag_id` or `name`".to_string(),
+        )),
+    }
+}
+
+/// Tags a bill with an existing tag, or one created on the fly from `name`.
+/// Attaching a tag the bill already has is a no-op.
+#[post("/bills/{id}/tags")]
+async fn attach_tag(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+    body: web::Json<AttachTagBody>,
+) -> Result<HttpResponse, ApiError> {
+    let repo = state.repo_for(&req);
+    let mut bill = load_bill(&repo, id.into_inner()).await?;
+    ensure_not_archived(&bill)?;
+    let tag = resolve_tag(&repo, body.into_inner()).await?;
+    if bill.tags.iter().any(|existing| existing.id == tag.id) {
+        return Ok(HttpResponse::Ok().json(bill));
+    }
+    if bill.tags.len() >= MAX_TAGS_PER_BILL {
+        return Err(ApiError::Conflict(format!(
+            "Bills can have at most {MAX_TAGS_PER_BILL} tags"
+        )));
+    }
+
+    repo.index_tagged_bill(&tag.name, bill.id).await?;
+    bill.tags.push(tag);
+    bill.touch();
+    repo.put_bill(&bill).await?;
+
+    Ok(HttpResponse::Ok().json(bill))
+}
+
+#[delete("/bills/{id}/tags/{tag_id}")]
+async fn detach_tag(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, tag_id) = path.into_inner();
+    let repo = state.repo_for(&req);
+    let mut bill = load_bill(&repo, id).await?;
+    ensure_not_archived(&bill)?;
+    let position = bill
+        .tags
+        .iter()
+        .position(|tag| tag.id == tag_id)
+        .ok_or_else(|| ApiError::NotFound(format!("Bill {id} is not tagged {tag_id}")))?;
+
+    let tag = bill.tags.remove(position);
+    bill.touch();
+    repo.put_bill(&bill).await?;
+    repo.unindex_tagged_bill(&tag.name, bill.id).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(attach_tag).service(detach_tag);
+}
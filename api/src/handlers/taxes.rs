@@ -0,0 +1,18 @@
+use actix_web::{get, web, HttpResponse};
+use uuid::Uuid;
+
+use super::load_bill;
+use crate::{error::ApiError, state::AppState, tax::summarise_taxes};
+
+#[get("/bills/{id}/taxes")]
+async fn get_taxes(
+    state: web::Data<AppState>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let bill = load_bill(&state.repo(), id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(summarise_taxes(&bill)))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_taxes);
+}
@@ -0,0 +1,52 @@
+use actix_web::{http::header::HeaderName, HttpRequest};
+
+/// `Prefer` request header (RFC 7240).
+pub const PREFER: HeaderName = HeaderName::from_static("prefer");
+/// Response header confirming which preference was honoured.
+pub const PREFERENCE_APPLIED: HeaderName = HeaderName::from_static("preference-applied");
+
+/// What the client asked a mutating endpoint to send back.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReturnPreference {
+    /// No `return=` preference; the endpoint picks its own default.
+    #[default]
+    Unspecified,
+    /// `Prefer: return=minimal`
+    Minimal,
+    /// `Prefer: return=representation`
+    Representation,
+}
+
+impl ReturnPreference {
+    /// Value for `Preference-Applied`, if a preference was given.
+    pub fn applied(self) -> Option<&'static str> {
+        match self {
+            ReturnPreference::Unspecified => None,
+            ReturnPreference::Minimal => Some("return=minimal"),
+            ReturnPreference::Representation => Some("return=representation"),
+        }
+    }
+}
+
+/// Reads the `return` preference from any `Prefer` headers on the request.
+/// Unknown preferences are ignored, as RFC 7240 requires.
+pub fn prefer_return(req: &HttpRequest) -> ReturnPreference {
+    req.headers()
+        .get_all(PREFER)
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|preference| {
+            let token = preference.split(';').next()?.trim();
+            let (name, value) = token.split_once('=')?;
+            if !name.trim().eq_ignore_ascii_case("return") {
+                return None;
+            }
+            match value.trim().trim_matches('"').to_ascii_lowercase().as_str() {
+                "minimal" => Some(ReturnPreference::Minimal),
+                "representation" => Some(ReturnPreference::Representation),
+                _ => None,
+            }
+        })
+        .next()
+        .unwrap_or_default()
+}
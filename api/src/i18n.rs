@@ -0,0 +1,212 @@
+//! Minimal message catalogue for user-facing `message` fields.
+//!
+//! The locale is negotiated from `Accept-Language` by
+//! [`crate::middleware::locale`] and kept in a task-local for the duration of
+//! the request, so handlers can call [`t`] / [`t_with`] without threading it
+//! through. Unknown keys and untranslated messages fall back to English.
+
+use std::{collections::HashMap, future::Future, sync::OnceLock};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Locale {
+    #[default]
+    En,
+    Es,
+    Fr,
+}
+
+impl Locale {
+    pub const SUPPORTED: &'static [Locale] = &[Locale::En, Locale::Es, Locale::Fr];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Es => "es",
+            Locale::Fr => "fr",
+        }
+    }
+
+    fn from_primary_tag(tag: &str) -> Option<Self> {
+        Self::SUPPORTED
+            .iter()
+            .copied()
+            .find(|locale| locale.code().eq_ignore_ascii_case(tag))
+    }
+}
+
+/// Picks the best supported locale for an `Accept-Language` header value,
+/// honouring `q` weights. Region subtags are ignored (`fr-CH` matches `fr`).
+pub fn negotiate(accept_language: &str) -> Locale {
+    let mut ranges: Vec<(&str, f32)> = accept_language
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.trim().split(';');
+            let tag = parts.next()?.trim();
+            if tag.is_empty() {
+                return None;
+            }
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .and_then(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            Some((tag, quality))
+        })
+        .filter(|(_, quality)| *quality > 0.0)
+        .collect();
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    ranges
+        .into_iter()
+        .find_map(|(tag, _)| {
+            if tag == "*" {
+                return Some(Locale::default());
+            }
+            Locale::from_primary_tag(tag.split('-').next().unwrap_or(tag))
+        })
+        .unwrap_or_default()
+}
+
+type Catalogue = HashMap<(&'static str, &'static str), &'static str>;
+
+fn catalogue() -> &'static Catalogue {
+    static CATALOGUE: OnceLock<Catalogue> = OnceLock::new();
+    CATALOGUE.get_or_init(|| {
+        [
+            ("en", "not_found", "The requested resource was not found"),
+            ("es", "not_found", "No se encontró el recurso solicitado"),
+            ("fr", "not_found", "La ressource demandée est introuvable"),
+            ("en", "internal_error", "An unexpected error occurred"),
+            ("es", "internal_error", "Se produjo un error inesperado"),
+            (
+                "fr",
+                "internal_error",
+                "Une erreur inattendue s'est produite",
+            ),
+            ("en", "bad_request", "The request was invalid"),
+            ("es", "bad_request", "La solicitud no es válida"),
+            ("fr", "bad_request", "La requête est invalide"),
+            ("en", "generic_error", "An error occurred"),
+            ("es", "generic_error", "Se produjo un error"),
+            ("fr", "generic_error", "Une erreur s'est produite"),
+            (
+                "en",
+                "method_not_allowed",
+                "{method} is not supported for {path}",
+            ),
+            (
+                "es",
+                "method_not_allowed",
+                "{method} no está permitido para {path}",
+            ),
+            (
+                "fr",
+                "method_not_allowed",
+                "{method} n'est pas pris en charge pour {path}",
+            ),
+            ("en", "route_not_found", "No route matches {path}"),
+            ("es", "route_not_found", "Ninguna ruta coincide con {path}"),
+            (
+                "fr",
+                "route_not_found",
+                "Aucune route ne correspond à {path}",
+            ),
+            ("en", "bill_not_found", "Bill {id} not found"),
+            ("es", "bill_not_found", "No se encontró la cuenta {id}"),
+            ("fr", "bill_not_found", "Addition {id} introuvable"),
+            ("en", "participant_not_found", "Participant {id} not found"),
+            (
+                "es",
+                "participant_not_found",
+                "No se encontró el participante {id}",
+            ),
+            (
+                "fr",
+                "participant_not_found",
+                "Participant {id} introuvable",
+            ),
+            (
+                "en",
+                "participant_not_on_bill",
+                "Participant {id} is not part of this bill",
+            ),
+            (
+                "es",
+                "participant_not_on_bill",
+                "El participante {id} no forma parte de esta cuenta",
+            ),
+            (
+                "fr",
+                "participant_not_on_bill",
+                "Le participant {id} ne fait pas partie de cette addition",
+            ),
+            ("en", "no_participants", "The bill has no participants"),
+            ("es", "no_participants", "La cuenta no tiene participantes"),
+            ("fr", "no_participants", "L'addition n'a aucun participant"),
+            (
+                "en",
+                "no_split_yet",
+                "No split has been computed for this bill yet",
+            ),
+            (
+                "es",
+                "no_split_yet",
+                "Todavía no se ha calculado ningún reparto para esta cuenta",
+            ),
+            (
+                "fr",
+                "no_split_yet",
+                "Aucune répartition n'a encore été calculée pour cette addition",
+            ),
+            ("en", "must_not_be_empty", "`{field}` must not be empty"),
+            ("es", "must_not_be_empty", "`{field}` no puede estar vacío"),
+            ("fr", "must_not_be_empty", "`{field}` ne doit pas être vide"),
+        ]
+        .into_iter()
+        .map(|(locale, key, text)| ((locale, key), text))
+        .collect()
+    })
+}
+
+/// Looks up `key` for `locale`, falling back to English and then to the key.
+pub fn translate(locale: Locale, key: &'static str) -> &'static str {
+    let catalogue = catalogue();
+    catalogue
+        .get(&(locale.code(), key))
+        .or_else(|| catalogue.get(&(Locale::En.code(), key)))
+        .copied()
+        .unwrap_or(key)
+}
+
+/// Like [`translate`], substituting `{name}` placeholders from `args`.
+pub fn translate_with(locale: Locale, key: &'static str, args: &[(&str, &dyn ToString)]) -> String {
+    args.iter()
+        .fold(translate(locale, key).to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), &value.to_string())
+        })
+}
+
+tokio::task_local! {
+    static CURRENT_LOCALE: Locale;
+}
+
+/// Runs `future` with `locale` as the current request locale.
+pub async fn with_locale<F: Future>(locale: Locale, future: F) -> F::Output {
+    CURRENT_LOCALE.scope(locale, future).await
+}
+
+/// Locale of the request being handled, or English outside a request.
+pub fn current_locale() -> Locale {
+    CURRENT_LOCALE
+        .try_with(|locale| *locale)
+        .unwrap_or_default()
+}
+
+/// Translates `key` into the current request locale.
+pub fn t(key: &'static str) -> String {
+    translate(current_locale(), key).to_string()
+}
+
+/// Translates `key` into the current request locale with placeholders filled.
+pub fn t_with(key: &'static str, args: &[(&str, &dyn ToString)]) -> String {
+    translate_with(current_locale(), key, args)
+}
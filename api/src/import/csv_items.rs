@@ -0,0 +1,217 @@
+//! Line items imported from CSV.
+//!
+//! The first row names the columns: `description` and `unit_price` are
+//! required, `quantity`, `tax_rate` and `category` optional, in any order.
+//! Fields may be quoted with `"`, doubling quotes inside them. Rows are
+//! numbered as lines of the file, the header being row 1, so errors point
+//! at the same row a spreadsheet shows.
+
+use std::fmt;
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::models::{LineItem, Money, MAX_LINE_ITEM_TOTAL};
+
+const COLUMNS: [&str; 5] = [
+    "description",
+    "quantity",
+    "unit_price",
+    "tax_rate",
+    "category",
+];
+
+/// Why a single row was skipped.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RowError {
+    pub row: usize,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImportLineItemsResult {
+    pub imported: usize,
+    pub skipped: usize,
+    pub errors: Vec<RowError>,
+}
+
+/// Line items parsed from a CSV, and the rows that could not be used.
+#[derive(Debug, Clone)]
+pub struct ParsedLineItems {
+    pub items: Vec<LineItem>,
+    pub errors: Vec<RowError>,
+}
+
+impl ParsedLineItems {
+    pub fn result(&self) -> ImportLineItemsResult {
+        ImportLineItemsResult {
+            imported: self.items.len(),
+            skipped: self.errors.len(),
+            errors: self.errors.clone(),
+        }
+    }
+}
+
+/// The file as a whole is unusable.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CsvImportError {
+    Empty,
+    UnknownColumn(String),
+    DuplicateColumn(String),
+    MissingColumn(&'static str),
+    UnterminatedQuote { row: usize },
+}
+
+impl fmt::Display for CsvImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvImportError::Empty => f.write_str("The CSV has no header row"),
+            CsvImportError::UnknownColumn(name) => write!(
+                f,
+                "Unknown column `{name}`; expected {}",
+                COLUMNS.join(", ")
+            ),
+            CsvImportError::DuplicateColumn(name) => write!(f, "Column `{name}` appears twice"),
+            CsvImportError::MissingColumn(name) => write!(f, "The `{name}` column is required"),
+            CsvImportError::UnterminatedQuote { row } => {
+                write!(f, "Row {row} has a quoted field that is never closed")
+            }
+        }
+    }
+}
+
+/// Parses `csv` into line items. Blank rows are ignored; rows that fail
+/// validation are reported in [`ParsedLineItems::errors`] and left out.
+pub fn parse_line_items(csv: &str) -> Result<ParsedLineItems, CsvImportError> {
+    let mut records = records(csv)?.into_iter();
+    let (_, header) = records.next().ok_or(CsvImportError::Empty)?;
+
+    let mut columns: [Option<usize>; COLUMNS.len()] = [None; COLUMNS.len()];
+    for (index, name) in header.iter().enumerate() {
+        let name = name.trim().to_ascii_lowercase();
+        let slot = COLUMNS
+            .iter()
+            .position(|column| *column == name)
+            .ok_or_else(|| CsvImportError::UnknownColumn(name.clone()))?;
+        if columns[slot].replace(index).is_some() {
+            return Err(CsvImportError::DuplicateColumn(name));
+        }
+    }
+    for required in ["description", "unit_price"] {
+        let slot = COLUMNS
+            .iter()
+            .position(|column| *column == required)
+            .unwrap();
+        if columns[slot].is_none() {
+            return Err(CsvImportError::MissingColumn(required));
+        }
+    }
+
+    let mut parsed = ParsedLineItems {
+        items: Vec::new(),
+        errors: Vec::new(),
+    };
+    for (row, fields) in records {
+        let field = |name: &str| {
+            let slot = COLUMNS.iter().position(|column| *column == name).unwrap();
+            columns[slot]
+                .and_then(|index| fields.get(index))
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+        };
+        match line_item(&field) {
+            Ok(item) => parsed.items.push(item),
+            Err(error) => parsed.errors.push(RowError { row, error }),
+        }
+    }
+    Ok(parsed)
+}
+
+fn line_item<'a>(field: &impl Fn(&str) -> Option<&'a str>) -> Result<LineItem, String> {
+    let description = field("description").ok_or("`description` must not be empty")?;
+    let quantity = match field("quantity") {
+        None => 1,
+        Some(value) => value
+            .parse::<u32>()
+            .ok()
+            .filter(|quantity| *quantity > 0)
+            .ok_or_else(|| format!("`quantity` `{value}` is not a whole number of at least 1"))?,
+    };
+    let unit_price = field("unit_price").ok_or("`unit_price` is required")?;
+    let unit_price: Money = unit_price
+        .parse()
+        .map_err(|_| format!("`unit_price` `{unit_price}` is not an amount like 12.50"))?;
+    if unit_price.is_negative() {
+        return Err("`unit_price` must not be negative".to_string());
+    }
+    let tax_rate = match field("tax_rate") {
+        None => None,
+        Some(value) => Some(
+            value
+                .parse::<Decimal>()
+                .ok()
+                .filter(|rate| !rate.is_sign_negative() && *rate <= Decimal::ONE)
+                .ok_or_else(|| format!("`tax_rate` `{value}` is not between 0 and 1"))?,
+        ),
+    };
+
+    let mut item = LineItem::new(description, quantity, unit_price);
+    if !item.is_within_limit() {
+        return Err(format!(
+            "`quantity` times `unit_price` must not exceed {MAX_LINE_ITEM_TOTAL}"
+        ));
+    }
+    item.tax_rate = tax_rate;
+    item.category = field("category").map(str::to_string);
+    Ok(item)
+}
+
+/// Splits `csv` into non-blank records with the row each one starts on.
+fn records(csv: &str) -> Result<Vec<(usize, Vec<String>)>, CsvImportError> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut row = 1;
+    let mut record_row = 1;
+    let mut in_quotes = false;
+    let mut chars = csv.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if field.is_empty() => in_quotes = true,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes && chars.peek() == Some(&'\n') => {}
+            '\n' if !in_quotes => {
+                fields.push(std::mem::take(&mut field));
+                push_record(&mut records, record_row, std::mem::take(&mut fields));
+                row += 1;
+                record_row = row;
+            }
+            '\n' => {
+                field.push(c);
+                row += 1;
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(CsvImportError::UnterminatedQuote { row: record_row });
+    }
+    fields.push(field);
+    push_record(&mut records, record_row, fields);
+    Ok(records)
+}
+
+fn push_record(records: &mut Vec<(usize, Vec<String>)>, row: usize, fields: Vec<String>) {
+    if fields.iter().any(|field| !field.trim().is_empty()) {
+        records.push((row, fields));
+    }
+}
@@ -0,0 +1,137 @@
+//! Formal invoices issued by a bill's payer.
+
+use std::sync::OnceLock;
+
+use chrono::{Days, NaiveDate, Utc};
+use handlebars::Handlebars;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    models::{Bill, CurrencyCode, Money, Participant},
+    tax::summarise_taxes,
+};
+
+const INVOICE_TEMPLATE: &str = "invoice";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InvoiceParty {
+    pub participant_id: Uuid,
+    pub name: String,
+    pub email: Option<String>,
+}
+
+impl From<&Participant> for InvoiceParty {
+    fn from(participant: &Participant) -> Self {
+        Self {
+            participant_id: participant.id,
+            name: participant.name.clone(),
+            email: participant.email.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InvoiceLine {
+    pub description: String,
+    pub quantity: u32,
+    pub unit_price: Money,
+    pub tax_rate: Option<Decimal>,
+    /// `quantity × unit_price`, tax included.
+    pub amount: Money,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Invoice {
+    pub invoice_number: String,
+    pub issued_date: NaiveDate,
+    pub due_date: NaiveDate,
+    pub issuer: InvoiceParty,
+    pub recipient: InvoiceParty,
+    pub bill_id: Uuid,
+    pub bill_title: String,
+    pub currency: CurrencyCode,
+    pub line_items: Vec<InvoiceLine>,
+    /// Bill-wide discount taken off the line amounts.
+    pub discount: Money,
+    /// `total` before tax.
+    pub subtotal: Money,
+    pub tax: Money,
+    pub total: Money,
+    pub payment_terms: String,
+}
+
+/// `INV-` followed by the first eight hex digits of the bill id, so the
+/// same bill always gets the same number.
+pub fn invoice_number(bill_id: Uuid) -> String {
+    let simple = bill_id.simple().to_string().to_ascii_uppercase();
+    format!("INV-{}", &simple[..8])
+}
+
+/// Invoices `recipient` for the whole of `bill` on behalf of `issuer`,
+/// dated today and due `payment_days` later. Line prices are tax-inclusive,
+/// as on the bill; `subtotal` and `tax` split the total the same way as
+/// `GET /bills/:id/taxes`.
+pub fn bill_to_invoice(
+    bill: &Bill,
+    issuer: &Participant,
+    recipient: &Participant,
+    payment_days: u32,
+) -> Invoice {
+    let issued_date = Utc::now().date_naive();
+    let due_date = issued_date
+        .checked_add_days(Days::new(payment_days.into()))
+        .unwrap_or(issued_date);
+    let taxes = summarise_taxes(bill);
+
+    Invoice {
+        invoice_number: invoice_number(bill.id),
+        issued_date,
+        due_date,
+        issuer: issuer.into(),
+        recipient: recipient.into(),
+        bill_id: bill.id,
+        bill_title: bill.title.clone(),
+        currency: bill.base_currency.clone(),
+        line_items: bill
+            .line_items
+            .iter()
+            .map(|item| InvoiceLine {
+                description: item.description.clone(),
+                quantity: item.quantity,
+                unit_price: item.unit_price,
+                tax_rate: item.tax_rate,
+                amount: item.total(),
+            })
+            .collect(),
+        discount: bill.discount,
+        subtotal: taxes.total_pre_tax,
+        tax: taxes.total_tax,
+        total: bill.total(),
+        payment_terms: format!(
+            "Net {payment_days}: payment is due within {payment_days} days of the invoice date"
+        ),
+    }
+}
+
+fn templates() -> &'static Handlebars<'static> {
+    static TEMPLATES: OnceLock<Handlebars<'static>> = OnceLock::new();
+    TEMPLATES.get_or_init(|| {
+        let mut handlebars = Handlebars::new();
+        handlebars.set_strict_mode(true);
+        handlebars
+            .register_template_string(INVOICE_TEMPLATE, include_str!("templates/invoice.hbs"))
+            .expect("invoice template is valid");
+        handlebars
+    })
+}
+
+/// Renders `invoice` as a printable HTML page.
+pub fn render_invoice_html(invoice: &Invoice) -> Result<String, handlebars::RenderError> {
+    let mut data = serde_json::to_value(invoice).expect("invoice serialises");
+    data["issued_date"] = invoice.issued_date.format("%-d %B %Y").to_string().into();
+    data["due_date"] = invoice.due_date.format("%-d %B %Y").to_string().into();
+    data["has_discount"] = (!invoice.discount.is_zero()).into();
+    templates().render(INVOICE_TEMPLATE, &data)
+}
@@ -0,0 +1,47 @@
+//! Nightly clean-up of bills that were started and never finished.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::{
+    models::{AuditAction, AuditEntry, BillStatus},
+    state::AppState,
+    storage::KvError,
+};
+
+/// What one run of [`expire_drafts`] did.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExpiryReport {
+    pub expired_count: u32,
+}
+
+/// Soft-deletes every draft created more than `draft_expiry_days` before
+/// `now`, recording an [`AuditAction::AutoExpired`] entry for each.
+pub async fn expire_drafts(state: &AppState, now: DateTime<Utc>) -> Result<ExpiryReport, KvError> {
+    let days = state.config.draft_expiry_days;
+    let cutoff = now - Duration::days(days.into());
+    let repo = state.repo();
+    let drafts = repo
+        .list_bills(None, usize::MAX, |bill| {
+            bill.status == BillStatus::Draft && bill.created_at < cutoff
+        })
+        .await?
+        .items;
+
+    let reason = format!("Draft older than {days} days");
+    for bill in &drafts {
+        repo.soft_delete_bill(bill).await?;
+        repo.append_audit_entry(&AuditEntry {
+            bill_id: bill.id,
+            action: AuditAction::AutoExpired,
+            reason: reason.clone(),
+            timestamp: now,
+            payer_change: None,
+        })
+        .await?;
+    }
+
+    Ok(ExpiryReport {
+        expired_count: drafts.len() as u32,
+    })
+}
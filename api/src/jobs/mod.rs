@@ -0,0 +1,60 @@
+//! Scheduled jobs. This is the in-process stand-in for Cloudflare Cron
+//! Triggers: [`spawn`] runs the nightly jobs once a day and checks for due
+//! reminders every [`REMINDER_CHECK_INTERVAL`] in the background, and the
+//! admin endpoints can run one on demand.
+
+use std::time::Duration;
+
+use actix_web::web;
+use chrono::Utc;
+
+use crate::state::AppState;
+
+pub mod expire_drafts;
+pub mod send_reminders;
+
+pub use expire_drafts::{expire_drafts, ExpiryReport};
+pub use send_reminders::{send_reminders, ReminderReport};
+
+/// How often the scheduled jobs run.
+pub const SCHEDULE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How often due reminders are sent; reminders fire up to this late.
+pub const REMINDER_CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Starts running the jobs on their intervals, the first run one interval
+/// after startup. Summaries are written to stderr, as a Worker's would be to
+/// its logs.
+pub fn spawn(state: web::Data<AppState>) {
+    let reminder_state = state.clone();
+    actix_web::rt::spawn(async move {
+        let mut interval = tokio::time::interval(REMINDER_CHECK_INTERVAL);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            match send_reminders(&reminder_state, Utc::now()).await {
+                Ok(report) if report.sent_count + report.skipped_count > 0 => eprintln!(
+                    "send_reminders: {}",
+                    serde_json::to_string(&report).unwrap_or_default()
+                ),
+                Ok(_) => {}
+                Err(err) => eprintln!("send_reminders failed: {err}"),
+            }
+        }
+    });
+    actix_web::rt::spawn(async move {
+        let mut interval = tokio::time::interval(SCHEDULE_INTERVAL);
+        // The first tick completes immediately.
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            match expire_drafts(&state, Utc::now()).await {
+                Ok(report) => eprintln!(
+                    "expire_drafts: {}",
+                    serde_json::to_string(&report).unwrap_or_default()
+                ),
+                Err(err) => eprintln!("expire_drafts failed: {err}"),
+            }
+        }
+    });
+}
@@ -0,0 +1,64 @@
+//! Sends the reminders scheduled by `POST /bills/:id/notify` once they fall
+//! due.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::{models::Money, queues::EmailTask, state::AppState, storage::KvError};
+
+/// What one run of [`send_reminders`] did.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReminderReport {
+    /// Reminders handed to the notification queue.
+    pub sent_count: u32,
+    /// Due reminders dropped because the share was paid or the bill is gone.
+    pub skipped_count: u32,
+}
+
+/// Queues a notification for every reminder due by `now`, removing it from
+/// the participant's pending reminders. Reminders the queue does not accept
+/// stay pending for the next run.
+pub async fn send_reminders(
+    state: &AppState,
+    now: DateTime<Utc>,
+) -> Result<ReminderReport, KvError> {
+    let repo = state.repo();
+    let mut report = ReminderReport {
+        sent_count: 0,
+        skipped_count: 0,
+    };
+
+    for (bill_id, participant_id) in repo.list_reminder_owners().await? {
+        let reminders = repo.get_reminders(bill_id, participant_id).await?;
+        let (due, mut pending): (Vec<_>, Vec<_>) = reminders
+            .into_iter()
+            .partition(|reminder| reminder.is_due(now));
+        if due.is_empty() {
+            continue;
+        }
+
+        let owes = repo
+            .get_bill(bill_id)
+            .await?
+            .and_then(|bill| bill.outstanding(participant_id))
+            .is_some_and(|owed| owed > Money::ZERO);
+        for reminder in due {
+            if !owes {
+                report.skipped_count += 1;
+            } else if state.notification_queue.send(EmailTask::new(
+                participant_id,
+                bill_id,
+                reminder.channel,
+            )) {
+                report.sent_count += 1;
+            } else {
+                pending.push(reminder);
+            }
+        }
+        pending.sort_by_key(|reminder| reminder.scheduled_for);
+        repo.put_reminders(bill_id, participant_id, &pending)
+            .await?;
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,75 @@
+// `openapi::schemas` is one large `json!` literal.
+#![recursion_limit = "256"]
+
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceFactory, ServiceRequest, ServiceResponse},
+    middleware::{from_fn, DefaultHeaders},
+    web, App, Error,
+};
+
+pub mod ai;
+pub mod analytics;
+pub mod auth;
+pub mod build_info;
+pub mod changelog;
+pub mod config;
+pub mod crypto;
+pub mod data;
+pub mod duplicates;
+pub mod error;
+pub mod exchange;
+pub mod explain;
+pub mod export;
+pub mod handlers;
+pub mod i18n;
+pub mod import;
+pub mod invoice;
+pub mod jobs;
+pub mod middleware;
+pub mod models;
+pub mod notifications;
+pub mod openapi;
+pub mod payment_links;
+pub mod qr;
+pub mod queues;
+pub mod receipt;
+pub mod resilience;
+pub mod routes;
+pub mod search;
+pub mod split;
+pub mod state;
+pub mod storage;
+pub mod tax;
+#[cfg(any(test, feature = "test-util"))]
+pub mod testing;
+pub mod util;
+pub mod validation;
+
+use middleware::{
+    cors::cors, error_handlers::error_handlers, locale::locale,
+    method_not_allowed::method_not_allowed,
+};
+use state::AppState;
+
+/// Builds the application with every API route and middleware registered.
+pub fn app(
+    state: web::Data<AppState>,
+) -> App<
+    impl ServiceFactory<
+        ServiceRequest,
+        Config = (),
+        Response = ServiceResponse<impl MessageBody>,
+        Error = Error,
+        InitError = (),
+    >,
+> {
+    App::new()
+        .app_data(state)
+        .configure(handlers::configure)
+        .default_service(web::to(method_not_allowed))
+        .wrap(from_fn(locale))
+        .wrap(from_fn(cors))
+        .wrap(error_handlers())
+        .wrap(DefaultHeaders::new().add((build_info::HEADER_NAME, build_info::header_value())))
+}
@@ -0,0 +1,58 @@
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::{self, HeaderValue},
+    middleware::Next,
+    web, Error,
+};
+
+use crate::{config::AllowedOrigins, state::AppState};
+
+/// `Access-Control-Allow-Origin` value for a request from `origin`, if that
+/// origin is allowed.
+pub fn allow_origin(allowed: &AllowedOrigins, origin: Option<&HeaderValue>) -> Option<HeaderValue> {
+    match allowed {
+        AllowedOrigins::Any => Some(HeaderValue::from_static("*")),
+        AllowedOrigins::List(origins) => {
+            let origin = origin?;
+            let value = origin.to_str().ok()?;
+            origins
+                .iter()
+                .any(|allowed| allowed == value)
+                .then(|| origin.clone())
+        }
+    }
+}
+
+/// Adds `Access-Control-Allow-Origin` to every response whose request came
+/// from an allowed origin.
+///
+/// With an explicit origin list the header echoes the caller's origin, so
+/// responses also get `Vary: Origin` to stop shared caches serving one
+/// origin's response to another. `Vary: Accept-Encoding` is always added.
+pub async fn cors(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let (origin, varies_by_origin) = match req.app_data::<web::Data<AppState>>() {
+        Some(state) => (
+            allow_origin(
+                &state.config.allowed_origins,
+                req.headers().get(header::ORIGIN),
+            ),
+            matches!(state.config.allowed_origins, AllowedOrigins::List(_)),
+        ),
+        None => (None, false),
+    };
+
+    let mut res = next.call(req).await?;
+    let headers = res.headers_mut();
+    if let Some(origin) = origin {
+        headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    }
+    if varies_by_origin {
+        headers.append(header::VARY, HeaderValue::from_static("Origin"));
+    }
+    headers.append(header::VARY, HeaderValue::from_static("Accept-Encoding"));
+    Ok(res)
+}
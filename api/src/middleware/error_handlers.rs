@@ -0,0 +1,58 @@
+use actix_web::{
+    dev::ServiceResponse,
+    http::{header, StatusCode},
+    middleware::{ErrorHandlerResponse, ErrorHandlers},
+    HttpResponse, Result,
+};
+use serde_json::json;
+
+use super::locale::request_locale;
+use crate::i18n::translate;
+
+/// Gives bare `404` and `500` responses the `{ error, message, status }` body.
+pub fn error_handlers<B: 'static>() -> ErrorHandlers<B> {
+    ErrorHandlers::new()
+        .handler(StatusCode::NOT_FOUND, generic_error_handler)
+        .handler(StatusCode::INTERNAL_SERVER_ERROR, generic_error_handler)
+}
+
+pub fn generic_error_handler<B>(res: ServiceResponse<B>) -> Result<ErrorHandlerResponse<B>> {
+    // Handlers that already produced a structured JSON error keep their body.
+    let is_json = res
+        .response()
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|value| value == "application/json");
+    if is_json {
+        return Ok(ErrorHandlerResponse::Response(res.map_into_left_body()));
+    }
+
+    let status_code = res.status();
+    let (error_message, description_key) = match status_code {
+        StatusCode::NOT_FOUND => ("Not Found", "not_found"),
+        StatusCode::INTERNAL_SERVER_ERROR => ("Internal Server Error", "internal_error"),
+        StatusCode::BAD_REQUEST => ("Bad Request", "bad_request"),
+        _ => ("Error", "generic_error"),
+    };
+    let locale = request_locale(res.request());
+    let description = translate(locale, description_key);
+
+    let mut response = HttpResponse::build(status_code)
+        .insert_header((header::CONTENT_LANGUAGE, locale.code()))
+        .json(json!({
+            "error": error_message,
+            "message": description,
+            "status": status_code.as_u16()
+        }));
+    // Keep headers set by inner middleware (CORS, Vary, ...); only the body
+    // and its metadata are replaced.
+    for (name, value) in res.response().headers() {
+        if !name.as_str().starts_with("content-") {
+            response.headers_mut().append(name.clone(), value.clone());
+        }
+    }
+
+    Ok(ErrorHandlerResponse::Response(
+        res.into_response(response.map_into_right_body()),
+    ))
+}
@@ -0,0 +1,34 @@
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::{self, HeaderValue},
+    middleware::Next,
+    Error, HttpRequest,
+};
+
+use crate::i18n::{self, Locale};
+
+/// Locale negotiated from the request's `Accept-Language` header.
+pub fn request_locale(req: &HttpRequest) -> Locale {
+    req.headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .map(i18n::negotiate)
+        .unwrap_or_default()
+}
+
+/// Makes the negotiated locale current while the request is handled and
+/// reports it back in `Content-Language`.
+pub async fn locale(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let locale = request_locale(req.request());
+
+    let mut res = i18n::with_locale(locale, next.call(req)).await?;
+    res.headers_mut().insert(
+        header::CONTENT_LANGUAGE,
+        HeaderValue::from_static(locale.code()),
+    );
+    Ok(res)
+}
@@ -0,0 +1,24 @@
+use actix_web::{http::header, HttpRequest, HttpResponse};
+use serde_json::json;
+
+use crate::{
+    i18n::t_with,
+    routes::{allow_header_value, allowed_methods},
+};
+
+/// Default service for requests no route accepted. Paths that exist under a
+/// different method get `405` with an `Allow` header; anything else is a
+/// plain `404` for the error handlers to format.
+pub async fn method_not_allowed(req: HttpRequest) -> HttpResponse {
+    let Some(methods) = allowed_methods(req.path()) else {
+        return HttpResponse::NotFound().finish();
+    };
+
+    HttpResponse::MethodNotAllowed()
+        .insert_header((header::ALLOW, allow_header_value(&methods)))
+        .json(json!({
+            "error": "Method Not Allowed",
+            "message": t_with("method_not_allowed", &[("method", req.method()), ("path", &req.path())]),
+            "status": 405
+        }))
+}
@@ -0,0 +1,4 @@
+pub mod cors;
+pub mod error_handlers;
+pub mod locale;
+pub mod method_not_allowed;
@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Something done to a bill that is kept on record after the bill is gone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditAction {
+    /// Soft-deleted by the nightly draft expiry job.
+    AutoExpired,
+    /// The bill's payer was set, changed or cleared; see
+    /// [`AuditEntry::payer_change`].
+    PayerChanged,
+}
+
+/// Who the payer was before and after a change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayerChange {
+    /// `None` when the payer was set for the first time.
+    pub previous_payer_id: Option<Uuid>,
+    pub new_payer_id: Option<Uuid>,
+    pub changed_at: DateTime<Utc>,
+    /// Name of the authenticated user who made the change, if known.
+    pub changed_by: Option<String>,
+}
+
+/// Record of an [`AuditAction`], kept after the bill itself is gone.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub bill_id: Uuid,
+    pub action: AuditAction,
+    pub reason: String,
+    pub timestamp: DateTime<Utc>,
+    /// Set for [`AuditAction::PayerChanged`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payer_change: Option<PayerChange>,
+}
+
+impl AuditEntry {
+    pub fn payer_changed(bill_id: Uuid, change: PayerChange) -> Self {
+        Self {
+            bill_id,
+            action: AuditAction::PayerChanged,
+            reason: "Payer changed".to_string(),
+            timestamp: change.changed_at,
+            payer_change: Some(change),
+        }
+    }
+}
@@ -0,0 +1,552 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::{
+    BillTag, CurrencyCode, ExchangeRate, LineItem, MergeAudit, Money, Participant,
+    ParticipantShare, Payment, SplitSnapshot,
+};
+use crate::split::{SplitConfig, SplitResult};
+
+pub const MAX_TAGS_PER_BILL: usize = 20;
+
+/// Membership of a participant in a bill. Participant details live in their
+/// own record so the same person can be shared across bills.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BillParticipant {
+    pub participant_id: Uuid,
+    pub joined_at: DateTime<Utc>,
+    /// When the participant confirmed they are on the bill; `None` until
+    /// they accept.
+    #[serde(default)]
+    pub accepted_at: Option<DateTime<Utc>>,
+    /// Set when the participant owes nothing, e.g. they came along but did
+    /// not eat.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exemption: Option<Exemption>,
+}
+
+/// Why a participant is left out of the bill's splits.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Exemption {
+    pub reason: String,
+    pub exempted_at: DateTime<Utc>,
+}
+
+/// Where a bill is in its lifecycle. Bills stored before statuses existed
+/// read back as `Open`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BillStatus {
+    Draft,
+    #[default]
+    Open,
+    Settled,
+    /// Hidden from listings and read-only until unarchived.
+    Archived,
+}
+
+impl fmt::Display for BillStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BillStatus::Draft => "draft",
+            BillStatus::Open => "open",
+            BillStatus::Settled => "settled",
+            BillStatus::Archived => "archived",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bill {
+    pub id: Uuid,
+    pub title: String,
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub status: BillStatus,
+    /// When the bill was archived, `None` unless `status` is `Archived`.
+    #[serde(default)]
+    pub archived_at: Option<DateTime<Utc>>,
+    /// Status to restore when the bill is unarchived.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_before_archive: Option<BillStatus>,
+    /// Currency every amount on the bill is in.
+    #[serde(default)]
+    pub base_currency: CurrencyCode,
+    /// Every rate used to convert a line item or payment into
+    /// `base_currency`, oldest first.
+    #[serde(default)]
+    pub exchange_rates_used: Vec<ExchangeRate>,
+    /// Rates set by hand, at most one per currency pair. They are used
+    /// instead of live rates for amounts in their `from` currency.
+    #[serde(default)]
+    pub manual_rates: Vec<ExchangeRate>,
+    /// Authenticated user who created the bill, `None` for anonymous bills.
+    #[serde(default)]
+    pub creator_id: Option<Uuid>,
+    #[serde(default)]
+    pub participants: Vec<BillParticipant>,
+    /// The participant who paid the bill up front.
+    #[serde(default)]
+    pub payer_id: Option<Uuid>,
+    #[serde(default)]
+    pub line_items: Vec<LineItem>,
+    /// Flat discount taken off the subtotal.
+    #[serde(default)]
+    pub discount: Money,
+    /// How splits are computed when a request does not pick a method.
+    #[serde(default)]
+    pub split_config: Option<SplitConfig>,
+    /// Every split computed for this bill, oldest first.
+    #[serde(default)]
+    pub split_history: Vec<SplitSnapshot>,
+    /// Payments recorded against this bill, in the order they were made.
+    #[serde(default)]
+    pub payments: Vec<Payment>,
+    /// Line items merged into others, oldest first.
+    #[serde(default)]
+    pub merge_audit: Vec<MergeAudit>,
+    /// At most [`MAX_TAGS_PER_BILL`], in the order they were attached.
+    #[serde(default)]
+    pub tags: Vec<BillTag>,
+    /// Due dates set explicitly by the creator, overriding the default of a
+    /// fixed number of days after the bill was created.
+    #[serde(default)]
+    pub due_dates: BTreeMap<Uuid, NaiveDate>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Replaces `from` with `into` in `ids`, keeping `into` only once.
+fn merge_ids(ids: &mut Vec<Uuid>, from: Uuid, into: Uuid) {
+    let Some(index) = ids.iter().position(|id| *id == from) else {
+        return;
+    };
+    if ids.contains(&into) {
+        ids.remove(index);
+    } else {
+        ids[index] = into;
+    }
+}
+
+/// Moves `from`'s value onto `into`, adding the two if both are present.
+fn merge_key<V: Copy + std::ops::Add<Output = V>>(
+    map: &mut BTreeMap<Uuid, V>,
+    from: Uuid,
+    into: Uuid,
+) {
+    if let Some(value) = map.remove(&from) {
+        map.entry(into)
+            .and_modify(|existing| *existing = *existing + value)
+            .or_insert(value);
+    }
+}
+
+impl Bill {
+    pub fn new(title: impl Into<String>, notes: Option<String>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            title: title.into(),
+            notes,
+            status: BillStatus::default(),
+            archived_at: None,
+            status_before_archive: None,
+            base_currency: CurrencyCode::default(),
+            exchange_rates_used: Vec::new(),
+            manual_rates: Vec::new(),
+            creator_id: None,
+            participants: Vec::new(),
+            payer_id: None,
+            line_items: Vec::new(),
+            discount: Money::ZERO,
+            split_config: None,
+            split_history: Vec::new(),
+            payments: Vec::new(),
+            merge_audit: Vec::new(),
+            tags: Vec::new(),
+            due_dates: BTreeMap::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Whether the bill can still be changed, i.e. it is not settled yet.
+    pub fn is_editable(&self) -> bool {
+        matches!(self.status, BillStatus::Draft | BillStatus::Open)
+    }
+
+    pub fn is_archived(&self) -> bool {
+        self.status == BillStatus::Archived
+    }
+
+    /// Moves the bill to `Archived`, remembering its current status.
+    pub fn archive(&mut self) {
+        if !self.is_archived() {
+            self.status_before_archive = Some(self.status);
+            self.status = BillStatus::Archived;
+            self.archived_at = Some(Utc::now());
+        }
+    }
+
+    /// Restores the status the bill had before it was archived.
+    pub fn unarchive(&mut self) {
+        if self.is_archived() {
+            self.status = self.status_before_archive.take().unwrap_or_default();
+            self.archived_at = None;
+        }
+    }
+
+    /// Sum of all line items before any bill-wide adjustment.
+    pub fn subtotal(&self) -> Money {
+        self.line_items.iter().map(LineItem::total).sum()
+    }
+
+    pub fn total(&self) -> Money {
+        self.subtotal() - self.discount
+    }
+
+    pub fn participant_ids(&self) -> Vec<Uuid> {
+        self.participants
+            .iter()
+            .map(|participant| participant.participant_id)
+            .collect()
+    }
+
+    pub fn has_participant(&self, participant_id: Uuid) -> bool {
+        self.participants
+            .iter()
+            .any(|participant| participant.participant_id == participant_id)
+    }
+
+    pub fn add_participant(&mut self, participant_id: Uuid) {
+        if !self.has_participant(participant_id) {
+            self.participants.push(BillParticipant {
+                participant_id,
+                joined_at: Utc::now(),
+                accepted_at: None,
+                exemption: None,
+            });
+        }
+    }
+
+    /// Records that the participant accepted at `at`, unless they already
+    /// had. Returns when they accepted, or `None` if they are not on the bill.
+    pub fn accept_participant(
+        &mut self,
+        participant_id: Uuid,
+        at: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let member = self
+            .participants
+            .iter_mut()
+            .find(|member| member.participant_id == participant_id)?;
+        Some(*member.accepted_at.get_or_insert(at))
+    }
+
+    /// Participants who have not accepted yet, in the order they joined.
+    pub fn unconfirmed_participants(&self) -> Vec<Uuid> {
+        self.participants
+            .iter()
+            .filter(|member| member.accepted_at.is_none())
+            .map(|member| member.participant_id)
+            .collect()
+    }
+
+    /// Exempts the participant from every split, replacing any earlier
+    /// reason. Returns `false` if they are not on the bill.
+    pub fn exempt_participant(
+        &mut self,
+        participant_id: Uuid,
+        reason: impl Into<String>,
+        at: DateTime<Utc>,
+    ) -> bool {
+        let Some(member) = self
+            .participants
+            .iter_mut()
+            .find(|member| member.participant_id == participant_id)
+        else {
+            return false;
+        };
+        member.exemption = Some(Exemption {
+            reason: reason.into(),
+            exempted_at: at,
+        });
+        true
+    }
+
+    /// Lifts the participant's exemption. Returns whether they had one.
+    pub fn remove_exemption(&mut self, participant_id: Uuid) -> bool {
+        self.participants
+            .iter_mut()
+            .find(|member| member.participant_id == participant_id)
+            .and_then(|member| member.exemption.take())
+            .is_some()
+    }
+
+    pub fn exemption(&self, participant_id: Uuid) -> Option<&Exemption> {
+        self.participants
+            .iter()
+            .find(|member| member.participant_id == participant_id)?
+            .exemption
+            .as_ref()
+    }
+
+    pub fn is_exempt(&self, participant_id: Uuid) -> bool {
+        self.exemption(participant_id).is_some()
+    }
+
+    /// Participants on the bill who are not exempt, in the order they joined.
+    pub fn liable_participant_ids(&self) -> Vec<Uuid> {
+        self.participant_ids()
+            .into_iter()
+            .filter(|id| !self.is_exempt(*id))
+            .collect()
+    }
+
+    /// Who pays for `item` in an itemised split: its participants except
+    /// the exempt ones, or everyone liable on the bill if only exempt
+    /// participants shared it. Empty for an unassigned item.
+    pub fn item_sharers(&self, item: &LineItem) -> Vec<Uuid> {
+        let sharers: Vec<Uuid> = item
+            .participant_ids
+            .iter()
+            .copied()
+            .filter(|id| !self.is_exempt(*id))
+            .collect();
+        if sharers.is_empty() && !item.participant_ids.is_empty() {
+            self.liable_participant_ids()
+        } else {
+            sharers
+        }
+    }
+
+    /// Moves everything `from` has on the bill over to `into`: membership,
+    /// line items, payments, split parameters and past shares, adding to what
+    /// `into` already has. Returns how many payments were moved.
+    pub fn merge_participant(&mut self, from: Uuid, into: &Participant) -> u32 {
+        if let Some(index) = self
+            .participants
+            .iter()
+            .position(|member| member.participant_id == from)
+        {
+            let source = self.participants.remove(index);
+            match self
+                .participants
+                .iter_mut()
+                .find(|member| member.participant_id == into.id)
+            {
+                Some(target) => {
+                    target.joined_at = target.joined_at.min(source.joined_at);
+                    target.accepted_at = target.accepted_at.or(source.accepted_at);
+                }
+                None => self.participants.insert(
+                    index,
+                    BillParticipant {
+                        participant_id: into.id,
+                        ..source
+                    },
+                ),
+            }
+        }
+        for id in self.creator_id.iter_mut().chain(&mut self.payer_id) {
+            if *id == from {
+                *id = into.id;
+            }
+        }
+
+        for item in &mut self.line_items {
+            merge_ids(&mut item.participant_ids, from, into.id);
+        }
+        if let Some(config) = &mut self.split_config {
+            if let Some(weights) = &mut config.weights {
+                merge_key(weights, from, into.id);
+            }
+            if let Some(amounts) = &mut config.amounts {
+                merge_key(amounts, from, into.id);
+            }
+            for ids in config.assignments.iter_mut().flat_map(BTreeMap::values_mut) {
+                merge_ids(ids, from, into.id);
+            }
+        }
+        for snapshot in &mut self.split_history {
+            let Some(index) = snapshot
+                .shares
+                .iter()
+                .position(|share| share.participant_id == from)
+            else {
+                continue;
+            };
+            let source = snapshot.shares.remove(index);
+            match snapshot
+                .shares
+                .iter_mut()
+                .find(|share| share.participant_id == into.id)
+            {
+                Some(target) => target.amount_owed += source.amount_owed,
+                None => snapshot.shares.insert(
+                    index,
+                    ParticipantShare {
+                        participant_id: into.id,
+                        name: into.name.clone(),
+                        amount_owed: source.amount_owed,
+                    },
+                ),
+            }
+        }
+        if let Some(due) = self.due_dates.remove(&from) {
+            self.due_dates.entry(into.id).or_insert(due);
+        }
+
+        let mut transferred = 0;
+        for payment in &mut self.payments {
+            if payment.participant_id == from {
+                payment.participant_id = into.id;
+                transferred += 1;
+            }
+        }
+        transferred
+    }
+
+    /// Appends `item` after the last line item.
+    pub fn add_line_item(&mut self, mut item: LineItem) -> &LineItem {
+        item.position = self
+            .line_items
+            .iter()
+            .map(|item| item.position + 1)
+            .max()
+            .unwrap_or(0);
+        self.line_items.push(item);
+        &self.line_items[self.line_items.len() - 1]
+    }
+
+    /// Gives each line item its position in `positions` and re-sorts them.
+    /// Items missing from `positions` keep theirs.
+    pub fn reorder_line_items(&mut self, positions: &HashMap<Uuid, u32>) {
+        for item in &mut self.line_items {
+            if let Some(position) = positions.get(&item.id) {
+                item.position = *position;
+            }
+        }
+        self.line_items.sort_by_key(|item| item.position);
+    }
+
+    /// Adds `result` to the split history. Archived bills are read-only, so
+    /// nothing is recorded for them; returns whether a snapshot was added.
+    pub fn record_split(&mut self, result: &SplitResult) -> bool {
+        if self.is_archived() {
+            return false;
+        }
+        self.split_history.push(SplitSnapshot::from_result(result));
+        true
+    }
+
+    /// The most recently computed split, if any.
+    pub fn latest_split(&self) -> Option<&SplitSnapshot> {
+        self.split_history.last()
+    }
+
+    /// Payments made by `participant_id`, oldest first.
+    pub fn payments_by(&self, participant_id: Uuid) -> impl Iterator<Item = &Payment> {
+        self.payments
+            .iter()
+            .filter(move |payment| payment.participant_id == participant_id)
+    }
+
+    /// The manual rate converting `from` into the bill's currency, if set.
+    pub fn manual_rate(&self, from: &CurrencyCode) -> Option<&ExchangeRate> {
+        self.manual_rates
+            .iter()
+            .find(|rate| rate.from == *from && rate.to == self.base_currency)
+    }
+
+    /// Sets a manual rate, replacing any earlier one for the same pair, and
+    /// reprices every line item and payment in `rate.from` with it.
+    pub fn set_manual_rate(&mut self, rate: ExchangeRate) {
+        self.manual_rates
+            .retain(|existing| existing.from != rate.from || existing.to != rate.to);
+        if rate.to == self.base_currency {
+            for item in &mut self.line_items {
+                if item.currency.as_ref() == Some(&rate.from) {
+                    item.reprice(rate.rate);
+                }
+            }
+            for payment in &mut self.payments {
+                if payment.currency.as_ref() == Some(&rate.from) {
+                    payment.reprice(rate.rate);
+                }
+            }
+        }
+        self.exchange_rates_used.push(rate.clone());
+        self.manual_rates.push(rate);
+    }
+
+    /// What `participant_id` still owes: their share of the latest split less
+    /// their payments. `None` until a split has been computed or if they have
+    /// no share in it.
+    pub fn outstanding(&self, participant_id: Uuid) -> Option<Money> {
+        let share = self
+            .latest_split()?
+            .shares
+            .iter()
+            .find(|share| share.participant_id == participant_id)?;
+        let paid: Money = self
+            .payments_by(participant_id)
+            .map(Payment::base_amount)
+            .sum();
+        Some(share.amount_owed - paid)
+    }
+
+    /// When `participant_id` should have paid by: their override if one is set,
+    /// otherwise `default_days` after the bill was created.
+    pub fn due_date(&self, participant_id: Uuid, default_days: u32) -> NaiveDate {
+        self.due_dates
+            .get(&participant_id)
+            .copied()
+            .unwrap_or_else(|| {
+                let created = self.created_at.date_naive();
+                created
+                    .checked_add_days(Days::new(default_days.into()))
+                    .unwrap_or(created)
+            })
+    }
+
+    /// Days past their due date that `participant_id` still owes money, as of
+    /// `today`. `None` if nothing is overdue.
+    pub fn days_overdue(
+        &self,
+        participant_id: Uuid,
+        default_days: u32,
+        today: NaiveDate,
+    ) -> Option<u32> {
+        let days_late = (today - self.due_date(participant_id, default_days)).num_days();
+        let owes = self
+            .outstanding(participant_id)
+            .is_some_and(|owed| owed > Money::ZERO);
+        (days_late > 0 && owes).then(|| u32::try_from(days_late).unwrap_or(u32::MAX))
+    }
+
+    /// Hex-encoded SHA-256 of the bill's JSON. Any mutation, including to line
+    /// items and payments, changes it. The split history is left out: every
+    /// split computed records a snapshot, and reading a split should not make
+    /// clients' copies of the bill stale.
+    pub fn etag(&self) -> String {
+        let mut json = serde_json::to_value(self).expect("bills always serialise to JSON");
+        if let Some(fields) = json.as_object_mut() {
+            fields.remove("split_history");
+        }
+        hex::encode(Sha256::digest(json.to_string()))
+    }
+
+    /// Marks the bill as modified. Call after every mutation before saving.
+    pub fn touch(&mut self) {
+        self.updated_at = Utc::now();
+    }
+}
@@ -0,0 +1,118 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use super::Money;
+
+/// ISO 4217 currency code such as `USD`, stored upper-case.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct CurrencyCode(String);
+
+/// ISO 4217 currencies without a minor unit.
+const ZERO_DECIMAL_CURRENCIES: &[&str] = &[
+    "BIF", "CLP", "DJF", "GNF", "ISK", "JPY", "KMF", "KRW", "PYG", "RWF", "UGX", "VND", "VUV",
+    "XAF", "XOF", "XPF",
+];
+
+impl CurrencyCode {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Digits after the decimal point in amounts of this currency.
+    pub fn decimal_places(&self) -> u32 {
+        if ZERO_DECIMAL_CURRENCIES.contains(&self.as_str()) {
+            0
+        } else {
+            2
+        }
+    }
+
+    /// The smallest amount that can be paid: `1.00` for currencies without a
+    /// minor unit, `0.01` otherwise.
+    pub fn smallest_unit(&self) -> Money {
+        Money::from_cents(10_i64.pow(2 - self.decimal_places()))
+    }
+}
+
+/// Bills created before currencies were tracked are in US dollars.
+impl Default for CurrencyCode {
+    fn default() -> Self {
+        Self("USD".to_string())
+    }
+}
+
+impl TryFrom<String> for CurrencyCode {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let code = value.trim().to_ascii_uppercase();
+        if code.len() == 3 && code.bytes().all(|byte| byte.is_ascii_uppercase()) {
+            Ok(Self(code))
+        } else {
+            Err(format!(
+                "`{value}` is not a currency code; expected three letters like `USD`"
+            ))
+        }
+    }
+}
+
+impl From<CurrencyCode> for String {
+    fn from(code: CurrencyCode) -> Self {
+        code.0
+    }
+}
+
+impl fmt::Display for CurrencyCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Where an exchange rate came from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RateSource {
+    /// Fetched from the exchange rate service.
+    #[default]
+    Live,
+    /// Set by hand, e.g. a month-end rate required by policy.
+    Manual,
+}
+
+/// A rate used to convert an amount on a bill into the bill's currency.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExchangeRate {
+    pub from: CurrencyCode,
+    pub to: CurrencyCode,
+    /// Units of `to` per unit of `from`.
+    pub rate: Decimal,
+    /// When the rate was fetched, or set for manual rates.
+    pub fetched_at: DateTime<Utc>,
+    #[serde(default)]
+    pub source: RateSource,
+}
+
+impl ExchangeRate {
+    /// A rate fetched just now.
+    pub fn new(from: CurrencyCode, to: CurrencyCode, rate: Decimal) -> Self {
+        Self {
+            from,
+            to,
+            rate,
+            fetched_at: Utc::now(),
+            source: RateSource::Live,
+        }
+    }
+
+    /// A rate set by hand just now.
+    pub fn manual(from: CurrencyCode, to: CurrencyCode, rate: Decimal) -> Self {
+        Self {
+            source: RateSource::Manual,
+            ..Self::new(from, to, rate)
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::HashSet;
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{CurrencyCode, Money};
+
+/// Largest `quantity * unit_price` a line item may have, so sums over a
+/// bill's items can never overflow.
+pub const MAX_LINE_ITEM_TOTAL: Money = Money::from_cents(100_000_000_000);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineItem {
+    pub id: Uuid,
+    pub description: String,
+    pub quantity: u32,
+    pub unit_price: Money,
+    /// Participants sharing this item. Empty means the item is unassigned.
+    #[serde(default)]
+    pub participant_ids: Vec<Uuid>,
+    /// Expense category, used to pick an account when exporting.
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Tax included in the price as a fraction, e.g. `0.07` for 7%.
+    #[serde(default)]
+    pub tax_rate: Option<Decimal>,
+    /// Currency the item was entered in; `None` means the bill's base
+    /// currency. `unit_price` is always in the base currency.
+    #[serde(default)]
+    pub currency: Option<CurrencyCode>,
+    /// The price as entered, in `currency`, for converted items.
+    #[serde(default)]
+    pub original_unit_price: Option<Money>,
+    /// Display position. Bills keep their line items sorted by it; items
+    /// stored before positions existed are all zero and stay in insertion
+    /// order.
+    #[serde(default)]
+    pub position: u32,
+}
+
+impl LineItem {
+    pub fn new(description: impl Into<String>, quantity: u32, unit_price: Money) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            description: description.into(),
+            quantity,
+            unit_price,
+            participant_ids: Vec::new(),
+            category: None,
+            tax_rate: None,
+            currency: None,
+            original_unit_price: None,
+            position: 0,
+        }
+    }
+
+    /// Treats `unit_price` as an amount in `currency` and converts it to the
+    /// bill's currency at `rate`.
+    pub fn converted(mut self, currency: CurrencyCode, rate: Decimal) -> Self {
+        self.original_unit_price = Some(self.unit_price);
+        self.unit_price = Money::from_decimal(self.unit_price.to_decimal() * rate);
+        self.currency = Some(currency);
+        self
+    }
+
+    /// Converts the price as entered again at `rate`. A no-op for items that
+    /// were not converted.
+    pub fn reprice(&mut self, rate: Decimal) {
+        if let Some(original) = self.original_unit_price {
+            self.unit_price = Money::from_decimal(original.to_decimal() * rate);
+        }
+    }
+
+    pub fn total(&self) -> Money {
+        self.unit_price * self.quantity
+    }
+
+    /// Whether the item's total is within [`MAX_LINE_ITEM_TOTAL`].
+    pub fn is_within_limit(&self) -> bool {
+        self.unit_price
+            .checked_mul(self.quantity)
+            .is_some_and(|total| total <= MAX_LINE_ITEM_TOTAL)
+    }
+
+    /// Replaces who shares the item, dropping repeated ids but keeping the
+    /// order they were first given in.
+    pub fn set_participants(&mut self, participant_ids: Vec<Uuid>) {
+        let mut seen = HashSet::new();
+        self.participant_ids = participant_ids
+            .into_iter()
+            .filter(|participant_id| seen.insert(*participant_id))
+            .collect();
+    }
+
+    /// Combines `other` into this item, keeping this item's id. The longer
+    /// description wins. Quantities are added when the unit prices are within
+    /// 1% of each other; otherwise the result is a single unit priced at both
+    /// totals combined. Participants are the union of both items. The result is
+    /// priced in the bill's currency.
+    pub fn merged_with(&self, other: &LineItem) -> LineItem {
+        let description = if other.description.chars().count() > self.description.chars().count() {
+            other.description.clone()
+        } else {
+            self.description.clone()
+        };
+
+        let price_gap = (self.unit_price - other.unit_price).abs().cents();
+        let larger_price = self
+            .unit_price
+            .cents()
+            .abs()
+            .max(other.unit_price.cents().abs());
+        let (quantity, unit_price) = match self.quantity.checked_add(other.quantity) {
+            Some(quantity) if price_gap * 100 <= larger_price => (quantity, self.unit_price),
+            _ => (1, self.total() + other.total()),
+        };
+
+        let mut participant_ids = self.participant_ids.clone();
+        for participant_id in &other.participant_ids {
+            if !participant_ids.contains(participant_id) {
+                participant_ids.push(*participant_id);
+            }
+        }
+
+        LineItem {
+            id: self.id,
+            description,
+            quantity,
+            unit_price,
+            participant_ids,
+            category: self.category.clone().or_else(|| other.category.clone()),
+            tax_rate: self.tax_rate.or(other.tax_rate),
+            // Combined prices only exist in the bill's currency.
+            currency: None,
+            original_unit_price: None,
+            position: self.position,
+        }
+    }
+}
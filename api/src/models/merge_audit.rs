@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::LineItem;
+
+/// Record of two line items merged into one, keeping both originals so the
+/// merge can be reviewed later.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MergeAudit {
+    pub id: Uuid,
+    pub source: LineItem,
+    pub target: LineItem,
+    pub merged_at: DateTime<Utc>,
+}
+
+impl MergeAudit {
+    pub fn new(source: LineItem, target: LineItem) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            source,
+            target,
+            merged_at: Utc::now(),
+        }
+    }
+}
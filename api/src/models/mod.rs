@@ -0,0 +1,23 @@
+mod audit_entry;
+mod bill;
+mod currency;
+mod line_item;
+mod merge_audit;
+mod money;
+mod participant;
+mod payment;
+mod share;
+mod split_snapshot;
+mod tag;
+
+pub use audit_entry::{AuditAction, AuditEntry, PayerChange};
+pub use bill::{Bill, BillParticipant, BillStatus, Exemption, MAX_TAGS_PER_BILL};
+pub use currency::{CurrencyCode, ExchangeRate, RateSource};
+pub use line_item::{LineItem, MAX_LINE_ITEM_TOTAL};
+pub use merge_audit::MergeAudit;
+pub use money::{Money, ParseMoneyError};
+pub use participant::Participant;
+pub use payment::{Payment, MAX_PAYMENT_AMOUNT};
+pub use share::ParticipantShare;
+pub use split_snapshot::SplitSnapshot;
+pub use tag::{BillTag, HexColor, MAX_TAG_NAME_LEN};
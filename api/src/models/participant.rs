@@ -0,0 +1,45 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use url::Url;
+use uuid::Uuid;
+
+/// A person who can take part in any number of bills.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Participant {
+    pub id: Uuid,
+    pub name: String,
+    pub email: Option<String>,
+    /// E.164 phone number used for SMS notifications.
+    #[serde(default)]
+    pub phone: Option<String>,
+    /// Venmo username others pay this participant at.
+    #[serde(default)]
+    pub venmo_handle: Option<String>,
+    /// Email address of their PayPal account.
+    #[serde(default)]
+    pub paypal_email: Option<String>,
+    /// Cash App `$cashtag`.
+    #[serde(default)]
+    pub cashapp_tag: Option<String>,
+    /// HTTPS image shown next to their name, served through
+    /// `GET /participants/{id}/avatar`.
+    #[serde(default)]
+    pub avatar_url: Option<Url>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Participant {
+    pub fn new(name: impl Into<String>, email: Option<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name: name.into(),
+            email,
+            phone: None,
+            venmo_handle: None,
+            paypal_email: None,
+            cashapp_tag: None,
+            avatar_url: None,
+            created_at: Utc::now(),
+        }
+    }
+}
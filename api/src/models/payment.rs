@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{CurrencyCode, Money};
+
+/// Largest amount a payment may be for, in its own currency and the bill's,
+/// so sums over a bill's payments can never overflow.
+pub const MAX_PAYMENT_AMOUNT: Money = Money::from_cents(100_000_000_000);
+
+/// Money a participant has paid towards their share of a bill.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Payment {
+    pub id: Uuid,
+    pub participant_id: Uuid,
+    /// Amount paid, in `currency`.
+    pub amount: Money,
+    /// Currency of `amount`; `None` means the bill's base currency.
+    #[serde(default)]
+    pub currency: Option<CurrencyCode>,
+    /// Units of base currency per unit of `currency` when the payment was made.
+    #[serde(default)]
+    pub conversion_rate: Option<Decimal>,
+    /// `amount` in the bill's base currency, set for foreign payments.
+    #[serde(default)]
+    pub converted_amount: Option<Money>,
+    pub note: Option<String>,
+    pub paid_at: DateTime<Utc>,
+}
+
+impl Payment {
+    pub fn new(participant_id: Uuid, amount: Money, note: Option<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            participant_id,
+            amount,
+            currency: None,
+            conversion_rate: None,
+            converted_amount: None,
+            note,
+            paid_at: Utc::now(),
+        }
+    }
+
+    /// Records `amount` in `currency`, converted to the bill's currency at `rate`.
+    pub fn converted(mut self, currency: CurrencyCode, rate: Decimal) -> Self {
+        self.converted_amount = Some(Money::from_decimal(self.amount.to_decimal() * rate));
+        self.conversion_rate = Some(rate);
+        self.currency = Some(currency);
+        self
+    }
+
+    /// Converts the amount again at `rate`. A no-op for payments in the
+    /// bill's currency.
+    pub fn reprice(&mut self, rate: Decimal) {
+        if self.currency.is_some() {
+            self.converted_amount = Some(Money::from_decimal(self.amount.to_decimal() * rate));
+            self.conversion_rate = Some(rate);
+        }
+    }
+
+    /// What the payment counts for in the bill's base currency.
+    pub fn base_amount(&self) -> Money {
+        self.converted_amount.unwrap_or(self.amount)
+    }
+
+    /// Whether the amount, and what it converts to, are within
+    /// [`MAX_PAYMENT_AMOUNT`].
+    pub fn is_within_limit(&self) -> bool {
+        self.amount <= MAX_PAYMENT_AMOUNT && self.base_amount() <= MAX_PAYMENT_AMOUNT
+    }
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::Money;
+
+/// What a single participant owes under a given split.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParticipantShare {
+    pub participant_id: Uuid,
+    pub name: String,
+    pub amount_owed: Money,
+}
@@ -0,0 +1,30 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::ParticipantShare;
+use crate::split::{gini_coefficient, SplitMethod, SplitResult};
+
+/// A split as it was computed at a point in time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SplitSnapshot {
+    pub id: Uuid,
+    pub method: SplitMethod,
+    pub computed_at: DateTime<Utc>,
+    pub shares: Vec<ParticipantShare>,
+    /// Recorded with the snapshot; `None` for snapshots saved before it was.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gini_coefficient: Option<f64>,
+}
+
+impl SplitSnapshot {
+    pub fn from_result(result: &SplitResult) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            method: result.method,
+            computed_at: Utc::now(),
+            shares: result.shares.clone(),
+            gini_coefficient: Some(gini_coefficient(&result.shares)),
+        }
+    }
+}
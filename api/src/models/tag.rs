@@ -0,0 +1,80 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Longest tag name accepted.
+pub const MAX_TAG_NAME_LEN: usize = 32;
+
+/// A `#rrggbb` colour, stored lower-case.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct HexColor(String);
+
+impl TryFrom<String> for HexColor {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let color = value.trim().to_ascii_lowercase();
+        match color.strip_prefix('#') {
+            Some(hex) if hex.len() == 6 && hex.bytes().all(|byte| byte.is_ascii_hexdigit()) => {
+                Ok(Self(color))
+            }
+            _ => Err(format!(
+                "`{value}` is not a colour; expected a hex colour like `#1e90ff`"
+            )),
+        }
+    }
+}
+
+impl From<HexColor> for String {
+    fn from(color: HexColor) -> Self {
+        color.0
+    }
+}
+
+impl fmt::Display for HexColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A label users attach to bills to organise them. Names are unique and
+/// shared across bills.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BillTag {
+    pub id: Uuid,
+    pub name: String,
+    pub color: Option<HexColor>,
+}
+
+impl BillTag {
+    /// Creates a tag named `name` once it passes [`BillTag::normalise_name`].
+    pub fn new(name: &str, color: Option<HexColor>) -> Result<Self, String> {
+        Ok(Self {
+            id: Uuid::new_v4(),
+            name: Self::normalise_name(name)?,
+            color,
+        })
+    }
+
+    /// Lower-cases `name`, which must be 1 to [`MAX_TAG_NAME_LEN`] ASCII
+    /// letters, digits, `-` or `_` so it can be used as-is in URLs and KV keys.
+    pub fn normalise_name(name: &str) -> Result<String, String> {
+        let name = name.trim().to_ascii_lowercase();
+        if name.is_empty() || name.len() > MAX_TAG_NAME_LEN {
+            return Err(format!(
+                "Tag names must be 1 to {MAX_TAG_NAME_LEN} characters long"
+            ));
+        }
+        if !name
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_')
+        {
+            return Err(format!(
+                "Tag name `{name}` may only contain ASCII letters, digits, `-` and `_`"
+            ));
+        }
+        Ok(name)
+    }
+}
@@ -0,0 +1,261 @@
+use std::{sync::OnceLock, time::Duration};
+
+use handlebars::Handlebars;
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{DeliveryReport, NotificationError};
+use crate::{
+    config::{EmailConfig, EmailProvider},
+    crypto::hmac::verify,
+    models::{Bill, Money, Participant},
+};
+
+const SHARE_TEMPLATE: &str = "share_email";
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn templates() -> &'static Handlebars<'static> {
+    static TEMPLATES: OnceLock<Handlebars<'static>> = OnceLock::new();
+    TEMPLATES.get_or_init(|| {
+        let mut handlebars = Handlebars::new();
+        handlebars.set_strict_mode(true);
+        // The body is sent as plain text, so HTML escaping would only mangle
+        // names like "O'Brien".
+        handlebars.register_escape_fn(handlebars::no_escape);
+
+        handlebars
+            .register_template_string(SHARE_TEMPLATE, include_str!("templates/share_email.hbs"))
+            .expect("share email template is valid");
+        handlebars
+    })
+}
+
+/// Renders the plain-text body telling `participant` they still owe `amount`.
+pub fn render_share_email(
+    participant: &Participant,
+    bill: &Bill,
+    amount: Money,
+) -> Result<String, NotificationError> {
+    templates()
+        .render(
+            SHARE_TEMPLATE,
+            &json!({
+                "name": participant.name,
+                "bill_title": bill.title,
+                "amount": amount.to_string(),
+                "notes": bill.notes,
+            }),
+        )
+        .map_err(|err| NotificationError::Template {
+            participant_id: participant.id,
+            reason: err.to_string(),
+        })
+}
+
+/// Emails `participant` the `amount` they still owe on `bill` through the
+/// configured provider. Returns the provider's message id when it reports one.
+pub async fn send_share_notification(
+    config: &EmailConfig,
+    participant: &Participant,
+    bill: &Bill,
+    amount: Money,
+) -> Result<Option<String>, NotificationError> {
+    let Some(to) = participant.email.as_deref() else {
+        return Err(NotificationError::MissingRecipient {
+            participant_id: participant.id,
+        });
+    };
+
+    let Some(from) = config.from.as_deref() else {
+        return Err(not_configured("EMAIL_FROM is not set"));
+    };
+
+    let subject = format!("Your share of {}", bill.title);
+    let body = render_share_email(participant, bill, amount)?;
+
+    let request = match config.provider {
+        Some(EmailProvider::Mailgun) => {
+            let (Some(api_key), Some(domain)) = (&config.mailgun_api_key, &config.mailgun_domain)
+            else {
+                return Err(not_configured(
+                    "MAILGUN_API_KEY and MAILGUN_DOMAIN are required",
+                ));
+            };
+            awc::Client::default()
+                .post(format!("https://api.mailgun.net/v3/{domain}/messages"))
+                .timeout(SEND_TIMEOUT)
+                .basic_auth("api", api_key)
+                .send_form(&[
+                    ("from", from),
+                    ("to", to),
+                    ("subject", subject.as_str()),
+                    ("text", body.as_str()),
+                ])
+        }
+        Some(EmailProvider::SendGrid) => {
+            let Some(api_key) = &config.sendgrid_api_key else {
+                return Err(not_configured("SENDGRID_API_KEY is required"));
+            };
+            awc::Client::default()
+                .post("https://api.sendgrid.com/v3/mail/send")
+                .timeout(SEND_TIMEOUT)
+                .bearer_auth(api_key)
+                .send_json(&json!({
+                    "personalizations": [{ "to": [{ "email": to }] }],
+                    "from": { "email": from },
+                    "subject": subject,
+                    "content": [{ "type": "text/plain", "value": body }],
+                }))
+        }
+        None => return Err(not_configured("EMAIL_PROVIDER is not set")),
+    };
+
+    let mut response = request.await.map_err(|err| NotificationError::Provider {
+        participant_id: participant.id,
+        status: None,
+        message: err.to_string(),
+    })?;
+
+    if !response.status().is_success() {
+        let body = response.body().await.unwrap_or_default();
+        return Err(NotificationError::Provider {
+            participant_id: participant.id,
+            status: Some(response.status().as_u16()),
+            message: String::from_utf8_lossy(&body).into_owned(),
+        });
+    }
+
+    Ok(match config.provider {
+        Some(EmailProvider::Mailgun) => response
+            .json::<MailgunQueued>()
+            .await
+            .ok()
+            .map(|queued| normalise_message_id(&queued.id)),
+        _ => response
+            .headers()
+            .get("X-Message-Id")
+            .and_then(|value| value.to_str().ok())
+            .map(normalise_message_id),
+    })
+}
+
+#[derive(Deserialize)]
+struct MailgunQueued {
+    id: String,
+}
+
+/// Mailgun wraps ids in angle brackets when sending but not in webhooks.
+pub fn normalise_message_id(id: &str) -> String {
+    id.trim()
+        .trim_start_matches('<')
+        .trim_end_matches('>')
+        .to_string()
+}
+
+/// Checks a Mailgun webhook signature: the hex HMAC-SHA256 of
+/// `{timestamp}{token}` keyed with the webhook signing key.
+pub fn verify_mailgun_signature(
+    signing_key: &str,
+    timestamp: &str,
+    token: &str,
+    signature: &str,
+) -> bool {
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    let payload = format!("{timestamp}{token}");
+    verify(payload.as_bytes(), &signature, signing_key.as_bytes())
+}
+
+/// Webhooks signed longer ago than this are rejected as replays.
+pub const MAILGUN_WEBHOOK_MAX_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// The body Mailgun posts to delivery webhooks.
+#[derive(Debug, Deserialize)]
+pub struct MailgunWebhook {
+    pub signature: MailgunSignature,
+    #[serde(rename = "event-data")]
+    pub event_data: MailgunEvent,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MailgunSignature {
+    pub timestamp: String,
+    pub token: String,
+    pub signature: String,
+}
+
+impl MailgunWebhook {
+    /// Whether the webhook is correctly signed with `signing_key` and recent
+    /// enough relative to `now` (a Unix timestamp).
+    pub fn is_authentic(&self, signing_key: &str, now: i64) -> bool {
+        let signature = &self.signature;
+        let Ok(timestamp) = signature.timestamp.parse::<i64>() else {
+            return false;
+        };
+        (now - timestamp).unsigned_abs() <= MAILGUN_WEBHOOK_MAX_AGE.as_secs()
+            && verify_mailgun_signature(
+                signing_key,
+                &signature.timestamp,
+                &signature.token,
+                &signature.signature,
+            )
+    }
+}
+
+/// The parts of a Mailgun webhook's `event-data` needed to track delivery.
+#[derive(Debug, Deserialize)]
+pub struct MailgunEvent {
+    pub event: String,
+    pub message: MailgunEventMessage,
+    #[serde(rename = "delivery-status", default)]
+    pub delivery_status: Option<MailgunDeliveryStatus>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MailgunEventMessage {
+    pub headers: MailgunEventHeaders,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MailgunEventHeaders {
+    #[serde(rename = "message-id")]
+    pub message_id: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct MailgunDeliveryStatus {
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+impl MailgunEvent {
+    /// `Pending` for events that say nothing about delivery, such as opens.
+    pub fn report(&self) -> DeliveryReport {
+        match self.event.as_str() {
+            "delivered" => DeliveryReport::Delivered,
+            "failed" => {
+                let status = self.delivery_status.as_ref();
+                let reason = status
+                    .and_then(|status| status.description.clone())
+                    .filter(|reason| !reason.is_empty())
+                    .or_else(|| status.and_then(|status| status.message.clone()))
+                    .filter(|reason| !reason.is_empty())
+                    .or_else(|| self.reason.clone())
+                    .unwrap_or_else(|| "failed".to_string());
+                DeliveryReport::Failed(reason)
+            }
+            _ => DeliveryReport::Pending,
+        }
+    }
+}
+
+fn not_configured(reason: &str) -> NotificationError {
+    NotificationError::NotConfigured {
+        reason: reason.to_string(),
+    }
+}
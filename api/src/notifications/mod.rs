@@ -0,0 +1,91 @@
+use std::fmt;
+
+use serde::Serialize;
+use uuid::Uuid;
+
+pub mod email;
+pub mod reminders;
+pub mod sms;
+mod status;
+
+pub use reminders::{ScheduledReminder, MAX_PENDING_REMINDERS};
+pub use status::{DeliveryReport, DeliverySummary, NotificationChannel, NotificationStatus};
+
+/// Why a single notification could not be delivered.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NotificationError {
+    NotConfigured {
+        reason: String,
+    },
+    MissingRecipient {
+        participant_id: Uuid,
+    },
+    Template {
+        participant_id: Uuid,
+        reason: String,
+    },
+    Provider {
+        participant_id: Uuid,
+        status: Option<u16>,
+        message: String,
+    },
+}
+
+impl fmt::Display for NotificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotificationError::NotConfigured { reason } => {
+                write!(f, "notifications are not configured: {reason}")
+            }
+            NotificationError::MissingRecipient { participant_id } => {
+                write!(f, "participant {participant_id} has no contact details")
+            }
+            NotificationError::Template {
+                participant_id,
+                reason,
+            } => write!(
+                f,
+                "could not render message for participant {participant_id}: {reason}"
+            ),
+            NotificationError::Provider {
+                participant_id,
+                status: Some(status),
+                message,
+            } => write!(
+                f,
+                "provider rejected message for participant {participant_id} ({status}): {message}"
+            ),
+            NotificationError::Provider {
+                participant_id,
+                status: None,
+                message,
+            } => write!(
+                f,
+                "could not reach provider for participant {participant_id}: {message}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for NotificationError {}
+
+/// Outcome of fanning a notification out to a bill's participants.
+#[derive(Debug, Default, Serialize)]
+pub struct NotifySummary {
+    pub sent: usize,
+    pub failed: usize,
+    pub errors: Vec<NotificationError>,
+}
+
+impl NotifySummary {
+    pub fn record<T>(&mut self, result: Result<T, NotificationError>) {
+        match result {
+            Ok(_) => self.sent += 1,
+            Err(err) => {
+                self.failed += 1;
+                self.errors.push(err);
+            }
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! Follow-up notifications scheduled when a bill's participants are notified.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::NotificationChannel;
+use crate::models::{Bill, Money};
+
+/// Pending reminders a participant can have on one bill.
+pub const MAX_PENDING_REMINDERS: usize = 5;
+
+/// A notification to send again later, unless the share is paid first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledReminder {
+    pub id: Uuid,
+    pub channel: NotificationChannel,
+    pub scheduled_for: DateTime<Utc>,
+    pub message_preview: String,
+}
+
+impl ScheduledReminder {
+    pub fn new(
+        channel: NotificationChannel,
+        scheduled_for: DateTime<Utc>,
+        bill: &Bill,
+        owed: Money,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            channel,
+            scheduled_for,
+            message_preview: format!(
+                "Reminder: your share of {} is {owed} {}",
+                bill.title, bill.base_currency
+            ),
+        }
+    }
+
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.scheduled_for <= now
+    }
+}
+
+/// Adds `reminder` unless [`MAX_PENDING_REMINDERS`] are already pending,
+/// keeping them in the order they fire. Returns whether it was added.
+pub fn schedule(reminders: &mut Vec<ScheduledReminder>, reminder: ScheduledReminder) -> bool {
+    if reminders.len() >= MAX_PENDING_REMINDERS {
+        return false;
+    }
+    reminders.push(reminder);
+    reminders.sort_by_key(|reminder| reminder.scheduled_for);
+    true
+}
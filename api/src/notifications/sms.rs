@@ -0,0 +1,186 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::{DeliveryReport, NotificationError};
+use crate::{
+    config::SmsConfig,
+    models::{Bill, Money, Participant},
+};
+
+/// Longest body that still fits in a single SMS segment.
+pub const MAX_SMS_LENGTH: usize = 160;
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Builds "Hi {name}, you owe {amount} for {bill_title}. Pay at: {share_link}",
+/// shortening the bill title (and then the name) so the message never
+/// exceeds [`MAX_SMS_LENGTH`] characters.
+pub fn compose_share_sms(name: &str, amount: &str, bill_title: &str, share_link: &str) -> String {
+    let build = |name: &str, title: &str| {
+        format!("Hi {name}, you owe {amount} for {title}. Pay at: {share_link}")
+    };
+
+    let message = build(name, bill_title);
+    let overflow = message.chars().count().saturating_sub(MAX_SMS_LENGTH);
+    if overflow == 0 {
+        return message;
+    }
+
+    let title = truncate(
+        bill_title,
+        bill_title.chars().count().saturating_sub(overflow),
+    );
+    let message = build(name, &title);
+    let overflow = message.chars().count().saturating_sub(MAX_SMS_LENGTH);
+    if overflow == 0 {
+        return message;
+    }
+
+    let name = truncate(name, name.chars().count().saturating_sub(overflow));
+    build(&name, &title).chars().take(MAX_SMS_LENGTH).collect()
+}
+
+/// Marks where text was cut. "…" is not in the GSM-7 alphabet, and one would
+/// force the whole message into UCS-2 with only 70 characters per segment.
+const ELLIPSIS: &str = "...";
+
+/// Shortens `text` to at most `max_chars` characters, marking the cut with
+/// [`ELLIPSIS`].
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut shortened: String = text
+        .chars()
+        .take(max_chars.saturating_sub(ELLIPSIS.len()))
+        .collect();
+    shortened.push_str(ELLIPSIS);
+    shortened
+}
+
+#[derive(Deserialize)]
+struct TwilioMessage {
+    sid: Option<String>,
+    #[serde(default)]
+    status: String,
+    error_code: Option<i64>,
+    error_message: Option<String>,
+}
+
+fn twilio_credentials(config: &SmsConfig) -> Result<(&str, &str, &str), NotificationError> {
+    match (
+        config.twilio_account_sid.as_deref(),
+        config.twilio_auth_token.as_deref(),
+        config.twilio_from_number.as_deref(),
+    ) {
+        (Some(account_sid), Some(auth_token), Some(from)) => Ok((account_sid, auth_token, from)),
+        _ => Err(NotificationError::NotConfigured {
+            reason: "TWILIO_ACCOUNT_SID, TWILIO_AUTH_TOKEN and TWILIO_FROM_NUMBER are required"
+                .to_string(),
+        }),
+    }
+}
+
+/// Texts `participant` the `amount` they still owe on `bill` via the Twilio
+/// Messages API. Returns the message's Twilio sid.
+pub async fn send_share_sms(
+    config: &SmsConfig,
+    participant: &Participant,
+    bill: &Bill,
+    amount: Money,
+    share_link: &str,
+) -> Result<Option<String>, NotificationError> {
+    let Some(to) = participant.phone.as_deref() else {
+        return Err(NotificationError::MissingRecipient {
+            participant_id: participant.id,
+        });
+    };
+    let (account_sid, auth_token, from) = twilio_credentials(config)?;
+
+    let body = compose_share_sms(
+        &participant.name,
+        &amount.to_string(),
+        &bill.title,
+        share_link,
+    );
+
+    let mut response = awc::Client::default()
+        .post(format!(
+            "https://api.twilio.com/2010-04-01/Accounts/{account_sid}/Messages.json"
+        ))
+        .timeout(SEND_TIMEOUT)
+        .basic_auth(account_sid, auth_token)
+        .send_form(&[("To", to), ("From", from), ("Body", body.as_str())])
+        .await
+        .map_err(|err| NotificationError::Provider {
+            participant_id: participant.id,
+            status: None,
+            message: err.to_string(),
+        })?;
+
+    if !response.status().is_success() {
+        let body = response.body().await.unwrap_or_default();
+        return Err(NotificationError::Provider {
+            participant_id: participant.id,
+            status: Some(response.status().as_u16()),
+            message: String::from_utf8_lossy(&body).into_owned(),
+        });
+    }
+
+    let message: Option<TwilioMessage> = response.json().await.ok();
+    Ok(message.and_then(|message| message.sid))
+}
+
+/// Asks Twilio whether the message `message_sid`, sent to `participant_id`,
+/// has been delivered.
+pub async fn fetch_delivery_report(
+    config: &SmsConfig,
+    participant_id: Uuid,
+    message_sid: &str,
+) -> Result<DeliveryReport, NotificationError> {
+    let (account_sid, auth_token, _) = twilio_credentials(config)?;
+    let provider_error = |status: Option<u16>, message: String| NotificationError::Provider {
+        participant_id,
+        status,
+        message,
+    };
+
+    let mut response = awc::Client::default()
+        .get(format!(
+            "https://api.twilio.com/2010-04-01/Accounts/{account_sid}/Messages/{message_sid}.json"
+        ))
+        .timeout(SEND_TIMEOUT)
+        .basic_auth(account_sid, auth_token)
+        .send()
+        .await
+        .map_err(|err| provider_error(None, err.to_string()))?;
+    if !response.status().is_success() {
+        let status = response.status().as_u16();
+        let body = response.body().await.unwrap_or_default();
+        return Err(provider_error(
+            Some(status),
+            String::from_utf8_lossy(&body).into_owned(),
+        ));
+    }
+
+    let message: TwilioMessage = response
+        .json()
+        .await
+        .map_err(|err| provider_error(None, err.to_string()))?;
+    Ok(match message.status.as_str() {
+        "delivered" => DeliveryReport::Delivered,
+        "failed" | "undelivered" => DeliveryReport::Failed(
+            message
+                .error_message
+                .or_else(|| {
+                    message
+                        .error_code
+                        .map(|code| format!("Twilio error {code}"))
+                })
+                .unwrap_or_else(|| message.status.clone()),
+        ),
+        _ => DeliveryReport::Pending,
+    })
+}
@@ -0,0 +1,98 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::NotificationError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationChannel {
+    Email,
+    Sms,
+}
+
+/// What a provider last said about a message it accepted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeliveryReport {
+    Pending,
+    Delivered,
+    Failed(String),
+}
+
+/// One notification sent to one participant, and what became of it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationStatus {
+    pub participant_id: Uuid,
+    pub channel: NotificationChannel,
+    pub sent_at: DateTime<Utc>,
+    /// `None` until the provider confirms delivery or failure.
+    pub delivered: Option<bool>,
+    pub failed_reason: Option<String>,
+    /// The provider's id for the message, used to match delivery reports.
+    pub provider_message_id: Option<String>,
+}
+
+impl NotificationStatus {
+    /// Records the outcome of sending; `Ok` carries the provider's message id.
+    pub fn from_result(
+        participant_id: Uuid,
+        channel: NotificationChannel,
+        result: &Result<Option<String>, NotificationError>,
+    ) -> Self {
+        let (delivered, failed_reason, provider_message_id) = match result {
+            Ok(message_id) => (None, None, message_id.clone()),
+            Err(err) => (Some(false), Some(err.to_string()), None),
+        };
+        Self {
+            participant_id,
+            channel,
+            sent_at: Utc::now(),
+            delivered,
+            failed_reason,
+            provider_message_id,
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.delivered.is_none()
+    }
+
+    /// Applies `report`, returning whether anything changed.
+    pub fn apply(&mut self, report: DeliveryReport) -> bool {
+        let (delivered, failed_reason) = match report {
+            DeliveryReport::Pending => return false,
+            DeliveryReport::Delivered => (Some(true), None),
+            DeliveryReport::Failed(reason) => (Some(false), Some(reason)),
+        };
+        let changed = self.delivered != delivered || self.failed_reason != failed_reason;
+        self.delivered = delivered;
+        self.failed_reason = failed_reason;
+        changed
+    }
+}
+
+/// Counts over a bill's notifications. Every notification counts as sent;
+/// delivered and failed are the ones the provider has reported on (or that
+/// were rejected outright), the rest are still pending.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct DeliverySummary {
+    pub sent: u32,
+    pub delivered: u32,
+    pub failed: u32,
+}
+
+impl DeliverySummary {
+    pub fn of(statuses: &[NotificationStatus]) -> Self {
+        let count = |delivered: Option<bool>| {
+            statuses
+                .iter()
+                .filter(|status| status.delivered == delivered)
+                .count() as u32
+        };
+        Self {
+            sent: statuses.len() as u32,
+            delivered: count(Some(true)),
+            failed: count(Some(false)),
+        }
+    }
+}
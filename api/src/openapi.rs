@@ -0,0 +1,1984 @@
+//! OpenAPI 3.1 description of the API, served at `GET /openapi.json`.
+//!
+//! Paths are generated from [`crate::routes::ROUTES`] so every route is
+//! listed; [`operation`] adds the summary, parameters and schemas for each
+//! one. Schemas mirror the JSON the `Serialize`/`Deserialize` impls produce
+//! and must be updated alongside the models.
+
+use std::sync::OnceLock;
+
+use actix_web::http::Method;
+use serde_json::{json, Map, Value};
+
+use crate::{build_info::VERSION, routes::ROUTES};
+
+/// The full spec, built once.
+pub fn spec() -> &'static Value {
+    static SPEC: OnceLock<Value> = OnceLock::new();
+    SPEC.get_or_init(|| {
+        json!({
+            "openapi": "3.1.0",
+            "info": {
+                "title": "Bill Splitter API",
+                "version": VERSION,
+                "description": "Create bills, add participants and line items, and split the total between them."
+            },
+            "paths": paths(),
+            "components": {
+                "schemas": schemas(),
+                "responses": {
+                    "Error": {
+                        "description": "The request could not be served",
+                        "content": json_content(schema_ref("Error"))
+                    }
+                }
+            }
+        })
+    })
+}
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{name}") })
+}
+
+fn array_of(items: Value) -> Value {
+    json!({ "type": "array", "items": items })
+}
+
+fn json_content(schema: Value) -> Value {
+    json!({ "application/json": { "schema": schema } })
+}
+
+fn paths() -> Value {
+    let mut paths = Map::new();
+    for route in ROUTES {
+        let mut item = Map::new();
+        for method in route.methods {
+            item.insert(
+                method.as_str().to_ascii_lowercase(),
+                operation(route.pattern, method),
+            );
+        }
+        paths.insert(route.pattern.to_string(), Value::Object(item));
+    }
+    Value::Object(paths)
+}
+
+/// `{name}` segments of a route pattern as OpenAPI path parameters.
+fn path_parameters(pattern: &str) -> Vec<Value> {
+    pattern
+        .split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+        .map(|name| {
+            let schema = if name == "name" {
+                json!({ "type": "string" })
+            } else {
+                json!({ "type": "string", "format": "uuid" })
+            };
+            json!({ "name": name, "in": "path", "required": true, "schema": schema })
+        })
+        .collect()
+}
+
+fn query(name: &str, schema: Value, description: &str) -> Value {
+    json!({ "name": name, "in": "query", "required": false, "schema": schema, "description": description })
+}
+
+fn split_query() -> Vec<Value> {
+    vec![
+        query(
+            "method",
+            json!({ "type": "string", "enum": ["equal", "proportional", "itemised", "custom"] }),
+            "Split method; defaults to the bill's saved split config, or `equal`",
+        ),
+        query(
+            "weights",
+            json!({ "type": "string" }),
+            "JSON object of participant id to weight, required for `proportional`",
+        ),
+        query(
+            "amounts",
+            json!({ "type": "string" }),
+            "JSON object of participant id to amount, required for `custom`",
+        ),
+        query(
+            "require_acceptance",
+            json!({ "type": "boolean", "default": false }),
+            "Fail with 409 while any participant has not accepted the bill",
+        ),
+    ]
+}
+
+fn round_to_query(description: &str) -> Value {
+    query(
+        "round_to",
+        json!({ "type": "string", "example": "0.05" }),
+        description,
+    )
+}
+
+fn inequality_query() -> Vec<Value> {
+    let mut params = split_query();
+    params.push(query(
+        "threshold",
+        json!({ "type": "number", "default": 3 }),
+        "Largest share to average ratio that triggers `inequality_warning`",
+    ));
+    params
+}
+
+struct Operation {
+    summary: &'static str,
+    query: Vec<Value>,
+    /// Request body `content`, keyed by media type.
+    request: Option<Value>,
+    status: u16,
+    response: Option<Value>,
+}
+
+fn op(summary: &'static str, status: u16, response: Option<Value>) -> Operation {
+    Operation {
+        summary,
+        query: Vec::new(),
+        request: None,
+        status,
+        response,
+    }
+}
+
+impl Operation {
+    fn query(mut self, query: Vec<Value>) -> Self {
+        self.query = query;
+        self
+    }
+
+    fn request(mut self, schema: Value) -> Self {
+        self.request = Some(json_content(schema));
+        self
+    }
+
+    fn request_as(mut self, media_type: &str, schema: Value) -> Self {
+        self.request = Some(json!({ media_type: { "schema": schema } }));
+        self
+    }
+}
+
+fn operation(pattern: &str, method: &Method) -> Value {
+    let doc = match (method.as_str(), pattern) {
+        ("GET", "/health") => op(
+            "Service health and build details",
+            200,
+            Some(schema_ref("Health")),
+        ),
+        ("GET", "/version") => op(
+            "API version, supported features and deprecated endpoints",
+            200,
+            Some(json!({
+                "type": "object",
+                "properties": {
+                    "api_version": { "type": "string", "example": "0.1.0" },
+                    "git_commit": { "type": "string", "example": "abc1234" },
+                    "build_timestamp": { "type": "string", "format": "date-time" },
+                    "features": array_of(json!({ "type": "string", "example": "split-itemised" })),
+                    "deprecated_endpoints": array_of(json!({ "type": "string" }))
+                }
+            })),
+        ),
+        ("GET", "/hello/{name}") => op("Greeting (demo)", 200, None),
+        ("GET", "/stream") => op("Single-chunk stream (demo)", 200, None),
+        ("GET", "/stream-delay") => op("Server-sent event stream (demo)", 200, None),
+        ("GET", "/admin/circuit-breakers") => op(
+            "State of every circuit breaker",
+            200,
+            Some(array_of(schema_ref("CircuitStatus"))),
+        ),
+        ("GET", "/admin/dead-letter-queue") => op(
+            "Notification tasks that failed after every retry; requires `X-Admin-Key`",
+            200,
+            Some(array_of(schema_ref("DeadLetter"))),
+        ),
+        ("POST", "/admin/dead-letter-queue/{task_id}/replay") => op(
+            "Queue a dead-lettered task again; requires `X-Admin-Key`",
+            202,
+            Some(schema_ref("EmailTask")),
+        ),
+        ("POST", "/admin/cron/expire-drafts") => op(
+            "Soft-delete drafts older than `DRAFT_EXPIRY_DAYS`, as the nightly job does; requires `X-Admin-Key`",
+            200,
+            Some(json!({
+                "type": "object",
+                "properties": { "expired_count": { "type": "integer", "minimum": 0 } }
+            })),
+        ),
+        ("POST", "/admin/cron/send-reminders") => op(
+            "Send the reminders that are due, as the reminder check does; requires `X-Admin-Key`",
+            200,
+            Some(json!({
+                "type": "object",
+                "properties": {
+                    "sent_count": { "type": "integer", "minimum": 0 },
+                    "skipped_count": { "type": "integer", "minimum": 0 }
+                }
+            })),
+        ),
+        ("GET", "/admin/kv-stats") => op(
+            "KV key counts and estimated storage; requires `X-Admin-Key`",
+            200,
+            Some(json!({
+                "type": "object",
+                "properties": {
+                    "total_keys": { "type": "integer" },
+                    "bills": { "type": "integer" },
+                    "participants": { "type": "integer" },
+                    "groups": { "type": "integer" },
+                    "share_links": { "type": "integer" },
+                    "estimated_storage_bytes": { "type": "integer" }
+                }
+            })),
+        ),
+        ("POST", "/ai/prompt") => op(
+            "Run a prompt against Workers AI",
+            200,
+            Some(json!({
+                "type": "object",
+                "properties": { "response": { "type": "string" } }
+            })),
+        )
+        .query(vec![query(
+            "use_cache",
+            json!({ "type": "boolean", "default": true }),
+            "Read and write the 24h response cache",
+        )])
+        .request(json!({
+            "type": "object",
+            "required": ["prompt"],
+            "properties": {
+                "prompt": { "type": "string" },
+                "max_tokens": { "type": "integer", "minimum": 1 }
+            }
+        })),
+        ("POST", "/batch") => op(
+            "Run up to 25 operations in one request; if any fails all are rolled back and `X-Batch-Committed` is `false`",
+            200,
+            Some(array_of(schema_ref("BatchResponse"))),
+        )
+        .query(vec![query(
+            "stop_on_error",
+            json!({ "type": "boolean", "default": false }),
+            "Stop at the first failing operation",
+        )])
+        .request(array_of(schema_ref("BatchOperation"))),
+        ("GET", "/bills/overdue") => op(
+            "The caller's bills with shares unpaid past their due date, most overdue first",
+            200,
+            Some(array_of(schema_ref("OverdueBillSummary"))),
+        )
+        .query(vec![query(
+            "participant_id",
+            json!({ "type": "string", "format": "uuid" }),
+            "Only what this participant owes",
+        )]),
+        ("GET", "/bills/shared-with-me") => op(
+            "Bills others created that the caller still owes on, largest balance first",
+            200,
+            Some(array_of(schema_ref("SharedBill"))),
+        ),
+        ("GET", "/bills/month/{year}/{month}") => op(
+            "The caller's bills (on or created) from a calendar month, with what they spent, paid and owe per currency",
+            200,
+            Some(schema_ref("MonthSummary")),
+        ),
+
+        ("GET", "/bills") => op("List bills", 200, Some(schema_ref("BillPage"))).query(vec![
+            query(
+                "cursor",
+                json!({ "type": "string", "format": "uuid" }),
+                "`next_cursor` from the previous page",
+            ),
+            query(
+                "limit",
+                json!({ "type": "integer", "minimum": 1, "maximum": 100, "default": 20 }),
+                "Page size",
+            ),
+            query(
+                "created_by",
+                json!({ "type": "string", "example": "me" }),
+                "`me` for bills the caller created, or a user id (requires `X-Admin-Key`)",
+            ),
+            query(
+                "participant",
+                json!({ "type": "string", "enum": ["me"] }),
+                "Bills the caller takes part in but did not create",
+            ),
+            query(
+                "include_archived",
+                json!({ "type": "boolean", "default": false }),
+                "Include archived bills",
+            ),
+            query(
+                "tag",
+                json!({ "type": "string", "example": "vacation" }),
+                "Only bills with this tag",
+            ),
+        ]),
+        ("POST", "/bills") => op("Create a bill", 201, Some(schema_ref("Bill"))).request(json!({
+            "type": "object",
+            "required": ["title"],
+            "properties": {
+                "title": { "type": "string", "example": "Dinner" },
+                "notes": { "type": ["string", "null"] },
+                "base_currency": { "type": "string", "default": "USD" }
+            }
+        })),
+        ("GET", "/bills/{id}") => op("Fetch a bill", 200, Some(schema_ref("Bill"))),
+        ("PATCH", "/bills/{id}") => {
+            op("Update a bill", 200, Some(schema_ref("Bill"))).request(json!({
+                "type": "object",
+                "properties": {
+                    "title": { "type": "string" },
+                    "notes": { "type": "string", "description": "Empty string clears the notes" },
+                    "discount": schema_ref("Money"),
+                    "status": { "type": "string", "enum": ["draft", "open", "settled"] }
+                }
+            }))
+        }
+        ("POST", "/bills/{id}/participants/{participant_id}/accept") => op(
+            "Confirm the participant is on the bill; with authentication, only they can",
+            200,
+            Some(json!({
+                "type": "object",
+                "properties": {
+                    "participant_id": { "type": "string", "format": "uuid" },
+                    "accepted_at": { "type": "string", "format": "date-time" }
+                }
+            })),
+        ),
+        ("POST", "/bills/{id}/participants/{participant_id}/exempt") => op(
+            "Exempt the participant: they owe nothing and their share falls to everyone else",
+            200,
+            Some(json!({
+                "type": "object",
+                "properties": {
+                    "participant_id": { "type": "string", "format": "uuid" },
+                    "exemption": schema_ref("Exemption")
+                }
+            })),
+        )
+        .request(json!({
+            "type": "object",
+            "required": ["reason"],
+            "properties": { "reason": { "type": "string", "example": "Designated driver" } }
+        })),
+        ("DELETE", "/bills/{id}/participants/{participant_id}/exempt") => {
+            op("Remove the participant's exemption", 204, None)
+        }
+        ("GET", "/bills/{id}/participants/{participant_id}/messages") => op(
+            "AI-written payment reminders for a participant who still owes money",
+            200,
+            Some(array_of(json!({
+                "type": "object",
+                "properties": {
+                    "channel": { "type": "string", "enum": ["sms", "email", "chat"] },
+                    "text": { "type": "string" },
+                    "tone": { "type": "string", "example": "friendly" }
+                }
+            }))),
+        ),
+        ("GET", "/bills/{id}/participants/{participant_id}/receipt") => op(
+            "One participant's receipt; HTML with `Accept: text/html`",
+            200,
+            Some(schema_ref("PersonalReceipt")),
+        ),
+        ("GET", "/bills/{id}/participants/{participant_id}/payment-history") => op(
+            "A participant's payments, newest first",
+            200,
+            Some(array_of(schema_ref("PaymentView"))),
+        ),
+        ("GET", "/bills/{id}/participants/{participant_id}/due-date") => op(
+            "When a participant's share is due and whether it is overdue",
+            200,
+            Some(schema_ref("DueDate")),
+        ),
+        ("GET", "/bills/{id}/participants/{participant_id}/suggested-payment-method") => op(
+            "The payment method to show a participant first, from the apps on their profile",
+            200,
+            Some(schema_ref("PaymentMethodSuggestion")),
+        ),
+        ("GET", "/bills/{id}/participants/{participant_id}/optimal-payment-route") => op(
+            "The cheapest payment app for a participant to pay what they owe, by each app's fees",
+            200,
+            Some(schema_ref("PaymentRoute")),
+        ),
+
+        ("PUT", "/bills/{id}/participants/{participant_id}/due-date") => op(
+            "Set a participant's due date (bill creator only)",
+            200,
+            Some(schema_ref("DueDate")),
+        )
+        .request(json!({
+            "type": "object",
+            "required": ["due_date"],
+            "properties": {
+                "due_date": { "type": ["string", "null"], "format": "date", "description": "null restores the default of DEFAULT_PAYMENT_DAYS after creation" }
+            }
+        })),
+        ("GET", "/bills/{id}/participants/{participant_id}/debt-chain") => op(
+            "How one participant's debt adds up, step by step",
+            200,
+            Some(array_of(schema_ref("DebtStep"))),
+        )
+        .query(split_query()),
+        ("GET", "/bills/{id}/changelog") => op(
+            "What changed on each save of the bill",
+            200,
+            Some(array_of(schema_ref("ChangelogEntry"))),
+        ),
+        ("POST", "/bills/{id}/tags") => op(
+            "Tag a bill, creating the tag if `name` is new",
+            200,
+            Some(schema_ref("Bill")),
+        )
+        .request(json!({
+            "type": "object",
+            "properties": {
+                "tag_id": { "type": "string", "format": "uuid" },
+                "name": { "type": "string", "example": "vacation" },
+                "color": { "type": "string", "example": "#1e90ff" }
+            }
+        })),
+        ("DELETE", "/bills/{id}/tags/{tag_id}") => op("Remove a tag from a bill", 204, None),
+        ("GET", "/bills/{id}/pdf-receipt") => op(
+            "One participant's receipt as `application/pdf`",
+            200,
+            None,
+        )
+        .query(vec![query(
+            "participant_id",
+            json!({ "type": "string", "format": "uuid" }),
+            "Whose receipt to render",
+        )]),
+        ("POST", "/bills/{id}/convert-to-invoice") => op(
+            "A formal invoice from the payer to a participant; `Accept: application/pdf` for a PDF",
+            200,
+            Some(schema_ref("Invoice")),
+        )
+        .request(json!({
+            "type": "object",
+            "required": ["recipient_id"],
+            "properties": { "recipient_id": { "type": "string", "format": "uuid" } }
+        })),
+        ("GET", "/bills/{id}/participants/{participant_id}/payment-qr") => op(
+            "QR code of what the participant owes the payer, as `image/svg+xml` or `image/png`",
+            200,
+            None,
+        )
+        .query(vec![query(
+            "format",
+            json!({ "type": "string", "enum": ["svg", "png"], "default": "svg" }),
+            "Image format",
+        )]),
+        ("GET", "/bills/{id}/completion-percentage") => op(
+            "How much of the latest split has been paid, with a projected settlement date",
+            200,
+            Some(schema_ref("Completion")),
+        ),
+        ("GET", "/bills/{id}/co-payment-links") => op(
+            "Payment app links for everyone who still owes the payer",
+            200,
+            Some(array_of(schema_ref("CoPaymentLink"))),
+        ),
+        ("GET", "/bills/{id}/anomaly-detection") => op(
+            "Line items with unusual prices or quantities, or uncategorised fees",
+            200,
+            Some(array_of(schema_ref("AnomalyAlert"))),
+        ),
+        ("GET", "/bills/{id}/legal-disclaimer") => op(
+            "Disclaimer text for the bill stating it is not a formal invoice",
+            200,
+            Some(json!({
+                "type": "object",
+                "properties": {
+                    "jurisdiction": { "type": "string", "example": "UK" },
+                    "disclaimer": { "type": "string" }
+                }
+            })),
+        )
+        .query(vec![json!({
+            "name": "jurisdiction",
+            "in": "query",
+            "required": true,
+            "schema": { "type": "string", "enum": ["US-CA", "UK", "DE", "FR"] }
+        })]),
+        ("GET", "/bills/{id}/validation-errors") => op(
+            "Every consistency problem with the bill, errors first",
+            200,
+            Some(array_of(schema_ref("ValidationIssue"))),
+        ),
+        ("PUT", "/bills/{id}/payer") => {
+            op("Record who paid the bill", 200, Some(schema_ref("Bill"))).request(json!({
+                "type": "object",
+                "required": ["participant_id"],
+                "properties": { "participant_id": { "type": "string", "format": "uuid" } }
+            }))
+        }
+        ("GET", "/bills/{id}/payer-history") => op(
+            "Every change of the bill's payer, oldest first",
+            200,
+            Some(array_of(schema_ref("PayerChange"))),
+        ),
+        ("GET", "/bills/{id}/suggested-payer") => op(
+            "Suggest whose turn it is to pay, from the group's recent bills",
+            200,
+            Some(json!({
+                "type": "object",
+                "properties": {
+                    "participant_id": { "type": "string", "format": "uuid" },
+                    "name": { "type": "string", "example": "Bob" },
+                    "times_paid_recently": { "type": "integer", "minimum": 0 },
+                    "reason": { "type": "string", "example": "Bob hasn't paid in the last 10 bills, less than anyone else" }
+                }
+            })),
+        )
+        .query(vec![query(
+            "lookback",
+            json!({ "type": "integer", "minimum": 1, "maximum": 100, "default": 10 }),
+            "How many of the group's most recent other bills to consider",
+        )]),
+        ("POST", "/bills/{id}/assign-payer-from-ai") => op(
+            "Let the AI infer who paid from the bill's notes",
+            200,
+            Some(json!({
+                "type": "object",
+                "properties": {
+                    "inferred_payer": schema_ref("Participant"),
+                    "confidence": { "type": "string", "enum": ["high", "medium", "low"] },
+                    "reasoning": { "type": "string" }
+                }
+            })),
+        ),
+        ("GET", "/bills/{id}/participants") => op(
+            "List participants with their share and payment status",
+            200,
+            Some(array_of(schema_ref("BillParticipantView"))),
+        ),
+        ("POST", "/bills/{id}/participants") => {
+            op("Add a participant", 201, Some(schema_ref("Participant"))).request(json!({
+                "type": "object",
+                "required": ["name"],
+                "properties": {
+                    "name": { "type": "string", "example": "Alice" },
+                    "email": { "type": ["string", "null"], "format": "email" },
+                    "phone": { "type": ["string", "null"], "example": "+15551234567" },
+                    "venmo_handle": { "type": ["string", "null"], "example": "alice-smith" },
+                    "paypal_email": { "type": ["string", "null"], "format": "email" },
+                    "cashapp_tag": { "type": ["string", "null"], "example": "$alicesmith" },
+                    "avatar_url": { "type": ["string", "null"], "format": "uri", "description": "Must use HTTPS" }
+                }
+            }))
+        }
+        ("POST", "/bills/{id}/line-items") => {
+            op("Add a line item", 201, Some(schema_ref("LineItem"))).request(json!({
+                "type": "object",
+                "required": ["description", "unit_price"],
+                "properties": {
+                    "description": { "type": "string", "example": "Pizza" },
+                    "quantity": { "type": "integer", "minimum": 1, "default": 1 },
+                    "unit_price": schema_ref("Money"),
+                    "participant_ids": array_of(json!({ "type": "string", "format": "uuid" })),
+                    "category": { "type": ["string", "null"], "example": "food" },
+                    "tax_rate": { "type": ["string", "null"], "description": "Tax included in the price, as a fraction", "example": "0.07" },
+                    "currency": { "type": "string", "description": "Currency of `unit_price`; defaults to the bill's", "example": "EUR" }
+                }
+            }))
+        }
+        ("POST", "/bills/{id}/line-items/{item_id}/merge-into/{other_id}") => op(
+            "Merge one line item into another",
+            200,
+            Some(schema_ref("LineItem")),
+        ),
+        ("POST", "/bills/{id}/line-items/import/csv") => op(
+            "Add line items from a CSV with a `description,quantity,unit_price,tax_rate,category` header; send the bill's ETag as `If-Match` to avoid overwriting concurrent changes",
+            200,
+            Some(schema_ref("ImportLineItemsResult")),
+        )
+        .request_as(
+            "text/csv",
+            json!({
+                "type": "string",
+                "example": "description,quantity,unit_price,tax_rate,category\nPizza,2,12.50,0.07,food\n"
+            }),
+        ),
+        ("POST", "/bills/{id}/line-items/reorder") => op(
+            "Set the display position of every line item",
+            200,
+            Some(array_of(schema_ref("LineItem"))),
+        )
+        .request(json!({
+            "type": "object",
+            "required": ["reorder"],
+            "properties": {
+                "reorder": array_of(json!({
+                    "type": "object",
+                    "required": ["line_item_id", "position"],
+                    "properties": {
+                        "line_item_id": { "type": "string", "format": "uuid" },
+                        "position": { "type": "integer", "minimum": 0 }
+                    }
+                }))
+            }
+        })),
+        ("POST", "/bills/{id}/archive") => op(
+            "Archive a bill, hiding it and making it read-only",
+            200,
+            Some(schema_ref("Bill")),
+        ),
+        ("POST", "/bills/{id}/unarchive") => op(
+            "Restore an archived bill to its previous status",
+            200,
+            Some(schema_ref("Bill")),
+        ),
+        ("POST", "/bills/{id}/duplicate-check") => op(
+            "Find line items that look like duplicates",
+            200,
+            Some(array_of(json!({
+                "type": "object",
+                "properties": {
+                    "item_a_id": { "type": "string", "format": "uuid" },
+                    "item_b_id": { "type": "string", "format": "uuid" },
+                    "similarity_score": { "type": "number", "example": 0.933 },
+                    "reason": { "type": "string" }
+                }
+            }))),
+        ),
+        ("GET", "/bills/{id}/split") => {
+            let mut params = inequality_query();
+            params.extend([
+                query(
+                    "tax_mode",
+                    json!({ "type": "string", "enum": ["inclusive", "exclusive"] }),
+                    "Return each share as gross, net and tax (`inclusive`) or pre-tax plus tax (`exclusive`)",
+                ),
+                query(
+                    "tax_exclusive",
+                    json!({ "type": "boolean", "default": false }),
+                    "Shorthand for `tax_mode=exclusive`",
+                ),
+                round_to_query(
+                    "Round each share up to this multiple (0.01 to 1.00) before recording it",
+                ),
+            ]);
+            op(
+                "Compute the split and record it",
+                200,
+                Some(json!({
+                    "allOf": [
+                        {
+                            "oneOf": [
+                                schema_ref("SplitResult"),
+                                schema_ref("TaxExclusiveSplitResult"),
+                                schema_ref("TaxInclusiveSplitResult")
+                            ]
+                        },
+                        {
+                            "type": "object",
+                            "description": "Carried by every shape, whatever the tax mode",
+                            "properties": {
+                                "inequality_warning": {
+                                    "oneOf": [schema_ref("InequalityWarning"), { "type": "null" }]
+                                },
+                                "unconfirmed_participants": array_of(json!({ "type": "string", "format": "uuid" })),
+                                "exemptions": array_of(json!({
+                                    "type": "object",
+                                    "properties": {
+                                        "participant_id": { "type": "string", "format": "uuid" },
+                                        "reason": { "type": "string" }
+                                    }
+                                }))
+                            }
+                        }
+                    ]
+                })),
+            )
+            .query(params)
+        }
+        ("GET", "/bills/{id}/split/async") => op(
+            "Queue the split to be computed in the background; poll `poll_url` for the result",
+            202,
+            Some(json!({
+                "type": "object",
+                "properties": {
+                    "job_id": { "type": "string", "format": "uuid" },
+                    "poll_url": { "type": "string" }
+                }
+            })),
+        )
+        .query(split_query()),
+        ("GET", "/bills/{id}/split/jobs/{job_id}") => op(
+            "Status of a queued split, with the result once done",
+            200,
+            Some(schema_ref("SplitJobStatus")),
+        ),
+        ("GET", "/bills/{id}/split/tax-inclusive") => op(
+            "Each share as a gross amount with its net and tax parts",
+            200,
+            Some(schema_ref("TaxInclusiveSplitResult")),
+        )
+        .query(split_query()),
+        ("GET", "/bills/{id}/split-inequality-warning") => op(
+            "Warn when one share is far above the average",
+            200,
+            Some(json!({
+                "allOf": [schema_ref("InequalityWarning")],
+                "required": ["warning"],
+                "properties": { "warning": { "type": "boolean" } }
+            })),
+        )
+        .query(inequality_query()),
+        ("GET", "/bills/{id}/split/simulate") => {
+            let mut params = split_query();
+            params.extend([
+                query(
+                    "remove_item",
+                    json!({ "type": "string", "format": "uuid" }),
+                    "Line item to leave out; may repeat",
+                ),
+                query(
+                    "add_discount",
+                    schema_ref("Money"),
+                    "Flat discount to apply",
+                ),
+                query(
+                    "add_discount_pct",
+                    json!({ "type": "string", "example": "0.15" }),
+                    "Fractional discount to apply",
+                ),
+            ]);
+            op(
+                "Preview the split under a hypothetical change",
+                200,
+                Some(schema_ref("SplitDiff")),
+            )
+            .query(params)
+        }
+        ("GET", "/bills/{id}/split/what-if") => {
+            let mut params = split_query();
+            params.extend([
+                query(
+                    "change_price",
+                    json!({ "type": "string", "format": "uuid" }),
+                    "Line item whose unit price changes",
+                ),
+                query("to", schema_ref("Money"), "Its hypothetical unit price"),
+            ]);
+            op(
+                "Preview the split with one line item's unit price changed",
+                200,
+                Some(schema_ref("WhatIfPriceResult")),
+            )
+            .query(params)
+        }
+        ("GET", "/bills/{id}/split/graph") => op(
+            "Debts between participants as a graph",
+            200,
+            Some(schema_ref("DebtGraph")),
+        ),
+        ("GET", "/bills/{id}/split/explain") => op(
+            "Step-by-step explanation of the split",
+            200,
+            Some(array_of(schema_ref("ExplanationStep"))),
+        )
+        .query(split_query()),
+        ("PUT", "/bills/{id}/split-config") => op(
+            "Save the split method and parameters used when requests omit `method`",
+            200,
+            Some(schema_ref("SplitConfig")),
+        )
+        .request(schema_ref("SplitConfig")),
+        ("GET", "/bills/{id}/split/sensitivity") => op(
+            "Line items ranked by how much a 1% price change moves each sharer's cost",
+            200,
+            Some(array_of(schema_ref("SensitivityEntry"))),
+        ),
+        ("GET", "/bills/{id}/split/comparison") => op(
+            "The bill's shares under each split method that needs no extra input",
+            200,
+            Some(schema_ref("SplitComparison")),
+        ),
+        ("GET", "/bills/{id}/split/comparison/visualisation") => op(
+            "One method's shares from the comparison as a `text/plain` bar chart",
+            200,
+            None,
+        )
+        .query(vec![query(
+            "method",
+            json!({ "type": "string", "enum": ["equal", "proportional", "itemised"], "default": "equal" }),
+            "Split method; `proportional` uses equal weights",
+        )]),
+        ("GET", "/bills/{id}/recommended-split-method") => op(
+            "The split method that suits the bill, from how its line items are shared",
+            200,
+            Some(json!({
+                "type": "object",
+                "properties": {
+                    "recommended": { "type": "string", "enum": ["equal", "proportional", "itemised"] },
+                    "confidence": { "type": "number", "minimum": 0, "maximum": 1 },
+                    "reasoning": { "type": "string" },
+                    "alternatives": array_of(json!({ "type": "string" }))
+                }
+            })),
+        ),
+        ("GET", "/bills/{id}/split/breakdown/compact") => op(
+            "Each share as name, total and line item count only, for mobile and push payloads",
+            200,
+            Some(array_of(json!({
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" },
+                    "total": schema_ref("Money"),
+                    "items_count": { "type": "integer", "minimum": 0 },
+                    "exempt_reason": { "type": "string", "description": "Present when the participant is exempt" }
+                }
+            }))),
+        )
+        .query(split_query()),
+        ("GET", "/bills/{id}/split/allocation-matrix") => op(
+            "The fraction of each line item each participant pays in the itemised split",
+            200,
+            Some(schema_ref("AllocationMatrix")),
+        ),
+        ("GET", "/bills/{id}/split/currency-breakdown") => op(
+            "How much of each share came from items in each original currency",
+            200,
+            Some(array_of(schema_ref("ParticipantCurrencyBreakdown"))),
+        )
+        .query(split_query()),
+        ("GET", "/bills/{id}/split/round-to-nearest") => {
+            let mut params = split_query();
+            params.push(round_to_query(
+                "Multiple each share is rounded up to, 0.01 to 1.00; defaults to the smallest unit of the bill's currency",
+            ));
+            op(
+                "The split with each share rounded up to a multiple, the excess taken off the last participant by name",
+                200,
+                Some(json!({
+                    "type": "object",
+                    "properties": {
+                        "method": { "type": "string" },
+                        "total": schema_ref("Money"),
+                        "round_to": schema_ref("Money"),
+                        "shares": array_of(schema_ref("ParticipantShare")),
+                        "remainder": schema_ref("Money"),
+                        "remainder_applied_to": { "type": ["string", "null"], "format": "uuid" }
+                    }
+                })),
+            )
+            .query(params)
+        }
+        ("GET", "/bills/{id}/split/rounding-report") => op(
+            "How the split was rounded to whole cents",
+            200,
+            Some(json!({
+                "type": "object",
+                "properties": {
+                    "total_rounding_applied": schema_ref("Money"),
+                    "cents_added_per_participant": array_of(json!({
+                        "type": "object",
+                        "properties": {
+                            "participant_id": { "type": "string", "format": "uuid" },
+                            "cents_added": { "type": "integer" }
+                        }
+                    })),
+                    "algorithm": { "type": "string" }
+                }
+            })),
+        )
+        .query(split_query()),
+        ("GET", "/bills/{id}/split/preview-as/{participant_id}") => op(
+            "The split as seen by one participant",
+            200,
+            Some(schema_ref("SplitPerspective")),
+        )
+        .query(split_query()),
+        ("POST", "/bills/{id}/split/adjust-rounding") => op(
+            "Fix rounding so shares add up to the total",
+            200,
+            Some(array_of(schema_ref("ParticipantShare"))),
+        )
+        .request(json!({
+            "type": "object",
+            "required": ["shares"],
+            "properties": {
+                "shares": array_of(json!({
+                    "type": "object",
+                    "required": ["participant_id", "amount_owed"],
+                    "properties": {
+                        "participant_id": { "type": "string", "format": "uuid" },
+                        "amount_owed": schema_ref("Money")
+                    }
+                }))
+            }
+        })),
+        ("GET", "/bills/{id}/split-history") => op(
+            "Every split computed for the bill",
+            200,
+            Some(array_of(schema_ref("SplitSnapshot"))),
+        ),
+        ("GET", "/bills/{id}/fairness-history") => op(
+            "The Gini coefficient of each recorded split, oldest first",
+            200,
+            Some(array_of(schema_ref("FairnessPoint"))),
+        )
+        .query(vec![query(
+            "format",
+            json!({ "type": "string", "enum": ["json", "text"], "default": "json" }),
+            "`text` returns a `text/plain` sparkline of the trend",
+        )]),
+        ("DELETE", "/bills/{id}/split-history") => op(
+            "Delete all but the most recent splits",
+            200,
+            Some(json!({
+                "type": "object",
+                "properties": { "deleted": { "type": "integer" }, "remaining": { "type": "integer" } }
+            })),
+        )
+        .query(vec![query(
+            "keep_last",
+            json!({ "type": "integer", "minimum": 0, "default": 1 }),
+            "How many of the most recent splits to keep",
+        )]),
+        ("GET", "/bills/{id}/split-history/{snapshot_id}") => {
+            op("One recorded split", 200, Some(schema_ref("SplitSnapshot")))
+        }
+        ("GET", "/bills/{id}/split/history/{snapshot_id}/replay") => op(
+            "Re-apply a recorded split's method to the bill as it is now",
+            200,
+            Some(json!({
+                "type": "object",
+                "properties": {
+                    "original_snapshot": schema_ref("SplitSnapshot"),
+                    "replayed_snapshot": schema_ref("SplitSnapshot")
+                }
+            })),
+        )
+        .query(split_query().into_iter().skip(1).collect()),
+        ("DELETE", "/bills/{id}/split-history/{snapshot_id}") => {
+            op("Delete one recorded split", 204, None)
+        }
+        ("POST", "/bills/{id}/set-currency-conversion-rates") => op(
+            "Override live exchange rates with manual ones",
+            200,
+            Some(array_of(schema_ref("ExchangeRate"))),
+        )
+        .request(array_of(json!({
+            "type": "object",
+            "required": ["from", "to", "rate"],
+            "properties": {
+                "from": { "type": "string", "example": "EUR" },
+                "to": { "type": "string", "description": "Must be the bill's currency", "example": "USD" },
+                "rate": { "type": "string", "description": "Units of `to` per unit of `from`; must be positive", "example": "1.0790" }
+            }
+        }))),
+        ("POST", "/bills/{id}/notify") => op(
+            "Queue an email to every participant with their share and schedule a reminder",
+            202,
+            Some(json!({
+                "type": "object",
+                "properties": {
+                    "queued": { "type": "integer", "minimum": 0 },
+                    "reminders_scheduled": { "type": "integer", "minimum": 0 }
+                }
+            })),
+        ),
+        ("GET", "/bills/{id}/participants/{participant_id}/comparative-spend") => op(
+            "The participant's share of the latest split compared with the average",
+            200,
+            Some(json!({
+                "type": "object",
+                "properties": {
+                    "participant_id": { "type": "string", "format": "uuid" },
+                    "their_share": schema_ref("Money"),
+                    "group_average": schema_ref("Money"),
+                    "delta": schema_ref("Money"),
+                    "percentile": {
+                        "type": "number",
+                        "minimum": 0,
+                        "maximum": 1,
+                        "description": "0 for the lowest share, 1 for the highest; 0.5 when everyone pays the same"
+                    }
+                }
+            })),
+        ),
+        ("GET", "/bills/{id}/participants/{participant_id}/reminders") => op(
+            "Reminders still to be sent to the participant, soonest first",
+            200,
+            Some(array_of(schema_ref("ScheduledReminder"))),
+        ),
+        ("DELETE", "/bills/{id}/participants/{participant_id}/reminders/{reminder_id}") => op(
+            "Cancel a pending reminder",
+            204,
+            None,
+        ),
+        ("POST", "/bills/{id}/notify/sms") => op(
+            "Text every participant their share",
+            200,
+            Some(schema_ref("NotifySummary")),
+        ),
+        ("GET", "/bills/{id}/notifications/status") => op(
+            "Delivery status of every notification sent for the bill",
+            200,
+            Some(schema_ref("NotificationStatusReport")),
+        ),
+        ("GET", "/participants/search") => op(
+            "Participants whose name is like `q` or whose email starts with it, best match first",
+            200,
+            Some(array_of(schema_ref("ParticipantMatch"))),
+        )
+        .query(vec![
+            query(
+                "q",
+                json!({ "type": "string", "example": "ali" }),
+                "Part of a name, or the start of an email address",
+            ),
+            query(
+                "limit",
+                json!({ "type": "integer", "minimum": 1, "maximum": 20, "default": 10 }),
+                "Most results to return; at most 20",
+            ),
+        ]),
+        ("GET", "/participants/{id}/stats") => op(
+            "A participant's spending across all their bills (cached for 10 minutes)",
+            200,
+            Some(schema_ref("ParticipantStats")),
+        ),
+        ("GET", "/participants/{id}/avatar") => op(
+            "The participant's avatar image, fetched from their `avatar_url` (cached for a day)",
+            200,
+            None,
+        ),
+        ("POST", "/participants/{id}/merge") => op(
+            "Merge a duplicate participant into another on every bill and delete it; requires `X-Confirm: merge`",
+            200,
+            Some(json!({
+                "type": "object",
+                "properties": {
+                    "bills_updated": { "type": "integer", "minimum": 0 },
+                    "payments_transferred": { "type": "integer", "minimum": 0 }
+                }
+            })),
+        )
+        .request(json!({
+            "type": "object",
+            "required": ["merge_into"],
+            "properties": { "merge_into": { "type": "string", "format": "uuid" } }
+        })),
+        ("POST", "/webhooks/mailgun") => op(
+            "Receive Mailgun delivery events (signed with MAILGUN_WEBHOOK_SIGNING_KEY)",
+            200,
+            None,
+        ),
+        ("POST", "/bills/{id}/payments") => {
+            op("Record a payment", 201, Some(schema_ref("Payment"))).request(json!({
+                "type": "object",
+                "required": ["participant_id", "amount"],
+                "properties": {
+                    "participant_id": { "type": "string", "format": "uuid" },
+                    "amount": schema_ref("Money"),
+                    "currency": { "type": "string", "description": "Defaults to the bill's base currency", "example": "EUR" },
+                    "note": { "type": ["string", "null"] }
+                }
+            }))
+        }
+        ("GET", "/bills/{id}/settlements") => op(
+            "Transfers that settle the bill",
+            200,
+            Some(array_of(schema_ref("Transfer"))),
+        ),
+        ("GET", "/bills/{id}/split/minimised-vs-naive") => op(
+            "Transfers saved by minimising settlements over every debtor paying every creditor",
+            200,
+            Some(schema_ref("SettlementSavings")),
+        ),
+        ("GET", "/bills/{id}/taxes") => op(
+            "Tax included in the bill, grouped by rate",
+            200,
+            Some(schema_ref("TaxSummary")),
+        ),
+        ("POST", "/bills/{id}/send-to-accounting") => {
+            op("Download the bill as a QuickBooks IIF file", 200, None)
+        }
+        ("GET", "/bills/{id}/export/spreadsheet-formulas") => op(
+            "Download the bill as CSV whose totals are Excel/Sheets formulas",
+            200,
+            None,
+        ),
+
+        _ => op("Undocumented", 200, None),
+    };
+
+    let mut parameters = path_parameters(pattern);
+    parameters.extend(doc.query);
+
+    let mut success = json!({ "description": "Success" });
+    if let Some(schema) = doc.response {
+        success["content"] = json_content(schema);
+    }
+    let mut operation = json!({
+        "summary": doc.summary,
+        "parameters": parameters,
+        "responses": {
+            doc.status.to_string(): success,
+            "4XX": { "$ref": "#/components/responses/Error" },
+            "5XX": { "$ref": "#/components/responses/Error" }
+        }
+    });
+    if let Some(content) = doc.request {
+        operation["requestBody"] = json!({ "required": true, "content": content });
+    }
+    operation
+}
+
+fn schemas() -> Value {
+    let uuid = json!({ "type": "string", "format": "uuid" });
+    let timestamp = json!({ "type": "string", "format": "date-time" });
+    let money = schema_ref("Money");
+
+    json!({
+        "Money": {
+            "type": "string",
+            "pattern": "^-?\\d+\\.\\d{2}$",
+            "description": "Decimal amount with two places. Numbers are also accepted on input.",
+            "example": "9.00"
+        },
+        "Error": {
+            "type": "object",
+            "properties": {
+                "error": { "type": "string", "example": "Not Found" },
+                "message": { "type": "string" },
+                "reason": { "type": "string", "description": "Set instead of `message` for `InsufficientData`" },
+                "status": { "type": "integer", "example": 404 }
+            }
+        },
+        "Health": {
+            "type": "object",
+            "properties": {
+                "status": { "type": "string", "example": "ok" },
+                "version": { "type": "string" },
+                "git_commit": { "type": "string" },
+                "build_timestamp": timestamp
+            }
+        },
+        "Participant": {
+            "type": "object",
+            "required": ["id", "name", "created_at"],
+            "properties": {
+                "id": uuid,
+                "name": { "type": "string", "example": "Alice" },
+                "email": { "type": ["string", "null"] },
+                "phone": { "type": ["string", "null"] },
+                "venmo_handle": { "type": ["string", "null"], "example": "alice-smith" },
+                "paypal_email": { "type": ["string", "null"], "format": "email" },
+                "cashapp_tag": { "type": ["string", "null"], "example": "$alicesmith" },
+                "avatar_url": { "type": ["string", "null"], "format": "uri", "example": "https://example.com/alice.png" },
+                "created_at": timestamp
+            }
+        },
+        "BillParticipant": {
+            "type": "object",
+            "properties": {
+                "participant_id": uuid,
+                "joined_at": timestamp,
+                "accepted_at": { "type": ["string", "null"], "format": "date-time" },
+                "exemption": schema_ref("Exemption")
+            }
+        },
+        "Exemption": {
+            "type": "object",
+            "properties": {
+                "reason": { "type": "string", "example": "Designated driver" },
+                "exempted_at": timestamp
+            }
+        },
+        "LineItem": {
+            "type": "object",
+            "required": ["id", "description", "quantity", "unit_price"],
+            "properties": {
+                "id": uuid,
+                "description": { "type": "string", "example": "Pizza" },
+                "quantity": { "type": "integer", "minimum": 1, "example": 1 },
+                "unit_price": money,
+                "participant_ids": array_of(uuid.clone()),
+                "category": { "type": ["string", "null"] },
+                "tax_rate": { "type": ["string", "null"], "example": "0.07" },
+                "currency": { "type": ["string", "null"], "example": "EUR" },
+                "original_unit_price": { "type": ["string", "null"], "example": "12.00" },
+                "position": { "type": "integer", "minimum": 0, "description": "Display position; line items are listed in this order" }
+            }
+        },
+        "Payment": {
+            "type": "object",
+            "properties": {
+                "id": uuid,
+                "participant_id": uuid,
+                "amount": money,
+                "currency": { "type": ["string", "null"], "example": "EUR" },
+                "conversion_rate": { "type": ["string", "null"], "example": "1.0842" },
+                "converted_amount": { "oneOf": [money, { "type": "null" }] },
+                "note": { "type": ["string", "null"] },
+                "paid_at": timestamp
+            }
+        },
+        "ParticipantShare": {
+            "type": "object",
+            "properties": {
+                "participant_id": uuid,
+                "name": { "type": "string" },
+                "amount_owed": money
+            },
+            "example": { "participant_id": "4b1f5c0e-8a43-4d1b-9d9a-1c2e3f4a5b6c", "name": "Alice", "amount_owed": "9.18" }
+        },
+        "SplitResult": {
+            "type": "object",
+            "properties": {
+                "method": { "type": "string" },
+                "total": money,
+                "shares": array_of(schema_ref("ParticipantShare")),
+                "inequality_warning": {
+                    "oneOf": [schema_ref("InequalityWarning"), { "type": "null" }]
+                },
+                "unconfirmed_participants": array_of(uuid.clone()),
+                "exemptions": array_of(json!({
+                    "type": "object",
+                    "description": "Participants who owe nothing; omitted when there are none",
+                    "properties": {
+                        "participant_id": uuid.clone(),
+                        "reason": { "type": "string" }
+                    }
+                }))
+            }
+        },
+        "AllocationMatrix": {
+            "type": "object",
+            "properties": {
+                "participants": array_of(json!({
+                    "type": "object",
+                    "properties": { "id": uuid.clone(), "name": { "type": "string" } }
+                })),
+                "items": array_of(json!({
+                    "type": "object",
+                    "properties": {
+                        "id": uuid.clone(),
+                        "description": { "type": "string" },
+                        "total": money.clone()
+                    }
+                })),
+                "matrix": {
+                    "type": "array",
+                    "description": "`matrix[i][j]` is the fraction of `items[j]` paid by `participants[i]`; each column sums to 1",
+                    "items": array_of(json!({ "type": "string", "example": "0.5" }))
+                }
+            }
+        },
+        "SplitJobStatus": {
+            "type": "object",
+            "properties": {
+                "job_id": uuid.clone(),
+                "bill_id": uuid.clone(),
+                "status": { "type": "string", "enum": ["pending", "running", "done", "failed"] },
+                "result": schema_ref("SplitResult"),
+                "error": { "type": "string" },
+                "created_at": timestamp,
+                "updated_at": timestamp
+            }
+        },
+        "PaymentMethodSuggestion": {
+            "type": "object",
+            "properties": {
+                "participant_id": uuid,
+                "recommended_method": {
+                    "type": "string",
+                    "enum": ["venmo", "paypal", "cashapp", "bank_transfer", "cash"]
+                },
+                "reason": { "type": "string" }
+            }
+        },
+        "PaymentRoute": {
+            "type": "object",
+            "properties": {
+                "participant_id": uuid.clone(),
+                "best_route": {
+                    "type": "string",
+                    "enum": ["venmo", "paypal", "cashapp", "bank_transfer", "cash"]
+                },
+                "reason": { "type": "string" },
+                "estimated_fee": money.clone(),
+                "alternative_routes": array_of(json!({
+                    "type": "object",
+                    "properties": {
+                        "method": { "type": "string", "enum": ["venmo", "paypal", "cashapp"] },
+                        "estimated_fee": money.clone(),
+                        "reason": { "type": "string" }
+                    }
+                }))
+            }
+        },
+        "SettlementSavings": {
+            "type": "object",
+            "properties": {
+                "naive_transfer_count": { "type": "integer" },
+                "naive_total_amount": money,
+                "minimised_transfer_count": { "type": "integer" },
+                "minimised_total_amount": money,
+                "transfers_saved": { "type": "integer" }
+            }
+        },
+        "Completion": {
+            "type": "object",
+            "properties": {
+                "total": money,
+                "paid": money,
+                "outstanding": money,
+                "completion_pct": { "type": "number", "example": 62.5 },
+                "fully_settled_participants": { "type": "integer" },
+                "total_participants": { "type": "integer" },
+                "projected_settlement_date": {
+                    "type": ["string", "null"],
+                    "format": "date",
+                    "description": "At the pace of the last 7 days of payments; null once settled or without recent payments"
+                }
+            }
+        },
+        "ImportLineItemsResult": {
+            "type": "object",
+            "properties": {
+                "imported": { "type": "integer" },
+                "skipped": { "type": "integer" },
+                "errors": array_of(json!({
+                    "type": "object",
+                    "properties": {
+                        "row": { "type": "integer", "description": "Line of the file, the header being 1" },
+                        "error": { "type": "string" }
+                    }
+                }))
+            }
+        },
+        "SplitComparison": {
+            "type": "object",
+            "properties": {
+                "equal": array_of(schema_ref("ParticipantShare")),
+                "proportional_equal_weights": array_of(schema_ref("ParticipantShare")),
+                "itemised": {
+                    "oneOf": [array_of(schema_ref("ParticipantShare")), { "type": "null" }],
+                    "description": "Null unless every line item is assigned to someone"
+                }
+            }
+        },
+        "InequalityWarning": {
+            "type": "object",
+            "properties": {
+                "max_payer_id": uuid,
+                "max_payer_amount": money,
+                "average_amount": money,
+                "ratio": { "type": "string", "example": "3.12" }
+            }
+        },
+        "SplitSnapshot": {
+            "type": "object",
+            "properties": {
+                "id": uuid,
+                "method": { "type": "string" },
+                "computed_at": timestamp,
+                "shares": array_of(schema_ref("ParticipantShare")),
+                "gini_coefficient": { "type": "number", "minimum": 0, "maximum": 1 }
+            }
+        },
+        "PayerChange": {
+            "type": "object",
+            "properties": {
+                "previous_payer_id": { "type": "string", "format": "uuid", "nullable": true },
+                "new_payer_id": { "type": "string", "format": "uuid", "nullable": true },
+                "changed_at": timestamp.clone(),
+                "changed_by": { "type": "string", "nullable": true }
+            }
+        },
+        "ParticipantMatch": {
+            "allOf": [
+                schema_ref("Participant"),
+                {
+                    "type": "object",
+                    "properties": {
+                        "score": {
+                            "type": "number",
+                            "description": "1 for an email prefix match, otherwise the Jaro-Winkler similarity of the name"
+                        }
+                    }
+                }
+            ]
+        },
+        "FairnessPoint": {
+            "type": "object",
+            "properties": {
+                "snapshot_id": uuid.clone(),
+                "computed_at": timestamp.clone(),
+                "method": { "type": "string" },
+                "gini_coefficient": { "type": "number", "minimum": 0, "maximum": 1 }
+            }
+        },
+        "Bill": {
+            "type": "object",
+            "required": ["id", "title", "created_at", "updated_at"],
+            "properties": {
+                "id": uuid,
+                "title": { "type": "string", "example": "Dinner" },
+                "notes": { "type": ["string", "null"] },
+                "status": { "type": "string", "enum": ["draft", "open", "settled", "archived"] },
+                "archived_at": { "type": ["string", "null"], "format": "date-time" },
+                "base_currency": { "type": "string", "example": "USD" },
+                "exchange_rates_used": array_of(schema_ref("ExchangeRate")),
+                "manual_rates": array_of(schema_ref("ExchangeRate")),
+                "split_config": { "oneOf": [schema_ref("SplitConfig"), { "type": "null" }] },
+                "creator_id": { "type": ["string", "null"], "format": "uuid" },
+                "participants": array_of(schema_ref("BillParticipant")),
+                "payer_id": { "type": ["string", "null"], "format": "uuid" },
+                "line_items": array_of(schema_ref("LineItem")),
+                "discount": money,
+                "split_history": array_of(schema_ref("SplitSnapshot")),
+                "payments": array_of(schema_ref("Payment")),
+                "merge_audit": array_of(json!({
+                    "type": "object",
+                    "properties": {
+                        "id": uuid,
+                        "source": schema_ref("LineItem"),
+                        "target": schema_ref("LineItem"),
+                        "merged_at": timestamp
+                    }
+                })),
+                "tags": array_of(schema_ref("BillTag")),
+                "due_dates": {
+                    "type": "object",
+                    "description": "Due dates set by the creator, keyed by participant id",
+                    "additionalProperties": { "type": "string", "format": "date" }
+                },
+                "created_at": timestamp,
+                "updated_at": timestamp
+            }
+        },
+        "OverdueBillSummary": {
+            "type": "object",
+            "properties": {
+                "bill_id": uuid,
+                "title": { "type": "string" },
+                "overdue_participants": array_of(json!({
+                    "type": "object",
+                    "properties": {
+                        "participant_id": uuid,
+                        "name": { "type": "string" },
+                        "amount_owed": money,
+                        "days_overdue": { "type": "integer", "minimum": 1 }
+                    }
+                }))
+            }
+        },
+        "ParticipantStats": {
+            "type": "object",
+            "properties": {
+                "participant_id": uuid,
+                "bill_count": { "type": "integer" },
+                "total_owed": money,
+                "total_paid": money,
+                "monthly_spend": array_of(json!({
+                    "type": "object",
+                    "properties": {
+                        "month": { "type": "string", "example": "2026-10" },
+                        "amount": schema_ref("Money")
+                    }
+                })),
+                "top_categories": array_of(json!({
+                    "type": "object",
+                    "properties": {
+                        "category": { "type": "string", "example": "food" },
+                        "amount": schema_ref("Money")
+                    }
+                })),
+                "most_frequent_co_participants": array_of(json!({
+                    "type": "object",
+                    "properties": {
+                        "participant_id": { "type": "string", "format": "uuid" },
+                        "name": { "type": "string" },
+                        "shared_bills_count": { "type": "integer" }
+                    }
+                })),
+                "generated_at": timestamp
+            }
+        },
+        "SplitConfig": {
+            "type": "object",
+            "required": ["method"],
+            "properties": {
+                "method": { "type": "string", "enum": ["equal", "proportional", "itemised", "custom"] },
+                "weights": {
+                    "type": "object",
+                    "description": "Participant id to weight, required for `proportional`",
+                    "additionalProperties": { "type": "string", "example": "2" }
+                },
+                "amounts": {
+                    "type": "object",
+                    "description": "Participant id to amount, required for `custom`",
+                    "additionalProperties": money
+                },
+                "assignments": {
+                    "type": "object",
+                    "description": "Line item id to participant ids, `itemised` only; applied to the line items on save",
+                    "additionalProperties": array_of(uuid.clone())
+                }
+            }
+        },
+        "SensitivityEntry": {
+            "type": "object",
+            "properties": {
+                "line_item_id": uuid,
+                "description": { "type": "string", "example": "Pizza" },
+                "impact_per_percent_change": money,
+                "high_impact": { "type": "boolean", "description": "More than twice the average impact" }
+            }
+        },
+        "ExchangeRate": {
+            "type": "object",
+            "properties": {
+                "from": { "type": "string", "example": "EUR" },
+                "to": { "type": "string", "example": "USD" },
+                "rate": { "type": "string", "example": "1.0842" },
+                "fetched_at": timestamp,
+                "source": { "type": "string", "enum": ["live", "manual"] }
+            }
+        },
+        "Invoice": {
+            "type": "object",
+            "properties": {
+                "invoice_number": { "type": "string", "example": "INV-3F2A9C1B" },
+                "issued_date": { "type": "string", "format": "date" },
+                "due_date": { "type": "string", "format": "date" },
+                "issuer": schema_ref("InvoiceParty"),
+                "recipient": schema_ref("InvoiceParty"),
+                "bill_id": uuid.clone(),
+                "bill_title": { "type": "string", "example": "Friday dinner" },
+                "currency": { "type": "string", "example": "USD" },
+                "line_items": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "description": { "type": "string", "example": "Pad thai" },
+                            "quantity": { "type": "integer", "minimum": 1 },
+                            "unit_price": money,
+                            "tax_rate": { "type": ["string", "null"], "example": "0.07" },
+                            "amount": money
+                        }
+                    }
+                },
+                "discount": money,
+                "subtotal": money,
+                "tax": money,
+                "total": money,
+                "payment_terms": { "type": "string", "example": "Net 7: payment is due within 7 days of the invoice date" }
+            }
+        },
+        "CoPaymentLink": {
+            "type": "object",
+            "properties": {
+                "participant_id": uuid.clone(),
+                "name": { "type": "string", "example": "Bob" },
+                "amount": money,
+                "venmo_link": { "type": ["string", "null"], "example": "venmo://paycharge?txn=pay&recipients=alice-smith&amount=12.50&note=Dinner" },
+                "paypal_link": { "type": ["string", "null"] },
+                "cashapp_link": { "type": ["string", "null"], "example": "https://cash.app/$alicesmith/12.50" }
+            }
+        },
+        "AnomalyAlert": {
+            "type": "object",
+            "properties": {
+                "line_item_id": uuid.clone(),
+                "description": { "type": "string", "example": "Service charge" },
+                "reason": { "type": "string", "example": "Looks like a service charge but has no category" },
+                "severity": { "type": "string", "enum": ["low", "medium", "high"] }
+            }
+        },
+        "ValidationIssue": {
+            "type": "object",
+            "properties": {
+                "severity": { "type": "string", "enum": ["error", "warning"] },
+                "code": { "type": "string", "example": "unassigned_line_item" },
+                "message": { "type": "string", "example": "\"Pizza\" is not assigned to anyone" },
+                "affected_id": { "type": ["string", "null"], "format": "uuid" }
+            }
+        },
+        "EmailTask": {
+            "type": "object",
+            "properties": {
+                "id": uuid.clone(),
+                "participant_id": uuid.clone(),
+                "bill_id": uuid.clone(),
+                "channel": { "type": "string", "enum": ["email", "sms"] }
+            }
+        },
+        "DeadLetter": {
+            "type": "object",
+            "properties": {
+                "task": schema_ref("EmailTask"),
+                "attempts": { "type": "integer", "minimum": 1 },
+                "error": { "type": "string" },
+                "failed_at": timestamp
+            }
+        },
+        "TaxExclusiveSplitResult": {
+            "type": "object",
+            "properties": {
+                "shares": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "participant_id": uuid.clone(),
+                            "pre_tax_amount": money,
+                            "tax_amount": money,
+                            "total_amount": money
+                        }
+                    }
+                },
+                "total_pre_tax": money,
+                "total_tax": money
+            }
+        },
+        "ParticipantCurrencyBreakdown": {
+            "type": "object",
+            "properties": {
+                "participant_id": uuid.clone(),
+                "currency_shares": array_of(json!({
+                    "type": "object",
+                    "properties": {
+                        "currency": { "type": "string", "example": "EUR" },
+                        "original_amount": money,
+                        "converted_amount": money,
+                        "rate_used": { "type": "string", "example": "1.0842" }
+                    }
+                }))
+            }
+        },
+        "TaxInclusiveSplitResult": {
+            "type": "object",
+            "properties": {
+                "shares": array_of(json!({
+                    "type": "object",
+                    "properties": {
+                        "participant_id": uuid.clone(),
+                        "gross_amount": money,
+                        "net_amount": money,
+                        "tax_component": money
+                    }
+                })),
+                "total_gross": money,
+                "total_net": money,
+                "total_tax": money
+            }
+        },
+        "InvoiceParty": {
+            "type": "object",
+            "properties": {
+                "participant_id": uuid.clone(),
+                "name": { "type": "string", "example": "Alice" },
+                "email": { "type": ["string", "null"], "format": "email" }
+            }
+        },
+        "DueDate": {
+            "type": "object",
+            "properties": {
+                "participant_id": uuid,
+                "due_date": { "type": "string", "format": "date" },
+                "is_override": { "type": "boolean" },
+                "outstanding": { "type": ["string", "null"], "example": "12.50" },
+                "is_overdue": { "type": "boolean" },
+                "days_overdue": { "type": "integer", "minimum": 0 }
+            }
+        },
+        "PaymentView": {
+            "type": "object",
+            "properties": {
+                "payment_id": uuid,
+                "counterpart_name": { "type": ["string", "null"], "example": "Carol" },
+                "amount": money,
+                "currency": { "type": "string", "example": "USD" },
+                "paid_at": timestamp,
+                "is_automated": { "type": "boolean" },
+                "memo": { "type": ["string", "null"] },
+                "remaining_balance": { "type": ["string", "null"], "example": "4.50" }
+            }
+        },
+        "ChangelogEntry": {
+            "type": "object",
+            "properties": {
+                "timestamp": timestamp,
+                "actor_name": { "type": ["string", "null"], "example": "Alice" },
+                "changes": array_of(json!({
+                    "type": "string",
+                    "example": "Line item 'Sushi' price changed from $12.00 to $14.00"
+                }))
+            }
+        },
+        "BillTag": {
+            "type": "object",
+            "properties": {
+                "id": uuid,
+                "name": { "type": "string", "example": "vacation" },
+                "color": { "type": ["string", "null"], "example": "#1e90ff" }
+            }
+        },
+        "MonthSummary": {
+            "type": "object",
+            "properties": {
+                "year": { "type": "integer", "minimum": 2020, "example": 2024 },
+                "month": { "type": "integer", "minimum": 1, "maximum": 12, "example": 12 },
+                "bills": array_of(json!({
+                    "type": "object",
+                    "properties": {
+                        "id": uuid.clone(),
+                        "title": { "type": "string" },
+                        "status": { "type": "string", "enum": ["draft", "open", "settled", "archived"] },
+                        "currency": { "type": "string", "example": "USD" },
+                        "total": money.clone(),
+                        "your_share": { "oneOf": [money.clone(), { "type": "null" }] },
+                        "created_at": timestamp.clone()
+                    }
+                })),
+                "totals": array_of(json!({
+                    "type": "object",
+                    "description": "One per bill currency; amounts are never added across currencies",
+                    "properties": {
+                        "currency": { "type": "string", "example": "USD" },
+                        "total_spent": money.clone(),
+                        "you_paid": money.clone(),
+                        "you_owe": money.clone()
+                    }
+                }))
+            }
+        },
+        "SharedBill": {
+            "allOf": [
+                schema_ref("Bill"),
+                {
+                    "type": "object",
+                    "properties": {
+                        "outstanding_amount": money,
+                        "creator_name": { "type": ["string", "null"] }
+                    }
+                }
+            ]
+        },
+        "BillPage": {
+            "type": "object",
+            "properties": {
+                "bills": array_of(schema_ref("Bill")),
+                "next_cursor": { "type": ["string", "null"], "format": "uuid" }
+            }
+        },
+        "BillParticipantView": {
+            "type": "object",
+            "properties": {
+                "participant_id": uuid,
+                "name": { "type": "string" },
+                "email": { "type": ["string", "null"] },
+                "share_amount": { "oneOf": [money, { "type": "null" }] },
+                "has_paid": { "type": "boolean" },
+                "payment_amount": { "oneOf": [money, { "type": "null" }] }
+            }
+        },
+        "PersonalReceipt": {
+            "type": "object",
+            "properties": {
+                "participant_id": uuid,
+                "name": { "type": "string" },
+                "bill_title": { "type": "string" },
+                "date": timestamp,
+                "your_items": array_of(json!({
+                    "type": "object",
+                    "properties": {
+                        "id": uuid,
+                        "description": { "type": "string" },
+                        "quantity": { "type": "integer" },
+                        "unit_price": money,
+                        "shared_with": { "type": "integer" },
+                        "your_portion": money
+                    }
+                })),
+                "subtotal": money,
+                "discount": money,
+                "tax": money,
+                "tip": money,
+                "your_total": money,
+                "paid": money,
+                "outstanding": money
+            }
+        },
+        "TaxSummary": {
+            "type": "object",
+            "properties": {
+                "total_pre_tax": money,
+                "total_tax": money,
+                "effective_tax_rate": { "type": "string", "example": "0.0700" },
+                "breakdown": array_of(json!({
+                    "type": "object",
+                    "properties": {
+                        "rate": { "type": "string", "example": "0.07" },
+                        "taxable_amount": money,
+                        "tax_amount": money
+                    }
+                }))
+            }
+        },
+        "Transfer": {
+            "type": "object",
+            "description": "Money one participant pays another to settle up",
+            "properties": { "from": uuid, "to": uuid, "amount": money },
+            "example": {
+                "from": "4b1f5c0e-8a43-4d1b-9d9a-1c2e3f4a5b6c",
+                "to": "9e8d7c6b-5a49-4382-a1b0-c9d8e7f6a5b4",
+                "amount": "9.18"
+            }
+        },
+        "DebtGraph": {
+            "type": "object",
+            "properties": {
+                "nodes": array_of(json!({
+                    "type": "object",
+                    "properties": { "id": uuid, "name": { "type": "string" }, "net_balance": money }
+                })),
+                "edges": array_of(json!({
+                    "type": "object",
+                    "properties": {
+                        "from": uuid,
+                        "to": uuid,
+                        "amount": money,
+                        "weight": { "type": "number", "minimum": 0, "maximum": 1 }
+                    }
+                }))
+            }
+        },
+        "SplitDiff": {
+            "type": "object",
+            "properties": {
+                "original_shares": array_of(schema_ref("ParticipantShare")),
+                "simulated_shares": array_of(schema_ref("ParticipantShare")),
+                "delta_per_participant": array_of(json!({
+                    "type": "object",
+                    "properties": { "participant_id": uuid, "name": { "type": "string" }, "delta": money }
+                }))
+            }
+        },
+        "WhatIfPriceResult": {
+            "type": "object",
+            "properties": {
+                "item_id": uuid,
+                "original_price": money,
+                "hypothetical_price": money,
+                "original_shares": array_of(schema_ref("ParticipantShare")),
+                "hypothetical_shares": array_of(schema_ref("ParticipantShare")),
+                "delta_per_participant": array_of(json!({
+                    "type": "object",
+                    "properties": { "participant_id": uuid, "name": { "type": "string" }, "delta": money }
+                }))
+            }
+        },
+        "SplitPerspective": {
+            "type": "object",
+            "properties": {
+                "method": { "type": "string" },
+                "total": money,
+                "shares": array_of(json!({
+                    "allOf": [schema_ref("ParticipantShare")],
+                    "properties": { "is_you": { "type": "boolean" } }
+                })),
+                "you_owe_total": money,
+                "others_owe_you_total": money
+            }
+        },
+        "ExplanationStep": {
+            "type": "object",
+            "properties": {
+                "step": { "type": "integer" },
+                "description": { "type": "string", "example": "Divided $27.50 total by 3 participants = $9.16 each." },
+                "running_total": money
+            }
+        },
+        "DebtStep": {
+            "type": "object",
+            "properties": {
+                "type": { "type": "string", "enum": ["LineItem", "Tip", "Discount", "Payment", "Rounding"] },
+                "description": { "type": "string", "example": "Pizza ($9.00): shared by Alice, Bob (2 ways), so you owe $4.50." },
+                "delta": money,
+                "running_total": money
+            }
+        },
+        "NotifySummary": {
+            "type": "object",
+            "properties": {
+                "sent": { "type": "integer" },
+                "failed": { "type": "integer" },
+                "errors": array_of(json!({ "type": "object", "properties": { "kind": { "type": "string" } } }))
+            }
+        },
+        "ScheduledReminder": {
+            "type": "object",
+            "properties": {
+                "id": uuid.clone(),
+                "channel": { "type": "string", "enum": ["email", "sms"] },
+                "scheduled_for": timestamp,
+                "message_preview": { "type": "string" }
+            }
+        },
+        "NotificationStatus": {
+            "type": "object",
+            "properties": {
+                "participant_id": uuid,
+                "channel": { "type": "string", "enum": ["email", "sms"] },
+                "sent_at": timestamp,
+                "delivered": { "type": ["boolean", "null"], "description": "null until the provider reports delivery or failure" },
+                "failed_reason": { "type": ["string", "null"] },
+                "provider_message_id": { "type": ["string", "null"] }
+            }
+        },
+        "NotificationStatusReport": {
+            "type": "object",
+            "properties": {
+                "summary": {
+                    "type": "object",
+                    "properties": {
+                        "sent": { "type": "integer" },
+                        "delivered": { "type": "integer" },
+                        "failed": { "type": "integer" }
+                    }
+                },
+                "notifications": array_of(schema_ref("NotificationStatus"))
+            }
+        },
+        "CircuitStatus": {
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "state": { "type": "string", "enum": ["closed", "open", "half_open"] },
+                "consecutive_failures": { "type": "integer" },
+                "retry_after_secs": { "type": ["integer", "null"] }
+            }
+        },
+        "BatchOperation": {
+            "type": "object",
+            "required": ["method", "path"],
+            "properties": {
+                "method": { "type": "string", "example": "POST" },
+                "path": { "type": "string", "example": "/bills" },
+                "body": {}
+            }
+        },
+        "BatchResponse": {
+            "type": "object",
+            "properties": { "status": { "type": "integer" }, "body": {} }
+        }
+    })
+}
@@ -0,0 +1,388 @@
+//! Deep links into payment apps and payment details for QR codes, prefilled
+//! to pay a bill's payer.
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    invoice::invoice_number,
+    models::{Bill, CurrencyCode, Money, Participant},
+};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CoPaymentLink {
+    pub participant_id: Uuid,
+    pub name: String,
+    /// What they still owe the payer.
+    pub amount: Money,
+    pub venmo_link: Option<String>,
+    pub paypal_link: Option<String>,
+    pub cashapp_link: Option<String>,
+}
+
+fn query(pairs: &[(&str, &str)]) -> String {
+    serde_urlencoded::to_string(pairs).expect("string pairs always encode")
+}
+
+/// `venmo://paycharge` link paying `handle`; a leading `@` is ignored.
+pub fn venmo_link(handle: &str, amount: Money, note: &str) -> String {
+    let handle = handle.trim_start_matches('@');
+    let amount = amount.to_string();
+    format!(
+        "venmo://paycharge?{}",
+        query(&[
+            ("txn", "pay"),
+            ("recipients", handle),
+            ("amount", &amount),
+            ("note", note),
+        ])
+    )
+}
+
+/// PayPal checkout link paying the account registered to `email`.
+pub fn paypal_link(email: &str, amount: Money, currency: &str, note: &str) -> String {
+    let amount = amount.to_string();
+    format!(
+        "https://www.paypal.com/cgi-bin/webscr?{}",
+        query(&[
+            ("cmd", "_xclick"),
+            ("business", email),
+            ("amount", &amount),
+            ("currency_code", currency),
+            ("item_name", note),
+        ])
+    )
+}
+
+/// `cash.app` link paying `cashtag`, with or without its leading `$`.
+pub fn cashapp_link(cashtag: &str, amount: Money) -> String {
+    format!(
+        "https://cash.app/${}/{amount}",
+        cashtag.trim_start_matches('$')
+    )
+}
+
+/// Links for `participant` to pay `payee` `amount` towards `bill`, one per
+/// payment app `payee` has an account on.
+pub fn co_payment_link(
+    bill: &Bill,
+    participant: &Participant,
+    payee: &Participant,
+    amount: Money,
+) -> CoPaymentLink {
+    CoPaymentLink {
+        participant_id: participant.id,
+        name: participant.name.clone(),
+        amount,
+        venmo_link: payee
+            .venmo_handle
+            .as_deref()
+            .map(|handle| venmo_link(handle, amount, &bill.title)),
+        paypal_link: payee
+            .paypal_email
+            .as_deref()
+            .map(|email| paypal_link(email, amount, bill.base_currency.as_str(), &bill.title)),
+        cashapp_link: payee
+            .cashapp_tag
+            .as_deref()
+            .map(|tag| cashapp_link(tag, amount)),
+    }
+}
+
+/// What a payment QR code encodes, as JSON.
+#[derive(Debug, Clone, Serialize)]
+pub struct PaymentDetails {
+    pub payee_name: String,
+    pub amount: Money,
+    /// The bill's invoice number, for the payee to match the payment to.
+    pub bill_reference: String,
+    pub currency: CurrencyCode,
+}
+
+impl PaymentDetails {
+    pub fn new(bill: &Bill, payee: &Participant, amount: Money) -> Self {
+        Self {
+            payee_name: payee.name.clone(),
+            amount,
+            bill_reference: invoice_number(bill.id),
+            currency: bill.base_currency.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentMethod {
+    Venmo,
+    Paypal,
+    Cashapp,
+    BankTransfer,
+    Cash,
+}
+
+impl PaymentMethod {
+    fn app_name(self) -> &'static str {
+        match self {
+            PaymentMethod::Venmo => "Venmo",
+            PaymentMethod::Paypal => "PayPal",
+            PaymentMethod::Cashapp => "Cash App",
+            PaymentMethod::BankTransfer => "a bank transfer",
+            PaymentMethod::Cash => "cash",
+        }
+    }
+
+    /// Whether the app works for a phone number starting with `prefix`.
+    /// Venmo is US-only and Cash App US and UK; PayPal works everywhere.
+    fn available_in(self, prefix: &str) -> bool {
+        match self {
+            PaymentMethod::Venmo => prefix == "+1",
+            PaymentMethod::Cashapp => prefix == "+1" || prefix == "+44",
+            _ => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PaymentMethodSuggestion {
+    pub participant_id: Uuid,
+    pub recommended_method: PaymentMethod,
+    pub reason: String,
+}
+
+/// Country calling codes the availability rules distinguish.
+const KNOWN_PREFIXES: [&str; 2] = ["+1", "+44"];
+
+/// The payment method to show most prominently for `participant`: one of
+/// the apps they have an account on, preferring the apps popular where
+/// their phone number is from, else cash.
+pub fn suggest_payment_method(participant: &Participant) -> PaymentMethodSuggestion {
+    let registered: Vec<PaymentMethod> = [
+        (PaymentMethod::Venmo, &participant.venmo_handle),
+        (PaymentMethod::Paypal, &participant.paypal_email),
+        (PaymentMethod::Cashapp, &participant.cashapp_tag),
+    ]
+    .into_iter()
+    .filter(|(_, handle)| handle.as_deref().is_some_and(|h| !h.trim().is_empty()))
+    .map(|(method, _)| method)
+    .collect();
+
+    let prefix = participant.phone.as_deref().map(|phone| {
+        let phone = phone.trim();
+        KNOWN_PREFIXES
+            .into_iter()
+            .find(|prefix| phone.starts_with(prefix))
+            .unwrap_or("other")
+    });
+    // US numbers favour the US apps; everyone else PayPal, which works in
+    // the most countries.
+    let preference = match prefix {
+        Some("+1") => [
+            PaymentMethod::Venmo,
+            PaymentMethod::Cashapp,
+            PaymentMethod::Paypal,
+        ],
+        Some("+44") => [
+            PaymentMethod::Paypal,
+            PaymentMethod::Cashapp,
+            PaymentMethod::Venmo,
+        ],
+        _ => [
+            PaymentMethod::Paypal,
+            PaymentMethod::Venmo,
+            PaymentMethod::Cashapp,
+        ],
+    };
+
+    let suggestion = |method: PaymentMethod, reason: String| PaymentMethodSuggestion {
+        participant_id: participant.id,
+        recommended_method: method,
+        reason,
+    };
+    if registered.is_empty() {
+        return suggestion(
+            PaymentMethod::Cash,
+            "They have not set up any payment apps".to_string(),
+        );
+    }
+    let usable: Vec<PaymentMethod> = registered
+        .iter()
+        .copied()
+        .filter(|method| prefix.is_none_or(|prefix| method.available_in(prefix)))
+        .collect();
+    let Some(method) = preference
+        .into_iter()
+        .find(|method| usable.contains(method))
+    else {
+        let apps: Vec<&str> = registered.iter().map(|method| method.app_name()).collect();
+        return suggestion(
+            PaymentMethod::BankTransfer,
+            format!(
+                "{} is not available where their phone number is from",
+                apps.join(" and ")
+            ),
+        );
+    };
+
+    let reason = if registered.len() == 1 {
+        format!(
+            "{} is the only payment app they have set up",
+            method.app_name()
+        )
+    } else if prefix.is_some() && prefix != Some("other") {
+        format!(
+            "{} is the most popular of their payment apps where their phone number is from",
+            method.app_name()
+        )
+    } else {
+        format!(
+            "{} is the most widely available of their payment apps",
+            method.app_name()
+        )
+    };
+    suggestion(method, reason)
+}
+
+/// Largest personal payment Venmo sends without a fee.
+const VENMO_FREE_LIMIT: Money = Money::from_cents(299_999);
+
+/// PayPal's goods and services rate, as a percentage, plus a fixed fee in
+/// the payment's currency.
+const PAYPAL_RATE_PERCENT: Decimal = Decimal::from_parts(19, 0, 0, false, 1);
+const PAYPAL_FIXED_FEE: Money = Money::from_cents(10);
+
+/// Currencies Cash App can send.
+const CASHAPP_CURRENCIES: [&str; 2] = ["USD", "GBP"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PaymentMethodOption {
+    pub method: PaymentMethod,
+    pub estimated_fee: Money,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PaymentRoute {
+    pub participant_id: Uuid,
+    pub best_route: PaymentMethod,
+    pub reason: String,
+    pub estimated_fee: Money,
+    /// The other payment apps they could use, cheapest first.
+    pub alternative_routes: Vec<PaymentMethodOption>,
+}
+
+/// What sending `amount` in `currency` by `method` costs, from the apps'
+/// published fee schedules, or why it cannot be used.
+fn payment_option(
+    method: PaymentMethod,
+    currency: &str,
+    amount: Money,
+) -> Result<PaymentMethodOption, String> {
+    let option = |estimated_fee: Money, reason: String| PaymentMethodOption {
+        method,
+        estimated_fee,
+        reason,
+    };
+    match method {
+        PaymentMethod::Venmo if currency != "USD" => Err("Venmo only sends USD".to_string()),
+        PaymentMethod::Venmo if amount > VENMO_FREE_LIMIT => Err(format!(
+            "Venmo is only free for payments up to {VENMO_FREE_LIMIT}"
+        )),
+        PaymentMethod::Venmo => Ok(option(
+            Money::ZERO,
+            format!("Venmo is free for personal payments in USD up to {VENMO_FREE_LIMIT}"),
+        )),
+        PaymentMethod::Cashapp if !CASHAPP_CURRENCIES.contains(&currency) => {
+            Err("Cash App only sends USD and GBP".to_string())
+        }
+        PaymentMethod::Cashapp => Ok(option(
+            Money::ZERO,
+            "Cash App is free for standard transfers".to_string(),
+        )),
+        PaymentMethod::Paypal => {
+            let fee = Money::from_decimal(
+                amount.to_decimal() * PAYPAL_RATE_PERCENT / Decimal::ONE_HUNDRED,
+            ) + PAYPAL_FIXED_FEE;
+            Ok(option(
+                fee,
+                format!(
+                    "PayPal charges {PAYPAL_RATE_PERCENT}% + {PAYPAL_FIXED_FEE} for goods and services"
+                ),
+            ))
+        }
+        PaymentMethod::BankTransfer | PaymentMethod::Cash => Ok(option(
+            Money::ZERO,
+            format!("{} has no fee", method.app_name()),
+        )),
+    }
+}
+
+/// The cheapest way for `participant` to pay `amount` in `currency`, among
+/// the payment apps on their profile. Ties go to Venmo, then Cash App, then
+/// PayPal. With no usable app it falls back like
+/// [`suggest_payment_method`]: cash when none is set up, else a bank
+/// transfer.
+pub fn optimal_payment_route(
+    participant: &Participant,
+    currency: &CurrencyCode,
+    amount: Money,
+) -> PaymentRoute {
+    let registered: Vec<PaymentMethod> = [
+        (PaymentMethod::Venmo, &participant.venmo_handle),
+        (PaymentMethod::Cashapp, &participant.cashapp_tag),
+        (PaymentMethod::Paypal, &participant.paypal_email),
+    ]
+    .into_iter()
+    .filter(|(_, handle)| handle.as_deref().is_some_and(|h| !h.trim().is_empty()))
+    .map(|(method, _)| method)
+    .collect();
+
+    let mut usable = Vec::new();
+    let mut unusable = Vec::new();
+    for method in &registered {
+        match payment_option(*method, currency.as_str(), amount) {
+            Ok(option) => usable.push(option),
+            Err(reason) => unusable.push(reason),
+        }
+    }
+    usable.sort_by_key(|option| option.estimated_fee);
+
+    if usable.is_empty() {
+        let (method, reason) = if registered.is_empty() {
+            (
+                PaymentMethod::Cash,
+                "They have not set up any payment apps".to_string(),
+            )
+        } else {
+            (PaymentMethod::BankTransfer, unusable.join("; "))
+        };
+        return PaymentRoute {
+            participant_id: participant.id,
+            best_route: method,
+            reason,
+            estimated_fee: Money::ZERO,
+            alternative_routes: Vec::new(),
+        };
+    }
+
+    let best = usable.remove(0);
+    let reason = if usable.is_empty() {
+        format!(
+            "{}, and it is the only payment app they can use for this",
+            best.reason
+        )
+    } else {
+        format!(
+            "{}, the lowest fee of the {} payment apps they can use",
+            best.reason,
+            usable.len() + 1
+        )
+    };
+    PaymentRoute {
+        participant_id: participant.id,
+        best_route: best.method,
+        reason,
+        estimated_fee: best.estimated_fee,
+        alternative_routes: usable,
+    }
+}
@@ -0,0 +1,499 @@
+//! A small QR code encoder: byte mode at error correction level M, which is
+//! all payment details need. Follows ISO/IEC 18004; versions above
+//! [`MAX_VERSION`] are not supported.
+
+use std::fmt;
+
+mod render;
+
+pub use render::{to_png, to_svg};
+
+/// Largest symbol supported, 57×57 modules.
+pub const MAX_VERSION: usize = 10;
+
+/// Error correction codewords per block at level M, by version.
+const ECC_CODEWORDS_PER_BLOCK: [usize; MAX_VERSION + 1] =
+    [0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26];
+
+/// Error correction blocks at level M, by version.
+const ECC_BLOCKS: [usize; MAX_VERSION + 1] = [0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5];
+
+/// Level M's two format bits.
+const ECC_LEVEL_M: u32 = 0b00;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataTooLong {
+    pub len: usize,
+    pub max: usize,
+}
+
+impl fmt::Display for DataTooLong {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} bytes do not fit in a QR code; at most {} do",
+            self.len, self.max
+        )
+    }
+}
+
+impl std::error::Error for DataTooLong {}
+
+/// A square grid of dark and light modules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QrCode {
+    version: usize,
+    size: usize,
+    modules: Vec<bool>,
+}
+
+impl QrCode {
+    /// Encodes `data` in the smallest version it fits in.
+    pub fn encode(data: &[u8]) -> Result<Self, DataTooLong> {
+        let version = (1..=MAX_VERSION)
+            .find(|&version| data.len() <= byte_capacity(version))
+            .ok_or(DataTooLong {
+                len: data.len(),
+                max: byte_capacity(MAX_VERSION),
+            })?;
+
+        let codewords = add_error_correction(version, &data_codewords(version, data));
+        let mut builder = Builder::new(version);
+        builder.draw_function_patterns();
+        builder.draw_codewords(&codewords);
+
+        let mask = (0..8)
+            .min_by_key(|&mask| {
+                let mut candidate = builder.clone();
+                candidate.apply_mask(mask);
+                candidate.draw_format_bits(mask);
+                candidate.penalty()
+            })
+            .expect("there are eight masks");
+        builder.apply_mask(mask);
+        builder.draw_format_bits(mask);
+
+        Ok(Self {
+            version,
+            size: builder.size,
+            modules: builder.modules,
+        })
+    }
+
+    pub fn version(&self) -> usize {
+        self.version
+    }
+
+    /// Modules per side, not counting the quiet zone.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Whether the module in column `x` and row `y` is dark.
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+}
+
+/// Bits left for data and error correction once function patterns are drawn.
+fn raw_data_modules(version: usize) -> usize {
+    let mut modules = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let alignments = version / 7 + 2;
+        modules -= (25 * alignments - 10) * alignments - 55;
+        if version >= 7 {
+            modules -= 36;
+        }
+    }
+    modules
+}
+
+fn data_codeword_count(version: usize) -> usize {
+    raw_data_modules(version) / 8 - ECC_CODEWORDS_PER_BLOCK[version] * ECC_BLOCKS[version]
+}
+
+fn length_bits(version: usize) -> usize {
+    if version <= 9 {
+        8
+    } else {
+        16
+    }
+}
+
+fn byte_capacity(version: usize) -> usize {
+    (data_codeword_count(version) * 8 - 4 - length_bits(version)) / 8
+}
+
+/// Mode indicator, length, data, terminator and padding, as codewords.
+fn data_codewords(version: usize, data: &[u8]) -> Vec<u8> {
+    let mut bits = BitBuffer::default();
+    bits.push(0b0100, 4);
+    bits.push(data.len() as u32, length_bits(version));
+    for &byte in data {
+        bits.push(byte.into(), 8);
+    }
+
+    let capacity = data_codeword_count(version) * 8;
+    bits.push(0, (capacity - bits.len()).min(4));
+    bits.push(0, (8 - bits.len() % 8) % 8);
+    let mut codewords = bits.into_bytes();
+    for pad in [0xEC, 0x11].into_iter().cycle() {
+        if codewords.len() == capacity / 8 {
+            break;
+        }
+        codewords.push(pad);
+    }
+    codewords
+}
+
+#[derive(Default)]
+struct BitBuffer(Vec<bool>);
+
+impl BitBuffer {
+    fn push(&mut self, value: u32, count: usize) {
+        self.0
+            .extend((0..count).rev().map(|bit| (value >> bit) & 1 == 1));
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        self.0
+            .chunks(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0u8, |byte, &bit| (byte << 1) | u8::from(bit))
+            })
+            .collect()
+    }
+}
+
+/// Splits `data` into blocks, appends each block's Reed-Solomon codewords
+/// and interleaves the result.
+fn add_error_correction(version: usize, data: &[u8]) -> Vec<u8> {
+    let blocks = ECC_BLOCKS[version];
+    let ecc_len = ECC_CODEWORDS_PER_BLOCK[version];
+    let raw_codewords = raw_data_modules(version) / 8;
+    let short_blocks = blocks - raw_codewords % blocks;
+    let short_len = raw_codewords / blocks;
+    let divisor = reed_solomon_divisor(ecc_len);
+
+    let mut split = Vec::with_capacity(blocks);
+    let mut start = 0;
+    for i in 0..blocks {
+        let len = short_len - ecc_len + usize::from(i >= short_blocks);
+        let block = &data[start..start + len];
+        start += len;
+        let ecc = reed_solomon_remainder(block, &divisor);
+        split.push((block, ecc));
+    }
+
+    let mut result = Vec::with_capacity(raw_codewords);
+    for i in 0..=short_len - ecc_len {
+        for (block, _) in &split {
+            if let Some(&codeword) = block.get(i) {
+                result.push(codeword);
+            }
+        }
+    }
+    for i in 0..ecc_len {
+        for (_, ecc) in &split {
+            result.push(ecc[i]);
+        }
+    }
+    result
+}
+
+/// Multiplication in GF(2^8) modulo x^8 + x^4 + x^3 + x^2 + 1.
+fn gf_multiply(x: u8, y: u8) -> u8 {
+    let mut z: u16 = 0;
+    for bit in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x11D);
+        z ^= u16::from((y >> bit) & 1) * u16::from(x);
+    }
+    z as u8
+}
+
+/// The generator polynomial of the given degree, highest coefficient first
+/// and the leading 1 left out.
+pub(crate) fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0u8; degree];
+    result[degree - 1] = 1;
+    let mut root = 1u8;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_multiply(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    result
+}
+
+pub(crate) fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0u8; divisor.len()];
+    for &byte in data {
+        let factor = byte ^ result.remove(0);
+        result.push(0);
+        for (coefficient, &d) in result.iter_mut().zip(divisor) {
+            *coefficient ^= gf_multiply(d, factor);
+        }
+    }
+    result
+}
+
+/// The symbol while it is being drawn.
+#[derive(Clone)]
+struct Builder {
+    version: usize,
+    size: usize,
+    modules: Vec<bool>,
+    /// Modules belonging to finder, timing, alignment, format and version
+    /// patterns, which data and masks leave alone.
+    is_function: Vec<bool>,
+}
+
+impl Builder {
+    fn new(version: usize) -> Self {
+        let size = version * 4 + 17;
+        Self {
+            version,
+            size,
+            modules: vec![false; size * size],
+            is_function: vec![false; size * size],
+        }
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        let index = y * self.size + x;
+        self.modules[index] = dark;
+        self.is_function[index] = true;
+    }
+
+    fn draw_function_patterns(&mut self) {
+        let size = self.size;
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        for (x, y) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            self.draw_finder(x, y);
+        }
+
+        let positions = alignment_positions(self.version);
+        let last = positions.len().saturating_sub(1);
+        for (i, &x) in positions.iter().enumerate() {
+            for (j, &y) in positions.iter().enumerate() {
+                // These would overlap the finder patterns.
+                let is_corner = (i == 0 && (j == 0 || j == last)) || (i == last && j == 0);
+                if !is_corner {
+                    self.draw_alignment(x, y);
+                }
+            }
+        }
+
+        // Reserve the format areas; the real bits go in once a mask is chosen.
+        self.draw_format_bits(0);
+        self.draw_version();
+    }
+
+    /// A finder pattern centred on (`x`, `y`), with its light separator.
+    fn draw_finder(&mut self, x: usize, y: usize) {
+        for dy in -4i32..=4 {
+            for dx in -4i32..=4 {
+                let (xx, yy) = (x as i32 + dx, y as i32 + dy);
+                if (0..self.size as i32).contains(&xx) && (0..self.size as i32).contains(&yy) {
+                    let distance = dx.abs().max(dy.abs());
+                    self.set_function(xx as usize, yy as usize, distance != 2 && distance != 4);
+                }
+            }
+        }
+    }
+
+    fn draw_alignment(&mut self, x: usize, y: usize) {
+        for dy in -2i32..=2 {
+            for dx in -2i32..=2 {
+                let distance = dx.abs().max(dy.abs());
+                self.set_function(
+                    (x as i32 + dx) as usize,
+                    (y as i32 + dy) as usize,
+                    distance != 1,
+                );
+            }
+        }
+    }
+
+    fn draw_format_bits(&mut self, mask: u32) {
+        let data = (ECC_LEVEL_M << 3) | mask;
+        let mut remainder = data;
+        for _ in 0..10 {
+            remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+        }
+        let bits = ((data << 10) | remainder) ^ 0x5412;
+        let bit = |i: usize| (bits >> i) & 1 == 1;
+
+        // Around the top-left finder.
+        for i in 0..=5 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        // Split between the other two finders.
+        let size = self.size;
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    fn draw_version(&mut self) {
+        if self.version < 7 {
+            return;
+        }
+        let mut remainder = self.version as u32;
+        for _ in 0..12 {
+            remainder = (remainder << 1) ^ ((remainder >> 11) * 0x1F25);
+        }
+        let bits = ((self.version as u32) << 12) | remainder;
+        for i in 0..18 {
+            let dark = (bits >> i) & 1 == 1;
+            let a = self.size - 11 + i % 3;
+            let b = i / 3;
+            self.set_function(a, b, dark);
+            self.set_function(b, a, dark);
+        }
+    }
+
+    /// Places codewords in the zigzag of two-module columns, right to left,
+    /// alternating upwards and downwards and skipping the vertical timing
+    /// pattern.
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let size = self.size;
+        let total_bits = codewords.len() * 8;
+        let mut bit = 0;
+        let mut right = size - 1;
+        loop {
+            if right == 6 {
+                right = 5;
+            }
+            let upward = (right + 1) & 2 == 0;
+            for vertical in 0..size {
+                let y = if upward {
+                    size - 1 - vertical
+                } else {
+                    vertical
+                };
+                for x in [right, right - 1] {
+                    let index = y * size + x;
+                    if !self.is_function[index] && bit < total_bits {
+                        self.modules[index] = (codewords[bit / 8] >> (7 - bit % 8)) & 1 == 1;
+                        bit += 1;
+                    }
+                }
+            }
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    fn apply_mask(&mut self, mask: u32) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                let index = y * self.size + x;
+                if invert && !self.is_function[index] {
+                    self.modules[index] = !self.modules[index];
+                }
+            }
+        }
+    }
+
+    /// The standard's score for how hard the symbol is to scan; lower is
+    /// better.
+    fn penalty(&self) -> usize {
+        let size = self.size;
+        let at = |x: usize, y: usize| self.modules[y * size + x];
+        let rows = (0..size).map(|y| (0..size).map(|x| at(x, y)).collect::<Vec<_>>());
+        let columns = (0..size).map(|x| (0..size).map(|y| at(x, y)).collect::<Vec<_>>());
+        let mut penalty = 0;
+
+        for line in rows.chain(columns) {
+            // Runs of five or more modules of one colour.
+            for run in line.chunk_by(|a, b| a == b) {
+                if run.len() >= 5 {
+                    penalty += run.len() - 2;
+                }
+            }
+            // Anything that looks like a finder pattern.
+            const FINDER_LIKE: [[bool; 11]; 2] = [
+                [
+                    true, false, true, true, true, false, true, false, false, false, false,
+                ],
+                [
+                    false, false, false, false, true, false, true, true, true, false, true,
+                ],
+            ];
+            penalty += line
+                .windows(11)
+                .filter(|window| FINDER_LIKE.iter().any(|pattern| window == pattern))
+                .count()
+                * 40;
+        }
+
+        // 2×2 blocks of one colour.
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let colour = at(x, y);
+                if at(x + 1, y) == colour && at(x, y + 1) == colour && at(x + 1, y + 1) == colour {
+                    penalty += 3;
+                }
+            }
+        }
+
+        // Distance from an even split of dark and light, in 5% steps.
+        let total = size * size;
+        let dark = self.modules.iter().filter(|&&dark| dark).count();
+        let k = ((dark * 20).abs_diff(total * 10))
+            .div_ceil(total)
+            .saturating_sub(1);
+        penalty + k * 10
+    }
+}
+
+/// Centres of the alignment patterns along each axis.
+fn alignment_positions(version: usize) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+    let count = version / 7 + 2;
+    let step = (version * 4 + count * 2 + 1) / (count * 2 - 2) * 2;
+    let size = version * 4 + 17;
+    let mut positions = vec![6];
+    positions.extend((0..count - 1).rev().map(|i| size - 7 - i * step));
+    positions
+}
@@ -0,0 +1,71 @@
+use std::{fmt::Write as _, io::Write as _};
+
+use flate2::{write::ZlibEncoder, Compression};
+
+use super::QrCode;
+
+/// Light modules around the symbol that scanners need to find it.
+const QUIET_ZONE: usize = 4;
+
+/// The code as a scalable SVG, one user unit per module.
+pub fn to_svg(code: &QrCode) -> String {
+    let dimension = code.size() + QUIET_ZONE * 2;
+    let mut path = String::new();
+    for y in 0..code.size() {
+        for x in 0..code.size() {
+            if code.is_dark(x, y) {
+                let _ = write!(path, "M{},{}h1v1h-1z", x + QUIET_ZONE, y + QUIET_ZONE);
+            }
+        }
+    }
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {dimension} {dimension}\" \
+         shape-rendering=\"crispEdges\"><rect width=\"100%\" height=\"100%\" fill=\"#fff\"/>\
+         <path d=\"{path}\" fill=\"#000\"/></svg>"
+    )
+}
+
+/// The code as a greyscale PNG, `scale` pixels per module.
+pub fn to_png(code: &QrCode, scale: usize) -> Vec<u8> {
+    let dimension = (code.size() + QUIET_ZONE * 2) * scale;
+    let mut pixels = Vec::with_capacity((dimension + 1) * dimension);
+    for py in 0..dimension {
+        // Each scanline starts with its filter type; 0 is none.
+        pixels.push(0);
+        for px in 0..dimension {
+            let (x, y) = (px / scale, py / scale);
+            let inside = (QUIET_ZONE..QUIET_ZONE + code.size()).contains(&x)
+                && (QUIET_ZONE..QUIET_ZONE + code.size()).contains(&y);
+            let dark = inside && code.is_dark(x - QUIET_ZONE, y - QUIET_ZONE);
+            pixels.push(if dark { 0x00 } else { 0xFF });
+        }
+    }
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&pixels)
+        .expect("writing to a Vec cannot fail");
+    let compressed = encoder.finish().expect("writing to a Vec cannot fail");
+
+    let mut header = Vec::with_capacity(13);
+    header.extend((dimension as u32).to_be_bytes());
+    header.extend((dimension as u32).to_be_bytes());
+    // 8-bit greyscale, deflate, adaptive filtering, no interlacing.
+    header.extend([8, 0, 0, 0, 0]);
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &compressed);
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    png.extend(kind);
+    png.extend(data);
+    let mut crc = crc32fast::Hasher::new();
+    crc.update(kind);
+    crc.update(data);
+    png.extend(crc.finalize().to_be_bytes());
+}
@@ -0,0 +1,213 @@
+use std::{fmt, time::Duration};
+
+use actix_web::web;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    models::Money,
+    notifications::{
+        email::send_share_notification, sms::send_share_sms, NotificationChannel,
+        NotificationError, NotificationStatus,
+    },
+    state::AppState,
+    storage::KvError,
+};
+
+/// Retries after the first attempt before a task is dead-lettered.
+pub const MAX_RETRIES: u32 = 3;
+
+/// Delay before the first retry; doubled on each subsequent one.
+pub const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// One participant to notify about their share of one bill.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmailTask {
+    pub id: Uuid,
+    pub participant_id: Uuid,
+    pub bill_id: Uuid,
+    pub channel: NotificationChannel,
+}
+
+impl EmailTask {
+    pub fn new(participant_id: Uuid, bill_id: Uuid, channel: NotificationChannel) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            participant_id,
+            bill_id,
+            channel,
+        }
+    }
+}
+
+/// A task that could not be delivered, kept until it is replayed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub task: EmailTask,
+    /// Attempts made, including the first.
+    pub attempts: u32,
+    pub error: String,
+    pub failed_at: DateTime<Utc>,
+}
+
+/// Why an attempt failed.
+#[derive(Debug)]
+enum TaskError {
+    /// The bill, participant or share the task refers to no longer exists.
+    Gone(String),
+    /// The participant paid off their share before the task ran, so there
+    /// is nothing left to tell them.
+    Settled,
+    Notification(NotificationError),
+    Storage(KvError),
+}
+
+impl TaskError {
+    /// Whether trying again could succeed.
+    fn is_transient(&self) -> bool {
+        match self {
+            TaskError::Gone(_) | TaskError::Settled => false,
+            TaskError::Notification(err) => matches!(err, NotificationError::Provider { .. }),
+            TaskError::Storage(err) => err.is_transient(),
+        }
+    }
+}
+
+impl fmt::Display for TaskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskError::Gone(reason) => f.write_str(reason),
+            TaskError::Settled => f.write_str("nothing left to pay"),
+            TaskError::Notification(err) => err.fmt(f),
+            TaskError::Storage(err) => err.fmt(f),
+        }
+    }
+}
+
+impl From<KvError> for TaskError {
+    fn from(err: KvError) -> Self {
+        TaskError::Storage(err)
+    }
+}
+
+/// Starts consuming `state`'s notification queue on the current runtime. Only
+/// the first call starts a consumer; later ones return `false`.
+pub fn spawn(state: web::Data<AppState>) -> bool {
+    let Some(mut receiver) = state.notification_queue.take_receiver() else {
+        return false;
+    };
+    actix_web::rt::spawn(async move {
+        // One task at a time, so status updates for the same bill never race.
+        while let Some(task) = receiver.recv().await {
+            process(&state, task).await;
+        }
+    });
+    true
+}
+
+/// Delivers `task`, retrying transient failures up to [`MAX_RETRIES`] times
+/// with exponential backoff. Delivery outcomes are added to the bill's
+/// notification statuses, and tasks that still fail are dead-lettered. Tasks
+/// for participants who have since paid in full are dropped. Returns whether
+/// the task was delivered.
+pub async fn process(state: &AppState, task: EmailTask) -> bool {
+    let mut attempts = 0;
+    let result = loop {
+        attempts += 1;
+        match deliver(state, &task).await {
+            Err(err) if err.is_transient() && attempts <= MAX_RETRIES => {
+                tokio::time::sleep(RETRY_BASE_DELAY * 2u32.pow(attempts - 1)).await;
+            }
+            result => break result,
+        }
+    };
+
+    match result {
+        Ok(message_id) => {
+            record_status(state, &task, &Ok(message_id)).await;
+            true
+        }
+        Err(TaskError::Settled) => false,
+        Err(err) => {
+            if let TaskError::Notification(err) = &err {
+                record_status(state, &task, &Err(err.clone())).await;
+            }
+            let letter = DeadLetter {
+                task,
+                attempts,
+                error: err.to_string(),
+                failed_at: Utc::now(),
+            };
+            // Nowhere left to report a failure to park the task.
+            let _ = state.repo().put_dead_letter(&letter).await;
+            false
+        }
+    }
+}
+
+/// Appends the outcome of sending to the bill's notification statuses. The
+/// send has already happened, so a status that fails to save is dropped
+/// rather than retried.
+async fn record_status(
+    state: &AppState,
+    task: &EmailTask,
+    result: &Result<Option<String>, NotificationError>,
+) {
+    let repo = state.repo();
+    if let Ok(mut statuses) = repo.get_notification_statuses(task.bill_id).await {
+        statuses.push(NotificationStatus::from_result(
+            task.participant_id,
+            task.channel,
+            result,
+        ));
+        let _ = repo
+            .put_notification_statuses(task.bill_id, &statuses)
+            .await;
+    }
+}
+
+/// One delivery attempt; `Ok` carries the provider's message id.
+async fn deliver(state: &AppState, task: &EmailTask) -> Result<Option<String>, TaskError> {
+    let repo = state.repo();
+    let bill = repo
+        .get_bill(task.bill_id)
+        .await?
+        .ok_or_else(|| TaskError::Gone(format!("bill {} no longer exists", task.bill_id)))?;
+    let participant = repo
+        .get_participant(task.participant_id)
+        .await?
+        .ok_or_else(|| {
+            TaskError::Gone(format!(
+                "participant {} no longer exists",
+                task.participant_id
+            ))
+        })?;
+    let outstanding = bill.outstanding(participant.id).ok_or_else(|| {
+        TaskError::Gone(format!(
+            "participant {} has no share in the latest split",
+            participant.id
+        ))
+    })?;
+    if outstanding <= Money::ZERO {
+        return Err(TaskError::Settled);
+    }
+
+    let result = match task.channel {
+        NotificationChannel::Email => {
+            send_share_notification(&state.config.email, &participant, &bill, outstanding).await
+        }
+        NotificationChannel::Sms => {
+            let share_link = state.config.bill_link(bill.id);
+            send_share_sms(
+                &state.config.sms,
+                &participant,
+                &bill,
+                outstanding,
+                &share_link,
+            )
+            .await
+        }
+    };
+    result.map_err(TaskError::Notification)
+}
@@ -0,0 +1,58 @@
+//! Background work. This is the in-process stand-in for Cloudflare Queues
+//! bindings: handlers publish to a [`Queue`] and a consumer works through it
+//! in the background, so requests never wait on a provider or a long
+//! calculation.
+//!
+//! - [`email_consumer`] delivers notifications. Tasks that keep failing are
+//!   parked in KV as dead letters until an admin replays them.
+//! - [`split_consumer`] computes splits too large to compute in a request,
+//!   storing each job's progress and result in KV.
+
+use std::sync::Mutex;
+
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+pub mod email_consumer;
+pub mod split_consumer;
+
+pub use email_consumer::{DeadLetter, EmailTask, MAX_RETRIES};
+pub use split_consumer::{JobState, SplitJob, SplitJobStatus};
+
+/// Notification tasks waiting to be delivered.
+pub type NotificationQueue = Queue<EmailTask>;
+
+/// Split jobs waiting to be computed.
+pub type SplitQueue = Queue<SplitJob>;
+
+/// Where messages wait for their consumer.
+pub struct Queue<T> {
+    sender: UnboundedSender<T>,
+    /// Handed to the consumer when it starts; `None` once taken.
+    receiver: Mutex<Option<UnboundedReceiver<T>>>,
+}
+
+impl<T> Queue<T> {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        Self {
+            sender,
+            receiver: Mutex::new(Some(receiver)),
+        }
+    }
+
+    /// Queues `message`. Returns `false` if the consumer has stopped.
+    pub fn send(&self, message: T) -> bool {
+        self.sender.send(message).is_ok()
+    }
+
+    /// The receiving end, for the single consumer. `None` if already taken.
+    pub fn take_receiver(&self) -> Option<UnboundedReceiver<T>> {
+        self.receiver.lock().unwrap().take()
+    }
+}
+
+impl<T> Default for Queue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
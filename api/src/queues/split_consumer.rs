@@ -0,0 +1,137 @@
+use actix_web::web;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    split::{compute_split, SplitConfig, SplitResult},
+    state::AppState,
+};
+
+/// A split to compute in the background.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SplitJob {
+    pub id: Uuid,
+    pub bill_id: Uuid,
+    /// The method and parameters, resolved when the job was queued.
+    pub config: SplitConfig,
+}
+
+impl SplitJob {
+    pub fn new(bill_id: Uuid, config: SplitConfig) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            bill_id,
+            config,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Pending,
+    Running,
+    Done,
+    Failed,
+}
+
+/// Progress of a [`SplitJob`], kept in KV for polling.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitJobStatus {
+    pub job_id: Uuid,
+    pub bill_id: Uuid,
+    pub status: JobState,
+    /// Set once the job is `done`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<SplitResult>,
+    /// Why the job `failed`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SplitJobStatus {
+    pub fn pending(job: &SplitJob) -> Self {
+        let now = Utc::now();
+        Self {
+            job_id: job.id,
+            bill_id: job.bill_id,
+            status: JobState::Pending,
+            result: None,
+            error: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn with_state(mut self, status: JobState) -> Self {
+        self.status = status;
+        self.updated_at = Utc::now();
+        self
+    }
+}
+
+/// Starts consuming `state`'s split queue on the current runtime. Only the
+/// first call starts a consumer; later ones return `false`.
+pub fn spawn(state: web::Data<AppState>) -> bool {
+    let Some(mut receiver) = state.split_queue.take_receiver() else {
+        return false;
+    };
+    actix_web::rt::spawn(async move {
+        while let Some(job) = receiver.recv().await {
+            process(&state, job).await;
+        }
+    });
+    true
+}
+
+/// Computes `job`'s split, marking it `running` while it does and storing
+/// the outcome. Returns whether the job is `done`.
+pub async fn process(state: &AppState, job: SplitJob) -> bool {
+    let repo = state.repo();
+    let status = match repo.get_split_job(job.id).await {
+        Ok(Some(status)) => status,
+        _ => SplitJobStatus::pending(&job),
+    };
+    let running = status.with_state(JobState::Running);
+    // The job runs regardless; polling just stays at `pending` until the
+    // outcome is saved.
+    let _ = repo.put_split_job(&running).await;
+
+    let finished = match compute(state, &job).await {
+        Ok(result) => SplitJobStatus {
+            result: Some(result),
+            ..running.with_state(JobState::Done)
+        },
+        Err(error) => SplitJobStatus {
+            error: Some(error),
+            ..running.with_state(JobState::Failed)
+        },
+    };
+    let done = finished.status == JobState::Done;
+    // Nowhere left to report a failure to save the outcome.
+    let _ = repo.put_split_job(&finished).await;
+    done
+}
+
+/// Computes the split and records it on the bill, like `GET /bills/:id/split`.
+async fn compute(state: &AppState, job: &SplitJob) -> Result<SplitResult, String> {
+    let repo = state.repo();
+    let mut bill = repo
+        .get_bill(job.bill_id)
+        .await
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| format!("bill {} no longer exists", job.bill_id))?;
+    let participants = repo
+        .get_bill_participants(&bill)
+        .await
+        .map_err(|err| err.to_string())?;
+    let spec = job.config.spec()?;
+    let result = compute_split(&bill, &participants, &spec).map_err(|err| err.to_string())?;
+    if bill.record_split(&result) {
+        repo.put_bill(&bill).await.map_err(|err| err.to_string())?;
+    }
+    Ok(result)
+}
@@ -0,0 +1,128 @@
+//! Personal receipts: one participant's part of a bill.
+
+use std::sync::OnceLock;
+
+use chrono::{DateTime, Utc};
+use handlebars::Handlebars;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::models::{Bill, Money, ParticipantShare, Payment};
+
+mod pdf;
+
+pub use pdf::{render_pdf, PdfError};
+
+const RECEIPT_TEMPLATE: &str = "receipt";
+
+/// A line item as it appears on one participant's receipt.
+#[derive(Debug, Clone, Serialize)]
+pub struct LineItemView {
+    pub id: Uuid,
+    pub description: String,
+    pub quantity: u32,
+    pub unit_price: Money,
+    /// How many participants share the item.
+    pub shared_with: usize,
+    pub your_portion: Money,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PersonalReceipt {
+    pub participant_id: Uuid,
+    pub name: String,
+    pub bill_title: String,
+    pub date: DateTime<Utc>,
+    pub your_items: Vec<LineItemView>,
+    /// Your portions of the items above, before the bill-wide discount.
+    pub subtotal: Money,
+    pub discount: Money,
+    /// Tax included in your portions.
+    pub tax: Money,
+    /// Bills do not record tips yet, so this is always zero.
+    pub tip: Money,
+    /// Your share from the latest split. Equals the item portions less the
+    /// discount only for itemised splits.
+    pub your_total: Money,
+    pub paid: Money,
+    pub outstanding: Money,
+}
+
+impl PersonalReceipt {
+    pub fn new(bill: &Bill, share: &ParticipantShare) -> Self {
+        let subtotal = bill.subtotal().to_decimal();
+        let scale = if subtotal.is_zero() {
+            Decimal::ONE
+        } else {
+            bill.total().to_decimal() / subtotal
+        };
+
+        let mut your_items = Vec::new();
+        let mut tax = Decimal::ZERO;
+        for item in &bill.line_items {
+            let sharers = bill.item_sharers(item);
+            if !sharers.contains(&share.participant_id) {
+                continue;
+            }
+            let shared_with = sharers.len();
+            let portion = item.total().to_decimal() / Decimal::from(shared_with);
+            if let Some(rate) = item.tax_rate {
+                tax += portion * scale * rate / (Decimal::ONE + rate);
+            }
+            your_items.push(LineItemView {
+                id: item.id,
+                description: item.description.clone(),
+                quantity: item.quantity,
+                unit_price: item.unit_price,
+                shared_with,
+                your_portion: Money::from_decimal(portion),
+            });
+        }
+
+        let items_subtotal: Money = your_items.iter().map(|item| item.your_portion).sum();
+        let discount = items_subtotal - Money::from_decimal(items_subtotal.to_decimal() * scale);
+        let paid: Money = bill
+            .payments_by(share.participant_id)
+            .map(Payment::base_amount)
+            .sum();
+        let outstanding = (share.amount_owed - paid).max(Money::ZERO);
+
+        Self {
+            participant_id: share.participant_id,
+            name: share.name.clone(),
+            bill_title: bill.title.clone(),
+            date: bill.created_at,
+            your_items,
+            subtotal: items_subtotal,
+            discount,
+            tax: Money::from_decimal(tax),
+            tip: Money::ZERO,
+            your_total: share.amount_owed,
+            paid,
+            outstanding,
+        }
+    }
+}
+
+fn templates() -> &'static Handlebars<'static> {
+    static TEMPLATES: OnceLock<Handlebars<'static>> = OnceLock::new();
+    TEMPLATES.get_or_init(|| {
+        let mut handlebars = Handlebars::new();
+        handlebars.set_strict_mode(true);
+        handlebars
+            .register_template_string(RECEIPT_TEMPLATE, include_str!("templates/receipt.hbs"))
+            .expect("receipt template is valid");
+        handlebars
+    })
+}
+
+/// Renders `receipt` as a printable HTML page.
+pub fn render_receipt_html(receipt: &PersonalReceipt) -> Result<String, handlebars::RenderError> {
+    let mut data = serde_json::to_value(receipt).expect("receipt serialises");
+    data["date"] = receipt.date.format("%-d %B %Y").to_string().into();
+    data["has_discount"] = (!receipt.discount.is_zero()).into();
+    data["has_tax"] = (!receipt.tax.is_zero()).into();
+    data["has_tip"] = (!receipt.tip.is_zero()).into();
+    templates().render(RECEIPT_TEMPLATE, &data)
+}
@@ -0,0 +1,81 @@
+//! PDF receipts, rendered from the receipt HTML by `wkhtmltopdf`.
+
+use std::{fmt, io, process::Stdio, time::Duration};
+
+use tokio::{io::AsyncWriteExt, process::Command};
+
+use crate::{config::Config, error::ApiError};
+
+const RENDER_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+pub enum PdfError {
+    /// The renderer is not installed at the configured path.
+    Unavailable,
+    Render(String),
+}
+
+impl fmt::Display for PdfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PdfError::Unavailable => write!(f, "PDF rendering is not available"),
+            PdfError::Render(reason) => write!(f, "PDF rendering failed: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for PdfError {}
+
+impl From<PdfError> for ApiError {
+    fn from(err: PdfError) -> Self {
+        match err {
+            PdfError::Unavailable => ApiError::ServiceUnavailable(err.to_string()),
+            PdfError::Render(_) => ApiError::Internal(err.to_string()),
+        }
+    }
+}
+
+fn render_error(err: io::Error) -> PdfError {
+    PdfError::Render(err.to_string())
+}
+
+/// Prints `html` to an A4 PDF, piping it through `wkhtmltopdf`.
+pub async fn render_pdf(config: &Config, html: &str) -> Result<Vec<u8>, PdfError> {
+    let mut child = Command::new(&config.wkhtmltopdf_path)
+        .args([
+            "--quiet",
+            "--page-size",
+            "A4",
+            "--print-media-type",
+            "-",
+            "-",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => PdfError::Unavailable,
+            _ => render_error(err),
+        })?;
+
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = html.as_bytes().to_vec();
+    let write = tokio::spawn(async move { stdin.write_all(&input).await });
+
+    let output = tokio::time::timeout(RENDER_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| PdfError::Render(format!("timed out after {RENDER_TIMEOUT:?}")))?
+        .map_err(render_error)?;
+    if !output.status.success() || output.stdout.is_empty() {
+        return Err(PdfError::Render(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    write
+        .await
+        .map_err(|err| PdfError::Render(err.to_string()))?
+        .map_err(render_error)?;
+    Ok(output.stdout)
+}
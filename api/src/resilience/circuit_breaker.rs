@@ -0,0 +1,124 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+pub const DEFAULT_OPEN_DURATION: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Calls go through; failures are counted.
+    Closed,
+    /// Calls are rejected without reaching the dependency.
+    Open,
+    /// The open period has elapsed; the next call decides whether to close
+    /// the circuit again or re-open it.
+    HalfOpen,
+}
+
+/// Returned instead of making a call while the circuit is open.
+#[derive(Debug, Clone, Copy)]
+pub struct CircuitOpen {
+    pub retry_after: Duration,
+}
+
+/// Point-in-time view of a breaker for the admin endpoint.
+#[derive(Debug, Clone, Serialize)]
+pub struct CircuitStatus {
+    pub name: &'static str,
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    /// Seconds until an open circuit lets a trial call through.
+    pub retry_after_secs: Option<u64>,
+}
+
+struct Inner {
+    state: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+/// Stops calling a failing dependency for a while after repeated failures.
+///
+/// State lives in memory and is shared by every worker through `AppState`;
+/// it resets when the process restarts.
+pub struct CircuitBreaker {
+    name: &'static str,
+    failure_threshold: u32,
+    open_duration: Duration,
+    inner: Mutex<Inner>,
+}
+
+impl CircuitBreaker {
+    pub fn new(name: &'static str, failure_threshold: u32, open_duration: Duration) -> Self {
+        Self {
+            name,
+            failure_threshold,
+            open_duration,
+            inner: Mutex::new(Inner {
+                state: CircuitState::Closed,
+                consecutive_failures: 0,
+                opened_at: None,
+            }),
+        }
+    }
+
+    /// Checks whether a call may go ahead. An open circuit whose open period
+    /// has elapsed moves to half-open and allows the call.
+    pub fn try_acquire(&self) -> Result<(), CircuitOpen> {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.state != CircuitState::Open {
+            return Ok(());
+        }
+
+        let elapsed = inner
+            .opened_at
+            .map_or(self.open_duration, |at| at.elapsed());
+        if elapsed >= self.open_duration {
+            inner.state = CircuitState::HalfOpen;
+            Ok(())
+        } else {
+            Err(CircuitOpen {
+                retry_after: self.open_duration - elapsed,
+            })
+        }
+    }
+
+    pub fn record_success(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.state = CircuitState::Closed;
+        inner.consecutive_failures = 0;
+        inner.opened_at = None;
+    }
+
+    pub fn record_failure(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.consecutive_failures += 1;
+        if inner.state == CircuitState::HalfOpen
+            || inner.consecutive_failures >= self.failure_threshold
+        {
+            inner.state = CircuitState::Open;
+            inner.opened_at = Some(Instant::now());
+        }
+    }
+
+    pub fn status(&self) -> CircuitStatus {
+        let inner = self.inner.lock().unwrap();
+        let retry_after_secs = match (inner.state, inner.opened_at) {
+            (CircuitState::Open, Some(at)) => {
+                Some(self.open_duration.saturating_sub(at.elapsed()).as_secs())
+            }
+            _ => None,
+        };
+        CircuitStatus {
+            name: self.name,
+            state: inner.state,
+            consecutive_failures: inner.consecutive_failures,
+            retry_after_secs,
+        }
+    }
+}
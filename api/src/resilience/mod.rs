@@ -0,0 +1,3 @@
+pub mod circuit_breaker;
+
+pub use circuit_breaker::{CircuitBreaker, CircuitOpen, CircuitState, CircuitStatus};
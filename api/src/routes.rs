@@ -0,0 +1,215 @@
+use std::sync::OnceLock;
+
+use actix_web::{dev::ResourceDef, http::Method};
+
+/// A path pattern and the methods it is served with.
+pub struct Route {
+    pub pattern: &'static str,
+    pub methods: &'static [Method],
+}
+
+const fn route(pattern: &'static str, methods: &'static [Method]) -> Route {
+    Route { pattern, methods }
+}
+
+/// Every route the application serves. Actix does not expose its routing
+/// table, so this list is what `OPTIONS` and `Allow` are generated from and
+/// must be kept in step with the handlers.
+pub const ROUTES: &[Route] = &[
+    route("/health", &[Method::GET]),
+    route("/version", &[Method::GET]),
+    route("/openapi.json", &[Method::GET]),
+    route("/hello/{name}", &[Method::GET]),
+    route("/stream", &[Method::GET]),
+    route("/stream-delay", &[Method::GET]),
+    route("/admin/circuit-breakers", &[Method::GET]),
+    route("/admin/kv-stats", &[Method::GET]),
+    route("/admin/dead-letter-queue", &[Method::GET]),
+    route("/admin/dead-letter-queue/{task_id}/replay", &[Method::POST]),
+    route("/admin/cron/expire-drafts", &[Method::POST]),
+    route("/admin/cron/send-reminders", &[Method::POST]),
+    route("/ai/prompt", &[Method::POST]),
+    route("/batch", &[Method::POST]),
+    route("/bills", &[Method::GET, Method::POST]),
+    route("/bills/overdue", &[Method::GET]),
+    route("/bills/shared-with-me", &[Method::GET]),
+    route("/bills/month/{year}/{month}", &[Method::GET]),
+    route("/bills/{id}", &[Method::GET, Method::PATCH]),
+    route("/bills/{id}/changelog", &[Method::GET]),
+    route("/bills/{id}/participants", &[Method::GET, Method::POST]),
+    route(
+        "/bills/{id}/participants/{participant_id}/accept",
+        &[Method::POST],
+    ),
+    route(
+        "/bills/{id}/participants/{participant_id}/exempt",
+        &[Method::POST, Method::DELETE],
+    ),
+    route(
+        "/bills/{id}/participants/{participant_id}/messages",
+        &[Method::GET],
+    ),
+    route(
+        "/bills/{id}/participants/{participant_id}/receipt",
+        &[Method::GET],
+    ),
+    route(
+        "/bills/{id}/participants/{participant_id}/debt-chain",
+        &[Method::GET],
+    ),
+    route(
+        "/bills/{id}/participants/{participant_id}/payment-history",
+        &[Method::GET],
+    ),
+    route(
+        "/bills/{id}/participants/{participant_id}/due-date",
+        &[Method::GET, Method::PUT],
+    ),
+    route(
+        "/bills/{id}/participants/{participant_id}/suggested-payment-method",
+        &[Method::GET],
+    ),
+    route(
+        "/bills/{id}/participants/{participant_id}/optimal-payment-route",
+        &[Method::GET],
+    ),
+    route(
+        "/bills/{id}/participants/{participant_id}/comparative-spend",
+        &[Method::GET],
+    ),
+    route(
+        "/bills/{id}/participants/{participant_id}/reminders",
+        &[Method::GET],
+    ),
+    route(
+        "/bills/{id}/participants/{participant_id}/reminders/{reminder_id}",
+        &[Method::DELETE],
+    ),
+    route("/bills/{id}/pdf-receipt", &[Method::GET]),
+    route("/bills/{id}/convert-to-invoice", &[Method::POST]),
+    route("/bills/{id}/co-payment-links", &[Method::GET]),
+    route("/bills/{id}/completion-percentage", &[Method::GET]),
+    route(
+        "/bills/{id}/participants/{participant_id}/payment-qr",
+        &[Method::GET],
+    ),
+    route("/bills/{id}/anomaly-detection", &[Method::GET]),
+    route("/bills/{id}/validation-errors", &[Method::GET]),
+    route("/bills/{id}/legal-disclaimer", &[Method::GET]),
+    route("/bills/{id}/tags", &[Method::POST]),
+    route("/bills/{id}/tags/{tag_id}", &[Method::DELETE]),
+    route("/bills/{id}/payer", &[Method::PUT]),
+    route("/bills/{id}/payer-history", &[Method::GET]),
+    route("/bills/{id}/suggested-payer", &[Method::GET]),
+    route("/bills/{id}/assign-payer-from-ai", &[Method::POST]),
+    route("/bills/{id}/line-items", &[Method::POST]),
+    route(
+        "/bills/{id}/line-items/{item_id}/merge-into/{other_id}",
+        &[Method::POST],
+    ),
+    route("/bills/{id}/line-items/reorder", &[Method::POST]),
+    route("/bills/{id}/line-items/import/csv", &[Method::POST]),
+    route("/bills/{id}/duplicate-check", &[Method::POST]),
+    route("/bills/{id}/archive", &[Method::POST]),
+    route("/bills/{id}/unarchive", &[Method::POST]),
+    route("/bills/{id}/split", &[Method::GET]),
+    route("/bills/{id}/split/simulate", &[Method::GET]),
+    route("/bills/{id}/split/what-if", &[Method::GET]),
+    route("/bills/{id}/split/graph", &[Method::GET]),
+    route("/bills/{id}/split/explain", &[Method::GET]),
+    route("/bills/{id}/split/rounding-report", &[Method::GET]),
+    route("/bills/{id}/split/round-to-nearest", &[Method::GET]),
+    route("/bills/{id}/split/currency-breakdown", &[Method::GET]),
+    route("/bills/{id}/split/breakdown/compact", &[Method::GET]),
+    route("/bills/{id}/split/allocation-matrix", &[Method::GET]),
+    route("/bills/{id}/split/sensitivity", &[Method::GET]),
+    route("/bills/{id}/split/tax-inclusive", &[Method::GET]),
+    route("/bills/{id}/split/async", &[Method::GET]),
+    route("/bills/{id}/split/jobs/{job_id}", &[Method::GET]),
+    route("/bills/{id}/split/comparison", &[Method::GET]),
+    route("/bills/{id}/split/comparison/visualisation", &[Method::GET]),
+    route("/bills/{id}/recommended-split-method", &[Method::GET]),
+    route("/bills/{id}/split-config", &[Method::PUT]),
+    route(
+        "/bills/{id}/split/preview-as/{participant_id}",
+        &[Method::GET],
+    ),
+    route("/bills/{id}/split/adjust-rounding", &[Method::POST]),
+    route("/bills/{id}/split-inequality-warning", &[Method::GET]),
+    route("/bills/{id}/split-history", &[Method::GET, Method::DELETE]),
+    route("/bills/{id}/fairness-history", &[Method::GET]),
+    route(
+        "/bills/{id}/split-history/{snapshot_id}",
+        &[Method::GET, Method::DELETE],
+    ),
+    route(
+        "/bills/{id}/split/history/{snapshot_id}/replay",
+        &[Method::GET],
+    ),
+    route("/bills/{id}/set-currency-conversion-rates", &[Method::POST]),
+    route("/bills/{id}/notify", &[Method::POST]),
+    route("/bills/{id}/notify/sms", &[Method::POST]),
+    route("/bills/{id}/notifications/status", &[Method::GET]),
+    route("/bills/{id}/payments", &[Method::POST]),
+    route("/bills/{id}/settlements", &[Method::GET]),
+    route("/bills/{id}/split/minimised-vs-naive", &[Method::GET]),
+    route("/bills/{id}/taxes", &[Method::GET]),
+    route("/bills/{id}/send-to-accounting", &[Method::POST]),
+    route("/bills/{id}/export/spreadsheet-formulas", &[Method::GET]),
+    route("/participants/search", &[Method::GET]),
+    route("/participants/{id}/stats", &[Method::GET]),
+    route("/participants/{id}/avatar", &[Method::GET]),
+    route("/participants/{id}/merge", &[Method::POST]),
+    route("/webhooks/mailgun", &[Method::POST]),
+];
+
+fn compiled() -> &'static [(ResourceDef, &'static [Method])] {
+    static COMPILED: OnceLock<Vec<(ResourceDef, &'static [Method])>> = OnceLock::new();
+    COMPILED.get_or_init(|| {
+        ROUTES
+            .iter()
+            .map(|route| (ResourceDef::new(route.pattern), route.methods))
+            .collect()
+    })
+}
+
+/// Methods accepted for `path`, always including `OPTIONS`, or `None` when
+/// no route matches the path at all. A static route such as `/bills/overdue`
+/// shadows dynamic ones like `/bills/{id}`, as it does in the router.
+pub fn allowed_methods(path: &str) -> Option<Vec<Method>> {
+    let mut matching: Vec<&(ResourceDef, &[Method])> = compiled()
+        .iter()
+        .filter(|(resource, _)| resource.is_match(path))
+        .collect();
+    if let Some(exact) = matching.iter().copied().find(|(resource, _)| {
+        resource
+            .pattern()
+            .is_some_and(|pattern| !pattern.contains('{'))
+    }) {
+        matching = vec![exact];
+    }
+
+    let mut methods: Vec<Method> = Vec::new();
+    for (_, route_methods) in matching {
+        for method in *route_methods {
+            if !methods.contains(method) {
+                methods.push(method.clone());
+            }
+        }
+    }
+
+    if methods.is_empty() {
+        return None;
+    }
+    methods.push(Method::OPTIONS);
+    Some(methods)
+}
+
+/// Formats methods for an `Allow` header, e.g. `GET, POST, OPTIONS`.
+pub fn allow_header_value(methods: &[Method]) -> String {
+    methods
+        .iter()
+        .map(Method::as_str)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
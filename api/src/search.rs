@@ -0,0 +1,61 @@
+//! Finding participants from part of their name or email.
+
+use serde::Serialize;
+
+use crate::{models::Participant, util::similarity::jaro_winkler};
+
+/// Names scoring below this against the query are not matches.
+pub const MIN_NAME_SIMILARITY: f64 = 0.8;
+
+/// Most results returned, whatever limit is asked for.
+pub const MAX_SEARCH_RESULTS: usize = 20;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParticipantMatch {
+    #[serde(flatten)]
+    pub participant: Participant,
+    /// `1.0` for an email prefix match, otherwise the name similarity.
+    pub score: f64,
+}
+
+/// Jaro-Winkler similarity of `query` to `name`, or to whichever word of
+/// it is closest, ignoring case. `None` below [`MIN_NAME_SIMILARITY`].
+pub fn name_score(query: &str, name: &str) -> Option<f64> {
+    let query = query.trim().to_lowercase();
+    let name = name.trim().to_lowercase();
+    let score = std::iter::once(name.as_str())
+        .chain(name.split_whitespace())
+        .map(|candidate| jaro_winkler(&query, candidate))
+        .fold(0.0, f64::max);
+    (score >= MIN_NAME_SIMILARITY).then_some(score)
+}
+
+/// Whether `email` starts with `query`, ignoring case.
+pub fn email_matches(query: &str, email: &str) -> bool {
+    let query = query.trim().to_lowercase();
+    !query.is_empty() && email.to_lowercase().starts_with(&query)
+}
+
+/// How well `participant` matches `query`, or `None` if it does not.
+pub fn score_participant(query: &str, participant: &Participant) -> Option<f64> {
+    if participant
+        .email
+        .as_deref()
+        .is_some_and(|email| email_matches(query, email))
+    {
+        return Some(1.0);
+    }
+    name_score(query, &participant.name)
+}
+
+/// Orders `matches` by score, best first, breaking ties by name, and keeps
+/// at most `limit` of them (never more than [`MAX_SEARCH_RESULTS`]).
+pub fn rank(mut matches: Vec<ParticipantMatch>, limit: usize) -> Vec<ParticipantMatch> {
+    matches.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.participant.name.cmp(&b.participant.name))
+    });
+    matches.truncate(limit.min(MAX_SEARCH_RESULTS));
+    matches
+}
@@ -0,0 +1,91 @@
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::{methods::check_assignments, SplitError};
+use crate::models::{Bill, Money, Participant};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParticipantRef {
+    pub id: Uuid,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LineItemRef {
+    pub id: Uuid,
+    pub description: String,
+    pub total: Money,
+}
+
+/// Which fraction of each line item each participant pays in an itemised
+/// split, before the fractions are turned into amounts.
+#[derive(Debug, Clone, Serialize)]
+pub struct AllocationMatrix {
+    pub participants: Vec<ParticipantRef>,
+    pub items: Vec<LineItemRef>,
+    /// `matrix[i][j]` is the fraction of `items[j]` paid by
+    /// `participants[i]`. Every column sums to exactly 1.
+    pub matrix: Vec<Vec<Decimal>>,
+}
+
+/// The itemised split of `bill` as fractions. Each item is shared equally
+/// by its sharers, with the last taking up whatever the division leaves so
+/// columns sum to exactly 1. Fails like an itemised split does when an item
+/// is unassigned or every participant is exempt.
+pub fn allocation_matrix(
+    bill: &Bill,
+    participants: &[Participant],
+) -> Result<AllocationMatrix, SplitError> {
+    if participants.is_empty() {
+        return Err(SplitError::NoParticipants);
+    }
+    check_assignments(bill)?;
+    if participants
+        .iter()
+        .all(|participant| bill.is_exempt(participant.id))
+    {
+        return Err(SplitError::AllExempt);
+    }
+
+    let mut matrix = vec![vec![Decimal::ZERO; bill.line_items.len()]; participants.len()];
+    for (column, item) in bill.line_items.iter().enumerate() {
+        let sharers: Vec<usize> = bill
+            .item_sharers(item)
+            .iter()
+            .filter_map(|id| {
+                participants
+                    .iter()
+                    .position(|participant| participant.id == *id)
+            })
+            .collect();
+        let Some((&last, rest)) = sharers.split_last() else {
+            continue;
+        };
+        let fraction = Decimal::ONE / Decimal::from(sharers.len());
+        for &row in rest {
+            matrix[row][column] = fraction;
+        }
+        matrix[last][column] = Decimal::ONE - fraction * Decimal::from(rest.len());
+    }
+
+    Ok(AllocationMatrix {
+        participants: participants
+            .iter()
+            .map(|participant| ParticipantRef {
+                id: participant.id,
+                name: participant.name.clone(),
+            })
+            .collect(),
+        items: bill
+            .line_items
+            .iter()
+            .map(|item| LineItemRef {
+                id: item.id,
+                description: item.description.clone(),
+                total: item.total(),
+            })
+            .collect(),
+        matrix,
+    })
+}
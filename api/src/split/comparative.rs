@@ -0,0 +1,55 @@
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::models::{Money, ParticipantShare};
+
+/// How one participant's share compares with everyone else's.
+#[derive(Debug, Clone, Serialize)]
+pub struct ComparativeSpend {
+    pub participant_id: Uuid,
+    pub their_share: Money,
+    pub group_average: Money,
+    /// `their_share - group_average`; negative when they pay less.
+    pub delta: Money,
+    /// Their rank from 0 (lowest share) to 1 (highest). Tied shares share
+    /// the middle of their ranks, so when everyone pays the same all are at
+    /// 0.5.
+    pub percentile: f64,
+}
+
+/// Compares `participant_id`'s share in `shares` with the others, or `None`
+/// if they have no share.
+pub fn comparative_spend(
+    shares: &[ParticipantShare],
+    participant_id: Uuid,
+) -> Option<ComparativeSpend> {
+    let theirs = shares
+        .iter()
+        .find(|share| share.participant_id == participant_id)?
+        .amount_owed;
+    let total: Money = shares.iter().map(|share| share.amount_owed).sum();
+    let group_average = Money::from_decimal(total.to_decimal() / Decimal::from(shares.len()));
+
+    let below = shares
+        .iter()
+        .filter(|share| share.amount_owed < theirs)
+        .count();
+    let tied = shares
+        .iter()
+        .filter(|share| share.amount_owed == theirs)
+        .count();
+    let percentile = if shares.len() == 1 {
+        0.5
+    } else {
+        (below as f64 + (tied - 1) as f64 / 2.0) / (shares.len() - 1) as f64
+    };
+
+    Some(ComparativeSpend {
+        participant_id,
+        their_share: theirs,
+        group_average,
+        delta: theirs - group_average,
+        percentile,
+    })
+}
@@ -0,0 +1,114 @@
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use serde::Serialize;
+
+use super::{compute_split, SplitError, SplitMethod, SplitSpec};
+use crate::models::{Bill, Money, Participant, ParticipantShare};
+
+/// The same bill split by each method that needs no extra input.
+#[derive(Debug, Clone, Serialize)]
+pub struct SplitComparison {
+    pub equal: Vec<ParticipantShare>,
+    /// Every participant weighted 1. This matches `equal` apart from where
+    /// leftover cents land.
+    pub proportional_equal_weights: Vec<ParticipantShare>,
+    /// `None` unless every line item is assigned to someone.
+    pub itemised: Option<Vec<ParticipantShare>>,
+}
+
+impl SplitComparison {
+    /// The shares for `method`, where `proportional` means equal weights.
+    /// `None` for `custom`, and for `itemised` while it is unavailable.
+    pub fn shares(&self, method: SplitMethod) -> Option<&[ParticipantShare]> {
+        match method {
+            SplitMethod::Equal => Some(&self.equal),
+            SplitMethod::Proportional => Some(&self.proportional_equal_weights),
+            SplitMethod::Itemised => self.itemised.as_deref(),
+            SplitMethod::Custom => None,
+        }
+    }
+}
+
+/// Characters in the longest bar of [`bar_chart`].
+pub const BAR_CHART_WIDTH: usize = 40;
+
+const BAR: char = '\u{2588}';
+
+/// A plain-text bar chart of `shares`, one row per participant, scaled so
+/// the largest share fills [`BAR_CHART_WIDTH`], followed by a legend.
+pub fn bar_chart(shares: &[ParticipantShare]) -> String {
+    let largest = shares
+        .iter()
+        .map(|share| share.amount_owed)
+        .max()
+        .filter(|largest| largest.cents() > 0);
+    let name_width = shares
+        .iter()
+        .map(|share| share.name.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut chart = String::new();
+    for share in shares {
+        let bar_len = match largest {
+            Some(largest) if share.amount_owed.cents() > 0 => {
+                let scaled = share.amount_owed.to_decimal() / largest.to_decimal()
+                    * Decimal::from(BAR_CHART_WIDTH);
+                scaled.round().to_usize().unwrap_or(0).max(1)
+            }
+            _ => 0,
+        };
+        let padding = name_width - share.name.chars().count();
+        chart.push_str(&share.name);
+        chart.extend(std::iter::repeat_n(' ', padding + 1));
+        chart.extend(std::iter::repeat_n(BAR, bar_len));
+        if bar_len > 0 {
+            chart.push(' ');
+        }
+        chart.push_str(&format!("{}\n", share.amount_owed));
+    }
+
+    chart.push('\n');
+    match largest {
+        Some(largest) => {
+            let per_char =
+                Money::from_decimal(largest.to_decimal() / Decimal::from(BAR_CHART_WIDTH));
+            chart.push_str(&format!(
+                "Scale: each {BAR} is about {per_char}; a full bar of {BAR_CHART_WIDTH} is {largest}\n"
+            ));
+        }
+        None => chart.push_str("Scale: nobody owes anything\n"),
+    }
+    chart
+}
+
+/// Splits `bill` between `participants` equally, proportionally with equal
+/// weights, and by item where possible.
+pub fn compare_methods(
+    bill: &Bill,
+    participants: &[Participant],
+) -> Result<SplitComparison, SplitError> {
+    let equal = compute_split(bill, participants, &SplitSpec::Equal)?.shares;
+    let weights = participants
+        .iter()
+        .map(|participant| (participant.id, Decimal::ONE))
+        .collect();
+    let proportional_equal_weights =
+        compute_split(bill, participants, &SplitSpec::Proportional { weights })?.shares;
+
+    let fully_assigned = !bill.line_items.is_empty()
+        && bill
+            .line_items
+            .iter()
+            .all(|item| !item.participant_ids.is_empty());
+    let itemised = if fully_assigned {
+        Some(compute_split(bill, participants, &SplitSpec::Itemised)?.shares)
+    } else {
+        None
+    };
+
+    Ok(SplitComparison {
+        equal,
+        proportional_equal_weights,
+        itemised,
+    })
+}
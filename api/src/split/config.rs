@@ -0,0 +1,74 @@
+use std::collections::BTreeMap;
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{SplitMethod, SplitSpec};
+use crate::models::Money;
+
+/// The split method a bill was configured with, and its parameters, so
+/// splits can be recomputed without repeating them. Maps are ordered so the
+/// bill's JSON, and with it its ETag, is stable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SplitConfig {
+    pub method: SplitMethod,
+    /// Participant id to weight, required for `proportional`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weights: Option<BTreeMap<Uuid, Decimal>>,
+    /// Participant id to amount, required for `custom`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amounts: Option<BTreeMap<Uuid, Money>>,
+    /// Line item id to the participants sharing it, for `itemised`. Copied
+    /// onto the line items when the config is saved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignments: Option<BTreeMap<Uuid, Vec<Uuid>>>,
+}
+
+impl From<&SplitSpec> for SplitConfig {
+    fn from(spec: &SplitSpec) -> Self {
+        let mut config = SplitConfig {
+            method: spec.method(),
+            weights: None,
+            amounts: None,
+            assignments: None,
+        };
+        match spec {
+            SplitSpec::Proportional { weights } => {
+                config.weights = Some(weights.clone().into_iter().collect());
+            }
+            SplitSpec::Custom { amounts } => {
+                config.amounts = Some(amounts.clone().into_iter().collect());
+            }
+            SplitSpec::Equal | SplitSpec::Itemised => {}
+        }
+        config
+    }
+}
+
+impl SplitConfig {
+    /// The spec splits are computed with, or why the config is incomplete.
+    pub fn spec(&self) -> Result<SplitSpec, String> {
+        let missing = |name: &str| format!("`{name}` is required for the `{}` method", self.method);
+        Ok(match self.method {
+            SplitMethod::Equal => SplitSpec::Equal,
+            SplitMethod::Itemised => SplitSpec::Itemised,
+            SplitMethod::Proportional => SplitSpec::Proportional {
+                weights: self
+                    .weights
+                    .clone()
+                    .ok_or_else(|| missing("weights"))?
+                    .into_iter()
+                    .collect(),
+            },
+            SplitMethod::Custom => SplitSpec::Custom {
+                amounts: self
+                    .amounts
+                    .clone()
+                    .ok_or_else(|| missing("amounts"))?
+                    .into_iter()
+                    .collect(),
+            },
+        })
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::BTreeMap;
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::{SplitMethod, SplitResult};
+use crate::models::{Bill, CurrencyCode, LineItem, Money};
+
+/// The part of a share that came from items entered in one currency.
+#[derive(Debug, Clone, Serialize)]
+pub struct CurrencyShare {
+    pub currency: CurrencyCode,
+    /// `converted_amount` back in `currency`.
+    pub original_amount: Money,
+    /// In the bill's base currency.
+    pub converted_amount: Money,
+    /// Units of base currency per unit of `currency`.
+    pub rate_used: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParticipantCurrencyBreakdown {
+    pub participant_id: Uuid,
+    /// One entry per currency, in code order. Converted amounts add up to
+    /// the participant's share.
+    pub currency_shares: Vec<CurrencyShare>,
+}
+
+/// Splits each share of `result` by the currency its line items were entered
+/// in. Itemised shares are made of their own items; other methods take the
+/// bill's currency mix in proportion to the share.
+pub fn currency_breakdown(bill: &Bill, result: &SplitResult) -> Vec<ParticipantCurrencyBreakdown> {
+    let subtotal = bill.subtotal().to_decimal();
+    let scale = if subtotal.is_zero() {
+        Decimal::ONE
+    } else {
+        bill.total().to_decimal() / subtotal
+    };
+    let currency_of = |item: &LineItem| {
+        item.currency
+            .clone()
+            .unwrap_or_else(|| bill.base_currency.clone())
+    };
+
+    let mut bill_mix: BTreeMap<CurrencyCode, Decimal> = BTreeMap::new();
+    for item in &bill.line_items {
+        *bill_mix.entry(currency_of(item)).or_default() += item.total().to_decimal();
+    }
+
+    result
+        .shares
+        .iter()
+        .map(|share| {
+            let mut exact: BTreeMap<CurrencyCode, Decimal> = BTreeMap::new();
+            if result.method == SplitMethod::Itemised {
+                for item in &bill.line_items {
+                    let sharers = bill.item_sharers(item);
+                    if sharers.contains(&share.participant_id) {
+                        *exact.entry(currency_of(item)).or_default() +=
+                            item.total().to_decimal() * scale / Decimal::from(sharers.len());
+                    }
+                }
+            } else if !subtotal.is_zero() {
+                for (currency, amount) in &bill_mix {
+                    exact.insert(
+                        currency.clone(),
+                        share.amount_owed.to_decimal() * amount / subtotal,
+                    );
+                }
+            }
+
+            let mut converted: Vec<(CurrencyCode, Money)> = exact
+                .into_iter()
+                .map(|(currency, amount)| (currency, Money::from_decimal_floor(amount)))
+                .collect();
+            let allocated: Money = converted.iter().map(|(_, amount)| *amount).sum();
+            if let Some(largest) = converted.iter_mut().max_by_key(|(_, amount)| *amount) {
+                largest.1 += share.amount_owed - allocated;
+            }
+
+            ParticipantCurrencyBreakdown {
+                participant_id: share.participant_id,
+                currency_shares: converted
+                    .into_iter()
+                    .map(|(currency, converted_amount)| {
+                        let rate_used = rate_into_base(bill, &currency);
+                        CurrencyShare {
+                            original_amount: Money::from_decimal(
+                                converted_amount.to_decimal() / rate_used,
+                            ),
+                            currency,
+                            converted_amount,
+                            rate_used,
+                        }
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// The rate items in `currency` were converted at: the manual rate when one
+/// is set, otherwise the most recent live rate used on the bill.
+fn rate_into_base(bill: &Bill, currency: &CurrencyCode) -> Decimal {
+    if *currency == bill.base_currency {
+        return Decimal::ONE;
+    }
+    bill.manual_rate(currency)
+        .or_else(|| {
+            bill.exchange_rates_used
+                .iter()
+                .rev()
+                .find(|rate| rate.from == *currency && rate.to == bill.base_currency)
+        })
+        .map(|rate| rate.rate)
+        .filter(|rate| !rate.is_zero())
+        .unwrap_or(Decimal::ONE)
+}
@@ -0,0 +1,141 @@
+use std::fmt;
+
+use uuid::Uuid;
+
+use crate::{error::ApiError, i18n::t, models::Money};
+
+/// Why a bill could not be split.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SplitError {
+    NoParticipants,
+    /// Every participant is exempt, so no one is left to pay.
+    AllExempt,
+    /// An itemised split was requested for a bill without line items.
+    NoLineItems,
+    UnassignedLineItem {
+        id: Uuid,
+    },
+    /// Proportional weights do not cover exactly the bill's participants.
+    WeightsMismatch {
+        missing: Vec<Uuid>,
+        extra: Vec<Uuid>,
+    },
+    NegativeWeight {
+        participant_id: Uuid,
+    },
+    ZeroTotalWeight,
+    /// Custom amounts do not cover exactly the bill's participants.
+    AmountsMismatch {
+        missing: Vec<Uuid>,
+        extra: Vec<Uuid>,
+    },
+    /// Custom amounts do not add up to the bill total.
+    AmountMismatch {
+        expected: Money,
+        got: Money,
+    },
+    /// A line item is negative.
+    NegativeAmount {
+        item_id: Uuid,
+    },
+    NegativeCustomAmount {
+        participant_id: Uuid,
+    },
+    /// Custom amounts add up to more than [`Money`] can hold.
+    CustomAmountsOverflow,
+    /// Rounding every share up to `round_to` overshoots the bill total by
+    /// more than one step, so no single share can absorb the excess.
+    RoundingExceedsTotal {
+        round_to: Money,
+        excess: Money,
+    },
+}
+
+fn join_ids(ids: &[Uuid]) -> String {
+    ids.iter()
+        .map(Uuid::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// "missing a, b; not on the bill: c", leaving out empty halves.
+fn describe_mismatch(missing: &[Uuid], extra: &[Uuid]) -> String {
+    let mut parts = Vec::new();
+    if !missing.is_empty() {
+        parts.push(format!("missing for {}", join_ids(missing)));
+    }
+    if !extra.is_empty() {
+        parts.push(format!(
+            "given for {}, who are not part of this bill",
+            join_ids(extra)
+        ));
+    }
+    parts.join("; ")
+}
+
+impl fmt::Display for SplitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SplitError::NoParticipants => f.write_str("The bill has no participants"),
+            SplitError::AllExempt => {
+                f.write_str("Every participant is exempt, so there is no one to split between")
+            }
+            SplitError::NoLineItems => f.write_str("The bill has no line items to split by item"),
+            SplitError::UnassignedLineItem { id } => {
+                write!(f, "Line item {id} has no participants assigned")
+            }
+            SplitError::WeightsMismatch { missing, extra } => {
+                write!(f, "Weights {}", describe_mismatch(missing, extra))
+            }
+            SplitError::NegativeWeight { participant_id } => {
+                write!(f, "Weight for {participant_id} must not be negative")
+            }
+            SplitError::ZeroTotalWeight => f.write_str("Weights must not all be zero"),
+            SplitError::AmountsMismatch { missing, extra } => {
+                write!(f, "Amounts {}", describe_mismatch(missing, extra))
+            }
+            SplitError::AmountMismatch { expected, got } => write!(
+                f,
+                "Custom amounts add up to {got} but the bill total is {expected}"
+            ),
+            SplitError::NegativeAmount { item_id } => {
+                write!(f, "Amount for {item_id} must not be negative")
+            }
+            SplitError::NegativeCustomAmount { participant_id } => {
+                write!(f, "Custom amount for {participant_id} must not be negative")
+            }
+            SplitError::CustomAmountsOverflow => {
+                f.write_str("Custom amounts add up to more than the largest supported amount")
+            }
+            SplitError::RoundingExceedsTotal { round_to, excess } => write!(
+                f,
+                "Rounding shares up to {round_to} overshoots the bill total by {excess}, \
+                 more than one step; choose a smaller `round_to`"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SplitError {}
+
+/// Problems with the stored bill are `InsufficientData`; problems with the
+/// parameters the client sent are `BadRequest`.
+impl From<SplitError> for ApiError {
+    fn from(err: SplitError) -> Self {
+        match err {
+            SplitError::NoParticipants => ApiError::InsufficientData(t("no_participants")),
+            SplitError::AllExempt
+            | SplitError::NoLineItems
+            | SplitError::UnassignedLineItem { .. }
+            | SplitError::NegativeAmount { .. } => ApiError::InsufficientData(err.to_string()),
+            SplitError::WeightsMismatch { .. }
+            | SplitError::NegativeWeight { .. }
+            | SplitError::ZeroTotalWeight
+            | SplitError::AmountsMismatch { .. }
+            | SplitError::AmountMismatch { .. }
+            | SplitError::NegativeCustomAmount { .. }
+            | SplitError::CustomAmountsOverflow
+            | SplitError::RoundingExceedsTotal { .. } => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
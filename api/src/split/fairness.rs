@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::SplitMethod;
+use crate::models::{ParticipantShare, SplitSnapshot};
+
+/// How unevenly `shares` are spread: 0 when everyone owes the same, rising
+/// towards 1 as one participant owes everything. Splits with nothing owed
+/// score 0. Rounded to four decimal places.
+pub fn gini_coefficient(shares: &[ParticipantShare]) -> f64 {
+    let amounts: Vec<Decimal> = shares
+        .iter()
+        .map(|share| share.amount_owed.to_decimal())
+        .collect();
+    let total: Decimal = amounts.iter().sum();
+    if total <= Decimal::ZERO {
+        return 0.0;
+    }
+
+    let differences: Decimal = amounts
+        .iter()
+        .flat_map(|a| amounts.iter().map(move |b| (a - b).abs()))
+        .sum();
+    let gini = differences / (Decimal::TWO * Decimal::from(amounts.len()) * total);
+    gini.round_dp(4).to_f64().unwrap_or(0.0)
+}
+
+/// The Gini coefficient of one recorded split.
+#[derive(Debug, Clone, Serialize)]
+pub struct FairnessPoint {
+    pub snapshot_id: Uuid,
+    pub computed_at: DateTime<Utc>,
+    pub method: SplitMethod,
+    pub gini_coefficient: f64,
+}
+
+/// The Gini coefficient of each snapshot in `history`, oldest first.
+/// Snapshots recorded before the coefficient was kept have it worked out
+/// from their shares.
+pub fn fairness_history(history: &[SplitSnapshot]) -> Vec<FairnessPoint> {
+    history
+        .iter()
+        .map(|snapshot| FairnessPoint {
+            snapshot_id: snapshot.id,
+            computed_at: snapshot.computed_at,
+            method: snapshot.method,
+            gini_coefficient: snapshot
+                .gini_coefficient
+                .unwrap_or_else(|| gini_coefficient(&snapshot.shares)),
+        })
+        .collect()
+}
+
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// `points` as a one-line sparkline scaled between the lowest and highest
+/// coefficient, followed by the first and latest values.
+pub fn fairness_sparkline(points: &[FairnessPoint]) -> String {
+    let (Some(first), Some(latest)) = (points.first(), points.last()) else {
+        return "No splits recorded yet\n".to_string();
+    };
+    let values: Vec<f64> = points.iter().map(|point| point.gini_coefficient).collect();
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let top = (SPARK_LEVELS.len() - 1) as f64;
+
+    let mut line: String = values
+        .iter()
+        .map(|value| {
+            if max > min {
+                SPARK_LEVELS[((value - min) / (max - min) * top).round() as usize]
+            } else {
+                SPARK_LEVELS[0]
+            }
+        })
+        .collect();
+    line.push_str(&format!(
+        "\nGini {:.4} -> {:.4} over {} splits (min {min:.4}, max {max:.4})\n",
+        first.gini_coefficient,
+        latest.gini_coefficient,
+        points.len()
+    ));
+    line
+}
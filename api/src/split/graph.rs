@@ -0,0 +1,63 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::{Balance, Settlement};
+use crate::models::Money;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Node {
+    pub id: Uuid,
+    pub name: String,
+    pub net_balance: Money,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Edge {
+    pub from: Uuid,
+    pub to: Uuid,
+    pub amount: Money,
+    /// `amount` relative to the largest transfer, in `[0, 1]`. Meant for
+    /// edge thickness when rendering.
+    pub weight: f64,
+}
+
+/// Participants and the transfers between them, shaped for graph renderers.
+#[derive(Debug, Clone, Serialize)]
+pub struct DebtGraph {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+impl DebtGraph {
+    pub fn new(balances: Vec<Balance>, settlements: Vec<Settlement>) -> Self {
+        let largest = settlements
+            .iter()
+            .map(|settlement| settlement.amount.cents())
+            .max()
+            .unwrap_or(0);
+
+        let edges = settlements
+            .into_iter()
+            .map(|settlement| Edge {
+                weight: if largest == 0 {
+                    0.0
+                } else {
+                    settlement.amount.cents() as f64 / largest as f64
+                },
+                from: settlement.from,
+                to: settlement.to,
+                amount: settlement.amount,
+            })
+            .collect();
+        let nodes = balances
+            .into_iter()
+            .map(|balance| Node {
+                id: balance.participant_id,
+                name: balance.name,
+                net_balance: balance.net_balance,
+            })
+            .collect();
+
+        Self { nodes, edges }
+    }
+}
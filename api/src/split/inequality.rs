@@ -0,0 +1,41 @@
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::models::{Money, ParticipantShare};
+
+/// Ratio of the largest share to the average at which a split is flagged.
+pub const DEFAULT_INEQUALITY_THRESHOLD: Decimal = Decimal::from_parts(3, 0, 0, false, 0);
+
+/// One participant owes far more than everyone else, which may be a mistake
+/// in how the bill was entered.
+#[derive(Debug, Clone, Serialize)]
+pub struct InequalityWarning {
+    pub max_payer_id: Uuid,
+    pub max_payer_amount: Money,
+    pub average_amount: Money,
+    /// `max_payer_amount / average_amount`, to two decimal places.
+    pub ratio: Decimal,
+}
+
+/// Flags `shares` when the largest is at least `threshold` times the
+/// average. Splits with nothing owed are never flagged.
+pub fn inequality_warning(
+    shares: &[ParticipantShare],
+    threshold: Decimal,
+) -> Option<InequalityWarning> {
+    let max = shares.iter().max_by_key(|share| share.amount_owed)?;
+    let total: Money = shares.iter().map(|share| share.amount_owed).sum();
+    let average = total.to_decimal() / Decimal::from(shares.len());
+    if average <= Decimal::ZERO {
+        return None;
+    }
+
+    let ratio = max.amount_owed.to_decimal() / average;
+    (ratio >= threshold).then(|| InequalityWarning {
+        max_payer_id: max.participant_id,
+        max_payer_amount: max.amount_owed,
+        average_amount: Money::from_decimal(average),
+        ratio: ratio.round_dp(2),
+    })
+}
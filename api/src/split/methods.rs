@@ -0,0 +1,329 @@
+use std::{collections::HashMap, fmt};
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{distribute_rounding_remainder, RoundingReport, SplitError};
+use crate::models::{Bill, Money, Participant, ParticipantShare};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitMethod {
+    #[default]
+    Equal,
+    Proportional,
+    Itemised,
+    Custom,
+}
+
+impl fmt::Display for SplitMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SplitMethod::Equal => "equal",
+            SplitMethod::Proportional => "proportional",
+            SplitMethod::Itemised => "itemised",
+            SplitMethod::Custom => "custom",
+        })
+    }
+}
+
+/// A split method together with the parameters it needs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SplitSpec {
+    Equal,
+    Proportional { weights: HashMap<Uuid, Decimal> },
+    Itemised,
+    Custom { amounts: HashMap<Uuid, Money> },
+}
+
+impl SplitSpec {
+    pub fn method(&self) -> SplitMethod {
+        match self {
+            SplitSpec::Equal => SplitMethod::Equal,
+            SplitSpec::Proportional { .. } => SplitMethod::Proportional,
+            SplitSpec::Itemised => SplitMethod::Itemised,
+            SplitSpec::Custom { .. } => SplitMethod::Custom,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitResult {
+    pub method: SplitMethod,
+    pub total: Money,
+    pub shares: Vec<ParticipantShare>,
+}
+
+/// Splits `bill` between `participants` using `spec`. Shares are returned in
+/// participant order and always sum to `bill.total()`. Exempt participants
+/// owe nothing; what they would have owed falls to everyone else.
+pub fn compute_split(
+    bill: &Bill,
+    participants: &[Participant],
+    spec: &SplitSpec,
+) -> Result<SplitResult, SplitError> {
+    let total = bill.total();
+    let shares = with_exempt(
+        bill,
+        participants,
+        distribute_rounding_remainder(unrounded_shares(bill, participants, spec)?, total),
+    );
+
+    Ok(SplitResult {
+        method: spec.method(),
+        total,
+        shares,
+    })
+}
+
+/// How [`compute_split`] rounds `bill`'s shares to whole cents.
+pub fn rounding_report(
+    bill: &Bill,
+    participants: &[Participant],
+    spec: &SplitSpec,
+) -> Result<RoundingReport, SplitError> {
+    let unrounded = with_exempt(
+        bill,
+        participants,
+        unrounded_shares(bill, participants, spec)?,
+    );
+    let rounded = compute_split(bill, participants, spec)?.shares;
+    Ok(RoundingReport::new(&unrounded, &rounded))
+}
+
+/// Shares of the participants who are not exempt, rounded down to whole
+/// cents, before the remainder is handed out.
+fn unrounded_shares(
+    bill: &Bill,
+    participants: &[Participant],
+    spec: &SplitSpec,
+) -> Result<Vec<ParticipantShare>, SplitError> {
+    if participants.is_empty() {
+        return Err(SplitError::NoParticipants);
+    }
+    let liable: Vec<Participant> = participants
+        .iter()
+        .filter(|participant| !bill.is_exempt(participant.id))
+        .cloned()
+        .collect();
+    if liable.is_empty() {
+        return Err(SplitError::AllExempt);
+    }
+    // Exempt participants need no weight; one given for them is ignored.
+    let without_exempt = |weights: &HashMap<Uuid, Decimal>| {
+        weights
+            .iter()
+            .filter(|(id, _)| !bill.is_exempt(**id))
+            .map(|(id, weight)| (*id, *weight))
+            .collect::<HashMap<_, _>>()
+    };
+
+    let total = bill.total();
+    Ok(match spec {
+        SplitSpec::Equal => split_equal(total, &liable),
+        SplitSpec::Proportional { weights } => {
+            split_proportional(total, &liable, &without_exempt(weights))?
+        }
+        SplitSpec::Itemised => split_itemised(bill, &liable)?,
+        SplitSpec::Custom { amounts } => {
+            let shares = split_custom(total, participants, amounts)?;
+            reassign_exempt_amounts(bill, shares)
+        }
+    })
+}
+
+/// Adds a zero share for each exempt participant, keeping participant order.
+fn with_exempt(
+    bill: &Bill,
+    participants: &[Participant],
+    shares: Vec<ParticipantShare>,
+) -> Vec<ParticipantShare> {
+    let mut shares = shares.into_iter();
+    participants
+        .iter()
+        .filter_map(|participant| {
+            if bill.is_exempt(participant.id) {
+                Some(share(participant, Money::ZERO))
+            } else {
+                shares.next()
+            }
+        })
+        .collect()
+}
+
+/// Drops exempt participants' custom amounts, spreading them evenly over
+/// everyone else.
+fn reassign_exempt_amounts(bill: &Bill, shares: Vec<ParticipantShare>) -> Vec<ParticipantShare> {
+    let (exempt, mut liable): (Vec<_>, Vec<_>) = shares
+        .into_iter()
+        .partition(|share| bill.is_exempt(share.participant_id));
+    let unallocated: Money = exempt.iter().map(|share| share.amount_owed).sum();
+    let each = Money::from_cents(unallocated.cents().div_euclid(liable.len() as i64));
+    for share in &mut liable {
+        share.amount_owed += each;
+    }
+    liable
+}
+
+fn share(participant: &Participant, amount_owed: Money) -> ParticipantShare {
+    ParticipantShare {
+        participant_id: participant.id,
+        name: participant.name.clone(),
+        amount_owed,
+    }
+}
+
+/// Participants without an entry in `values`, and keys that are not
+/// participants (sorted, so errors are stable).
+fn key_mismatch<V>(
+    participants: &[Participant],
+    values: &HashMap<Uuid, V>,
+) -> (Vec<Uuid>, Vec<Uuid>) {
+    let missing = participants
+        .iter()
+        .filter(|participant| !values.contains_key(&participant.id))
+        .map(|participant| participant.id)
+        .collect();
+    let mut extra: Vec<Uuid> = values
+        .keys()
+        .filter(|id| {
+            !participants
+                .iter()
+                .any(|participant| participant.id == **id)
+        })
+        .copied()
+        .collect();
+    extra.sort();
+    (missing, extra)
+}
+
+fn split_equal(total: Money, participants: &[Participant]) -> Vec<ParticipantShare> {
+    let each = Money::from_cents(total.cents().div_euclid(participants.len() as i64));
+    participants
+        .iter()
+        .map(|participant| share(participant, each))
+        .collect()
+}
+
+fn split_proportional(
+    total: Money,
+    participants: &[Participant],
+    weights: &HashMap<Uuid, Decimal>,
+) -> Result<Vec<ParticipantShare>, SplitError> {
+    let (missing, extra) = key_mismatch(participants, weights);
+    if !missing.is_empty() || !extra.is_empty() {
+        return Err(SplitError::WeightsMismatch { missing, extra });
+    }
+    if let Some(participant) = participants
+        .iter()
+        .find(|participant| weights[&participant.id].is_sign_negative())
+    {
+        return Err(SplitError::NegativeWeight {
+            participant_id: participant.id,
+        });
+    }
+
+    let total_weight: Decimal = weights.values().sum();
+    if total_weight.is_zero() {
+        return Err(SplitError::ZeroTotalWeight);
+    }
+
+    Ok(participants
+        .iter()
+        .map(|participant| {
+            let exact = total.to_decimal() * weights[&participant.id] / total_weight;
+            share(participant, Money::from_decimal_floor(exact))
+        })
+        .collect())
+}
+
+/// Fails unless `bill` has line items and every one is assigned.
+pub(crate) fn check_assignments(bill: &Bill) -> Result<(), SplitError> {
+    if bill.line_items.is_empty() {
+        return Err(SplitError::NoLineItems);
+    }
+    if let Some(item) = bill
+        .line_items
+        .iter()
+        .find(|item| item.participant_ids.is_empty())
+    {
+        return Err(SplitError::UnassignedLineItem { id: item.id });
+    }
+    Ok(())
+}
+
+fn split_itemised(
+    bill: &Bill,
+    participants: &[Participant],
+) -> Result<Vec<ParticipantShare>, SplitError> {
+    check_assignments(bill)?;
+    if let Some(item) = bill
+        .line_items
+        .iter()
+        .find(|item| item.total().is_negative())
+    {
+        return Err(SplitError::NegativeAmount { item_id: item.id });
+    }
+
+    // Bill-wide adjustments are spread in proportion to each item's share of
+    // the subtotal, so a 10% discount takes 10% off everyone's items.
+    let subtotal = bill.subtotal().to_decimal();
+    let scale = if subtotal.is_zero() {
+        Decimal::ONE
+    } else {
+        bill.total().to_decimal() / subtotal
+    };
+
+    let mut exact: HashMap<Uuid, Decimal> = HashMap::new();
+    for item in &bill.line_items {
+        let sharers = bill.item_sharers(item);
+        let portion = item.total().to_decimal() * scale / Decimal::from(sharers.len());
+        for participant_id in &sharers {
+            *exact.entry(*participant_id).or_default() += portion;
+        }
+    }
+
+    Ok(participants
+        .iter()
+        .map(|participant| {
+            let amount = exact.get(&participant.id).copied().unwrap_or_default();
+            share(participant, Money::from_decimal_floor(amount))
+        })
+        .collect())
+}
+
+fn split_custom(
+    total: Money,
+    participants: &[Participant],
+    amounts: &HashMap<Uuid, Money>,
+) -> Result<Vec<ParticipantShare>, SplitError> {
+    let (missing, extra) = key_mismatch(participants, amounts);
+    if !missing.is_empty() || !extra.is_empty() {
+        return Err(SplitError::AmountsMismatch { missing, extra });
+    }
+    if let Some(participant) = participants
+        .iter()
+        .find(|participant| amounts[&participant.id].is_negative())
+    {
+        return Err(SplitError::NegativeCustomAmount {
+            participant_id: participant.id,
+        });
+    }
+
+    let allocated = amounts
+        .values()
+        .try_fold(Money::ZERO, |sum, amount| sum.checked_add(*amount))
+        .ok_or(SplitError::CustomAmountsOverflow)?;
+    if allocated != total {
+        return Err(SplitError::AmountMismatch {
+            expected: total,
+            got: allocated,
+        });
+    }
+
+    Ok(participants
+        .iter()
+        .map(|participant| share(participant, amounts[&participant.id]))
+        .collect())
+}
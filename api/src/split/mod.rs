@@ -0,0 +1,45 @@
+mod allocation;
+mod comparative;
+mod comparison;
+mod config;
+mod currency_breakdown;
+mod error;
+mod fairness;
+mod graph;
+mod inequality;
+mod methods;
+mod perspective;
+mod recommendation;
+mod rounding;
+mod sensitivity;
+mod settlement;
+mod simulate;
+mod tax_exclusive;
+
+pub use allocation::{allocation_matrix, AllocationMatrix, LineItemRef, ParticipantRef};
+pub use comparative::{comparative_spend, ComparativeSpend};
+pub use comparison::{bar_chart, compare_methods, SplitComparison, BAR_CHART_WIDTH};
+pub use config::SplitConfig;
+pub use currency_breakdown::{currency_breakdown, CurrencyShare, ParticipantCurrencyBreakdown};
+pub use error::SplitError;
+pub use fairness::{fairness_history, fairness_sparkline, gini_coefficient, FairnessPoint};
+pub use graph::{DebtGraph, Edge, Node};
+pub use inequality::{inequality_warning, InequalityWarning, DEFAULT_INEQUALITY_THRESHOLD};
+pub use methods::{compute_split, rounding_report, SplitMethod, SplitResult, SplitSpec};
+pub use perspective::{PerspectiveShare, SplitPerspective};
+pub use recommendation::{
+    recommend_method, SplitRecommendation, HEAVY_SPENDER_RATIO, QUANTITY_SPREAD_RATIO,
+};
+pub use rounding::{
+    distribute_rounding_remainder, round_to_nearest, RoundedShares, RoundingAdjustment,
+    RoundingReport, ROUNDING_ALGORITHM,
+};
+pub use sensitivity::{sensitivity, SensitivityEntry};
+pub use settlement::{
+    minimise_settlements, naive_settlements, net_balances, Balance, Settlement, SettlementSavings,
+};
+pub use simulate::{ShareDelta, SplitDiff, WhatIfPriceResult};
+pub use tax_exclusive::{
+    split_tax_exclusive, split_tax_inclusive, TaxExclusiveShare, TaxExclusiveSplitResult,
+    TaxInclusiveShare, TaxInclusiveSplitResult,
+};
@@ -0,0 +1,53 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::{net_balances, SplitMethod, SplitResult};
+use crate::models::{Bill, Money, Participant, ParticipantShare, SplitSnapshot};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PerspectiveShare {
+    #[serde(flatten)]
+    pub share: ParticipantShare,
+    pub is_you: bool,
+}
+
+/// A split result as seen by one participant. The totals come from their
+/// net balance, so payments already made are taken into account.
+#[derive(Debug, Clone, Serialize)]
+pub struct SplitPerspective {
+    pub method: SplitMethod,
+    pub total: Money,
+    pub shares: Vec<PerspectiveShare>,
+    pub you_owe_total: Money,
+    pub others_owe_you_total: Money,
+}
+
+impl SplitPerspective {
+    pub fn new(bill: &Bill, participants: &[Participant], result: SplitResult, you: Uuid) -> Self {
+        let snapshot = SplitSnapshot::from_result(&result);
+        let net_balance = net_balances(bill, &snapshot, participants)
+            .into_iter()
+            .find(|balance| balance.participant_id == you)
+            .map_or(Money::ZERO, |balance| balance.net_balance);
+        let (you_owe_total, others_owe_you_total) = if net_balance.is_negative() {
+            (net_balance.abs(), Money::ZERO)
+        } else {
+            (Money::ZERO, net_balance)
+        };
+
+        Self {
+            method: result.method,
+            total: result.total,
+            shares: result
+                .shares
+                .into_iter()
+                .map(|share| PerspectiveShare {
+                    is_you: share.participant_id == you,
+                    share,
+                })
+                .collect(),
+            you_owe_total,
+            others_owe_you_total,
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::SplitMethod;
+use crate::models::{Bill, Participant};
+
+/// How many times the median spend one participant must reach before a
+/// proportional split is suggested.
+pub const HEAVY_SPENDER_RATIO: u32 = 2;
+
+/// How many times the smallest quantity the largest must reach for the items
+/// to count as differing significantly.
+pub const QUANTITY_SPREAD_RATIO: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SplitRecommendation {
+    pub recommended: SplitMethod,
+    /// From 0 to 1.
+    pub confidence: f64,
+    pub reasoning: String,
+    /// The other methods that would work, best first.
+    pub alternatives: Vec<SplitMethod>,
+}
+
+/// Suggests a split method from how the bill's line items are shared:
+/// `equal` when everyone shares everything, `proportional` when one person
+/// accounts for at least [`HEAVY_SPENDER_RATIO`] times the median spend,
+/// `itemised` when item quantities differ widely. `None` for bills without
+/// participants or line items.
+pub fn recommend_method(bill: &Bill, participants: &[Participant]) -> Option<SplitRecommendation> {
+    let participant_ids: Vec<Uuid> = participants
+        .iter()
+        .map(|participant| participant.id)
+        .collect();
+    if participant_ids.is_empty() || bill.line_items.is_empty() {
+        return None;
+    }
+
+    let everyone_shares_everything = bill.line_items.iter().all(|item| {
+        participant_ids
+            .iter()
+            .all(|id| item.participant_ids.contains(id))
+    });
+    let all_assigned = bill
+        .line_items
+        .iter()
+        .all(|item| !item.participant_ids.is_empty());
+
+    let (recommended, confidence, reasoning) = if everyone_shares_everything {
+        (
+            SplitMethod::Equal,
+            0.95,
+            "Every participant shares every line item, so an equal split is exact".to_string(),
+        )
+    } else if let Some((ratio, heavy)) = heaviest_spender(bill, participants) {
+        let ratio_f64 = ratio.to_f64().unwrap_or(f64::from(HEAVY_SPENDER_RATIO));
+        (
+            SplitMethod::Proportional,
+            (0.6 + 0.1 * (ratio_f64 - f64::from(HEAVY_SPENDER_RATIO))).min(0.9),
+            format!(
+                "{} accounts for {}x the median spend, so weighting shares by spend is fairer than splitting equally",
+                heavy.name,
+                ratio.round_dp(1).normalize()
+            ),
+        )
+    } else if quantities_differ(bill) {
+        (
+            SplitMethod::Itemised,
+            if all_assigned { 0.8 } else { 0.6 },
+            "Line item quantities differ widely, so charging each participant for what they had is fairest".to_string(),
+        )
+    } else {
+        (
+            SplitMethod::Equal,
+            0.5,
+            "Spending is even across participants, so an equal split is close to exact".to_string(),
+        )
+    };
+
+    let alternatives = [
+        SplitMethod::Itemised,
+        SplitMethod::Proportional,
+        SplitMethod::Equal,
+    ]
+    .into_iter()
+    .filter(|method| *method != recommended)
+    .filter(|method| *method != SplitMethod::Itemised || all_assigned)
+    .collect();
+
+    Some(SplitRecommendation {
+        recommended,
+        confidence,
+        reasoning,
+        alternatives,
+    })
+}
+
+/// The participant whose share of the items is at least
+/// [`HEAVY_SPENDER_RATIO`] times the median, with how many times it is.
+/// Unassigned items count as shared by everyone.
+fn heaviest_spender<'a>(
+    bill: &Bill,
+    participants: &'a [Participant],
+) -> Option<(Decimal, &'a Participant)> {
+    let mut spend: HashMap<Uuid, Decimal> = participants
+        .iter()
+        .map(|participant| (participant.id, Decimal::ZERO))
+        .collect();
+    let everyone: Vec<Uuid> = participants
+        .iter()
+        .map(|participant| participant.id)
+        .collect();
+    for item in &bill.line_items {
+        let sharers = if item.participant_ids.is_empty() {
+            &everyone
+        } else {
+            &item.participant_ids
+        };
+        let part = item.total().to_decimal() / Decimal::from(sharers.len());
+        for id in sharers {
+            if let Some(total) = spend.get_mut(id) {
+                *total += part;
+            }
+        }
+    }
+
+    let mut amounts: Vec<Decimal> = spend.values().copied().collect();
+    amounts.sort();
+    let middle = amounts.len() / 2;
+    let median = if amounts.len().is_multiple_of(2) {
+        (amounts[middle - 1] + amounts[middle]) / Decimal::TWO
+    } else {
+        amounts[middle]
+    };
+    if median <= Decimal::ZERO {
+        return None;
+    }
+
+    let (heavy, most) = participants
+        .iter()
+        .map(|participant| (participant, spend[&participant.id]))
+        .max_by_key(|(_, amount)| *amount)?;
+    let ratio = most / median;
+    (ratio >= Decimal::from(HEAVY_SPENDER_RATIO)).then_some((ratio, heavy))
+}
+
+fn quantities_differ(bill: &Bill) -> bool {
+    let quantities = bill.line_items.iter().map(|item| item.quantity);
+    match (quantities.clone().min(), quantities.max()) {
+        (Some(least), Some(most)) => most >= least.max(1) * QUANTITY_SPREAD_RATIO,
+        _ => false,
+    }
+}
@@ -0,0 +1,136 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::SplitError;
+use crate::models::{Money, ParticipantShare};
+
+/// Plain-language description of [`distribute_rounding_remainder`].
+pub const ROUNDING_ALGORITHM: &str = "Each share is rounded down to whole cents; the cents left over go to the first participant alphabetically (ties broken by id)";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RoundingAdjustment {
+    pub participant_id: Uuid,
+    pub cents_added: i32,
+}
+
+/// How much rounding changed each share of a split.
+#[derive(Debug, Clone, Serialize)]
+pub struct RoundingReport {
+    pub total_rounding_applied: Money,
+    /// Every participant, in split order, including those left untouched.
+    pub cents_added_per_participant: Vec<RoundingAdjustment>,
+    pub algorithm: &'static str,
+}
+
+impl RoundingReport {
+    /// Compares shares before and after [`distribute_rounding_remainder`].
+    pub fn new(unrounded: &[ParticipantShare], rounded: &[ParticipantShare]) -> Self {
+        let cents_added_per_participant: Vec<RoundingAdjustment> = rounded
+            .iter()
+            .map(|share| {
+                let before = unrounded
+                    .iter()
+                    .find(|unrounded| unrounded.participant_id == share.participant_id)
+                    .map_or(Money::ZERO, |unrounded| unrounded.amount_owed);
+                RoundingAdjustment {
+                    participant_id: share.participant_id,
+                    cents_added: (share.amount_owed - before).cents() as i32,
+                }
+            })
+            .collect();
+        let total_rounding_applied = Money::from_cents(
+            cents_added_per_participant
+                .iter()
+                .map(|adjustment| i64::from(adjustment.cents_added))
+                .sum(),
+        );
+
+        Self {
+            total_rounding_applied,
+            cents_added_per_participant,
+            algorithm: ROUNDING_ALGORITHM,
+        }
+    }
+}
+
+/// Shares rounded to a multiple of [`Self::round_to`] by [`round_to_nearest`].
+#[derive(Debug, Clone, Serialize)]
+pub struct RoundedShares {
+    pub round_to: Money,
+    pub shares: Vec<ParticipantShare>,
+    /// Taken off one share so the shares still add up to the bill total;
+    /// zero or negative.
+    pub remainder: Money,
+    /// Whose share the remainder was taken from, `None` if it was zero.
+    pub remainder_applied_to: Option<Uuid>,
+}
+
+/// Rounds each share up to a multiple of `round_to`, then takes the excess
+/// over `total` off the last participant by name (ties broken by id) among
+/// those who owe something, so everyone but them pays a round amount. Zero
+/// shares, such as exempt participants', are never given the remainder.
+///
+/// Fails when the excess is more than one step: it could then push the
+/// share absorbing it below zero.
+pub fn round_to_nearest(
+    mut shares: Vec<ParticipantShare>,
+    total: Money,
+    round_to: Money,
+) -> Result<RoundedShares, SplitError> {
+    let step = round_to.cents().max(1);
+    for share in &mut shares {
+        let cents = share.amount_owed.cents();
+        share.amount_owed = Money::from_cents((cents + step - 1).div_euclid(step) * step);
+    }
+
+    let allocated: Money = shares.iter().map(|share| share.amount_owed).sum();
+    let remainder = total - allocated;
+    let excess = -remainder;
+    if excess.cents() > step {
+        return Err(SplitError::RoundingExceedsTotal { round_to, excess });
+    }
+    let mut remainder_applied_to = None;
+    if !remainder.is_zero() {
+        if let Some(last) = shares
+            .iter_mut()
+            .filter(|share| !share.amount_owed.is_zero())
+            .max_by(|a, b| (&a.name, a.participant_id).cmp(&(&b.name, b.participant_id)))
+        {
+            last.amount_owed += remainder;
+            remainder_applied_to = Some(last.participant_id);
+        }
+    }
+
+    Ok(RoundedShares {
+        round_to,
+        shares,
+        remainder,
+        remainder_applied_to,
+    })
+}
+
+/// Makes `shares` add up to exactly `total`.
+///
+/// Shares are computed per participant and truncated to whole cents, so their
+/// sum can be a few cents off the bill total. The whole difference is given
+/// to the first participant by name (ties broken by id) so the result is
+/// deterministic regardless of input order. Returns `shares` untouched when
+/// there is no one to assign the remainder to.
+pub fn distribute_rounding_remainder(
+    mut shares: Vec<ParticipantShare>,
+    total: Money,
+) -> Vec<ParticipantShare> {
+    let allocated: Money = shares.iter().map(|share| share.amount_owed).sum();
+    let remainder = total - allocated;
+    if remainder.is_zero() {
+        return shares;
+    }
+
+    if let Some(first) = shares
+        .iter_mut()
+        .min_by(|a, b| (&a.name, a.participant_id).cmp(&(&b.name, b.participant_id)))
+    {
+        first.amount_owed += remainder;
+    }
+    shares
+}
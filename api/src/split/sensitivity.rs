@@ -0,0 +1,54 @@
+use std::cmp::Reverse;
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::models::{Bill, Money};
+
+/// How much one sharer's cost moves when a line item's price changes.
+#[derive(Debug, Clone, Serialize)]
+pub struct SensitivityEntry {
+    pub line_item_id: Uuid,
+    pub description: String,
+    /// Change in each sharer's cost for a 1% change in the item's price.
+    pub impact_per_percent_change: Money,
+    /// More than twice the average impact across the bill's items.
+    pub high_impact: bool,
+}
+
+/// Ranks `bill`'s line items by how much a 1% price change moves the cost of
+/// each participant sharing them, largest first. A sharer pays
+/// `1 / sharers` of every change to an item; unassigned items count as
+/// shared by everyone on the bill. Exempt participants share nothing.
+pub fn sensitivity(bill: &Bill) -> Vec<SensitivityEntry> {
+    let everyone = bill.liable_participant_ids().len().max(1);
+    let mut impacts: Vec<(Decimal, &_)> = bill
+        .line_items
+        .iter()
+        .map(|item| {
+            let sharers = match bill.item_sharers(item).len() {
+                0 => everyone,
+                n => n,
+            };
+            let impact = item.total().to_decimal() / Decimal::ONE_HUNDRED / Decimal::from(sharers);
+            (impact, item)
+        })
+        .collect();
+    impacts.sort_by_key(|(impact, _)| Reverse(*impact));
+
+    let average = if impacts.is_empty() {
+        Decimal::ZERO
+    } else {
+        impacts.iter().map(|(impact, _)| *impact).sum::<Decimal>() / Decimal::from(impacts.len())
+    };
+    impacts
+        .into_iter()
+        .map(|(impact, item)| SensitivityEntry {
+            line_item_id: item.id,
+            description: item.description.clone(),
+            impact_per_percent_change: Money::from_decimal(impact),
+            high_impact: impact > average * Decimal::TWO,
+        })
+        .collect()
+}
@@ -0,0 +1,209 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::models::{Bill, Money, Participant, Payment, SplitSnapshot};
+
+/// Where a participant stands once payments are set against their share.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Balance {
+    pub participant_id: Uuid,
+    pub name: String,
+    /// `paid - owed`; positive means the participant is owed money.
+    pub net_balance: Money,
+}
+
+/// A single transfer that settles part of the bill.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Settlement {
+    pub from: Uuid,
+    pub to: Uuid,
+    pub amount: Money,
+}
+
+/// Net balance of every participant against `snapshot`. Participants who
+/// joined after the snapshot owe nothing towards it.
+pub fn net_balances(
+    bill: &Bill,
+    snapshot: &SplitSnapshot,
+    participants: &[Participant],
+) -> Vec<Balance> {
+    participants
+        .iter()
+        .map(|participant| {
+            let owed = snapshot
+                .shares
+                .iter()
+                .find(|share| share.participant_id == participant.id)
+                .map_or(Money::ZERO, |share| share.amount_owed);
+            let paid: Money = bill
+                .payments_by(participant.id)
+                .map(Payment::base_amount)
+                .sum();
+            Balance {
+                participant_id: participant.id,
+                name: participant.name.clone(),
+                net_balance: paid - owed,
+            }
+        })
+        .collect()
+}
+
+/// Transfers that clear the balances with as few payments as practical: the
+/// largest debtor repeatedly pays the largest creditor. Ties are broken by
+/// participant id so the result does not depend on input order.
+///
+/// When balances do not sum to zero (the bill is not fully paid yet) only the
+/// side that can be matched is settled.
+pub fn minimise_settlements(balances: &[Balance]) -> Vec<Settlement> {
+    let mut creditors: Vec<(Uuid, Money)> = balances
+        .iter()
+        .filter(|balance| balance.net_balance > Money::ZERO)
+        .map(|balance| (balance.participant_id, balance.net_balance))
+        .collect();
+    let mut debtors: Vec<(Uuid, Money)> = balances
+        .iter()
+        .filter(|balance| balance.net_balance.is_negative())
+        .map(|balance| (balance.participant_id, balance.net_balance.abs()))
+        .collect();
+    let largest_first = |a: &(Uuid, Money), b: &(Uuid, Money)| b.1.cmp(&a.1).then(a.0.cmp(&b.0));
+    creditors.sort_by(largest_first);
+    debtors.sort_by(largest_first);
+
+    let mut settlements = Vec::new();
+    let (mut creditor, mut debtor) = (0, 0);
+    while creditor < creditors.len() && debtor < debtors.len() {
+        let amount = creditors[creditor].1.min(debtors[debtor].1);
+        settlements.push(Settlement {
+            from: debtors[debtor].0,
+            to: creditors[creditor].0,
+            amount,
+        });
+        creditors[creditor].1 -= amount;
+        debtors[debtor].1 -= amount;
+        if creditors[creditor].1.is_zero() {
+            creditor += 1;
+        }
+        if debtors[debtor].1.is_zero() {
+            debtor += 1;
+        }
+    }
+    settlements
+}
+
+/// Transfers that clear the balances without any consolidation: every
+/// debtor pays every creditor in proportion to what each is owed. Cents
+/// lost to rounding are handed out so that everyone still pays, or
+/// receives, exactly their balance. Like [`minimise_settlements`], only the
+/// side that can be matched is settled.
+pub fn naive_settlements(balances: &[Balance]) -> Vec<Settlement> {
+    let mut creditors: Vec<(Uuid, i64)> = balances
+        .iter()
+        .filter(|balance| balance.net_balance > Money::ZERO)
+        .map(|balance| (balance.participant_id, balance.net_balance.cents()))
+        .collect();
+    let mut debtors: Vec<(Uuid, i64)> = balances
+        .iter()
+        .filter(|balance| balance.net_balance.is_negative())
+        .map(|balance| (balance.participant_id, balance.net_balance.abs().cents()))
+        .collect();
+    creditors.sort();
+    debtors.sort();
+
+    let matched = matched_side(&mut creditors, &mut debtors);
+    if matched == 0 {
+        return Vec::new();
+    }
+
+    // Whole cents of each debtor's proportional payment to each creditor,
+    // then whatever rounding left over on both sides.
+    let mut amounts: Vec<Vec<i64>> = debtors
+        .iter()
+        .map(|(_, debt)| {
+            creditors
+                .iter()
+                .map(|(_, credit)| {
+                    (i128::from(*debt) * i128::from(*credit) / i128::from(matched)) as i64
+                })
+                .collect()
+        })
+        .collect();
+    let mut debt_left: Vec<i64> = debtors
+        .iter()
+        .zip(&amounts)
+        .map(|((_, debt), row)| debt - row.iter().sum::<i64>())
+        .collect();
+    let mut credit_left: Vec<i64> = creditors
+        .iter()
+        .enumerate()
+        .map(|(c, (_, credit))| credit - amounts.iter().map(|row| row[c]).sum::<i64>())
+        .collect();
+    for (d, row) in amounts.iter_mut().enumerate() {
+        for (c, amount) in row.iter_mut().enumerate() {
+            let extra = debt_left[d].min(credit_left[c]);
+            *amount += extra;
+            debt_left[d] -= extra;
+            credit_left[c] -= extra;
+        }
+    }
+
+    let mut settlements = Vec::new();
+    for ((debtor, _), row) in debtors.iter().zip(&amounts) {
+        for ((creditor, _), amount) in creditors.iter().zip(row) {
+            if *amount > 0 {
+                settlements.push(Settlement {
+                    from: *debtor,
+                    to: *creditor,
+                    amount: Money::from_cents(*amount),
+                });
+            }
+        }
+    }
+    settlements
+}
+
+/// Scales down whichever side is larger so both add up to the smaller
+/// total, which is returned. The first entry takes the rounding remainder.
+fn matched_side(creditors: &mut [(Uuid, i64)], debtors: &mut [(Uuid, i64)]) -> i64 {
+    let total_credit: i64 = creditors.iter().map(|(_, amount)| amount).sum();
+    let total_debt: i64 = debtors.iter().map(|(_, amount)| amount).sum();
+    let matched = total_credit.min(total_debt);
+    let (side, total) = if total_credit > matched {
+        (creditors, total_credit)
+    } else {
+        (debtors, total_debt)
+    };
+    if matched > 0 && total > matched {
+        for (_, amount) in side.iter_mut() {
+            *amount = (i128::from(*amount) * i128::from(matched) / i128::from(total)) as i64;
+        }
+        let scaled: i64 = side.iter().map(|(_, amount)| amount).sum();
+        side[0].1 += matched - scaled;
+    }
+    matched
+}
+
+/// How many transfers [`minimise_settlements`] saves over
+/// [`naive_settlements`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SettlementSavings {
+    pub naive_transfer_count: usize,
+    pub naive_total_amount: Money,
+    pub minimised_transfer_count: usize,
+    pub minimised_total_amount: Money,
+    pub transfers_saved: usize,
+}
+
+impl SettlementSavings {
+    pub fn new(naive: &[Settlement], minimised: &[Settlement]) -> Self {
+        let total = |settlements: &[Settlement]| -> Money {
+            settlements.iter().map(|settlement| settlement.amount).sum()
+        };
+        Self {
+            naive_transfer_count: naive.len(),
+            naive_total_amount: total(naive),
+            minimised_transfer_count: minimised.len(),
+            minimised_total_amount: total(minimised),
+            transfers_saved: naive.len().saturating_sub(minimised.len()),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::models::{Money, ParticipantShare};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ShareDelta {
+    pub participant_id: Uuid,
+    pub name: String,
+    /// `simulated - original`; negative when the participant would pay less.
+    pub delta: Money,
+}
+
+/// Side-by-side comparison of a split before and after a hypothetical change.
+#[derive(Debug, Clone, Serialize)]
+pub struct SplitDiff {
+    pub original_shares: Vec<ParticipantShare>,
+    pub simulated_shares: Vec<ParticipantShare>,
+    pub delta_per_participant: Vec<ShareDelta>,
+}
+
+impl SplitDiff {
+    pub fn new(original: Vec<ParticipantShare>, simulated: Vec<ParticipantShare>) -> Self {
+        let delta_per_participant = original
+            .iter()
+            .map(|before| {
+                let after = simulated
+                    .iter()
+                    .find(|share| share.participant_id == before.participant_id)
+                    .map_or(Money::ZERO, |share| share.amount_owed);
+                ShareDelta {
+                    participant_id: before.participant_id,
+                    name: before.name.clone(),
+                    delta: after - before.amount_owed,
+                }
+            })
+            .collect();
+
+        Self {
+            original_shares: original,
+            simulated_shares: simulated,
+            delta_per_participant,
+        }
+    }
+}
+
+/// The split before and after one line item's unit price changes.
+#[derive(Debug, Clone, Serialize)]
+pub struct WhatIfPriceResult {
+    pub item_id: Uuid,
+    pub original_price: Money,
+    pub hypothetical_price: Money,
+    pub original_shares: Vec<ParticipantShare>,
+    pub hypothetical_shares: Vec<ParticipantShare>,
+    pub delta_per_participant: Vec<ShareDelta>,
+}
+
+impl WhatIfPriceResult {
+    pub fn new(
+        item_id: Uuid,
+        original_price: Money,
+        hypothetical_price: Money,
+        original: Vec<ParticipantShare>,
+        hypothetical: Vec<ParticipantShare>,
+    ) -> Self {
+        let diff = SplitDiff::new(original, hypothetical);
+        Self {
+            item_id,
+            original_price,
+            hypothetical_price,
+            original_shares: diff.original_shares,
+            hypothetical_shares: diff.simulated_shares,
+            delta_per_participant: diff.delta_per_participant,
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::{distribute_rounding_remainder, SplitMethod, SplitResult};
+use crate::{
+    models::{Bill, Money, ParticipantShare},
+    tax::summarise_taxes,
+};
+
+/// One participant's share with the tax in it separated out.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaxExclusiveShare {
+    pub participant_id: Uuid,
+    pub pre_tax_amount: Money,
+    pub tax_amount: Money,
+    /// The share itself, as in the ordinary split.
+    pub total_amount: Money,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaxExclusiveSplitResult {
+    pub shares: Vec<TaxExclusiveShare>,
+    pub total_pre_tax: Money,
+    pub total_tax: Money,
+}
+
+/// Splits the tax in each share of `result` out from the rest. Itemised
+/// shares carry the tax of the items in them; other methods spread the
+/// bill's tax in proportion to each share. Tax amounts add up to the bill's
+/// total tax, with leftover cents settled the same way as shares are.
+pub fn split_tax_exclusive(bill: &Bill, result: &SplitResult) -> TaxExclusiveSplitResult {
+    let total_tax = summarise_taxes(bill).total_tax;
+    let exact = if result.method == SplitMethod::Itemised {
+        itemised_tax(bill, result)
+    } else {
+        proportional_tax(total_tax, result)
+    };
+
+    let taxes: Vec<ParticipantShare> = result
+        .shares
+        .iter()
+        .zip(exact)
+        .map(|(share, tax)| ParticipantShare {
+            amount_owed: Money::from_decimal_floor(tax),
+            ..share.clone()
+        })
+        .collect();
+    let taxes = distribute_rounding_remainder(taxes, total_tax);
+
+    let shares = result
+        .shares
+        .iter()
+        .zip(taxes)
+        .map(|(share, tax)| TaxExclusiveShare {
+            participant_id: share.participant_id,
+            pre_tax_amount: share.amount_owed - tax.amount_owed,
+            tax_amount: tax.amount_owed,
+            total_amount: share.amount_owed,
+        })
+        .collect();
+
+    TaxExclusiveSplitResult {
+        shares,
+        total_pre_tax: result.total - total_tax,
+        total_tax,
+    }
+}
+
+/// The tax in each participant's items, after the discount is spread over
+/// them as in the itemised split. Exempt participants' items are taxed to
+/// whoever pays for them instead.
+fn itemised_tax(bill: &Bill, result: &SplitResult) -> Vec<Decimal> {
+    let subtotal = bill.subtotal().to_decimal();
+    let scale = if subtotal.is_zero() {
+        Decimal::ONE
+    } else {
+        bill.total().to_decimal() / subtotal
+    };
+    let sharers: Vec<Vec<Uuid>> = bill
+        .line_items
+        .iter()
+        .map(|item| bill.item_sharers(item))
+        .collect();
+
+    result
+        .shares
+        .iter()
+        .map(|share| {
+            bill.line_items
+                .iter()
+                .zip(&sharers)
+                .filter(|(_, sharers)| sharers.contains(&share.participant_id))
+                .filter_map(|(item, sharers)| {
+                    let rate = item.tax_rate?;
+                    let gross = item.total().to_decimal() * scale;
+                    let tax = gross - gross / (Decimal::ONE + rate);
+                    Some(tax / Decimal::from(sharers.len()))
+                })
+                .sum()
+        })
+        .collect()
+}
+
+fn proportional_tax(total_tax: Money, result: &SplitResult) -> Vec<Decimal> {
+    let total = result.total.to_decimal();
+    result
+        .shares
+        .iter()
+        .map(|share| {
+            if total.is_zero() {
+                Decimal::ZERO
+            } else {
+                total_tax.to_decimal() * share.amount_owed.to_decimal() / total
+            }
+        })
+        .collect()
+}
+
+/// One participant's share as the tax-inclusive amount they pay, and how
+/// much of it is net of tax.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaxInclusiveShare {
+    pub participant_id: Uuid,
+    /// The share itself, tax included, as in the ordinary split.
+    pub gross_amount: Money,
+    /// `gross_amount / (1 + tax_rate)` for the items in the share.
+    pub net_amount: Money,
+    pub tax_component: Money,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaxInclusiveSplitResult {
+    pub shares: Vec<TaxInclusiveShare>,
+    pub total_gross: Money,
+    pub total_net: Money,
+    pub total_tax: Money,
+}
+
+/// [`split_tax_exclusive`] presented for prices shown with VAT or GST
+/// included: each share is a gross amount with its net and tax parts.
+pub fn split_tax_inclusive(bill: &Bill, result: &SplitResult) -> TaxInclusiveSplitResult {
+    let exclusive = split_tax_exclusive(bill, result);
+    TaxInclusiveSplitResult {
+        shares: exclusive
+            .shares
+            .into_iter()
+            .map(|share| TaxInclusiveShare {
+                participant_id: share.participant_id,
+                gross_amount: share.total_amount,
+                net_amount: share.pre_tax_amount,
+                tax_component: share.tax_amount,
+            })
+            .collect(),
+        total_gross: result.total,
+        total_net: exclusive.total_pre_tax,
+        total_tax: exclusive.total_tax,
+    }
+}
@@ -0,0 +1,58 @@
+use actix_web::HttpRequest;
+
+use crate::{
+    auth::authenticate,
+    config::Config,
+    queues::{NotificationQueue, SplitQueue},
+    resilience::{
+        circuit_breaker::{DEFAULT_FAILURE_THRESHOLD, DEFAULT_OPEN_DURATION},
+        CircuitBreaker,
+    },
+    storage::{KvRepository, KvStore},
+};
+
+/// Shared application state, registered once with `web::Data`.
+pub struct AppState {
+    pub config: Config,
+    pub kv: KvStore,
+    /// Guards calls to the Cloudflare Workers AI API.
+    pub ai_breaker: CircuitBreaker,
+    /// Notifications waiting for the background consumer.
+    pub notification_queue: NotificationQueue,
+    /// Splits waiting to be computed in the background.
+    pub split_queue: SplitQueue,
+}
+
+impl AppState {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            kv: KvStore::new(),
+            ai_breaker: CircuitBreaker::new(
+                "cloudflare_ai",
+                DEFAULT_FAILURE_THRESHOLD,
+                DEFAULT_OPEN_DURATION,
+            ),
+            notification_queue: NotificationQueue::new(),
+            split_queue: SplitQueue::new(),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(Config::from_env())
+    }
+
+    pub fn repo(&self) -> KvRepository<'_> {
+        KvRepository::new(&self.kv)
+    }
+
+    /// A repository whose writes are attributed to the caller in bill
+    /// changelogs. A missing or invalid token just leaves them anonymous.
+    pub fn repo_for(&self, req: &HttpRequest) -> KvRepository<'_> {
+        let actor = authenticate(req, &self.config)
+            .ok()
+            .flatten()
+            .map(|user| user.display_name());
+        self.repo().acting_as(actor)
+    }
+}
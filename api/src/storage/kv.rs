@@ -0,0 +1,182 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    future::Future,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+#[derive(Debug)]
+pub enum KvError {
+    Serialization(serde_json::Error),
+    /// The store could not serve the request right now; safe to retry.
+    Unavailable(String),
+}
+
+impl KvError {
+    /// Whether retrying the same operation may succeed.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, KvError::Unavailable(_))
+    }
+}
+
+impl fmt::Display for KvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KvError::Serialization(err) => write!(f, "KV value could not be (de)serialised: {err}"),
+            KvError::Unavailable(reason) => write!(f, "KV temporarily unavailable: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for KvError {}
+
+impl From<serde_json::Error> for KvError {
+    fn from(err: serde_json::Error) -> Self {
+        KvError::Serialization(err)
+    }
+}
+
+#[derive(Clone)]
+struct Entry {
+    value: String,
+    expires_at: Option<Instant>,
+}
+
+impl Entry {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+}
+
+tokio::task_local! {
+    /// What each key held before the enclosing [`KvStore::journaled`] call
+    /// first wrote to it.
+    static JOURNAL: RefCell<HashMap<String, Option<Entry>>>;
+}
+
+/// The writes made while running a future, so they can be undone with
+/// [`KvStore::roll_back`].
+#[derive(Default)]
+pub struct Journal(HashMap<String, Option<Entry>>);
+
+impl Journal {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// In-process key/value store with the same surface as Cloudflare KV:
+/// string values, optional per-key TTL and prefix listing.
+#[derive(Default)]
+pub struct KvStore {
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl KvStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Option<String>, KvError> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some(entry) if entry.is_expired(Instant::now()) => {
+                entries.remove(key);
+                Ok(None)
+            }
+            Some(entry) => Ok(Some(entry.value.clone())),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn put(
+        &self,
+        key: &str,
+        value: String,
+        ttl: Option<Duration>,
+    ) -> Result<(), KvError> {
+        let entry = Entry {
+            value,
+            expires_at: ttl.map(|ttl| Instant::now() + ttl),
+        };
+        let mut entries = self.entries.lock().unwrap();
+        record(&entries, key);
+        entries.insert(key.to_string(), entry);
+        Ok(())
+    }
+
+    pub async fn delete(&self, key: &str) -> Result<(), KvError> {
+        let mut entries = self.entries.lock().unwrap();
+        record(&entries, key);
+        entries.remove(key);
+        Ok(())
+    }
+
+    /// Runs `future`, journaling every write it makes on the current task.
+    /// Writes from tasks it spawns are not journaled.
+    pub async fn journaled<F: Future>(future: F) -> (F::Output, Journal) {
+        JOURNAL
+            .scope(RefCell::new(HashMap::new()), async {
+                let output = future.await;
+                (output, Journal(JOURNAL.with(RefCell::take)))
+            })
+            .await
+    }
+
+    /// Puts every key in `journal` back as it was before it was written.
+    /// Writes others made to those keys since are lost.
+    pub fn roll_back(&self, journal: Journal) {
+        let mut entries = self.entries.lock().unwrap();
+        for (key, previous) in journal.0 {
+            match previous {
+                Some(entry) => entries.insert(key, entry),
+                None => entries.remove(&key),
+            };
+        }
+    }
+
+    /// Returns every live key starting with `prefix`, sorted lexicographically.
+    pub async fn list(&self, prefix: &str) -> Result<Vec<String>, KvError> {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, entry| !entry.is_expired(now));
+
+        let mut keys: Vec<String> = entries
+            .keys()
+            .filter(|key| key.starts_with(prefix))
+            .cloned()
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
+
+    pub async fn get_json<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, KvError> {
+        match self.get(key).await? {
+            Some(value) => Ok(Some(serde_json::from_str(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn put_json<T: Serialize>(
+        &self,
+        key: &str,
+        value: &T,
+        ttl: Option<Duration>,
+    ) -> Result<(), KvError> {
+        self.put(key, serde_json::to_string(value)?, ttl).await
+    }
+}
+
+/// Notes what `key` held before its first write under [`KvStore::journaled`].
+fn record(entries: &HashMap<String, Entry>, key: &str) {
+    let _ = JOURNAL.try_with(|journal| {
+        journal
+            .borrow_mut()
+            .entry(key.to_string())
+            .or_insert_with(|| entries.get(key).cloned());
+    });
+}
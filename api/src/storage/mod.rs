@@ -0,0 +1,7 @@
+mod kv;
+mod repository;
+mod retry;
+
+pub use kv::{Journal, KvError, KvStore};
+pub use repository::{KvRepository, KvStats, Page};
+pub use retry::with_retry;
@@ -0,0 +1,719 @@
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    time::Duration,
+};
+
+use chrono::Utc;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::{
+    retry::{with_retry, DEFAULT_BASE_DELAY_MS, DEFAULT_MAX_ATTEMPTS},
+    KvError, KvStore,
+};
+use crate::{
+    analytics::stats::ParticipantStats,
+    changelog::{describe_changes, ChangelogEntry},
+    models::{AuditEntry, Bill, BillTag, Participant, PayerChange},
+    notifications::{NotificationStatus, ScheduledReminder},
+    queues::{DeadLetter, SplitJobStatus},
+};
+
+const BILL_KEY_PREFIX: &str = "bill:";
+const PARTICIPANT_KEY_PREFIX: &str = "participant:";
+const GROUP_KEY_PREFIX: &str = "group:";
+const SHARE_LINK_KEY_PREFIX: &str = "share:";
+const RECEIPT_PDF_KEY_PREFIX: &str = "receipt-pdf:";
+const TAG_KEY_PREFIX: &str = "tag:";
+const TAG_NAME_KEY_PREFIX: &str = "tag-name:";
+const CHANGELOG_KEY_PREFIX: &str = "changelog:";
+const NOTIFICATIONS_KEY_PREFIX: &str = "notifications:";
+const NOTIFICATION_MESSAGE_KEY_PREFIX: &str = "notification-message:";
+const PARTICIPANT_STATS_KEY_PREFIX: &str = "participant-stats:";
+const DEAD_LETTER_KEY_PREFIX: &str = "dead-letter:";
+const DELETED_BILL_KEY_PREFIX: &str = "deleted-bill:";
+const AUDIT_KEY_PREFIX: &str = "audit:";
+const PARTICIPANT_BILLS_KEY_PREFIX: &str = "participant_bills:";
+const SPLIT_JOB_KEY_PREFIX: &str = "split-job:";
+const REMINDERS_KEY_PREFIX: &str = "reminders:";
+const PARTICIPANT_NAME_KEY_PREFIX: &str = "participants-by-name:";
+
+/// Cached PDFs are dropped when their bill changes; the TTL only bounds how
+/// long unused ones linger.
+const RECEIPT_PDF_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Stats are not invalidated when bills change, so they may be this stale.
+const PARTICIPANT_STATS_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// How long soft-deleted bills can still be recovered from KV.
+const DELETED_BILL_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// How long a split job's status and result can be polled for.
+const SPLIT_JOB_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Values read per prefix when estimating storage size.
+const STATS_SAMPLE_SIZE: usize = 50;
+
+fn bill_key(id: Uuid) -> String {
+    format!("{BILL_KEY_PREFIX}{id}")
+}
+
+fn deleted_bill_key(id: Uuid) -> String {
+    format!("{DELETED_BILL_KEY_PREFIX}{id}")
+}
+
+/// Ids of the bills a participant is on, kept up to date by
+/// [`KvRepository::put_bill`].
+fn participant_bills_key(participant_id: Uuid) -> String {
+    format!("{PARTICIPANT_BILLS_KEY_PREFIX}{participant_id}")
+}
+
+fn split_job_key(job_id: Uuid) -> String {
+    format!("{SPLIT_JOB_KEY_PREFIX}{job_id}")
+}
+
+/// `{bill_id}:{participant_id}`, so the reminder job can tell whose they are
+/// from the key alone.
+fn reminders_key(bill_id: Uuid, participant_id: Uuid) -> String {
+    format!("{REMINDERS_KEY_PREFIX}{bill_id}:{participant_id}")
+}
+
+fn audit_key(bill_id: Uuid) -> String {
+    format!("{AUDIT_KEY_PREFIX}{bill_id}")
+}
+
+fn participant_key(id: Uuid) -> String {
+    format!("{PARTICIPANT_KEY_PREFIX}{id}")
+}
+
+/// Ids of the participants called `name`, ignoring case and surrounding
+/// whitespace, kept up to date by [`KvRepository::put_participant`].
+fn participant_name_key(name: &str) -> String {
+    format!(
+        "{PARTICIPANT_NAME_KEY_PREFIX}{}",
+        name.trim().to_lowercase()
+    )
+}
+
+fn changelog_key(bill_id: Uuid) -> String {
+    format!("{CHANGELOG_KEY_PREFIX}{bill_id}")
+}
+
+fn dead_letter_key(task_id: Uuid) -> String {
+    format!("{DEAD_LETTER_KEY_PREFIX}{task_id}")
+}
+
+fn participant_stats_key(participant_id: Uuid) -> String {
+    format!("{PARTICIPANT_STATS_KEY_PREFIX}{participant_id}")
+}
+
+fn notifications_key(bill_id: Uuid) -> String {
+    format!("{NOTIFICATIONS_KEY_PREFIX}{bill_id}")
+}
+
+/// Maps a provider's message id to the bill it was sent for.
+fn notification_message_key(message_id: &str) -> String {
+    format!("{NOTIFICATION_MESSAGE_KEY_PREFIX}{message_id}")
+}
+
+fn tag_key(id: Uuid) -> String {
+    format!("{TAG_KEY_PREFIX}{id}")
+}
+
+fn tag_name_key(name: &str) -> String {
+    format!("{TAG_NAME_KEY_PREFIX}{name}")
+}
+
+/// Ids of the bills carrying the tag called `name`.
+fn tagged_bills_key(name: &str) -> String {
+    format!("tags:{name}:bills")
+}
+
+fn receipt_pdf_prefix(bill_id: Uuid) -> String {
+    format!("{RECEIPT_PDF_KEY_PREFIX}{bill_id}-")
+}
+
+/// `{bill_id}-{bill_version}-{latest_split}`, plus the participant the
+/// receipt is for. The ETag leaves splits out, so the latest one is named
+/// separately.
+fn receipt_pdf_key(bill: &Bill, participant_id: Uuid) -> String {
+    let latest_split = bill
+        .latest_split()
+        .map_or_else(String::new, |snapshot| snapshot.id.to_string());
+    format!(
+        "{}{}-{latest_split}-{participant_id}.pdf",
+        receipt_pdf_prefix(bill.id),
+        bill.etag()
+    )
+}
+
+/// Runs a KV operation with the default retry policy.
+async fn retry<F, Fut, T>(op: F) -> Result<T, KvError>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, KvError>>,
+{
+    with_retry(op, DEFAULT_MAX_ATTEMPTS, DEFAULT_BASE_DELAY_MS).await
+}
+
+/// One page of a keyset-paginated listing.
+#[derive(Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Pass back as the cursor to fetch the following page. `None` on the
+    /// last page.
+    pub next_cursor: Option<Uuid>,
+}
+
+/// Key counts and an estimate of how much KV holds, for debugging.
+#[derive(Debug, Clone, Serialize)]
+pub struct KvStats {
+    pub total_keys: u32,
+    pub bills: u32,
+    pub participants: u32,
+    pub groups: u32,
+    pub share_links: u32,
+    /// Average size of up to 50 sampled values per prefix, times the key
+    /// count. Keys are included in the size.
+    pub estimated_storage_bytes: u64,
+}
+
+/// Typed access to the records kept in KV.
+pub struct KvRepository<'a> {
+    kv: &'a KvStore,
+    /// Named as the author of changelog entries written by [`Self::put_bill`].
+    actor: Option<String>,
+}
+
+impl<'a> KvRepository<'a> {
+    pub fn new(kv: &'a KvStore) -> Self {
+        Self { kv, actor: None }
+    }
+
+    pub fn acting_as(mut self, actor: Option<String>) -> Self {
+        self.actor = actor;
+        self
+    }
+
+    pub async fn stats(&self) -> Result<KvStats, KvError> {
+        let total_keys = retry(|| self.kv.list("")).await?.len();
+        let mut counts = [0u32; 4];
+        let mut estimated_storage_bytes = 0u64;
+        let prefixes = [
+            BILL_KEY_PREFIX,
+            PARTICIPANT_KEY_PREFIX,
+            GROUP_KEY_PREFIX,
+            SHARE_LINK_KEY_PREFIX,
+        ];
+        for (count, prefix) in counts.iter_mut().zip(prefixes) {
+            let keys = retry(|| self.kv.list(prefix)).await?;
+            *count = keys.len() as u32;
+
+            let mut sampled = 0u64;
+            let mut sampled_bytes = 0u64;
+            for key in keys.iter().take(STATS_SAMPLE_SIZE) {
+                if let Some(value) = retry(|| self.kv.get(key)).await? {
+                    sampled += 1;
+                    sampled_bytes += (key.len() + value.len()) as u64;
+                }
+            }
+            if let Some(average) = sampled_bytes.checked_div(sampled) {
+                estimated_storage_bytes += average * keys.len() as u64;
+            }
+        }
+
+        let [bills, participants, groups, share_links] = counts;
+        Ok(KvStats {
+            total_keys: total_keys as u32,
+            bills,
+            participants,
+            groups,
+            share_links,
+            estimated_storage_bytes,
+        })
+    }
+
+    pub async fn get_bill(&self, id: Uuid) -> Result<Option<Bill>, KvError> {
+        let key = bill_key(id);
+        retry(|| self.kv.get_json(&key)).await
+    }
+
+    /// Saves `bill`, logs what changed since the stored version, updates the
+    /// participant indexes and drops the PDF receipts rendered from earlier
+    /// versions.
+    pub async fn put_bill(&self, bill: &Bill) -> Result<(), KvError> {
+        let previous = self.get_bill(bill.id).await?;
+        let key = bill_key(bill.id);
+        retry(|| self.kv.put_json(&key, bill, None)).await?;
+        self.record_changes(previous.as_ref(), bill).await?;
+        let previous_payer_id = previous.as_ref().and_then(|previous| previous.payer_id);
+        if previous_payer_id != bill.payer_id {
+            let change = PayerChange {
+                previous_payer_id,
+                new_payer_id: bill.payer_id,
+                changed_at: Utc::now(),
+                changed_by: self.actor.clone(),
+            };
+            self.append_audit_entry(&AuditEntry::payer_changed(bill.id, change))
+                .await?;
+        }
+
+        let before: HashSet<Uuid> = previous.iter().flat_map(Bill::participant_ids).collect();
+        let after: HashSet<Uuid> = bill.participant_ids().into_iter().collect();
+        for participant_id in after.difference(&before) {
+            self.index_participant_bill(*participant_id, bill.id)
+                .await?;
+        }
+        for participant_id in before.difference(&after) {
+            self.unindex_participant_bill(*participant_id, bill.id)
+                .await?;
+        }
+
+        let prefix = receipt_pdf_prefix(bill.id);
+        for key in retry(|| self.kv.list(&prefix)).await? {
+            retry(|| self.kv.delete(&key)).await?;
+        }
+        Ok(())
+    }
+
+    /// Moves `bill` out of the way of every lookup and listing. It is kept
+    /// under its own key, readable with [`Self::get_deleted_bill`], until
+    /// the TTL runs out.
+    pub async fn soft_delete_bill(&self, bill: &Bill) -> Result<(), KvError> {
+        let deleted_key = deleted_bill_key(bill.id);
+        retry(|| self.kv.put_json(&deleted_key, bill, Some(DELETED_BILL_TTL))).await?;
+        let key = bill_key(bill.id);
+        retry(|| self.kv.delete(&key)).await?;
+        for participant_id in bill.participant_ids() {
+            self.unindex_participant_bill(participant_id, bill.id)
+                .await?;
+        }
+
+        let prefix = receipt_pdf_prefix(bill.id);
+        for key in retry(|| self.kv.list(&prefix)).await? {
+            retry(|| self.kv.delete(&key)).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn get_deleted_bill(&self, id: Uuid) -> Result<Option<Bill>, KvError> {
+        let key = deleted_bill_key(id);
+        retry(|| self.kv.get_json(&key)).await
+    }
+
+    async fn record_changes(&self, previous: Option<&Bill>, bill: &Bill) -> Result<(), KvError> {
+        let mut names = HashMap::new();
+        for participant_id in previous
+            .into_iter()
+            .flat_map(Bill::participant_ids)
+            .chain(bill.participant_ids())
+        {
+            if let Entry::Vacant(entry) = names.entry(participant_id) {
+                if let Some(participant) = self.get_participant(participant_id).await? {
+                    entry.insert(participant.name);
+                }
+            }
+        }
+
+        let changes = describe_changes(previous, bill, &names);
+        if changes.is_empty() {
+            return Ok(());
+        }
+        let mut changelog = self.get_changelog(bill.id).await?;
+        changelog.push(ChangelogEntry {
+            timestamp: Utc::now(),
+            actor_name: self.actor.clone(),
+            changes,
+        });
+        let key = changelog_key(bill.id);
+        retry(|| self.kv.put_json(&key, &changelog, None)).await
+    }
+
+    /// Every change saved to the bill, oldest first.
+    pub async fn get_changelog(&self, bill_id: Uuid) -> Result<Vec<ChangelogEntry>, KvError> {
+        let key = changelog_key(bill_id);
+        Ok(retry(|| self.kv.get_json(&key)).await?.unwrap_or_default())
+    }
+
+    /// Every audited action taken on the bill, oldest first.
+    pub async fn get_audit_log(&self, bill_id: Uuid) -> Result<Vec<AuditEntry>, KvError> {
+        let key = audit_key(bill_id);
+        Ok(retry(|| self.kv.get_json(&key)).await?.unwrap_or_default())
+    }
+
+    /// Every change of the bill's payer, oldest first. Empty if it has
+    /// never had one.
+    pub async fn get_payer_history(&self, bill_id: Uuid) -> Result<Vec<PayerChange>, KvError> {
+        Ok(self
+            .get_audit_log(bill_id)
+            .await?
+            .into_iter()
+            .filter_map(|entry| entry.payer_change)
+            .collect())
+    }
+
+    pub async fn append_audit_entry(&self, entry: &AuditEntry) -> Result<(), KvError> {
+        let mut log = self.get_audit_log(entry.bill_id).await?;
+        log.push(entry.clone());
+        let key = audit_key(entry.bill_id);
+        retry(|| self.kv.put_json(&key, &log, None)).await
+    }
+
+    /// Every notification sent for the bill, oldest first.
+    pub async fn get_notification_statuses(
+        &self,
+        bill_id: Uuid,
+    ) -> Result<Vec<NotificationStatus>, KvError> {
+        let key = notifications_key(bill_id);
+        Ok(retry(|| self.kv.get_json(&key)).await?.unwrap_or_default())
+    }
+
+    /// Replaces the bill's notification statuses, indexing any new provider
+    /// message ids so delivery webhooks can find the bill.
+    pub async fn put_notification_statuses(
+        &self,
+        bill_id: Uuid,
+        statuses: &[NotificationStatus],
+    ) -> Result<(), KvError> {
+        let key = notifications_key(bill_id);
+        retry(|| self.kv.put_json(&key, &statuses, None)).await?;
+        for message_id in statuses
+            .iter()
+            .filter_map(|status| status.provider_message_id.as_deref())
+        {
+            let message_key = notification_message_key(message_id);
+            retry(|| self.kv.put_json(&message_key, &bill_id, None)).await?;
+        }
+        Ok(())
+    }
+
+    /// Every notification task that gave up, oldest failure first.
+    pub async fn list_dead_letters(&self) -> Result<Vec<DeadLetter>, KvError> {
+        let keys = retry(|| self.kv.list(DEAD_LETTER_KEY_PREFIX)).await?;
+        let mut letters = Vec::with_capacity(keys.len());
+        for key in keys {
+            if let Some(letter) = retry(|| self.kv.get_json::<DeadLetter>(&key)).await? {
+                letters.push(letter);
+            }
+        }
+        letters.sort_by_key(|letter: &DeadLetter| letter.failed_at);
+        Ok(letters)
+    }
+
+    pub async fn get_dead_letter(&self, task_id: Uuid) -> Result<Option<DeadLetter>, KvError> {
+        let key = dead_letter_key(task_id);
+        retry(|| self.kv.get_json(&key)).await
+    }
+
+    pub async fn put_dead_letter(&self, letter: &DeadLetter) -> Result<(), KvError> {
+        let key = dead_letter_key(letter.task.id);
+        retry(|| self.kv.put_json(&key, letter, None)).await
+    }
+
+    pub async fn delete_dead_letter(&self, task_id: Uuid) -> Result<(), KvError> {
+        let key = dead_letter_key(task_id);
+        retry(|| self.kv.delete(&key)).await
+    }
+
+    pub async fn get_split_job(&self, job_id: Uuid) -> Result<Option<SplitJobStatus>, KvError> {
+        let key = split_job_key(job_id);
+        retry(|| self.kv.get_json(&key)).await
+    }
+
+    pub async fn put_split_job(&self, status: &SplitJobStatus) -> Result<(), KvError> {
+        let key = split_job_key(status.job_id);
+        retry(|| self.kv.put_json(&key, status, Some(SPLIT_JOB_TTL))).await
+    }
+
+    /// `participant_id`'s pending reminders on the bill, soonest first.
+    pub async fn get_reminders(
+        &self,
+        bill_id: Uuid,
+        participant_id: Uuid,
+    ) -> Result<Vec<ScheduledReminder>, KvError> {
+        let key = reminders_key(bill_id, participant_id);
+        Ok(retry(|| self.kv.get_json(&key)).await?.unwrap_or_default())
+    }
+
+    /// Replaces `participant_id`'s reminders on the bill; an empty list
+    /// removes the key.
+    pub async fn put_reminders(
+        &self,
+        bill_id: Uuid,
+        participant_id: Uuid,
+        reminders: &[ScheduledReminder],
+    ) -> Result<(), KvError> {
+        let key = reminders_key(bill_id, participant_id);
+        if reminders.is_empty() {
+            retry(|| self.kv.delete(&key)).await
+        } else {
+            retry(|| self.kv.put_json(&key, &reminders, None)).await
+        }
+    }
+
+    /// The bill and participant of every list of pending reminders.
+    pub async fn list_reminder_owners(&self) -> Result<Vec<(Uuid, Uuid)>, KvError> {
+        let keys = retry(|| self.kv.list(REMINDERS_KEY_PREFIX)).await?;
+        Ok(keys
+            .iter()
+            .filter_map(|key| {
+                let (bill_id, participant_id) =
+                    key.strip_prefix(REMINDERS_KEY_PREFIX)?.split_once(':')?;
+                Some((bill_id.parse().ok()?, participant_id.parse().ok()?))
+            })
+            .collect())
+    }
+
+    /// The bill a provider message was sent for.
+    pub async fn find_notification_bill(&self, message_id: &str) -> Result<Option<Uuid>, KvError> {
+        let key = notification_message_key(message_id);
+        retry(|| self.kv.get_json(&key)).await
+    }
+
+    /// The PDF receipt cached for this version of `bill`, if any.
+    pub async fn get_receipt_pdf(
+        &self,
+        bill: &Bill,
+        participant_id: Uuid,
+    ) -> Result<Option<Vec<u8>>, KvError> {
+        let key = receipt_pdf_key(bill, participant_id);
+        let Some(encoded) = retry(|| self.kv.get(&key)).await? else {
+            return Ok(None);
+        };
+        // A corrupt entry is treated as a miss and overwritten.
+        Ok(BASE64.decode(encoded).ok())
+    }
+
+    pub async fn put_receipt_pdf(
+        &self,
+        bill: &Bill,
+        participant_id: Uuid,
+        pdf: &[u8],
+    ) -> Result<(), KvError> {
+        let key = receipt_pdf_key(bill, participant_id);
+        let encoded = BASE64.encode(pdf);
+        retry(|| self.kv.put(&key, encoded.clone(), Some(RECEIPT_PDF_TTL))).await
+    }
+
+    pub async fn get_tag(&self, id: Uuid) -> Result<Option<BillTag>, KvError> {
+        let key = tag_key(id);
+        retry(|| self.kv.get_json(&key)).await
+    }
+
+    /// Looks a tag up by its normalised name.
+    pub async fn find_tag(&self, name: &str) -> Result<Option<BillTag>, KvError> {
+        let key = tag_name_key(name);
+        match retry(|| self.kv.get_json::<Uuid>(&key)).await? {
+            Some(id) => self.get_tag(id).await,
+            None => Ok(None),
+        }
+    }
+
+    pub async fn put_tag(&self, tag: &BillTag) -> Result<(), KvError> {
+        let key = tag_key(tag.id);
+        retry(|| self.kv.put_json(&key, tag, None)).await?;
+        let name_key = tag_name_key(&tag.name);
+        retry(|| self.kv.put_json(&name_key, &tag.id, None)).await
+    }
+
+    /// Ids of the bills tagged `name`, in the order they were tagged.
+    pub async fn tagged_bill_ids(&self, name: &str) -> Result<Vec<Uuid>, KvError> {
+        let key = tagged_bills_key(name);
+        Ok(retry(|| self.kv.get_json(&key)).await?.unwrap_or_default())
+    }
+
+    /// Adds `bill_id` to the `name` tag's index; a no-op if already there.
+    pub async fn index_tagged_bill(&self, name: &str, bill_id: Uuid) -> Result<(), KvError> {
+        let mut ids = self.tagged_bill_ids(name).await?;
+        if ids.contains(&bill_id) {
+            return Ok(());
+        }
+        ids.push(bill_id);
+        let key = tagged_bills_key(name);
+        retry(|| self.kv.put_json(&key, &ids, None)).await
+    }
+
+    pub async fn unindex_tagged_bill(&self, name: &str, bill_id: Uuid) -> Result<(), KvError> {
+        let mut ids = self.tagged_bill_ids(name).await?;
+        ids.retain(|id| *id != bill_id);
+        let key = tagged_bills_key(name);
+        retry(|| self.kv.put_json(&key, &ids, None)).await
+    }
+
+    /// Ids of the bills `participant_id` is on, in the order they joined.
+    pub async fn participant_bill_ids(&self, participant_id: Uuid) -> Result<Vec<Uuid>, KvError> {
+        let key = participant_bills_key(participant_id);
+        Ok(retry(|| self.kv.get_json(&key)).await?.unwrap_or_default())
+    }
+
+    async fn index_participant_bill(
+        &self,
+        participant_id: Uuid,
+        bill_id: Uuid,
+    ) -> Result<(), KvError> {
+        let mut ids = self.participant_bill_ids(participant_id).await?;
+        if ids.contains(&bill_id) {
+            return Ok(());
+        }
+        ids.push(bill_id);
+        let key = participant_bills_key(participant_id);
+        retry(|| self.kv.put_json(&key, &ids, None)).await
+    }
+
+    async fn unindex_participant_bill(
+        &self,
+        participant_id: Uuid,
+        bill_id: Uuid,
+    ) -> Result<(), KvError> {
+        let mut ids = self.participant_bill_ids(participant_id).await?;
+        ids.retain(|id| *id != bill_id);
+        let key = participant_bills_key(participant_id);
+        retry(|| self.kv.put_json(&key, &ids, None)).await
+    }
+
+    /// Lists bills matching `filter` in id order, starting after `cursor`.
+    pub async fn list_bills(
+        &self,
+        cursor: Option<Uuid>,
+        limit: usize,
+        filter: impl Fn(&Bill) -> bool,
+    ) -> Result<Page<Bill>, KvError> {
+        let after = cursor.map(bill_key);
+        let keys: Vec<String> = retry(|| self.kv.list(BILL_KEY_PREFIX))
+            .await?
+            .into_iter()
+            .filter(|key| after.as_ref().is_none_or(|after| key > after))
+            .collect();
+
+        let mut items = Vec::with_capacity(limit.min(keys.len()));
+        let mut scanned = 0;
+        for key in &keys {
+            if items.len() == limit {
+                break;
+            }
+            scanned += 1;
+            if let Some(bill) = retry(|| self.kv.get_json::<Bill>(key)).await? {
+                if filter(&bill) {
+                    items.push(bill);
+                }
+            }
+        }
+        let next_cursor = if scanned < keys.len() {
+            items.last().map(|bill| bill.id)
+        } else {
+            None
+        };
+        Ok(Page { items, next_cursor })
+    }
+
+    pub async fn get_participant(&self, id: Uuid) -> Result<Option<Participant>, KvError> {
+        let key = participant_key(id);
+        retry(|| self.kv.get_json(&key)).await
+    }
+
+    pub async fn put_participant(&self, participant: &Participant) -> Result<(), KvError> {
+        let key = participant_key(participant.id);
+        let previous: Option<Participant> = retry(|| self.kv.get_json(&key)).await?;
+        retry(|| self.kv.put_json(&key, participant, None)).await?;
+        if let Some(previous) = previous {
+            if participant_name_key(&previous.name) != participant_name_key(&participant.name) {
+                self.unindex_participant_name(&previous.name, participant.id)
+                    .await?;
+            }
+        }
+        self.index_participant_name(&participant.name, participant.id)
+            .await
+    }
+
+    /// Ids of the participants called `name`, ignoring case.
+    pub async fn participant_ids_named(&self, name: &str) -> Result<Vec<Uuid>, KvError> {
+        let key = participant_name_key(name);
+        Ok(retry(|| self.kv.get_json(&key)).await?.unwrap_or_default())
+    }
+
+    /// Every participant name in the index, lowercased.
+    pub async fn participant_names(&self) -> Result<Vec<String>, KvError> {
+        let keys = retry(|| self.kv.list(PARTICIPANT_NAME_KEY_PREFIX)).await?;
+        Ok(keys
+            .iter()
+            .filter_map(|key| key.strip_prefix(PARTICIPANT_NAME_KEY_PREFIX))
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Every participant, in id order.
+    pub async fn list_participants(&self) -> Result<Vec<Participant>, KvError> {
+        let keys = retry(|| self.kv.list(PARTICIPANT_KEY_PREFIX)).await?;
+        let mut participants = Vec::with_capacity(keys.len());
+        for key in &keys {
+            if let Some(participant) = retry(|| self.kv.get_json(key)).await? {
+                participants.push(participant);
+            }
+        }
+        Ok(participants)
+    }
+
+    async fn index_participant_name(&self, name: &str, id: Uuid) -> Result<(), KvError> {
+        let mut ids = self.participant_ids_named(name).await?;
+        if ids.contains(&id) {
+            return Ok(());
+        }
+        ids.push(id);
+        let key = participant_name_key(name);
+        retry(|| self.kv.put_json(&key, &ids, None)).await
+    }
+
+    async fn unindex_participant_name(&self, name: &str, id: Uuid) -> Result<(), KvError> {
+        let mut ids = self.participant_ids_named(name).await?;
+        ids.retain(|other| *other != id);
+        let key = participant_name_key(name);
+        if ids.is_empty() {
+            retry(|| self.kv.delete(&key)).await
+        } else {
+            retry(|| self.kv.put_json(&key, &ids, None)).await
+        }
+    }
+
+    /// Deletes the participant and what is kept about them. Bills still
+    /// referring to them are left as they are.
+    pub async fn delete_participant(&self, id: Uuid) -> Result<(), KvError> {
+        if let Some(participant) = self.get_participant(id).await? {
+            self.unindex_participant_name(&participant.name, id).await?;
+        }
+        for key in [
+            participant_key(id),
+            participant_stats_key(id),
+            participant_bills_key(id),
+        ] {
+            retry(|| self.kv.delete(&key)).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn get_participant_stats(
+        &self,
+        participant_id: Uuid,
+    ) -> Result<Option<ParticipantStats>, KvError> {
+        let key = participant_stats_key(participant_id);
+        retry(|| self.kv.get_json(&key)).await
+    }
+
+    pub async fn put_participant_stats(&self, stats: &ParticipantStats) -> Result<(), KvError> {
+        let key = participant_stats_key(stats.participant_id);
+        retry(|| self.kv.put_json(&key, stats, Some(PARTICIPANT_STATS_TTL))).await
+    }
+
+    /// Loads the participants of `bill` in the order they joined, skipping
+    /// any whose record no longer exists.
+    pub async fn get_bill_participants(&self, bill: &Bill) -> Result<Vec<Participant>, KvError> {
+        let mut participants = Vec::with_capacity(bill.participants.len());
+        for member in &bill.participants {
+            if let Some(participant) = self.get_participant(member.participant_id).await? {
+                participants.push(participant);
+            }
+        }
+        Ok(participants)
+    }
+}
@@ -0,0 +1,54 @@
+use std::{
+    collections::hash_map::RandomState,
+    future::Future,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+use super::KvError;
+
+/// Attempts per repository call, including the first.
+pub const DEFAULT_MAX_ATTEMPTS: u8 = 4;
+/// Delay before the first retry; doubled on each subsequent one.
+pub const DEFAULT_BASE_DELAY_MS: u64 = 50;
+/// Upper bound on the total time spent waiting between attempts.
+pub const MAX_TOTAL_WAIT: Duration = Duration::from_secs(10);
+
+/// Runs `op` until it succeeds, fails with a non-transient error, or
+/// `max_attempts` is reached. Retries back off exponentially from
+/// `base_delay_ms` with full jitter, and never wait more than
+/// [`MAX_TOTAL_WAIT`] in total.
+pub async fn with_retry<F, Fut, T>(
+    mut op: F,
+    max_attempts: u8,
+    base_delay_ms: u64,
+) -> Result<T, KvError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, KvError>>,
+{
+    let mut waited = Duration::ZERO;
+    let mut attempt: u8 = 1;
+    loop {
+        match op().await {
+            Err(err) if err.is_transient() && attempt < max_attempts => {
+                let remaining = MAX_TOTAL_WAIT.saturating_sub(waited);
+                if remaining.is_zero() {
+                    return Err(err);
+                }
+                let delay = backoff(attempt, base_delay_ms).min(remaining);
+                tokio::time::sleep(delay).await;
+                waited += delay;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Random delay in `[0, base * 2^(attempt - 1)]`.
+fn backoff(attempt: u8, base_delay_ms: u64) -> Duration {
+    let ceiling = base_delay_ms.saturating_mul(1 << (attempt - 1).min(16));
+    let jitter = RandomState::new().build_hasher().finish() % (ceiling + 1);
+    Duration::from_millis(jitter)
+}
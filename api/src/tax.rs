@@ -0,0 +1,74 @@
+//! Tax summaries. Line item prices are tax-inclusive; `tax_rate` says how
+//! much of each price is tax.
+
+use std::collections::BTreeMap;
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::models::{Bill, Money};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaxBand {
+    pub rate: Decimal,
+    pub taxable_amount: Money,
+    pub tax_amount: Money,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaxSummary {
+    pub total_pre_tax: Money,
+    pub total_tax: Money,
+    /// `total_tax / total_pre_tax`, to four decimal places.
+    pub effective_tax_rate: Decimal,
+    /// One band per distinct rate, lowest first. Untaxed items are left out.
+    pub breakdown: Vec<TaxBand>,
+}
+
+/// Groups the bill's line items into tax bands. Any bill-wide discount is
+/// spread over items in proportion to their price before tax is taken out.
+pub fn summarise_taxes(bill: &Bill) -> TaxSummary {
+    let total = bill.total();
+    let subtotal = bill.subtotal().to_decimal();
+    let scale = if subtotal.is_zero() {
+        Decimal::ONE
+    } else {
+        total.to_decimal() / subtotal
+    };
+
+    let mut gross_by_rate: BTreeMap<Decimal, Decimal> = BTreeMap::new();
+    for item in &bill.line_items {
+        if let Some(rate) = item.tax_rate {
+            *gross_by_rate.entry(rate.normalize()).or_default() +=
+                item.total().to_decimal() * scale;
+        }
+    }
+
+    let breakdown: Vec<TaxBand> = gross_by_rate
+        .into_iter()
+        .map(|(rate, gross)| {
+            let gross = Money::from_decimal(gross);
+            let taxable_amount = Money::from_decimal(gross.to_decimal() / (Decimal::ONE + rate));
+            TaxBand {
+                rate,
+                taxable_amount,
+                tax_amount: gross - taxable_amount,
+            }
+        })
+        .collect();
+
+    let total_tax: Money = breakdown.iter().map(|band| band.tax_amount).sum();
+    let total_pre_tax = total - total_tax;
+    let effective_tax_rate = if total_tax.is_zero() || total_pre_tax.is_zero() {
+        Decimal::new(0, 2)
+    } else {
+        (total_tax.to_decimal() / total_pre_tax.to_decimal()).round_dp(4)
+    };
+
+    TaxSummary {
+        total_pre_tax,
+        total_tax,
+        effective_tax_rate,
+        breakdown,
+    }
+}
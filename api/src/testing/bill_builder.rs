@@ -0,0 +1,166 @@
+//! Builds bills and their participants by name, so tests can describe a
+//! fixture in one chain instead of wiring ids together by hand.
+
+use std::fmt;
+
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::models::{Bill, LineItem, Money, Participant};
+
+/// Why [`BillBuilder::build`] could not build the bill.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BillBuilderError {
+    DuplicateParticipant(String),
+    /// A payer, creator or sharer who was not added with
+    /// [`BillBuilder::with_participant`].
+    UnknownParticipant(String),
+    /// A unit price that is not a finite number.
+    InvalidPrice {
+        description: String,
+        unit_price: f64,
+    },
+}
+
+impl fmt::Display for BillBuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BillBuilderError::DuplicateParticipant(name) => {
+                write!(f, "participant {name:?} was added twice")
+            }
+            BillBuilderError::UnknownParticipant(name) => {
+                write!(f, "no participant called {name:?}")
+            }
+            BillBuilderError::InvalidPrice {
+                description,
+                unit_price,
+            } => write!(f, "{description:?} has an invalid unit price {unit_price}"),
+        }
+    }
+}
+
+impl std::error::Error for BillBuilderError {}
+
+struct ItemSpec {
+    description: String,
+    quantity: u32,
+    unit_price: f64,
+    /// Names of the participants sharing the item; empty leaves it
+    /// unassigned.
+    shared_by: Vec<String>,
+}
+
+/// A bill described by participant names, checked when it is built.
+pub struct BillBuilder {
+    title: String,
+    participants: Vec<Participant>,
+    items: Vec<ItemSpec>,
+    payer: Option<String>,
+    creator: Option<String>,
+}
+
+impl BillBuilder {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            participants: Vec::new(),
+            items: Vec::new(),
+            payer: None,
+            creator: None,
+        }
+    }
+
+    /// Adds a participant; they are returned from [`build`](Self::build) in
+    /// the order they were added.
+    pub fn with_participant(mut self, name: impl Into<String>) -> Self {
+        self.participants.push(Participant::new(name, None));
+        self
+    }
+
+    /// Adds a participant who already exists, keeping their id, so one
+    /// person can be shared between several built bills.
+    pub fn with_existing_participant(mut self, participant: &Participant) -> Self {
+        self.participants.push(participant.clone());
+        self
+    }
+
+    /// Adds an unassigned line item, shared by everyone.
+    pub fn with_item(self, description: impl Into<String>, quantity: u32, unit_price: f64) -> Self {
+        self.with_item_shared_by(description, quantity, unit_price, &[])
+    }
+
+    /// Adds a line item assigned to the named participants.
+    pub fn with_item_shared_by(
+        mut self,
+        description: impl Into<String>,
+        quantity: u32,
+        unit_price: f64,
+        shared_by: &[&str],
+    ) -> Self {
+        self.items.push(ItemSpec {
+            description: description.into(),
+            quantity,
+            unit_price,
+            shared_by: shared_by.iter().map(|name| name.to_string()).collect(),
+        });
+        self
+    }
+
+    pub fn with_payer(mut self, name: impl Into<String>) -> Self {
+        self.payer = Some(name.into());
+        self
+    }
+
+    pub fn with_creator(mut self, name: impl Into<String>) -> Self {
+        self.creator = Some(name.into());
+        self
+    }
+
+    pub fn build(self) -> Result<(Bill, Vec<Participant>), BillBuilderError> {
+        let participants = self.participants;
+        for (index, participant) in participants.iter().enumerate() {
+            if participants[..index]
+                .iter()
+                .any(|earlier| earlier.name == participant.name)
+            {
+                return Err(BillBuilderError::DuplicateParticipant(
+                    participant.name.clone(),
+                ));
+            }
+        }
+        let id_of = |name: &str| {
+            participants
+                .iter()
+                .find(|participant| participant.name == name)
+                .map(|participant| participant.id)
+                .ok_or_else(|| BillBuilderError::UnknownParticipant(name.to_string()))
+        };
+
+        let mut bill = Bill::new(self.title, None);
+        for participant in &participants {
+            bill.add_participant(participant.id);
+        }
+        for spec in self.items {
+            let unit_price =
+                Decimal::try_from(spec.unit_price).map_err(|_| BillBuilderError::InvalidPrice {
+                    description: spec.description.clone(),
+                    unit_price: spec.unit_price,
+                })?;
+            let mut item = LineItem::new(
+                spec.description,
+                spec.quantity,
+                Money::from_decimal(unit_price),
+            );
+            item.participant_ids = spec
+                .shared_by
+                .iter()
+                .map(|name| id_of(name))
+                .collect::<Result<Vec<Uuid>, _>>()?;
+            bill.add_line_item(item);
+        }
+        bill.payer_id = self.payer.as_deref().map(id_of).transpose()?;
+        bill.creator_id = self.creator.as_deref().map(id_of).transpose()?;
+
+        Ok((bill, participants))
+    }
+}
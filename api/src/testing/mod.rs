@@ -0,0 +1,13 @@
+//! Fixtures for tests. Only compiled for unit tests and with the
+//! `test-util` feature, which the integration tests under `tests/` enable.
+
+pub mod bill_builder;
+
+pub use bill_builder::{BillBuilder, BillBuilderError};
+
+use crate::models::Money;
+
+/// Parses a decimal amount such as `"12.50"`, panicking if it is not one.
+pub fn money(amount: &str) -> Money {
+    Money::from_decimal(amount.parse().unwrap())
+}
@@ -0,0 +1,239 @@
+//! Consistency checks run over a whole bill at once, so every problem can be
+//! shown together before the bill is locked.
+
+use std::collections::{HashMap, HashSet};
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    models::{Bill, Money},
+    split::SplitMethod,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The bill cannot be split as it stands.
+    Error,
+    /// The bill can be split, but probably not the way it was meant to be.
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    /// Stable identifier for the kind of problem, e.g. `unassigned_line_item`.
+    pub code: String,
+    pub message: String,
+    /// The line item, participant or payment the issue is about, if any.
+    pub affected_id: Option<Uuid>,
+}
+
+impl ValidationIssue {
+    fn new(severity: Severity, code: &str, message: String, affected_id: Option<Uuid>) -> Self {
+        Self {
+            severity,
+            code: code.to_string(),
+            message,
+            affected_id,
+        }
+    }
+}
+
+/// The method the bill will be split with: its saved config, otherwise the
+/// method of its latest split, otherwise an equal split.
+pub fn effective_method(bill: &Bill) -> SplitMethod {
+    bill.split_config
+        .as_ref()
+        .map(|config| config.method)
+        .or_else(|| bill.latest_split().map(|snapshot| snapshot.method))
+        .unwrap_or(SplitMethod::Equal)
+}
+
+/// Every problem found in `bill`, errors first. Assignment checks only apply
+/// when the bill is split by item.
+pub fn validate(bill: &Bill) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    check_required_fields(bill, &mut issues);
+    check_negative_amounts(bill, &mut issues);
+    if effective_method(bill) == SplitMethod::Itemised {
+        check_assignments(bill, &mut issues);
+    }
+    check_duplicate_descriptions(bill, &mut issues);
+    issues.sort_by_key(|issue| issue.severity != Severity::Error);
+    issues
+}
+
+fn check_required_fields(bill: &Bill, issues: &mut Vec<ValidationIssue>) {
+    if bill.title.trim().is_empty() {
+        issues.push(ValidationIssue::new(
+            Severity::Error,
+            "missing_field",
+            "The bill has no title".to_string(),
+            Some(bill.id),
+        ));
+    }
+    if bill.participants.is_empty() {
+        issues.push(ValidationIssue::new(
+            Severity::Error,
+            "no_participants",
+            "The bill has no participants".to_string(),
+            None,
+        ));
+    }
+    if bill.line_items.is_empty() {
+        issues.push(ValidationIssue::new(
+            Severity::Warning,
+            "no_line_items",
+            "The bill has no line items".to_string(),
+            None,
+        ));
+    }
+    for item in &bill.line_items {
+        if item.description.trim().is_empty() {
+            issues.push(ValidationIssue::new(
+                Severity::Error,
+                "missing_field",
+                "A line item has no description".to_string(),
+                Some(item.id),
+            ));
+        }
+        if item.quantity == 0 {
+            issues.push(ValidationIssue::new(
+                Severity::Error,
+                "missing_field",
+                format!("\"{}\" has a quantity of zero", item.description),
+                Some(item.id),
+            ));
+        }
+    }
+}
+
+fn check_negative_amounts(bill: &Bill, issues: &mut Vec<ValidationIssue>) {
+    for item in &bill.line_items {
+        if item.unit_price.is_negative() {
+            issues.push(ValidationIssue::new(
+                Severity::Error,
+                "negative_amount",
+                format!(
+                    "\"{}\" has a negative price of {}",
+                    item.description, item.unit_price
+                ),
+                Some(item.id),
+            ));
+        }
+    }
+    if bill.discount.is_negative() {
+        issues.push(ValidationIssue::new(
+            Severity::Error,
+            "negative_amount",
+            format!("The discount is negative ({})", bill.discount),
+            None,
+        ));
+    }
+    if bill.total().is_negative() {
+        issues.push(ValidationIssue::new(
+            Severity::Error,
+            "negative_amount",
+            format!(
+                "The discount is larger than the subtotal of {}",
+                bill.subtotal()
+            ),
+            None,
+        ));
+    }
+    for payment in &bill.payments {
+        if payment.amount.is_negative() {
+            issues.push(ValidationIssue::new(
+                Severity::Error,
+                "negative_amount",
+                format!("A payment of {} is negative", payment.amount),
+                Some(payment.id),
+            ));
+        }
+    }
+}
+
+fn check_assignments(bill: &Bill, issues: &mut Vec<ValidationIssue>) {
+    let members: HashSet<Uuid> = bill.participant_ids().into_iter().collect();
+    let mut assigned: HashSet<Uuid> = HashSet::new();
+    // What the participants on the bill are charged for their items, and
+    // whether any item is also charged to someone no longer on it.
+    let mut charged = Decimal::ZERO;
+    let mut has_outsiders = false;
+
+    for item in &bill.line_items {
+        if item.participant_ids.is_empty() {
+            issues.push(ValidationIssue::new(
+                Severity::Error,
+                "unassigned_line_item",
+                format!("\"{}\" is not assigned to anyone", item.description),
+                Some(item.id),
+            ));
+            continue;
+        }
+        let sharers = bill.item_sharers(item);
+        let insiders = sharers.iter().filter(|id| members.contains(id)).count();
+        has_outsiders |= insiders < sharers.len();
+        charged +=
+            item.total().to_decimal() * Decimal::from(insiders) / Decimal::from(sharers.len());
+
+        assigned.extend(sharers);
+    }
+
+    // Exempt participants owe nothing by design, so they are not warned about.
+    for participant_id in &bill.liable_participant_ids() {
+        if !assigned.contains(participant_id) {
+            issues.push(ValidationIssue::new(
+                Severity::Warning,
+                "participant_without_items",
+                format!("Participant {participant_id} is not assigned any line items and will owe nothing"),
+                Some(*participant_id),
+            ));
+        }
+    }
+
+    if has_outsiders {
+        // Discounts are spread over items the same way the itemised split does.
+        let subtotal = bill.subtotal().to_decimal();
+        if !subtotal.is_zero() {
+            charged = charged * bill.total().to_decimal() / subtotal;
+        }
+        let shares = Money::from_decimal(charged);
+
+        issues.push(ValidationIssue::new(
+            Severity::Error,
+            "share_total_mismatch",
+            format!(
+                "Itemised shares add up to {shares} but the bill total is {}: some items are assigned to participants no longer on the bill",
+                bill.total()
+            ),
+            None,
+        ));
+    }
+}
+
+fn check_duplicate_descriptions(bill: &Bill, issues: &mut Vec<ValidationIssue>) {
+    let mut seen: HashMap<String, &str> = HashMap::new();
+    for item in &bill.line_items {
+        let key = item.description.trim().to_lowercase();
+        if key.is_empty() {
+            continue;
+        }
+        if let Some(first) = seen.get(&key) {
+            issues.push(ValidationIssue::new(
+                Severity::Warning,
+                "duplicate_description",
+                format!(
+                    "\"{}\" has the same description as \"{first}\"",
+                    item.description
+                ),
+                Some(item.id),
+            ));
+        } else {
+            seen.insert(key, &item.description);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use std::{future::poll_fn, pin::pin, time::Duration};
+
+use actix_web::{
+    body::MessageBody,
+    http::{header, StatusCode},
+    test, web, App, HttpResponse,
+};
+use bill_splitter_api::{
+    app, config::Config, middleware::error_handlers::error_handlers, state::AppState,
+};
+
+fn state() -> web::Data<AppState> {
+    web::Data::new(AppState::new(Config::default()))
+}
+
+#[actix_web::test]
+async fn hello_greets_by_name() {
+    let app = test::init_service(app(state())).await;
+    let req = test::TestRequest::get().uri("/hello/Sarah").to_request();
+
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(test::read_body(res).await, "Hello Sarah!");
+}
+
+#[actix_web::test]
+async fn stream_is_json() {
+    let app = test::init_service(app(state())).await;
+    let req = test::TestRequest::get().uri("/stream").to_request();
+
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.headers().get(header::CONTENT_TYPE).unwrap(),
+        "application/json"
+    );
+    assert_eq!(test::read_body(res).await, "test");
+}
+
+#[actix_web::test]
+async fn stream_delay_sends_first_event_quickly() {
+    let app = test::init_service(app(state())).await;
+    let req = test::TestRequest::get().uri("/stream-delay").to_request();
+
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.headers().get(header::CONTENT_TYPE).unwrap(),
+        "text/event-stream"
+    );
+
+    let mut body = pin!(res.into_body());
+    let first = tokio::time::timeout(
+        Duration::from_millis(100),
+        poll_fn(|cx| body.as_mut().poll_next(cx)),
+    )
+    .await
+    .expect("first event within 100ms")
+    .expect("stream is not empty");
+    assert_eq!(first.ok().unwrap(), "data: 0\n\n");
+}
+
+#[actix_web::test]
+async fn not_found_is_structured_json() {
+    let app = test::init_service(app(state())).await;
+    let req = test::TestRequest::get().uri("/no-such-route").to_request();
+
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    let body: serde_json::Value = test::read_body_json(res).await;
+    assert_eq!(body["error"], "Not Found");
+    assert_eq!(body["message"], "The requested resource was not found");
+    assert_eq!(body["status"], 404);
+}
+
+#[actix_web::test]
+async fn internal_server_error_is_structured_json() {
+    let app = test::init_service(
+        App::new()
+            .route(
+                "/boom",
+                web::get().to(|| async { HttpResponse::InternalServerError().body("boom") }),
+            )
+            .wrap(error_handlers()),
+    )
+    .await;
+    let req = test::TestRequest::get().uri("/boom").to_request();
+
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body: serde_json::Value = test::read_body_json(res).await;
+    assert_eq!(body["error"], "Internal Server Error");
+    assert_eq!(body["message"], "An unexpected error occurred");
+    assert_eq!(body["status"], 500);
+}
+
+#[actix_web::test]
+async fn structured_errors_are_left_alone() {
+    let app = test::init_service(app(state())).await;
+    let req = test::TestRequest::get()
+        .uri("/bills/00000000-0000-0000-0000-000000000000")
+        .to_request();
+
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    let body: serde_json::Value = test::read_body_json(res).await;
+    assert_eq!(
+        body["message"],
+        "Bill 00000000-0000-0000-0000-000000000000 not found"
+    );
+}
+
+#[actix_web::test]
+async fn version_lists_features() {
+    let app = test::init_service(app(state())).await;
+    let req = test::TestRequest::get().uri("/version").to_request();
+
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(res).await;
+    assert_eq!(body["api_version"], env!("CARGO_PKG_VERSION"));
+    assert!(body["features"]
+        .as_array()
+        .unwrap()
+        .contains(&"split-itemised".into()));
+    assert_eq!(body["deprecated_endpoints"], serde_json::json!([]));
+}
@@ -0,0 +1,130 @@
+use actix_web::{
+    http::StatusCode,
+    test::{call_and_read_body_json, call_service, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    models::{Bill, LineItem, Money, Participant},
+    split::{allocation_matrix, SplitError},
+    state::AppState,
+    testing::BillBuilder,
+};
+use chrono::Utc;
+use rust_decimal::Decimal;
+use serde_json::Value;
+
+/// Alice and Bob share the wine three ways with Carol, who also has soup.
+fn dinner() -> (Bill, Vec<Participant>) {
+    BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_participant("Bob")
+        .with_participant("Carol")
+        .with_item_shared_by("Steak", 1, 30.0, &["Alice"])
+        .with_item_shared_by("Wine", 1, 21.0, &["Alice", "Bob", "Carol"])
+        .with_item_shared_by("Soup", 1, 6.0, &["Carol"])
+        .build()
+        .unwrap()
+}
+
+fn column_sums(matrix: &[Vec<Decimal>]) -> Vec<Decimal> {
+    (0..matrix[0].len())
+        .map(|column| matrix.iter().map(|row| row[column]).sum())
+        .collect()
+}
+
+#[test]
+fn each_item_is_divided_between_its_sharers() {
+    let (bill, participants) = dinner();
+    let allocation = allocation_matrix(&bill, &participants).unwrap();
+
+    let names: Vec<&str> = allocation
+        .participants
+        .iter()
+        .map(|participant| participant.name.as_str())
+        .collect();
+    assert_eq!(names, ["Alice", "Bob", "Carol"]);
+    assert_eq!(allocation.items[1].description, "Wine");
+    assert_eq!(
+        allocation.items[1].total,
+        Money::from_decimal("21.00".parse().unwrap())
+    );
+
+    let third = Decimal::ONE / Decimal::from(3);
+    assert_eq!(allocation.matrix[0][0], Decimal::ONE);
+    assert_eq!(allocation.matrix[1][0], Decimal::ZERO);
+    assert_eq!(allocation.matrix[0][1], third);
+    assert_eq!(allocation.matrix[1][1], third);
+    assert_eq!(allocation.matrix[2][2], Decimal::ONE);
+    assert_eq!(column_sums(&allocation.matrix), vec![Decimal::ONE; 3]);
+}
+
+#[test]
+fn exempt_participants_pay_no_fraction_of_anything() {
+    let (mut bill, participants) = dinner();
+    bill.exempt_participant(participants[2].id, "Designated driver", Utc::now());
+    let allocation = allocation_matrix(&bill, &participants).unwrap();
+
+    assert_eq!(allocation.matrix[2], vec![Decimal::ZERO; 3]);
+    let half = Decimal::new(5, 1);
+    assert_eq!(allocation.matrix[0][1], half);
+    assert_eq!(allocation.matrix[1][1], half);
+    // Soup only Carol had falls to everyone who is liable.
+    assert_eq!(allocation.matrix[0][2], half);
+    assert_eq!(column_sums(&allocation.matrix), vec![Decimal::ONE; 3]);
+}
+
+#[test]
+fn unassigned_items_are_rejected() {
+    let (mut bill, participants) = dinner();
+    let bread = LineItem::new("Bread", 1, Money::from_cents(400));
+    let bread_id = bread.id;
+    bill.add_line_item(bread);
+    assert!(matches!(
+        allocation_matrix(&bill, &participants),
+        Err(SplitError::UnassignedLineItem { id }) if id == bread_id
+    ));
+}
+
+#[actix_web::test]
+async fn endpoint_returns_the_matrix() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let repo = state.repo();
+    let (bill, participants) = dinner();
+    for participant in &participants {
+        repo.put_participant(participant).await.unwrap();
+    }
+    repo.put_bill(&bill).await.unwrap();
+    let app = init_service(app(state.clone())).await;
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split/allocation-matrix", bill.id))
+        .to_request();
+    let body: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(body["participants"][0]["name"], "Alice");
+    assert_eq!(body["items"][0]["description"], "Steak");
+    assert_eq!(body["matrix"][0][0], "1");
+    assert_eq!(body["matrix"][0][1], body["matrix"][1][1]);
+    assert!(repo
+        .get_bill(bill.id)
+        .await
+        .unwrap()
+        .unwrap()
+        .split_history
+        .is_empty());
+
+    let (unassigned, _) = BillBuilder::new("Lunch")
+        .with_participant("Alice")
+        .with_item("Soup", 1, 6.0)
+        .build()
+        .unwrap();
+    repo.put_bill(&unassigned).await.unwrap();
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split/allocation-matrix", unassigned.id))
+        .to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+}
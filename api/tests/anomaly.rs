@@ -0,0 +1,58 @@
+use bill_splitter_api::{
+    analytics::anomaly::{detect_anomalies, mean_and_std_dev, AnomalySeverity},
+    models::{Bill, LineItem},
+    testing::money,
+};
+
+fn bill_with(prices: &[&str]) -> Bill {
+    let mut bill = Bill::new("Dinner", None);
+    for (i, price) in prices.iter().enumerate() {
+        bill.line_items
+            .push(LineItem::new(format!("Dish {i}"), 1, money(price)));
+    }
+    bill
+}
+
+#[test]
+fn standard_deviation_is_the_population_one() {
+    let (mean, std_dev) = mean_and_std_dev(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]).unwrap();
+    assert_eq!((mean, std_dev), (5.0, 2.0));
+    assert!(mean_and_std_dev(&[]).is_none());
+}
+
+#[test]
+fn flags_prices_far_above_the_average() {
+    let bill = bill_with(&["10", "11", "9", "10", "12", "10", "8", "95"]);
+
+    let alerts = detect_anomalies(&bill);
+
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].line_item_id, bill.line_items[7].id);
+    assert_eq!(alerts[0].severity, AnomalySeverity::Medium);
+    assert!(alerts[0].reason.contains("2.6 standard deviations"));
+}
+
+#[test]
+fn flags_zero_quantities_and_uncategorised_fees() {
+    let mut bill = bill_with(&["10", "10"]);
+    bill.line_items[0].quantity = 0;
+    bill.line_items
+        .push(LineItem::new("10% Service Charge", 1, money("2")));
+    let mut categorised = LineItem::new("Card surcharge", 1, money("1"));
+    categorised.category = Some("fees".to_string());
+    bill.line_items.push(categorised);
+
+    let alerts = detect_anomalies(&bill);
+
+    let flagged: Vec<_> = alerts
+        .iter()
+        .map(|alert| (alert.description.as_str(), alert.severity))
+        .collect();
+    assert_eq!(
+        flagged,
+        [
+            ("Dish 0", AnomalySeverity::High),
+            ("10% Service Charge", AnomalySeverity::Low),
+        ]
+    );
+}
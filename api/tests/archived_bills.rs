@@ -0,0 +1,116 @@
+use actix_web::{
+    http::StatusCode,
+    test::{call_and_read_body_json, call_service, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    models::{Bill, BillStatus},
+    state::AppState,
+    testing::BillBuilder,
+};
+use serde_json::{json, Value};
+
+async fn seed(state: &AppState) -> Bill {
+    let repo = state.repo();
+    let (mut bill, participants) = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_item("Pizza", 1, 30.0)
+        .build()
+        .unwrap();
+    repo.put_participant(&participants[0]).await.unwrap();
+    bill.status = BillStatus::Open;
+    repo.put_bill(&bill).await.unwrap();
+    bill
+}
+
+fn titles(body: &Value) -> Vec<&str> {
+    body["bills"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|bill| bill["title"].as_str().unwrap())
+        .collect()
+}
+
+#[actix_web::test]
+async fn archived_bills_are_hidden_from_the_list_unless_asked_for() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let bill = seed(&state).await;
+    let app = init_service(app(state)).await;
+
+    let req = TestRequest::post()
+        .uri(&format!("/bills/{}/archive", bill.id))
+        .to_request();
+    let archived: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(archived["status"], "archived");
+
+    let req = TestRequest::get().uri("/bills").to_request();
+    let body: Value = call_and_read_body_json(&app, req).await;
+    assert!(titles(&body).is_empty());
+    let req = TestRequest::get()
+        .uri("/bills?include_archived=true")
+        .to_request();
+    let body: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(titles(&body), ["Dinner"]);
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}", bill.id))
+        .to_request();
+    assert_eq!(call_service(&app, req).await.status(), StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn archived_bills_reject_changes_until_unarchived() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let bill = seed(&state).await;
+    let participant_id = bill.participants[0].participant_id;
+    let app = init_service(app(state.clone())).await;
+
+    let req = TestRequest::post()
+        .uri(&format!("/bills/{}/archive", bill.id))
+        .to_request();
+    assert_eq!(call_service(&app, req).await.status(), StatusCode::OK);
+
+    let changes = || {
+        [
+            TestRequest::patch()
+                .uri(&format!("/bills/{}", bill.id))
+                .set_json(json!({ "title": "Lunch" })),
+            TestRequest::post()
+                .uri(&format!("/bills/{}/participants", bill.id))
+                .set_json(json!({ "name": "Bob" })),
+            TestRequest::post()
+                .uri(&format!("/bills/{}/line-items", bill.id))
+                .set_json(json!({ "description": "Salad", "unit_price": "5.00" })),
+            TestRequest::post()
+                .uri(&format!("/bills/{}/payments", bill.id))
+                .set_json(json!({ "participant_id": participant_id, "amount": "5.00" })),
+            TestRequest::post()
+                .uri(&format!("/bills/{}/tags", bill.id))
+                .set_json(json!({ "name": "food" })),
+            TestRequest::put()
+                .uri(&format!("/bills/{}/split-config", bill.id))
+                .set_json(json!({ "method": "equal" })),
+        ]
+    };
+    for req in changes() {
+        let res = call_service(&app, req.to_request()).await;
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+    }
+    let stored = state.repo().get_bill(bill.id).await.unwrap().unwrap();
+    assert_eq!(stored.title, "Dinner");
+    assert_eq!(stored.line_items.len(), 1);
+    assert!(stored.payments.is_empty());
+
+    let req = TestRequest::post()
+        .uri(&format!("/bills/{}/unarchive", bill.id))
+        .to_request();
+    let unarchived: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(unarchived["status"], "open");
+    for req in changes() {
+        let res = call_service(&app, req.to_request()).await;
+        assert!(res.status().is_success(), "{}", res.status());
+    }
+}
@@ -0,0 +1,127 @@
+use actix_web::{
+    http::{header, StatusCode},
+    test::{call_service, init_service, read_body, TestRequest},
+    web, App, HttpRequest, HttpResponse, HttpServer,
+};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    models::{Bill, Participant},
+    state::AppState,
+};
+use serde_json::json;
+
+/// Serves a tiny "image" that records whether the request carried cookies
+/// or credentials, and tries to set a cookie of its own. `/redirect.png`
+/// redirects to it. Returns the host's base URL.
+fn image_host() -> String {
+    let server = HttpServer::new(|| {
+        App::new()
+            .route(
+                "/alice.png",
+                web::get().to(|req: HttpRequest| async move {
+                    let leaked = req.headers().contains_key(header::COOKIE)
+                        || req.headers().contains_key(header::AUTHORIZATION);
+                    HttpResponse::Ok()
+                        .content_type("image/png")
+                        .insert_header((header::SET_COOKIE, "tracker=1"))
+                        .body(if leaked { "leaked" } else { "png-bytes" })
+                }),
+            )
+            .route(
+                "/redirect.png",
+                web::get().to(|| async {
+                    HttpResponse::Found()
+                        .insert_header((header::LOCATION, "/alice.png"))
+                        .finish()
+                }),
+            )
+    })
+    .bind("127.0.0.1:0")
+    .unwrap();
+    let addr = server.addrs()[0];
+    actix_web::rt::spawn(server.run());
+    format!("http://{addr}")
+}
+
+#[actix_web::test]
+async fn avatars_are_proxied_without_the_callers_credentials() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let mut alice = Participant::new("Alice", None);
+    // Stored directly: the API itself only accepts HTTPS avatar URLs.
+    alice.avatar_url = Some(format!("{}/alice.png", image_host()).parse().unwrap());
+    state.repo().put_participant(&alice).await.unwrap();
+    let app = init_service(app(state)).await;
+
+    let req = TestRequest::get()
+        .uri(&format!("/participants/{}/avatar", alice.id))
+        .insert_header((header::COOKIE, "session=secret"))
+        .insert_header((header::AUTHORIZATION, "Bearer secret"))
+        .to_request();
+    let res = call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.headers().get(header::CONTENT_TYPE).unwrap(),
+        "image/png"
+    );
+    assert_eq!(
+        res.headers().get(header::CACHE_CONTROL).unwrap(),
+        "public, max-age=86400"
+    );
+    assert!(!res.headers().contains_key(header::SET_COOKIE));
+    assert_eq!(read_body(res).await, "png-bytes");
+}
+
+#[actix_web::test]
+async fn redirects_from_the_avatar_host_are_not_followed() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let mut alice = Participant::new("Alice", None);
+    alice.avatar_url = Some(format!("{}/redirect.png", image_host()).parse().unwrap());
+    state.repo().put_participant(&alice).await.unwrap();
+    let app = init_service(app(state)).await;
+
+    let req = TestRequest::get()
+        .uri(&format!("/participants/{}/avatar", alice.id))
+        .to_request();
+
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::BAD_GATEWAY
+    );
+}
+
+#[actix_web::test]
+async fn participants_without_an_avatar_have_none_to_serve() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let alice = Participant::new("Alice", None);
+    state.repo().put_participant(&alice).await.unwrap();
+    let app = init_service(app(state)).await;
+
+    let req = TestRequest::get()
+        .uri(&format!("/participants/{}/avatar", alice.id))
+        .to_request();
+
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[actix_web::test]
+async fn avatar_urls_must_use_https() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let bill = Bill::new("Dinner", None);
+    state.repo().put_bill(&bill).await.unwrap();
+    let app = init_service(app(state)).await;
+
+    let req = TestRequest::post()
+        .uri(&format!("/bills/{}/participants", bill.id))
+        .set_json(json!({ "name": "Alice", "avatar_url": "http://example.com/alice.png" }))
+        .to_request();
+
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+}
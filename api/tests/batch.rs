@@ -0,0 +1,151 @@
+use actix_web::{
+    http::StatusCode,
+    test::{call_and_read_body_json, call_service, init_service, read_body_json, TestRequest},
+    web,
+};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    handlers::batch::{COMMITTED_HEADER, MAX_BATCH_OPERATIONS},
+    models::Bill,
+    state::AppState,
+};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+const JWT_SECRET: &str = "jwt-secret";
+
+fn token(user_id: Uuid) -> String {
+    let claims = json!({
+        "sub": user_id,
+        "exp": (Utc::now() + Duration::hours(1)).timestamp(),
+    });
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap()
+}
+
+fn state() -> web::Data<AppState> {
+    web::Data::new(AppState::new(Config {
+        jwt_secret: Some(JWT_SECRET.to_string()),
+        ..Config::default()
+    }))
+}
+
+#[actix_web::test]
+async fn operations_run_as_the_caller() {
+    let state = state();
+    let app = init_service(app(state.clone())).await;
+    let user = Uuid::new_v4();
+
+    let req = TestRequest::post()
+        .uri("/batch")
+        .insert_header(("Authorization", format!("Bearer {}", token(user))))
+        .set_json(json!([
+            { "method": "POST", "path": "/bills", "body": { "title": "Dinner" } },
+            { "method": "GET", "path": "/bills/month/2024/12" },
+        ]))
+        .to_request();
+    let res = call_service(&app, req).await;
+    assert_eq!(res.headers().get(COMMITTED_HEADER).unwrap(), "true");
+    let responses: Value = read_body_json(res).await;
+
+    assert_eq!(responses[0]["status"], 201);
+    assert_eq!(responses[0]["body"]["creator_id"], user.to_string());
+    assert_eq!(responses[1]["status"], 200);
+}
+
+#[actix_web::test]
+async fn a_failing_operation_rolls_the_batch_back() {
+    let state = state();
+    let app = init_service(app(state.clone())).await;
+    let bill = Bill::new("Dinner", None);
+    state.repo().put_bill(&bill).await.unwrap();
+
+    let req = TestRequest::post()
+        .uri("/batch")
+        .set_json(json!([
+            { "method": "POST", "path": "/bills", "body": { "title": "Lunch" } },
+            {
+                "method": "POST",
+                "path": format!("/bills/{}/participants", bill.id),
+                "body": { "name": "Alice" }
+            },
+            { "method": "GET", "path": format!("/bills/{}", Uuid::new_v4()) },
+            { "method": "GET", "path": "/health" },
+        ]))
+        .to_request();
+    let res = call_service(&app, req).await;
+    assert_eq!(res.headers().get(COMMITTED_HEADER).unwrap(), "false");
+    let responses: Value = read_body_json(res).await;
+    let statuses: Vec<u64> = responses
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|response| response["status"].as_u64().unwrap())
+        .collect();
+    assert_eq!(statuses, [201, 201, 404, 200]);
+
+    let repo = state.repo();
+    let created: Uuid = responses[0]["body"]["id"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(repo.get_bill(created).await.unwrap().is_none());
+    let bill = repo.get_bill(bill.id).await.unwrap().unwrap();
+    assert!(bill.participants.is_empty());
+    let alice: Uuid = responses[1]["body"]["id"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(repo.get_participant(alice).await.unwrap().is_none());
+}
+
+#[actix_web::test]
+async fn stop_on_error_returns_partial_results() {
+    let app = init_service(app(state())).await;
+    let req = TestRequest::post()
+        .uri("/batch?stop_on_error=true")
+        .set_json(json!([
+            { "method": "GET", "path": "/health" },
+            { "method": "GET", "path": "health" },
+            { "method": "GET", "path": "/health" },
+        ]))
+        .to_request();
+    let responses: Value = call_and_read_body_json(&app, req).await;
+    let responses = responses.as_array().unwrap();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[1]["status"], 400);
+}
+
+#[actix_web::test]
+async fn batches_are_bounded_and_cannot_nest() {
+    let app = init_service(app(state())).await;
+    let too_many: Vec<Value> = (0..MAX_BATCH_OPERATIONS + 1)
+        .map(|_| json!({ "method": "GET", "path": "/health" }))
+        .collect();
+    for body in [json!([]), json!(too_many)] {
+        let req = TestRequest::post()
+            .uri("/batch")
+            .set_json(body)
+            .to_request();
+        assert_eq!(
+            call_service(&app, req).await.status(),
+            StatusCode::BAD_REQUEST
+        );
+    }
+
+    let req = TestRequest::post()
+        .uri("/batch")
+        .set_json(json!([{ "method": "POST", "path": "/batch", "body": [] }]))
+        .to_request();
+    let responses: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(responses[0]["status"], 400);
+}
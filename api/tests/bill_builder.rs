@@ -0,0 +1,103 @@
+use bill_splitter_api::{
+    models::Participant,
+    testing::{money, BillBuilder, BillBuilderError},
+};
+
+#[test]
+fn builds_participants_items_and_payer_by_name() {
+    let (bill, participants) = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_participant("Bob")
+        .with_item("Pizza", 1, 20.0)
+        .with_item_shared_by("Beer", 2, 5.0, &["Bob"])
+        .with_payer("Alice")
+        .with_creator("Bob")
+        .build()
+        .unwrap();
+
+    let names: Vec<&str> = participants.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["Alice", "Bob"]);
+    assert_eq!(
+        bill.participant_ids(),
+        [participants[0].id, participants[1].id]
+    );
+    assert_eq!(bill.title, "Dinner");
+    assert_eq!(bill.total(), money("30.00"));
+    assert!(bill.line_items[0].participant_ids.is_empty());
+    assert_eq!(bill.line_items[1].participant_ids, [participants[1].id]);
+    assert_eq!(bill.line_items[1].unit_price, money("5.00"));
+    assert_eq!(bill.payer_id, Some(participants[0].id));
+    assert_eq!(bill.creator_id, Some(participants[1].id));
+}
+
+#[test]
+fn existing_participants_keep_their_ids_across_bills() {
+    let alice = Participant::new("Alice", None);
+    let build = |title: &str| {
+        BillBuilder::new(title)
+            .with_existing_participant(&alice)
+            .with_participant("Bob")
+            .with_item("Pizza", 1, 20.0)
+            .with_payer("Alice")
+            .build()
+            .unwrap()
+    };
+
+    let (dinner, dinner_participants) = build("Dinner");
+    let (lunch, lunch_participants) = build("Lunch");
+    assert_eq!(dinner_participants[0], alice);
+    assert_eq!(dinner.payer_id, Some(alice.id));
+    assert_eq!(lunch.payer_id, Some(alice.id));
+    assert_ne!(dinner_participants[1].id, lunch_participants[1].id);
+
+    let duplicate = BillBuilder::new("Dinner")
+        .with_existing_participant(&alice)
+        .with_participant("Alice")
+        .build();
+    assert_eq!(
+        duplicate.unwrap_err(),
+        BillBuilderError::DuplicateParticipant("Alice".to_string())
+    );
+}
+
+#[test]
+fn names_must_be_known_and_unique() {
+    let unknown = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_payer("Bob")
+        .build();
+    assert_eq!(
+        unknown.unwrap_err(),
+        BillBuilderError::UnknownParticipant("Bob".to_string())
+    );
+
+    let unknown_sharer = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_item_shared_by("Pizza", 1, 20.0, &["Carol"])
+        .build();
+    assert_eq!(
+        unknown_sharer.unwrap_err(),
+        BillBuilderError::UnknownParticipant("Carol".to_string())
+    );
+
+    let duplicate = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_participant("Alice")
+        .build();
+    assert_eq!(
+        duplicate.unwrap_err(),
+        BillBuilderError::DuplicateParticipant("Alice".to_string())
+    );
+}
+
+#[test]
+fn prices_must_be_finite() {
+    let result = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_item("Pizza", 1, f64::NAN)
+        .build();
+    assert!(matches!(
+        result,
+        Err(BillBuilderError::InvalidPrice { description, .. }) if description == "Pizza"
+    ));
+}
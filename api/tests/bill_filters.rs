@@ -0,0 +1,194 @@
+use actix_web::{
+    http::StatusCode,
+    test::{call_and_read_body_json, call_service, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{app, config::Config, models::Bill, state::AppState};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+const JWT_SECRET: &str = "jwt-secret";
+const ADMIN_KEY: &str = "admin-secret";
+
+fn token_expiring(user_id: Uuid, secret: &str, expires_in: Duration) -> String {
+    let claims = json!({
+        "sub": user_id,
+        "exp": (Utc::now() + expires_in).timestamp(),
+    });
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+    .unwrap()
+}
+
+fn bearer(user_id: Uuid) -> (&'static str, String) {
+    (
+        "Authorization",
+        format!(
+            "Bearer {}",
+            token_expiring(user_id, JWT_SECRET, Duration::hours(1))
+        ),
+    )
+}
+
+fn state() -> web::Data<AppState> {
+    web::Data::new(AppState::new(Config {
+        jwt_secret: Some(JWT_SECRET.to_string()),
+        admin_key: Some(ADMIN_KEY.to_string()),
+        ..Config::default()
+    }))
+}
+
+fn bill(title: &str, creator: Option<Uuid>, participants: &[Uuid]) -> Bill {
+    let mut bill = Bill::new(title, None);
+    bill.creator_id = creator;
+    for participant in participants {
+        bill.add_participant(*participant);
+    }
+    bill
+}
+
+fn titles(body: &Value) -> Vec<&str> {
+    let mut titles: Vec<&str> = body["bills"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|bill| bill["title"].as_str().unwrap())
+        .collect();
+    titles.sort();
+    titles
+}
+
+/// Me, someone else, and bills between us: mine, theirs with me on it, and
+/// theirs without me.
+async fn seed(state: &AppState) -> (Uuid, Uuid) {
+    let (me, other) = (Uuid::new_v4(), Uuid::new_v4());
+    for bill in [
+        bill("Mine", Some(me), &[me, other]),
+        bill("Theirs with me", Some(other), &[me, other]),
+        bill("Theirs", Some(other), &[other]),
+        bill("Anonymous", None, &[me]),
+    ] {
+        state.repo().put_bill(&bill).await.unwrap();
+    }
+    (me, other)
+}
+
+#[actix_web::test]
+async fn created_by_me_lists_the_callers_bills() {
+    let state = state();
+    let (me, _) = seed(&state).await;
+    let app = init_service(app(state.clone())).await;
+
+    let req = TestRequest::get()
+        .uri("/bills?created_by=me")
+        .insert_header(bearer(me))
+        .to_request();
+    let body: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(titles(&body), ["Mine"]);
+
+    let req = TestRequest::get().uri("/bills?created_by=me").to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+}
+
+#[actix_web::test]
+async fn participant_me_lists_bills_others_created_for_the_caller() {
+    let state = state();
+    let (me, _) = seed(&state).await;
+    let app = init_service(app(state.clone())).await;
+
+    let req = TestRequest::get()
+        .uri("/bills?participant=me")
+        .insert_header(bearer(me))
+        .to_request();
+    let body: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(titles(&body), ["Anonymous", "Theirs with me"]);
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills?participant={me}"))
+        .insert_header(bearer(me))
+        .to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+}
+
+#[actix_web::test]
+async fn created_by_another_user_is_admin_only() {
+    let state = state();
+    let (me, other) = seed(&state).await;
+    let app = init_service(app(state.clone())).await;
+    let uri = format!("/bills?created_by={other}");
+
+    let req = TestRequest::get()
+        .uri(&uri)
+        .insert_header(bearer(me))
+        .to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    let req = TestRequest::get()
+        .uri(&uri)
+        .insert_header(("X-Admin-Key", ADMIN_KEY))
+        .to_request();
+    let body: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(titles(&body), ["Theirs", "Theirs with me"]);
+
+    let req = TestRequest::get()
+        .uri("/bills?created_by=someone")
+        .insert_header(("X-Admin-Key", ADMIN_KEY))
+        .to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+}
+
+#[actix_web::test]
+async fn bad_tokens_are_rejected_rather_than_treated_as_anonymous() {
+    let state = state();
+    let app = init_service(app(state.clone())).await;
+    let user = Uuid::new_v4();
+
+    for authorization in [
+        format!(
+            "Bearer {}",
+            token_expiring(user, JWT_SECRET, -Duration::hours(2))
+        ),
+        format!(
+            "Bearer {}",
+            token_expiring(user, "another-secret", Duration::hours(1))
+        ),
+        "Bearer not-a-jwt".to_string(),
+        "Basic dXNlcjpwYXNz".to_string(),
+    ] {
+        let req = TestRequest::post()
+            .uri("/bills")
+            .insert_header(("Authorization", authorization.clone()))
+            .set_json(json!({ "title": "Dinner" }))
+            .to_request();
+        assert_eq!(
+            call_service(&app, req).await.status(),
+            StatusCode::UNAUTHORIZED,
+            "{authorization}"
+        );
+    }
+
+    let req = TestRequest::post()
+        .uri("/bills")
+        .insert_header(bearer(user))
+        .set_json(json!({ "title": "Dinner" }))
+        .to_request();
+    let created: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(created["creator_id"], user.to_string());
+}
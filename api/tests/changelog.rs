@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use bill_splitter_api::{
+    changelog::describe_changes,
+    models::{Bill, LineItem, Money, Participant, Payment},
+};
+
+#[test]
+fn new_bill_is_reported_as_created() {
+    let bill = Bill::new("Dinner", None);
+
+    assert_eq!(
+        describe_changes(None, &bill, &HashMap::new()),
+        vec!["Bill 'Dinner' created"]
+    );
+}
+
+#[test]
+fn describes_item_price_participant_and_payment_changes() {
+    let bob = Participant::new("Bob", None);
+    let mut before = Bill::new("Dinner", None);
+    before.add_participant(bob.id);
+    before
+        .line_items
+        .push(LineItem::new("Sushi", 1, Money::from_cents(1_200)));
+
+    let mut after = before.clone();
+    after.line_items[0].unit_price = Money::from_cents(1_400);
+    after.participants.clear();
+    after
+        .payments
+        .push(Payment::new(bob.id, Money::from_cents(500), None));
+    let names = HashMap::from([(bob.id, bob.name.clone())]);
+
+    assert_eq!(
+        describe_changes(Some(&before), &after, &names),
+        vec![
+            "Participant Bob removed",
+            "Line item 'Sushi' price changed from $12.00 to $14.00",
+            "Bob paid $5.00",
+        ]
+    );
+}
+
+#[test]
+fn unchanged_bill_has_no_changes() {
+    let mut bill = Bill::new("Dinner", None);
+    let before = bill.clone();
+    bill.touch();
+
+    assert!(describe_changes(Some(&before), &bill, &HashMap::new()).is_empty());
+}
+
+#[test]
+fn reordering_line_items_is_reported_once() {
+    let mut before = Bill::new("Dinner", None);
+    before.add_line_item(LineItem::new("Pizza", 1, Money::from_cents(1_000)));
+    before.add_line_item(LineItem::new("Salad", 1, Money::from_cents(800)));
+
+    let mut after = before.clone();
+    let positions = HashMap::from([(after.line_items[0].id, 1), (after.line_items[1].id, 0)]);
+    after.reorder_line_items(&positions);
+
+    assert_eq!(
+        describe_changes(Some(&before), &after, &HashMap::new()),
+        vec!["Line items reordered"]
+    );
+}
@@ -0,0 +1,71 @@
+use actix_web::{
+    test::{call_and_read_body, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    models::{Bill, LineItem, Participant},
+    state::AppState,
+    testing::money,
+};
+use serde_json::{json, Value};
+
+#[actix_web::test]
+async fn shares_are_name_total_and_item_count() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let repo = state.repo();
+    let alice = Participant::new("Alice", None);
+    let bob = Participant::new("Bob", None);
+    let mut bill = Bill::new("Dinner", None);
+    for participant in [&alice, &bob] {
+        repo.put_participant(participant).await.unwrap();
+        bill.add_participant(participant.id);
+    }
+    let mut steak = LineItem::new("Steak", 1, money("30.00"));
+    steak.participant_ids = vec![alice.id];
+    bill.add_line_item(steak);
+    let mut salad = LineItem::new("Salad", 1, money("10.00"));
+    salad.participant_ids = vec![bob.id];
+    bill.add_line_item(salad);
+    bill.add_line_item(LineItem::new("Bread", 1, money("4.00")));
+    repo.put_bill(&bill).await.unwrap();
+
+    let app = init_service(app(state.clone())).await;
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split/breakdown/compact", bill.id))
+        .to_request();
+    let body: Value = serde_json::from_slice(&call_and_read_body(&app, req).await).unwrap();
+
+    assert_eq!(
+        body,
+        json!([
+            { "name": "Alice", "total": "22.00", "items_count": 2 },
+            { "name": "Bob", "total": "22.00", "items_count": 2 }
+        ])
+    );
+}
+
+#[actix_web::test]
+async fn a_ten_person_bill_fits_in_a_kilobyte() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let repo = state.repo();
+    let mut bill = Bill::new("Team offsite", None);
+    for n in 1..=10 {
+        let participant = Participant::new(format!("Participant {n:02}"), None);
+        repo.put_participant(&participant).await.unwrap();
+        bill.add_participant(participant.id);
+    }
+    for n in 0..25 {
+        bill.add_line_item(LineItem::new(format!("Item {n}"), 3, money("123.45")));
+    }
+    repo.put_bill(&bill).await.unwrap();
+
+    let app = init_service(app(state.clone())).await;
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split/breakdown/compact", bill.id))
+        .to_request();
+    let body = call_and_read_body(&app, req).await;
+
+    assert!(body.len() <= 1024, "{} bytes", body.len());
+}
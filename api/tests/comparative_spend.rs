@@ -0,0 +1,113 @@
+use actix_web::{
+    http::StatusCode,
+    test::{call_and_read_body_json, call_service, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    models::{Bill, LineItem, Money, Participant, ParticipantShare},
+    split::comparative_spend,
+    state::AppState,
+    testing::money,
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+fn share(amount: &str) -> ParticipantShare {
+    ParticipantShare {
+        participant_id: Uuid::new_v4(),
+        name: String::new(),
+        amount_owed: money(amount),
+    }
+}
+
+#[test]
+fn ranks_shares_from_lowest_to_highest() {
+    let shares = [share("10.00"), share("20.00"), share("30.00")];
+
+    let lowest = comparative_spend(&shares, shares[0].participant_id).unwrap();
+    assert_eq!(lowest.group_average, money("20.00"));
+    assert_eq!(lowest.delta, money("-10.00"));
+    assert_eq!(lowest.percentile, 0.0);
+
+    let middle = comparative_spend(&shares, shares[1].participant_id).unwrap();
+    assert_eq!(middle.delta, Money::ZERO);
+    assert_eq!(middle.percentile, 0.5);
+
+    let highest = comparative_spend(&shares, shares[2].participant_id).unwrap();
+    assert_eq!(highest.delta, money("10.00"));
+    assert_eq!(highest.percentile, 1.0);
+
+    assert!(comparative_spend(&shares, Uuid::new_v4()).is_none());
+}
+
+#[test]
+fn equal_shares_are_all_at_the_middle() {
+    let shares = [
+        share("15.00"),
+        share("15.00"),
+        share("15.00"),
+        share("15.00"),
+    ];
+    for share in &shares {
+        let comparison = comparative_spend(&shares, share.participant_id).unwrap();
+        assert_eq!(comparison.delta, Money::ZERO);
+        assert_eq!(comparison.percentile, 0.5);
+    }
+}
+
+#[actix_web::test]
+async fn compares_against_the_latest_split() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let repo = state.repo();
+    let alice = Participant::new("Alice", None);
+    let bob = Participant::new("Bob", None);
+    let mut bill = Bill::new("Dinner", None);
+    for participant in [&alice, &bob] {
+        repo.put_participant(participant).await.unwrap();
+        bill.add_participant(participant.id);
+    }
+    let mut steak = LineItem::new("Steak", 1, money("30.00"));
+    steak.participant_ids = vec![alice.id];
+    bill.add_line_item(steak);
+    let mut salad = LineItem::new("Salad", 1, money("10.00"));
+    salad.participant_ids = vec![bob.id];
+    bill.add_line_item(salad);
+    repo.put_bill(&bill).await.unwrap();
+    let app = init_service(app(state.clone())).await;
+    let uri = format!(
+        "/bills/{}/participants/{}/comparative-spend",
+        bill.id, alice.id
+    );
+
+    let req = TestRequest::get().uri(&uri).to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split?method=itemised", bill.id))
+        .to_request();
+    call_service(&app, req).await;
+    let req = TestRequest::get().uri(&uri).to_request();
+    let body: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(body["participant_id"], alice.id.to_string());
+    assert_eq!(body["their_share"], "30.00");
+    assert_eq!(body["group_average"], "20.00");
+    assert_eq!(body["delta"], "10.00");
+    assert_eq!(body["percentile"], 1.0);
+
+    let req = TestRequest::get()
+        .uri(&format!(
+            "/bills/{}/participants/{}/comparative-spend",
+            bill.id,
+            Uuid::new_v4()
+        ))
+        .to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::NOT_FOUND
+    );
+}
@@ -0,0 +1,80 @@
+use bill_splitter_api::{
+    analytics::completion::completion,
+    models::{Bill, Participant, Payment, SplitSnapshot},
+    split::{compute_split, SplitSpec},
+    testing::{money, BillBuilder},
+};
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+
+/// 40.00 split equally between Alice, Bob, Carol and Dan.
+fn dinner() -> (Bill, Vec<Participant>) {
+    let (mut bill, participants) = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_participant("Bob")
+        .with_participant("Carol")
+        .with_participant("Dan")
+        .with_item("Feast", 1, 40.0)
+        .build()
+        .unwrap();
+    let split = compute_split(&bill, &participants, &SplitSpec::Equal).unwrap();
+    bill.split_history.push(SplitSnapshot::from_result(&split));
+    (bill, participants)
+}
+
+#[test]
+fn progress_counts_payments_and_settled_participants() {
+    let (mut bill, participants) = dinner();
+    let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+    let mut paid = |participant: &Participant, amount: &str, days_ago: i64| {
+        let mut payment = Payment::new(participant.id, money(amount), None);
+        payment.paid_at = now - Duration::days(days_ago);
+        bill.payments.push(payment);
+    };
+    paid(&participants[0], "10.00", 20);
+    paid(&participants[1], "9.99", 3);
+    paid(&participants[2], "5.01", 1);
+
+    let completion = completion(&bill, now).unwrap();
+
+    assert_eq!(completion.total, money("40.00"));
+    assert_eq!(completion.paid, money("25.00"));
+    assert_eq!(completion.outstanding, money("15.00"));
+    assert_eq!(completion.completion_pct, 62.5);
+    // Bob is a cent short, which still counts.
+    assert_eq!(completion.fully_settled_participants, 2);
+    assert_eq!(completion.total_participants, 4);
+    // 15.00 paid over the last week is 15.00 / 7 a day, so the remaining
+    // 15.00 takes another seven days.
+    assert_eq!(
+        completion.projected_settlement_date,
+        NaiveDate::from_ymd_opt(2026, 3, 17)
+    );
+}
+
+#[test]
+fn no_projection_without_recent_payments_or_once_settled() {
+    let (mut bill, participants) = dinner();
+    let now = Utc::now();
+    let mut old = Payment::new(participants[0].id, money("10.00"), None);
+    old.paid_at = now - Duration::days(30);
+    bill.payments.push(old);
+
+    assert_eq!(
+        completion(&bill, now).unwrap().projected_settlement_date,
+        None
+    );
+
+    for participant in &participants[1..] {
+        bill.payments
+            .push(Payment::new(participant.id, money("10.00"), None));
+    }
+    let settled = completion(&bill, now).unwrap();
+    assert_eq!(settled.completion_pct, 100.0);
+    assert_eq!(settled.fully_settled_participants, 4);
+    assert_eq!(settled.projected_settlement_date, None);
+}
+
+#[test]
+fn unsplit_bills_have_no_completion() {
+    assert!(completion(&Bill::new("Dinner", None), Utc::now()).is_none());
+}
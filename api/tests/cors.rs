@@ -0,0 +1,86 @@
+use actix_web::{
+    dev::ServiceResponse,
+    http::{header, StatusCode},
+    test, web,
+};
+use bill_splitter_api::{
+    app,
+    config::{AllowedOrigins, Config},
+    state::AppState,
+};
+
+fn state(allowed_origins: AllowedOrigins) -> web::Data<AppState> {
+    let config = Config {
+        allowed_origins,
+        ..Config::default()
+    };
+    web::Data::new(AppState::new(config))
+}
+
+fn header_values<B>(res: &ServiceResponse<B>, name: header::HeaderName) -> Vec<String> {
+    res.headers()
+        .get_all(name)
+        .map(|value| value.to_str().unwrap().to_string())
+        .collect()
+}
+
+#[actix_web::test]
+async fn listed_origins_get_their_own_allow_origin_and_vary() {
+    let app = test::init_service(app(state(AllowedOrigins::List(vec![
+        "https://a.example".to_string(),
+        "https://b.example".to_string(),
+    ]))))
+    .await;
+
+    let mut seen = Vec::new();
+    for origin in ["https://a.example", "https://b.example"] {
+        let req = test::TestRequest::get()
+            .uri("/health")
+            .insert_header((header::ORIGIN, origin))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(header_values(&res, header::VARY).contains(&"Origin".to_string()));
+        seen.push(header_values(&res, header::ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+
+    assert_eq!(seen, [["https://a.example"], ["https://b.example"]]);
+}
+
+#[actix_web::test]
+async fn unlisted_origin_still_varies_on_origin() {
+    let app = test::init_service(app(state(AllowedOrigins::List(vec![
+        "https://a.example".to_string()
+    ]))))
+    .await;
+    let req = test::TestRequest::get()
+        .uri("/no-such-route")
+        .insert_header((header::ORIGIN, "https://evil.example"))
+        .to_request();
+
+    let res = test::call_service(&app, req).await;
+
+    assert!(res
+        .headers()
+        .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+        .is_none());
+    assert!(header_values(&res, header::VARY).contains(&"Origin".to_string()));
+}
+
+#[actix_web::test]
+async fn wildcard_does_not_vary_on_origin() {
+    let app = test::init_service(app(state(AllowedOrigins::Any))).await;
+    let req = test::TestRequest::get()
+        .uri("/health")
+        .insert_header((header::ORIGIN, "https://a.example"))
+        .to_request();
+
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(
+        header_values(&res, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+        ["*"]
+    );
+    assert_eq!(header_values(&res, header::VARY), ["Accept-Encoding"]);
+}
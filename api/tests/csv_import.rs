@@ -0,0 +1,110 @@
+use actix_web::{
+    http::{header, StatusCode},
+    test::{call_service, init_service, read_body_json, TestRequest},
+    web,
+};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    import::csv_items::{parse_line_items, CsvImportError, ImportLineItemsResult},
+    models::{Bill, Money},
+    state::AppState,
+};
+use serde_json::Value;
+
+#[test]
+fn rows_become_line_items_in_any_column_order() {
+    let csv = "unit_price,description,quantity,category,tax_rate\r\n\
+               12.50,Pizza,2,food,0.07\r\n\
+               \r\n\
+               \"4.00\",\"Bread, \"\"sourdough\"\"\",,,\r\n";
+
+    let parsed = parse_line_items(csv).unwrap();
+
+    assert!(parsed.errors.is_empty());
+    assert_eq!(parsed.items.len(), 2);
+    assert_eq!(parsed.items[0].description, "Pizza");
+    assert_eq!(parsed.items[0].quantity, 2);
+    assert_eq!(parsed.items[0].unit_price, Money::from_cents(1_250));
+    assert_eq!(parsed.items[0].tax_rate, Some("0.07".parse().unwrap()));
+    assert_eq!(parsed.items[0].category.as_deref(), Some("food"));
+    assert_eq!(parsed.items[1].description, "Bread, \"sourdough\"");
+    assert_eq!(parsed.items[1].quantity, 1);
+    assert_eq!(parsed.items[1].tax_rate, None);
+}
+
+#[test]
+fn invalid_rows_are_skipped_with_their_row_number() {
+    let csv = "description,unit_price\nPizza,twelve\nSalad,8.00\n,3.00\nWine,9.999\n";
+
+    let parsed = parse_line_items(csv).unwrap();
+
+    assert_eq!(parsed.items.len(), 1);
+    let rows: Vec<usize> = parsed.errors.iter().map(|error| error.row).collect();
+    assert_eq!(rows, [2, 4, 5]);
+    assert!(parsed.errors[0].error.contains("twelve"));
+    assert_eq!(
+        parsed.result(),
+        ImportLineItemsResult {
+            imported: 1,
+            skipped: 3,
+            errors: parsed.errors.clone(),
+        }
+    );
+}
+
+#[test]
+fn unusable_files_are_rejected_as_a_whole() {
+    assert_eq!(parse_line_items("").unwrap_err(), CsvImportError::Empty);
+    assert_eq!(
+        parse_line_items("description,price\n").unwrap_err(),
+        CsvImportError::UnknownColumn("price".to_string())
+    );
+    assert_eq!(
+        parse_line_items("description,quantity\n").unwrap_err(),
+        CsvImportError::MissingColumn("unit_price")
+    );
+    assert_eq!(
+        parse_line_items("description,unit_price\n\"Pizza,1.00\n").unwrap_err(),
+        CsvImportError::UnterminatedQuote { row: 2 }
+    );
+}
+
+#[actix_web::test]
+async fn imports_need_the_current_etag_when_one_is_sent() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let bill = Bill::new("Dinner", None);
+    state.repo().put_bill(&bill).await.unwrap();
+    let app = init_service(app(state)).await;
+    let uri = format!("/bills/{}/line-items/import/csv", bill.id);
+    let csv = "description,unit_price\nPizza,12.50\nSalad,oops\n";
+
+    let req = TestRequest::post()
+        .uri(&uri)
+        .insert_header((header::IF_MATCH, "\"stale\""))
+        .set_payload(csv)
+        .to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::PRECONDITION_FAILED
+    );
+
+    let req = TestRequest::post()
+        .uri(&uri)
+        .insert_header((header::IF_MATCH, format!("\"{}\"", bill.etag())))
+        .set_payload(csv)
+        .to_request();
+    let res = call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let result: Value = read_body_json(res).await;
+    assert_eq!(result["imported"], 1);
+    assert_eq!(result["skipped"], 1);
+    assert_eq!(result["errors"][0]["row"], 3);
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}", bill.id))
+        .to_request();
+    let stored: Bill = read_body_json(call_service(&app, req).await).await;
+    assert_eq!(stored.line_items.len(), 1);
+    assert_eq!(stored.line_items[0].description, "Pizza");
+}
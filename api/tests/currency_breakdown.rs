@@ -0,0 +1,68 @@
+use bill_splitter_api::{
+    models::{Bill, CurrencyCode, ExchangeRate, Money, Participant},
+    split::{compute_split, currency_breakdown, SplitSpec},
+    testing::{money, BillBuilder},
+};
+use rust_decimal::Decimal;
+
+fn code(code: &str) -> CurrencyCode {
+    CurrencyCode::try_from(code.to_string()).unwrap()
+}
+
+/// A USD bill with a 20.00 EUR item converted at 1.10 and a 10.00 USD item.
+fn trip() -> (Bill, Vec<Participant>) {
+    let (mut bill, participants) = BillBuilder::new("Trip")
+        .with_participant("Alice")
+        .with_participant("Bob")
+        .with_item_shared_by("Museum", 1, 20.0, &["Alice"])
+        .with_item_shared_by("Taxi", 1, 10.0, &["Alice", "Bob"])
+        .build()
+        .unwrap();
+
+    let rate: Decimal = "1.10".parse().unwrap();
+    bill.line_items[0] = bill.line_items[0].clone().converted(code("EUR"), rate);
+    bill.exchange_rates_used
+        .push(ExchangeRate::new(code("EUR"), code("USD"), rate));
+    (bill, participants)
+}
+
+#[test]
+fn itemised_shares_are_broken_down_by_their_items_currencies() {
+    let (bill, participants) = trip();
+    let split = compute_split(&bill, &participants, &SplitSpec::Itemised).unwrap();
+
+    let breakdown = currency_breakdown(&bill, &split);
+
+    let alice = &breakdown[0].currency_shares;
+    assert_eq!(alice.len(), 2);
+    assert_eq!(alice[0].currency, code("EUR"));
+    assert_eq!(alice[0].converted_amount, money("22.00"));
+    assert_eq!(alice[0].original_amount, money("20.00"));
+    assert_eq!(alice[0].rate_used, "1.10".parse().unwrap());
+    assert_eq!(alice[1].currency, code("USD"));
+    assert_eq!(alice[1].converted_amount, money("5.00"));
+    assert_eq!(alice[1].rate_used, Decimal::ONE);
+
+    let bob = &breakdown[1].currency_shares;
+    assert_eq!(bob.len(), 1);
+    assert_eq!(bob[0].converted_amount, money("5.00"));
+}
+
+#[test]
+fn other_methods_take_the_bills_currency_mix() {
+    let (bill, participants) = trip();
+    let split = compute_split(&bill, &participants, &SplitSpec::Equal).unwrap();
+
+    let breakdown = currency_breakdown(&bill, &split);
+
+    for (entry, share) in breakdown.iter().zip(&split.shares) {
+        let converted: Money = entry
+            .currency_shares
+            .iter()
+            .map(|part| part.converted_amount)
+            .sum();
+        assert_eq!(converted, share.amount_owed);
+        assert_eq!(entry.currency_shares[0].converted_amount, money("11.00"));
+        assert_eq!(entry.currency_shares[0].original_amount, money("10.00"));
+    }
+}
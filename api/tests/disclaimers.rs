@@ -0,0 +1,41 @@
+use bill_splitter_api::{
+    data::disclaimers::{disclaimer, jurisdictions},
+    models::{Bill, LineItem, Money},
+};
+use chrono::{TimeZone, Utc};
+use uuid::Uuid;
+
+fn bill() -> Bill {
+    let mut bill = Bill::new("Team offsite", None);
+    bill.created_at = Utc.with_ymd_and_hms(2026, 3, 4, 18, 30, 0).unwrap();
+    bill.add_participant(Uuid::new_v4());
+    bill.add_participant(Uuid::new_v4());
+    bill.add_participant(Uuid::new_v4());
+    bill.add_line_item(LineItem::new("Dinner", 1, Money::from_cents(12_345)));
+    bill
+}
+
+#[test]
+fn disclaimers_state_the_amount_date_and_participants() {
+    let text = disclaimer(&bill(), "US-CA").unwrap();
+
+    assert!(text.contains("123.45 USD"), "{text}");
+    assert!(text.contains("03/04/2026"), "{text}");
+    assert!(text.contains("3 participants"), "{text}");
+    assert!(text.contains("not a formal invoice"), "{text}");
+}
+
+#[test]
+fn dates_are_written_the_local_way() {
+    assert!(disclaimer(&bill(), "UK").unwrap().contains("04/03/2026"));
+    assert!(disclaimer(&bill(), "de").unwrap().contains("04.03.2026"));
+}
+
+#[test]
+fn unknown_jurisdictions_have_no_disclaimer() {
+    assert_eq!(disclaimer(&bill(), "US-NY"), None);
+    assert_eq!(
+        jurisdictions().collect::<Vec<_>>(),
+        ["US-CA", "UK", "DE", "FR"]
+    );
+}
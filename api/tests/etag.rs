@@ -0,0 +1,70 @@
+use actix_web::{
+    http::{header, StatusCode},
+    test::{call_service, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{app, config::Config, state::AppState, testing::BillBuilder};
+use serde_json::json;
+
+#[actix_web::test]
+async fn unchanged_bills_are_not_modified() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let repo = state.repo();
+    let (bill, participants) = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_item("Pizza", 1, 30.0)
+        .build()
+        .unwrap();
+    repo.put_participant(&participants[0]).await.unwrap();
+    repo.put_bill(&bill).await.unwrap();
+    let app = init_service(app(state)).await;
+    let uri = format!("/bills/{}", bill.id);
+
+    let res = call_service(&app, TestRequest::get().uri(&uri).to_request()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let etag = res
+        .headers()
+        .get(header::ETAG)
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert_eq!(etag, format!("\"{}\"", bill.etag()));
+
+    for if_none_match in [etag.clone(), format!("W/{etag}"), "*".to_string()] {
+        let req = TestRequest::get()
+            .uri(&uri)
+            .insert_header((header::IF_NONE_MATCH, if_none_match))
+            .to_request();
+        let res = call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(res.headers().get(header::ETAG).unwrap(), etag.as_str());
+    }
+
+    // Computing a split records a snapshot but leaves the ETag alone.
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split", bill.id))
+        .to_request();
+    assert_eq!(call_service(&app, req).await.status(), StatusCode::OK);
+    let req = TestRequest::get()
+        .uri(&uri)
+        .insert_header((header::IF_NONE_MATCH, etag.clone()))
+        .to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::NOT_MODIFIED
+    );
+
+    let req = TestRequest::patch()
+        .uri(&uri)
+        .set_json(json!({ "title": "Lunch" }))
+        .to_request();
+    assert!(call_service(&app, req).await.status().is_success());
+    let req = TestRequest::get()
+        .uri(&uri)
+        .insert_header((header::IF_NONE_MATCH, etag.clone()))
+        .to_request();
+    let res = call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_ne!(res.headers().get(header::ETAG).unwrap(), etag.as_str());
+}
@@ -0,0 +1,197 @@
+use actix_web::{
+    http::StatusCode,
+    test::{call_and_read_body_json, call_service, init_service, read_body_json, TestRequest},
+    web, App, HttpResponse, HttpServer,
+};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    models::{Bill, Money, Participant, MAX_PAYMENT_AMOUNT},
+    state::AppState,
+};
+use serde_json::{json, Value};
+
+/// Quotes 1 EUR = 1.10 USD; every other base currency is unknown.
+fn rate_service() -> String {
+    let server = HttpServer::new(|| {
+        App::new().route(
+            "/{from}",
+            web::get().to(|from: web::Path<String>| async move {
+                HttpResponse::Ok().json(if from.as_str() == "EUR" {
+                    json!({ "result": "success", "rates": { "USD": 1.1 } })
+                } else {
+                    json!({ "result": "error", "error-type": "unsupported-code" })
+                })
+            }),
+        )
+    })
+    .bind("127.0.0.1:0")
+    .unwrap();
+    let addr = server.addrs()[0];
+    actix_web::rt::spawn(server.run());
+    format!("http://{addr}")
+}
+
+/// A USD bill with Alice on it, priced through [`rate_service`].
+async fn seed() -> (web::Data<AppState>, Bill, Participant) {
+    let state = web::Data::new(AppState::new(Config {
+        exchange_rate_api_url: rate_service(),
+        ..Config::default()
+    }));
+    let alice = Participant::new("Alice", None);
+    let mut bill = Bill::new("Trip", None);
+    bill.add_participant(alice.id);
+    state.repo().put_participant(&alice).await.unwrap();
+    state.repo().put_bill(&bill).await.unwrap();
+    (state, bill, alice)
+}
+
+#[actix_web::test]
+async fn foreign_payments_keep_the_rate_they_were_converted_at() {
+    let (state, bill, alice) = seed().await;
+    let app = init_service(app(state.clone())).await;
+
+    let req = TestRequest::post()
+        .uri(&format!("/bills/{}/payments", bill.id))
+        .set_json(json!({ "participant_id": alice.id, "amount": "20.00", "currency": "EUR" }))
+        .to_request();
+    let payment: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(payment["amount"], "20.00");
+    assert_eq!(payment["currency"], "EUR");
+    assert_eq!(payment["conversion_rate"], "1.1");
+    assert_eq!(payment["converted_amount"], "22.00");
+
+    let req = TestRequest::post()
+        .uri(&format!("/bills/{}/payments", bill.id))
+        .set_json(json!({ "participant_id": alice.id, "amount": "5.00" }))
+        .to_request();
+    let payment: Value = call_and_read_body_json(&app, req).await;
+    assert!(payment["conversion_rate"].is_null());
+    assert!(payment["converted_amount"].is_null());
+
+    let req = TestRequest::post()
+        .uri(&format!("/bills/{}/payments", bill.id))
+        .set_json(json!({ "participant_id": alice.id, "amount": "5.00", "currency": "XYZ" }))
+        .to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+    let stored = state.repo().get_bill(bill.id).await.unwrap().unwrap();
+    assert_eq!(stored.payments.len(), 2);
+}
+
+#[actix_web::test]
+async fn rates_used_for_line_items_are_shown_on_the_bill() {
+    let (state, bill, _) = seed().await;
+    let app = init_service(app(state)).await;
+
+    let req = TestRequest::post()
+        .uri(&format!("/bills/{}/line-items", bill.id))
+        .set_json(json!({ "description": "Museum", "unit_price": "10.00", "currency": "EUR" }))
+        .to_request();
+    let item: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(item["unit_price"], "11.00");
+    assert_eq!(item["original_unit_price"], "10.00");
+    assert_eq!(item["currency"], "EUR");
+    let req = TestRequest::post()
+        .uri(&format!("/bills/{}/line-items", bill.id))
+        .set_json(json!({ "description": "Taxi", "unit_price": "4.00" }))
+        .to_request();
+    assert_eq!(call_service(&app, req).await.status(), StatusCode::CREATED);
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}", bill.id))
+        .to_request();
+    let stored: Value = call_and_read_body_json(&app, req).await;
+    let rates = stored["exchange_rates_used"].as_array().unwrap();
+    assert_eq!(rates.len(), 1);
+    assert_eq!(rates[0]["from"], "EUR");
+    assert_eq!(rates[0]["to"], "USD");
+    assert_eq!(rates[0]["rate"], "1.1");
+    assert!(rates[0]["fetched_at"].is_string());
+}
+
+#[actix_web::test]
+async fn manual_rates_override_live_ones_and_reprice_the_bill() {
+    let (state, bill, alice) = seed().await;
+    let app = init_service(app(state.clone())).await;
+    let uri = format!("/bills/{}/set-currency-conversion-rates", bill.id);
+
+    let req = TestRequest::post()
+        .uri(&format!("/bills/{}/line-items", bill.id))
+        .set_json(json!({ "description": "Museum", "unit_price": "10.00", "currency": "EUR" }))
+        .to_request();
+    assert_eq!(call_service(&app, req).await.status(), StatusCode::CREATED);
+    let req = TestRequest::post()
+        .uri(&format!("/bills/{}/payments", bill.id))
+        .set_json(json!({ "participant_id": alice.id, "amount": "20.00", "currency": "EUR" }))
+        .to_request();
+    assert_eq!(call_service(&app, req).await.status(), StatusCode::CREATED);
+
+    for rates in [
+        json!([]),
+        json!([{ "from": "EUR", "to": "USD", "rate": "0" }]),
+        json!([{ "from": "EUR", "to": "GBP", "rate": "0.9" }]),
+        json!([{ "from": "USD", "to": "USD", "rate": "1" }]),
+    ] {
+        let req = TestRequest::post().uri(&uri).set_json(&rates).to_request();
+        let res = call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST, "{rates}");
+    }
+
+    let req = TestRequest::post()
+        .uri(&uri)
+        .set_json(json!([{ "from": "EUR", "to": "USD", "rate": "1.2" }]))
+        .to_request();
+    let used: Value = call_and_read_body_json(&app, req).await;
+    let sources: Vec<&str> = used
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|rate| rate["source"].as_str().unwrap())
+        .collect();
+    assert_eq!(sources, ["live", "live", "manual"]);
+    assert_eq!(used[2]["rate"], "1.2");
+
+    let stored = state.repo().get_bill(bill.id).await.unwrap().unwrap();
+    assert_eq!(stored.line_items[0].unit_price.to_string(), "12.00");
+    assert_eq!(stored.payments[0].base_amount().to_string(), "24.00");
+
+    // New items in EUR use the manual rate rather than the live one.
+    let req = TestRequest::post()
+        .uri(&format!("/bills/{}/line-items", bill.id))
+        .set_json(json!({ "description": "Dinner", "unit_price": "5.00", "currency": "EUR" }))
+        .to_request();
+    let item: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(item["unit_price"], "6.00");
+}
+
+#[actix_web::test]
+async fn payments_are_capped_before_and_after_conversion() {
+    let (state, bill, alice) = seed().await;
+    let app = init_service(app(state.clone())).await;
+    let pay = |amount: String, currency: &str| {
+        TestRequest::post()
+            .uri(&format!("/bills/{}/payments", bill.id))
+            .set_json(json!({ "participant_id": alice.id, "amount": amount, "currency": currency }))
+            .to_request()
+    };
+    let over = MAX_PAYMENT_AMOUNT + Money::from_cents(1);
+
+    let res = call_service(&app, pay(over.to_string(), "USD")).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let body: Value = read_body_json(res).await;
+    assert_eq!(
+        body["message"],
+        format!("`amount` must not exceed {MAX_PAYMENT_AMOUNT}")
+    );
+    // Within the cap in euros, but not once converted to dollars.
+    let res = call_service(&app, pay(MAX_PAYMENT_AMOUNT.to_string(), "EUR")).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let res = call_service(&app, pay(MAX_PAYMENT_AMOUNT.to_string(), "USD")).await;
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let stored = state.repo().get_bill(bill.id).await.unwrap().unwrap();
+    assert_eq!(stored.payments.len(), 1);
+}
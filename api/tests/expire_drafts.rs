@@ -0,0 +1,89 @@
+use actix_web::{http::StatusCode, test, web};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    jobs::{expire_drafts, ExpiryReport},
+    models::{AuditAction, Bill, BillStatus},
+    state::AppState,
+};
+use chrono::{Duration, Utc};
+
+const ADMIN_KEY: &str = "admin-secret";
+
+fn state(draft_expiry_days: u32) -> web::Data<AppState> {
+    let config = Config {
+        admin_key: Some(ADMIN_KEY.to_string()),
+        draft_expiry_days,
+        ..Config::default()
+    };
+    web::Data::new(AppState::new(config))
+}
+
+fn bill(status: BillStatus, days_old: i64) -> Bill {
+    let mut bill = Bill::new("Dinner", None);
+    bill.status = status;
+    bill.created_at = Utc::now() - Duration::days(days_old);
+    bill
+}
+
+#[actix_web::test]
+async fn only_drafts_past_the_window_are_expired() {
+    let state = state(7);
+    let repo = state.repo();
+    let stale_draft = bill(BillStatus::Draft, 8);
+    let fresh_draft = bill(BillStatus::Draft, 6);
+    let stale_open = bill(BillStatus::Open, 30);
+    for bill in [&stale_draft, &fresh_draft, &stale_open] {
+        repo.put_bill(bill).await.unwrap();
+    }
+
+    let report = expire_drafts(&state, Utc::now()).await.unwrap();
+
+    assert_eq!(report, ExpiryReport { expired_count: 1 });
+    assert!(repo.get_bill(stale_draft.id).await.unwrap().is_none());
+    assert_eq!(
+        repo.get_deleted_bill(stale_draft.id).await.unwrap(),
+        Some(stale_draft.clone())
+    );
+    assert!(repo.get_bill(fresh_draft.id).await.unwrap().is_some());
+    assert!(repo.get_bill(stale_open.id).await.unwrap().is_some());
+
+    let audit = repo.get_audit_log(stale_draft.id).await.unwrap();
+    assert_eq!(audit.len(), 1);
+    assert_eq!(audit[0].action, AuditAction::AutoExpired);
+    assert_eq!(audit[0].reason, "Draft older than 7 days");
+    assert!(repo.get_audit_log(fresh_draft.id).await.unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn the_window_comes_from_the_config() {
+    let state = state(3);
+    let draft = bill(BillStatus::Draft, 4);
+    state.repo().put_bill(&draft).await.unwrap();
+
+    let app = test::init_service(app(state.clone())).await;
+    let req = test::TestRequest::post()
+        .uri("/admin/cron/expire-drafts")
+        .insert_header(("X-Admin-Key", ADMIN_KEY))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+
+    assert_eq!(body, serde_json::json!({ "expired_count": 1 }));
+    let req = test::TestRequest::get()
+        .uri(&format!("/bills/{}", draft.id))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let audit = state.repo().get_audit_log(draft.id).await.unwrap();
+    assert_eq!(audit[0].reason, "Draft older than 3 days");
+}
+
+#[actix_web::test]
+async fn running_the_job_needs_the_admin_key() {
+    let app = test::init_service(app(state(7))).await;
+    let req = test::TestRequest::post()
+        .uri("/admin/cron/expire-drafts")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
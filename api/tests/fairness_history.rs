@@ -0,0 +1,92 @@
+use actix_web::{
+    test::{call_and_read_body, call_and_read_body_json, call_service, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    models::{Money, ParticipantShare, SplitSnapshot},
+    split::{fairness_history, gini_coefficient},
+    state::AppState,
+    testing::BillBuilder,
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+fn shares(amounts: &[i64]) -> Vec<ParticipantShare> {
+    amounts
+        .iter()
+        .map(|cents| ParticipantShare {
+            participant_id: Uuid::new_v4(),
+            name: String::new(),
+            amount_owed: Money::from_cents(*cents),
+        })
+        .collect()
+}
+
+#[test]
+fn gini_is_zero_for_even_splits_and_grows_with_inequality() {
+    assert_eq!(gini_coefficient(&shares(&[1500, 1500, 1500])), 0.0);
+    assert_eq!(gini_coefficient(&shares(&[0, 0])), 0.0);
+    assert_eq!(gini_coefficient(&[]), 0.0);
+    // One of two owes everything.
+    assert_eq!(gini_coefficient(&shares(&[0, 3000])), 0.5);
+    assert_eq!(gini_coefficient(&shares(&[1000, 2000, 3000])), 0.2222);
+}
+
+#[test]
+fn old_snapshots_have_their_coefficient_worked_out() {
+    let mut snapshot: SplitSnapshot = serde_json::from_value(serde_json::json!({
+        "id": Uuid::new_v4(),
+        "method": "custom",
+        "computed_at": "2024-01-01T00:00:00Z",
+        "shares": [],
+    }))
+    .unwrap();
+    assert_eq!(snapshot.gini_coefficient, None);
+    snapshot.shares = shares(&[0, 3000]);
+    assert_eq!(fairness_history(&[snapshot])[0].gini_coefficient, 0.5);
+}
+
+#[actix_web::test]
+async fn history_follows_each_recorded_split() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let repo = state.repo();
+    let (bill, participants) = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_participant("Bob")
+        .with_item_shared_by("Steak", 1, 30.0, &["Alice"])
+        .with_item_shared_by("Salad", 1, 10.0, &["Bob"])
+        .build()
+        .unwrap();
+    for participant in &participants {
+        repo.put_participant(participant).await.unwrap();
+    }
+    repo.put_bill(&bill).await.unwrap();
+    let app = init_service(app(state.clone())).await;
+
+    for method in ["equal", "itemised"] {
+        let req = TestRequest::get()
+            .uri(&format!("/bills/{}/split?method={method}", bill.id))
+            .to_request();
+        call_service(&app, req).await;
+    }
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/fairness-history", bill.id))
+        .to_request();
+    let history: Value = call_and_read_body_json(&app, req).await;
+    let history = history.as_array().unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0]["method"], "equal");
+    assert_eq!(history[0]["gini_coefficient"], 0.0);
+    assert_eq!(history[1]["method"], "itemised");
+    assert_eq!(history[1]["gini_coefficient"], 0.25);
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/fairness-history?format=text", bill.id))
+        .to_request();
+    let text = String::from_utf8(call_and_read_body(&app, req).await.to_vec()).unwrap();
+    assert!(text.starts_with("▁█\n"));
+    assert!(text.contains("Gini 0.0000 -> 0.2500 over 2 splits"));
+}
@@ -0,0 +1,39 @@
+use bill_splitter_api::{
+    invoice::{bill_to_invoice, invoice_number, render_invoice_html},
+    models::{Bill, LineItem, Participant},
+    testing::money,
+};
+
+#[test]
+fn invoices_the_whole_bill_with_tax_split_out() {
+    let alice = Participant::new("Alice", Some("alice@example.com".to_string()));
+    let bob = Participant::new("Bob", None);
+    let mut bill = Bill::new("Dinner", None);
+    let mut wine = LineItem::new("Wine", 2, money("21.40"));
+    wine.tax_rate = Some("0.07".parse().unwrap());
+    bill.line_items.push(wine);
+    bill.line_items
+        .push(LineItem::new("Bread", 1, money("5.00")));
+    bill.discount = money("2.00");
+
+    let invoice = bill_to_invoice(&bill, &alice, &bob, 14);
+
+    assert_eq!(invoice.invoice_number, invoice_number(bill.id));
+    assert_eq!((invoice.due_date - invoice.issued_date).num_days(), 14);
+    assert_eq!(invoice.issuer.name, "Alice");
+    assert_eq!(invoice.recipient.participant_id, bob.id);
+    assert_eq!(invoice.line_items[0].amount, money("42.80"));
+    assert_eq!(invoice.total, money("45.80"));
+    assert_eq!(invoice.subtotal + invoice.tax, invoice.total);
+    assert!(invoice.payment_terms.starts_with("Net 14"));
+
+    let html = render_invoice_html(&invoice).unwrap();
+    assert!(html.contains(&invoice.invoice_number));
+    assert!(html.contains("Discount"));
+}
+
+#[test]
+fn invoice_numbers_use_the_bill_id_prefix() {
+    let id = "3f2a9c1b-0000-4000-8000-000000000000".parse().unwrap();
+    assert_eq!(invoice_number(id), "INV-3F2A9C1B");
+}
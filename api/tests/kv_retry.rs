@@ -0,0 +1,62 @@
+use std::cell::Cell;
+
+use bill_splitter_api::storage::{with_retry, KvError};
+
+fn unavailable() -> KvError {
+    KvError::Unavailable("busy".to_string())
+}
+
+#[actix_web::test]
+async fn transient_failures_are_retried_until_they_succeed() {
+    let attempts = Cell::new(0);
+    let result = with_retry(
+        || {
+            attempts.set(attempts.get() + 1);
+            let attempt = attempts.get();
+            async move {
+                if attempt < 3 {
+                    Err(unavailable())
+                } else {
+                    Ok(attempt)
+                }
+            }
+        },
+        4,
+        1,
+    )
+    .await;
+    assert_eq!(result.unwrap(), 3);
+    assert_eq!(attempts.get(), 3);
+}
+
+#[actix_web::test]
+async fn retries_stop_after_max_attempts() {
+    let attempts = Cell::new(0);
+    let result: Result<(), KvError> = with_retry(
+        || {
+            attempts.set(attempts.get() + 1);
+            async { Err(unavailable()) }
+        },
+        4,
+        1,
+    )
+    .await;
+    assert!(matches!(result, Err(KvError::Unavailable(_))));
+    assert_eq!(attempts.get(), 4);
+}
+
+#[actix_web::test]
+async fn logical_errors_are_not_retried() {
+    let attempts = Cell::new(0);
+    let result: Result<u8, KvError> = with_retry(
+        || {
+            attempts.set(attempts.get() + 1);
+            async { serde_json::from_str::<u8>("not a number").map_err(KvError::from) }
+        },
+        4,
+        1,
+    )
+    .await;
+    assert!(matches!(result, Err(KvError::Serialization(_))));
+    assert_eq!(attempts.get(), 1);
+}
@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+
+use actix_web::{
+    http::StatusCode,
+    test::{call_and_read_body_json, call_service, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    models::{Bill, LineItem, Money},
+    state::AppState,
+};
+use serde_json::json;
+
+fn bill_with(descriptions: &[&str]) -> Bill {
+    let mut bill = Bill::new("Dinner", None);
+    for description in descriptions {
+        bill.add_line_item(LineItem::new(*description, 1, Money::from_cents(500)));
+    }
+    bill
+}
+
+fn descriptions(items: &[LineItem]) -> Vec<&str> {
+    items.iter().map(|item| item.description.as_str()).collect()
+}
+
+#[test]
+fn new_line_items_go_after_the_last_one() {
+    let mut bill = bill_with(&["Pizza", "Salad"]);
+    bill.line_items[0].position = 7;
+    bill.line_items[1].position = 3;
+
+    let added = bill.add_line_item(LineItem::new("Wine", 1, Money::from_cents(900)));
+
+    assert_eq!(added.position, 8);
+}
+
+#[test]
+fn reordering_sorts_line_items_by_position() {
+    let mut bill = bill_with(&["Pizza", "Salad", "Wine"]);
+    let positions = HashMap::from([
+        (bill.line_items[0].id, 2),
+        (bill.line_items[1].id, 0),
+        (bill.line_items[2].id, 1),
+    ]);
+
+    bill.reorder_line_items(&positions);
+
+    assert_eq!(descriptions(&bill.line_items), ["Salad", "Wine", "Pizza"]);
+}
+
+#[actix_web::test]
+async fn reorder_endpoint_needs_exactly_the_bills_line_items() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let bill = bill_with(&["Pizza", "Salad"]);
+    state.repo().put_bill(&bill).await.unwrap();
+    let app = init_service(app(state)).await;
+    let uri = format!("/bills/{}/line-items/reorder", bill.id);
+    let (pizza, salad) = (bill.line_items[0].id, bill.line_items[1].id);
+
+    let missing = json!({ "reorder": [{ "line_item_id": pizza, "position": 0 }] });
+    let req = TestRequest::post().uri(&uri).set_json(missing).to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+
+    let reorder = json!({ "reorder": [
+        { "line_item_id": pizza, "position": 1 },
+        { "line_item_id": salad, "position": 0 }
+    ] });
+    let req = TestRequest::post().uri(&uri).set_json(reorder).to_request();
+    let items: Vec<LineItem> = call_and_read_body_json(&app, req).await;
+    assert_eq!(descriptions(&items), ["Salad", "Pizza"]);
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}", bill.id))
+        .to_request();
+    let stored: Bill = call_and_read_body_json(&app, req).await;
+    assert_eq!(descriptions(&stored.line_items), ["Salad", "Pizza"]);
+}
@@ -0,0 +1,75 @@
+use actix_web::{
+    http::StatusCode,
+    test::{call_and_read_body_json, call_service, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    models::{LineItem, Money, MAX_LINE_ITEM_TOTAL},
+    state::AppState,
+    testing::BillBuilder,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+#[test]
+fn repeated_participants_are_dropped_wherever_they_appear() {
+    let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+    let mut item = LineItem::new("Pizza", 1, Money::from_cents(3000));
+    item.set_participants(vec![a, b, a, b, a]);
+    assert_eq!(item.participant_ids, [a, b]);
+}
+
+#[test]
+fn checked_arithmetic_reports_overflow() {
+    let max = Money::from_cents(i64::MAX);
+    assert_eq!(max.checked_add(Money::from_cents(1)), None);
+    assert_eq!(max.checked_mul(2), None);
+    assert_eq!(
+        Money::from_cents(250).checked_mul(4),
+        Some(Money::from_cents(1000))
+    );
+
+    assert!(LineItem::new("Car", 1, MAX_LINE_ITEM_TOTAL).is_within_limit());
+    assert!(!LineItem::new("Cars", 2, MAX_LINE_ITEM_TOTAL).is_within_limit());
+    assert!(!LineItem::new("Grains", u32::MAX, Money::from_cents(i64::MAX / 2)).is_within_limit());
+}
+
+#[actix_web::test]
+async fn adding_line_items_dedupes_sharers_and_bounds_the_total() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let (bill, participants) = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_participant("Bob")
+        .build()
+        .unwrap();
+    state.repo().put_bill(&bill).await.unwrap();
+    let app = init_service(app(state)).await;
+    let uri = format!("/bills/{}/line-items", bill.id);
+    let (alice, bob) = (participants[0].id, participants[1].id);
+
+    let req = TestRequest::post()
+        .uri(&uri)
+        .set_json(json!({
+            "description": "Pizza",
+            "unit_price": "30.00",
+            "participant_ids": [alice, bob, alice]
+        }))
+        .to_request();
+    let item: LineItem = call_and_read_body_json(&app, req).await;
+    assert_eq!(item.participant_ids, [alice, bob]);
+
+    let req = TestRequest::post()
+        .uri(&uri)
+        .set_json(json!({
+            "description": "Everything",
+            "quantity": u32::MAX,
+            "unit_price": "90000000000000.00"
+        }))
+        .to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+}
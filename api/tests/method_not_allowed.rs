@@ -0,0 +1,99 @@
+use actix_web::{
+    dev::ServiceResponse,
+    http::{header, Method, StatusCode},
+    test, web,
+};
+use bill_splitter_api::{app, config::Config, state::AppState};
+use uuid::Uuid;
+
+fn state() -> web::Data<AppState> {
+    web::Data::new(AppState::new(Config::default()))
+}
+
+fn allow_header<B>(res: &ServiceResponse<B>) -> &str {
+    res.headers()
+        .get(header::ALLOW)
+        .expect("Allow header is set")
+        .to_str()
+        .unwrap()
+}
+
+#[actix_web::test]
+async fn wrong_method_on_get_only_route_is_405() {
+    let app = test::init_service(app(state())).await;
+    let req = test::TestRequest::post()
+        .uri(&format!("/bills/{}/taxes", Uuid::new_v4()))
+        .to_request();
+
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(allow_header(&res), "GET, OPTIONS");
+    let body: serde_json::Value = test::read_body_json(res).await;
+    assert_eq!(body["status"], 405);
+}
+
+#[actix_web::test]
+async fn wrong_method_on_post_only_route_is_405() {
+    let app = test::init_service(app(state())).await;
+    let req = test::TestRequest::get()
+        .uri(&format!("/bills/{}/line-items", Uuid::new_v4()))
+        .to_request();
+
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(allow_header(&res), "POST, OPTIONS");
+}
+
+#[actix_web::test]
+async fn wrong_method_on_nested_route_is_405() {
+    let app = test::init_service(app(state())).await;
+    let req = test::TestRequest::delete()
+        .uri(&format!("/bills/{}/split", Uuid::new_v4()))
+        .to_request();
+
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(allow_header(&res), "GET, OPTIONS");
+}
+
+#[actix_web::test]
+async fn unknown_path_is_still_404() {
+    let app = test::init_service(app(state())).await;
+    let req = test::TestRequest::post().uri("/no-such-route").to_request();
+
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    assert!(res.headers().get(header::ALLOW).is_none());
+}
+
+#[actix_web::test]
+async fn options_lists_the_same_methods() {
+    let app = test::init_service(app(state())).await;
+    let req = test::TestRequest::default()
+        .method(Method::OPTIONS)
+        .uri("/bills")
+        .to_request();
+
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    assert_eq!(allow_header(&res), "GET, POST, OPTIONS");
+}
+
+#[actix_web::test]
+async fn static_route_shadows_dynamic_sibling() {
+    let app = test::init_service(app(state())).await;
+    let req = test::TestRequest::default()
+        .method(Method::OPTIONS)
+        .uri("/bills/overdue")
+        .to_request();
+
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    assert_eq!(allow_header(&res), "GET, OPTIONS");
+}
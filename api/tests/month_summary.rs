@@ -0,0 +1,190 @@
+use actix_web::{http::StatusCode, test, web};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    models::{Bill, CurrencyCode, Participant, Payment, SplitSnapshot},
+    split::{compute_split, SplitSpec},
+    state::AppState,
+    testing::{money, BillBuilder},
+};
+use chrono::{Duration, TimeZone, Utc};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+const JWT_SECRET: &str = "jwt-secret";
+
+fn token(user_id: Uuid) -> String {
+    let claims = json!({
+        "sub": user_id,
+        "exp": (Utc::now() + Duration::hours(1)).timestamp(),
+    });
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap()
+}
+
+/// `total` split equally between `participants`, created on `day` of
+/// `month` 2024.
+fn bill(title: &str, total: f64, month: u32, day: u32, participants: &[&Participant]) -> Bill {
+    let (mut bill, participants) = participants
+        .iter()
+        .fold(BillBuilder::new(title), |builder, participant| {
+            builder.with_existing_participant(participant)
+        })
+        .with_item("Food", 1, total)
+        .build()
+        .unwrap();
+    bill.created_at = Utc.with_ymd_and_hms(2024, month, day, 12, 0, 0).unwrap();
+    let split = compute_split(&bill, &participants, &SplitSpec::Equal).unwrap();
+    bill.split_history.push(SplitSnapshot::from_result(&split));
+    bill
+}
+
+fn state() -> web::Data<AppState> {
+    web::Data::new(AppState::new(Config {
+        jwt_secret: Some(JWT_SECRET.to_string()),
+        ..Config::default()
+    }))
+}
+
+#[actix_web::test]
+async fn summarises_my_bills_from_the_month() {
+    let state = state();
+    let repo = state.repo();
+    let me = Participant::new("Me", None);
+    let alice = Participant::new("Alice", None);
+    for participant in [&me, &alice] {
+        repo.put_participant(participant).await.unwrap();
+    }
+
+    let mut dinner = bill("Dinner", 40.0, 12, 20, &[&me, &alice]);
+    dinner
+        .payments
+        .push(Payment::new(me.id, money("5.00"), None));
+    let mut lunch = bill("Lunch", 10.0, 12, 3, &[&me, &alice]);
+    lunch
+        .payments
+        .push(Payment::new(me.id, money("8.00"), None));
+    let november = bill("Taxi", 30.0, 11, 30, &[&me, &alice]);
+    let not_mine = bill("Cinema", 24.0, 12, 10, &[&alice]);
+    for bill in [&dinner, &lunch, &november, &not_mine] {
+        repo.put_bill(bill).await.unwrap();
+    }
+
+    let app = test::init_service(app(state.clone())).await;
+    let req = test::TestRequest::get()
+        .uri("/bills/month/2024/12")
+        .insert_header(("Authorization", format!("Bearer {}", token(me.id))))
+        .to_request();
+    let summary: Value = test::call_and_read_body_json(&app, req).await;
+
+    assert_eq!(summary["year"], 2024);
+    assert_eq!(summary["month"], 12);
+    let titles: Vec<&str> = summary["bills"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|bill| bill["title"].as_str().unwrap())
+        .collect();
+    assert_eq!(titles, ["Lunch", "Dinner"]);
+    assert_eq!(summary["bills"][1]["total"], "40.00");
+    assert_eq!(summary["bills"][1]["your_share"], "20.00");
+    // Lunch is overpaid by 3.00, which does not reduce what is owed on dinner.
+    assert_eq!(
+        summary["totals"],
+        json!([{
+            "currency": "USD",
+            "total_spent": "25.00",
+            "you_paid": "13.00",
+            "you_owe": "15.00"
+        }])
+    );
+}
+
+#[actix_web::test]
+async fn totals_are_kept_per_currency_and_include_bills_i_created() {
+    let state = state();
+    let repo = state.repo();
+    let me = Participant::new("Me", None);
+    let alice = Participant::new("Alice", None);
+    for participant in [&me, &alice] {
+        repo.put_participant(participant).await.unwrap();
+    }
+
+    let dinner = bill("Dinner", 40.0, 12, 20, &[&me, &alice]);
+    let mut street_food = bill("Street food", 300.0, 12, 5, &[&me, &alice]);
+    street_food.base_currency = CurrencyCode::try_from("THB".to_string()).unwrap();
+    street_food
+        .payments
+        .push(Payment::new(me.id, money("100.00"), None));
+    // Organised for Alice, without being on it.
+    let mut gift = bill("Gift", 50.0, 12, 8, &[&alice]);
+    gift.creator_id = Some(me.id);
+    for bill in [&dinner, &street_food, &gift] {
+        repo.put_bill(bill).await.unwrap();
+    }
+
+    let app = test::init_service(app(state.clone())).await;
+    let req = test::TestRequest::get()
+        .uri("/bills/month/2024/12")
+        .insert_header(("Authorization", format!("Bearer {}", token(me.id))))
+        .to_request();
+    let summary: Value = test::call_and_read_body_json(&app, req).await;
+
+    let titles: Vec<&str> = summary["bills"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|bill| bill["title"].as_str().unwrap())
+        .collect();
+    assert_eq!(titles, ["Street food", "Gift", "Dinner"]);
+    assert_eq!(summary["bills"][1]["your_share"], Value::Null);
+    assert_eq!(
+        summary["totals"],
+        json!([
+            {
+                "currency": "THB",
+                "total_spent": "150.00",
+                "you_paid": "100.00",
+                "you_owe": "50.00"
+            },
+            {
+                "currency": "USD",
+                "total_spent": "20.00",
+                "you_paid": "0.00",
+                "you_owe": "20.00"
+            }
+        ])
+    );
+}
+
+#[actix_web::test]
+async fn rejects_months_and_years_out_of_range() {
+    let app = test::init_service(app(state())).await;
+    for uri in [
+        "/bills/month/2024/0",
+        "/bills/month/2024/13",
+        "/bills/month/2019/6",
+    ] {
+        let req = test::TestRequest::get()
+            .uri(uri)
+            .insert_header(("Authorization", format!("Bearer {}", token(Uuid::new_v4()))))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST, "{uri}");
+    }
+}
+
+#[actix_web::test]
+async fn requires_authentication() {
+    let app = test::init_service(app(state())).await;
+    let req = test::TestRequest::get()
+        .uri("/bills/month/2024/12")
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
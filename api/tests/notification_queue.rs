@@ -0,0 +1,97 @@
+use actix_web::{http::StatusCode, test, web};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    models::{Money, Payment},
+    notifications::NotificationChannel,
+    queues::{email_consumer::process, DeadLetter, EmailTask},
+    state::AppState,
+    testing::BillBuilder,
+};
+use uuid::Uuid;
+
+const ADMIN_KEY: &str = "admin-secret";
+
+fn state() -> web::Data<AppState> {
+    let config = Config {
+        admin_key: Some(ADMIN_KEY.to_string()),
+        ..Config::default()
+    };
+    web::Data::new(AppState::new(config))
+}
+
+#[actix_web::test]
+async fn undeliverable_tasks_are_dead_lettered_and_can_be_replayed() {
+    let state = state();
+    let mut queue = state.notification_queue.take_receiver().unwrap();
+    let task = EmailTask::new(Uuid::new_v4(), Uuid::new_v4(), NotificationChannel::Email);
+
+    // The bill does not exist, so there is nothing to retry.
+    assert!(!process(&state, task.clone()).await);
+
+    let app = test::init_service(app(state.clone())).await;
+    let req = test::TestRequest::get()
+        .uri("/admin/dead-letter-queue")
+        .insert_header(("X-Admin-Key", ADMIN_KEY))
+        .to_request();
+    let letters: Vec<DeadLetter> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(letters.len(), 1);
+    assert_eq!(letters[0].task, task);
+    assert_eq!(letters[0].attempts, 1);
+    assert!(letters[0].error.contains("no longer exists"));
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/admin/dead-letter-queue/{}/replay", task.id))
+        .insert_header(("X-Admin-Key", ADMIN_KEY))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::ACCEPTED);
+    assert_eq!(queue.try_recv().unwrap(), task);
+    assert!(state.repo().list_dead_letters().await.unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn the_dead_letter_queue_needs_the_admin_key() {
+    let app = test::init_service(app(state())).await;
+    let req = test::TestRequest::get()
+        .uri("/admin/dead-letter-queue")
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn tasks_for_participants_who_paid_in_full_are_dropped() {
+    let state = state();
+    let repo = state.repo();
+    let (mut bill, participants) = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_item("Pizza", 1, 30.0)
+        .build()
+        .unwrap();
+    let alice = &participants[0];
+    repo.put_participant(alice).await.unwrap();
+    repo.put_bill(&bill).await.unwrap();
+    let app = test::init_service(app(state.clone())).await;
+    let req = test::TestRequest::get()
+        .uri(&format!("/bills/{}/split", bill.id))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    bill = repo.get_bill(bill.id).await.unwrap().unwrap();
+    bill.payments.push(Payment::new(
+        alice.id,
+        Money::from_decimal("30.00".parse().unwrap()),
+        None,
+    ));
+    repo.put_bill(&bill).await.unwrap();
+
+    let task = EmailTask::new(alice.id, bill.id, NotificationChannel::Email);
+    assert!(!process(&state, task).await);
+    assert!(repo.list_dead_letters().await.unwrap().is_empty());
+    assert!(repo
+        .get_notification_statuses(bill.id)
+        .await
+        .unwrap()
+        .is_empty());
+}
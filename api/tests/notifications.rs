@@ -0,0 +1,119 @@
+use bill_splitter_api::{
+    models::{Bill, Money, Participant},
+    notifications::{
+        email::{render_share_email, verify_mailgun_signature, MailgunWebhook},
+        sms::{compose_share_sms, MAX_SMS_LENGTH},
+        DeliveryReport,
+    },
+};
+use serde_json::json;
+
+const KEY: &str = "key-secret";
+const TIMESTAMP: i64 = 1_700_000_000;
+const SIGNATURE: &str = "5984f6ccb5077f55fc795018e60a1de9327ae3d9fe772bd224a2923de5c4228c";
+
+fn webhook(event: &str, signature: &str) -> MailgunWebhook {
+    serde_json::from_value(json!({
+        "signature": {
+            "timestamp": TIMESTAMP.to_string(),
+            "token": "abc123",
+            "signature": signature
+        },
+        "event-data": {
+            "event": event,
+            "message": { "headers": { "message-id": "20240101.abc@mg.example.com" } },
+            "delivery-status": { "description": "Mailbox full", "message": "" }
+        }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn mailgun_signature_is_hmac_of_timestamp_and_token() {
+    assert!(verify_mailgun_signature(
+        KEY,
+        &TIMESTAMP.to_string(),
+        "abc123",
+        SIGNATURE
+    ));
+    assert!(!verify_mailgun_signature(
+        "other-key",
+        &TIMESTAMP.to_string(),
+        "abc123",
+        SIGNATURE
+    ));
+    assert!(!verify_mailgun_signature(
+        KEY,
+        &TIMESTAMP.to_string(),
+        "abc123",
+        "not-hex"
+    ));
+}
+
+#[test]
+fn stale_mailgun_webhooks_are_rejected() {
+    let webhook = webhook("delivered", SIGNATURE);
+
+    assert!(webhook.is_authentic(KEY, TIMESTAMP + 60));
+    assert!(!webhook.is_authentic(KEY, TIMESTAMP + 60 * 60));
+}
+
+#[test]
+fn mailgun_events_map_to_delivery_reports() {
+    assert_eq!(
+        webhook("delivered", SIGNATURE).event_data.report(),
+        DeliveryReport::Delivered
+    );
+    assert_eq!(
+        webhook("failed", SIGNATURE).event_data.report(),
+        DeliveryReport::Failed("Mailbox full".to_string())
+    );
+    assert_eq!(
+        webhook("opened", SIGNATURE).event_data.report(),
+        DeliveryReport::Pending
+    );
+}
+
+#[test]
+fn share_emails_are_plain_text_and_not_html_escaped() {
+    let participant = Participant::new("O'Brien", None);
+    let mut bill = Bill::new("Tom & Jerry's <birthday>", None);
+    bill.notes = Some("Bring \"cake\"".to_string());
+
+    let body = render_share_email(&participant, &bill, Money::from_cents(1250)).unwrap();
+    assert!(body.starts_with("Hi O'Brien,"));
+    assert!(body.contains(r#"Your share of "Tom & Jerry's <birthday>" comes to 12.50."#));
+    assert!(body.contains(r#"Notes from the bill: Bring "cake""#));
+    assert!(!body.contains("&amp;") && !body.contains("&#x27;"));
+}
+
+const LINK: &str = "https://split.example/b/42";
+
+#[test]
+fn short_share_sms_are_sent_as_is() {
+    assert_eq!(
+        compose_share_sms("Alice", "12.50", "Dinner", LINK),
+        "Hi Alice, you owe 12.50 for Dinner. Pay at: https://split.example/b/42"
+    );
+}
+
+#[test]
+fn long_bill_titles_are_cut_with_three_dots() {
+    let title = "Team offsite ".repeat(20);
+    let message = compose_share_sms("Alice", "12.50", &title, LINK);
+    assert_eq!(message.chars().count(), MAX_SMS_LENGTH);
+    assert!(message.starts_with("Hi Alice, you owe 12.50 for Team offsite"));
+    assert!(message.ends_with(&format!("... Pay at: {LINK}")));
+    assert!(message.is_ascii());
+}
+
+#[test]
+fn long_names_are_cut_once_the_title_cannot_shrink_further() {
+    let name = "Bartholomew ".repeat(20);
+    let title = "Weekend away ".repeat(20);
+    let message = compose_share_sms(&name, "12.50", &title, LINK);
+    assert!(message.chars().count() <= MAX_SMS_LENGTH);
+    assert!(message.starts_with("Hi Bartholomew"));
+    assert!(message.contains("..., you owe 12.50 for ..."));
+    assert!(message.is_ascii());
+}
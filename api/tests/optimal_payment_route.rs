@@ -0,0 +1,111 @@
+use actix_web::{
+    http::StatusCode,
+    test::{call_and_read_body_json, call_service, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    models::{CurrencyCode, Money, Participant},
+    payment_links::{optimal_payment_route, PaymentMethod},
+    state::AppState,
+    testing::{money, BillBuilder},
+};
+use serde_json::Value;
+
+fn currency(code: &str) -> CurrencyCode {
+    CurrencyCode::try_from(code.to_string()).unwrap()
+}
+
+fn with_apps(venmo: bool, paypal: bool, cashapp: bool) -> Participant {
+    let mut participant = Participant::new("Alice", None);
+    participant.venmo_handle = venmo.then(|| "@alice".to_string());
+    participant.paypal_email = paypal.then(|| "alice@example.com".to_string());
+    participant.cashapp_tag = cashapp.then(|| "$alice".to_string());
+    participant
+}
+
+#[test]
+fn venmo_is_free_for_usd_under_its_limit() {
+    let route = optimal_payment_route(
+        &with_apps(true, true, false),
+        &currency("USD"),
+        money("50.00"),
+    );
+    assert_eq!(route.best_route, PaymentMethod::Venmo);
+    assert_eq!(route.estimated_fee, Money::ZERO);
+    assert_eq!(route.alternative_routes.len(), 1);
+    assert_eq!(route.alternative_routes[0].method, PaymentMethod::Paypal);
+    // 1.9% of 50.00 is 0.95, plus 0.10.
+    assert_eq!(route.alternative_routes[0].estimated_fee, money("1.05"));
+}
+
+#[test]
+fn paypal_is_used_where_the_free_apps_cannot_be() {
+    let participant = with_apps(true, true, true);
+
+    let over_limit = optimal_payment_route(&participant, &currency("USD"), money("3000.00"));
+    assert_eq!(over_limit.best_route, PaymentMethod::Cashapp);
+
+    let euros = optimal_payment_route(&participant, &currency("EUR"), money("3000.00"));
+    assert_eq!(euros.best_route, PaymentMethod::Paypal);
+    assert_eq!(euros.estimated_fee, money("57.10"));
+    assert!(euros.alternative_routes.is_empty());
+    assert!(euros.reason.contains("only payment app"));
+}
+
+#[test]
+fn falls_back_when_no_app_can_be_used() {
+    let none = optimal_payment_route(
+        &with_apps(false, false, false),
+        &currency("USD"),
+        money("10.00"),
+    );
+    assert_eq!(none.best_route, PaymentMethod::Cash);
+
+    let venmo_only = optimal_payment_route(
+        &with_apps(true, false, false),
+        &currency("EUR"),
+        money("10.00"),
+    );
+    assert_eq!(venmo_only.best_route, PaymentMethod::BankTransfer);
+    assert_eq!(venmo_only.reason, "Venmo only sends USD");
+}
+
+#[actix_web::test]
+async fn endpoint_routes_what_is_still_owed() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let repo = state.repo();
+    let (bill, mut participants) = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_participant("Bob")
+        .with_item("Pizza", 1, 40.0)
+        .build()
+        .unwrap();
+    participants[0].paypal_email = Some("alice@example.com".to_string());
+    for participant in &participants {
+        repo.put_participant(participant).await.unwrap();
+    }
+    repo.put_bill(&bill).await.unwrap();
+    let app = init_service(app(state.clone())).await;
+    let uri = format!(
+        "/bills/{}/participants/{}/optimal-payment-route",
+        bill.id, participants[0].id
+    );
+
+    let req = TestRequest::get().uri(&uri).to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split", bill.id))
+        .to_request();
+    call_service(&app, req).await;
+    let req = TestRequest::get().uri(&uri).to_request();
+    let route: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(route["best_route"], "paypal");
+    // 1.9% of 20.00 is 0.38, plus 0.10.
+    assert_eq!(route["estimated_fee"], "0.48");
+}
@@ -0,0 +1,168 @@
+use actix_web::{
+    http::StatusCode,
+    test::{call_and_read_body_json, call_service, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    models::{Bill, Participant},
+    state::AppState,
+    testing::BillBuilder,
+};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+const JWT_SECRET: &str = "jwt-secret";
+
+fn token(user_id: Uuid) -> String {
+    let claims = json!({
+        "sub": user_id,
+        "exp": (Utc::now() + Duration::hours(1)).timestamp(),
+    });
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap()
+}
+
+async fn dinner(state: &web::Data<AppState>) -> (Bill, Participant, Participant) {
+    let repo = state.repo();
+    let (bill, participants) = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_participant("Bob")
+        .with_item("Feast", 1, 20.0)
+        .build()
+        .unwrap();
+    for participant in &participants {
+        repo.put_participant(participant).await.unwrap();
+    }
+    repo.put_bill(&bill).await.unwrap();
+    let [alice, bob] = participants.try_into().unwrap();
+    (bill, alice, bob)
+}
+
+fn accept(bill: &Bill, participant: &Participant) -> TestRequest {
+    TestRequest::post().uri(&format!(
+        "/bills/{}/participants/{}/accept",
+        bill.id, participant.id
+    ))
+}
+
+#[actix_web::test]
+async fn the_split_lists_participants_who_have_not_accepted() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let (bill, alice, bob) = dinner(&state).await;
+    let app = init_service(app(state.clone())).await;
+
+    let accepted: Value = call_and_read_body_json(&app, accept(&bill, &alice).to_request()).await;
+    assert_eq!(accepted["participant_id"], json!(alice.id));
+    let again: Value = call_and_read_body_json(&app, accept(&bill, &alice).to_request()).await;
+    assert_eq!(again["accepted_at"], accepted["accepted_at"]);
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split", bill.id))
+        .to_request();
+    let split: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(split["unconfirmed_participants"], json!([bob.id]));
+
+    let changelog = state.repo().get_changelog(bill.id).await.unwrap();
+    assert!(changelog
+        .iter()
+        .any(|entry| entry.changes == ["Participant Alice accepted"]));
+}
+
+#[actix_web::test]
+async fn every_tax_mode_carries_unconfirmed_participants_and_the_warning() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let (bill, alice, bob) = dinner(&state).await;
+    let app = init_service(app(state.clone())).await;
+    call_service(&app, accept(&bill, &alice).to_request()).await;
+
+    let amounts = json!({ alice.id: "18.00", bob.id: "2.00" }).to_string();
+    for (tax_mode, field) in [
+        (None, "method"),
+        (Some("exclusive"), "total_pre_tax"),
+        (Some("inclusive"), "total_gross"),
+    ] {
+        let mut params = vec![
+            ("method", "custom"),
+            ("amounts", amounts.as_str()),
+            ("threshold", "1.5"),
+        ];
+        params.extend(tax_mode.map(|mode| ("tax_mode", mode)));
+        let req = TestRequest::get()
+            .uri(&format!(
+                "/bills/{}/split?{}",
+                bill.id,
+                serde_urlencoded::to_string(&params).unwrap()
+            ))
+            .to_request();
+        let split: Value = call_and_read_body_json(&app, req).await;
+        assert!(split.get(field).is_some(), "{tax_mode:?}: {split}");
+        assert_eq!(split["unconfirmed_participants"], json!([bob.id]));
+        assert_eq!(
+            split["inequality_warning"]["max_payer_id"],
+            json!(alice.id),
+            "{tax_mode:?}"
+        );
+    }
+}
+
+#[actix_web::test]
+async fn require_acceptance_refuses_to_split_until_everyone_accepts() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let (bill, alice, bob) = dinner(&state).await;
+    let app = init_service(app(state.clone())).await;
+    let split = || {
+        TestRequest::get()
+            .uri(&format!("/bills/{}/split?require_acceptance=true", bill.id))
+            .to_request()
+    };
+
+    call_service(&app, accept(&bill, &alice).to_request()).await;
+    assert_eq!(
+        call_service(&app, split()).await.status(),
+        StatusCode::CONFLICT
+    );
+
+    call_service(&app, accept(&bill, &bob).to_request()).await;
+    let resp = call_service(&app, split()).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn with_authentication_only_the_participant_can_accept() {
+    let state = web::Data::new(AppState::new(Config {
+        jwt_secret: Some(JWT_SECRET.to_string()),
+        ..Config::default()
+    }));
+    let (bill, alice, bob) = dinner(&state).await;
+    let app = init_service(app(state.clone())).await;
+
+    let as_bob = accept(&bill, &alice)
+        .insert_header(("Authorization", format!("Bearer {}", token(bob.id))))
+        .to_request();
+    assert_eq!(
+        call_service(&app, as_bob).await.status(),
+        StatusCode::FORBIDDEN
+    );
+
+    let as_alice = accept(&bill, &alice)
+        .insert_header(("Authorization", format!("Bearer {}", token(alice.id))))
+        .to_request();
+    assert_eq!(call_service(&app, as_alice).await.status(), StatusCode::OK);
+
+    let stranger = Participant::new("Eve", None);
+    let req = accept(&bill, &stranger)
+        .insert_header(("Authorization", format!("Bearer {}", token(stranger.id))))
+        .to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::NOT_FOUND
+    );
+}
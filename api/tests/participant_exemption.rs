@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+
+use actix_web::{
+    http::StatusCode,
+    test::{call_and_read_body_json, call_service, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    explain::explain_split,
+    models::{Bill, LineItem, Money, Participant, ParticipantShare},
+    receipt::PersonalReceipt,
+    split::{compute_split, currency_breakdown, split_tax_exclusive, SplitError, SplitSpec},
+    state::AppState,
+    testing::{money, BillBuilder},
+};
+use chrono::Utc;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+
+fn owed(shares: &[ParticipantShare]) -> Vec<Money> {
+    shares.iter().map(|share| share.amount_owed).collect()
+}
+
+/// Alice, Bob and a designated driver, Carol, who is exempt.
+fn dinner() -> (Bill, Vec<Participant>) {
+    let (mut bill, participants) = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_participant("Bob")
+        .with_participant("Carol")
+        .build()
+        .unwrap();
+    bill.exempt_participant(participants[2].id, "Designated driver", Utc::now());
+    (bill, participants)
+}
+
+#[test]
+fn exempt_participants_owe_nothing_in_equal_and_itemised_splits() {
+    let (mut bill, participants) = dinner();
+    let (alice, bob, carol) = (&participants[0], &participants[1], &participants[2]);
+    let mut steak = LineItem::new("Steak", 1, money("30.00"));
+    steak.participant_ids = vec![alice.id];
+    bill.add_line_item(steak);
+    let mut shared = LineItem::new("Wine", 1, money("21.00"));
+    shared.participant_ids = vec![alice.id, bob.id, carol.id];
+    bill.add_line_item(shared);
+    let mut soda = LineItem::new("Soda", 1, money("3.00"));
+    soda.participant_ids = vec![carol.id];
+    bill.add_line_item(soda);
+
+    let equal = compute_split(&bill, &participants, &SplitSpec::Equal).unwrap();
+    assert_eq!(
+        owed(&equal.shares),
+        vec![money("27.00"), money("27.00"), Money::ZERO]
+    );
+
+    // Carol's part of the wine, and the soda only she had, fall to the others.
+    let itemised = compute_split(&bill, &participants, &SplitSpec::Itemised).unwrap();
+    assert_eq!(
+        owed(&itemised.shares),
+        vec![money("42.00"), money("12.00"), Money::ZERO]
+    );
+}
+
+#[test]
+fn breakdowns_of_an_itemised_split_agree_with_it_for_exempt_participants() {
+    let (mut bill, participants) = dinner();
+    let (alice, bob, carol) = (&participants[0], &participants[1], &participants[2]);
+    let mut steak = LineItem::new("Steak", 1, money("30.00"));
+    steak.participant_ids = vec![alice.id];
+    bill.add_line_item(steak);
+    let mut wine = LineItem::new("Wine", 1, money("21.00"));
+    wine.participant_ids = vec![alice.id, bob.id, carol.id];
+    wine.tax_rate = Some("0.05".parse().unwrap());
+    bill.add_line_item(wine);
+    let mut soda = LineItem::new("Soda", 1, money("3.00"));
+    soda.participant_ids = vec![carol.id];
+    bill.add_line_item(soda);
+    let split = compute_split(&bill, &participants, &SplitSpec::Itemised).unwrap();
+
+    // The wine's 1.00 of tax falls on the two who pay for it.
+    let taxed = split_tax_exclusive(&bill, &split);
+    let tax: Vec<Money> = taxed.shares.iter().map(|share| share.tax_amount).collect();
+    assert_eq!(tax, vec![money("0.50"), money("0.50"), Money::ZERO]);
+    assert_eq!(taxed.shares[2].pre_tax_amount, Money::ZERO);
+
+    let breakdown = currency_breakdown(&bill, &split);
+    for (entry, share) in breakdown.iter().zip(&split.shares) {
+        let converted: Money = entry
+            .currency_shares
+            .iter()
+            .map(|currency| currency.converted_amount)
+            .sum();
+        assert_eq!(converted, share.amount_owed);
+    }
+
+    let alice_receipt = PersonalReceipt::new(&bill, &split.shares[0]);
+    let shared_with: Vec<(&str, usize)> = alice_receipt
+        .your_items
+        .iter()
+        .map(|item| (item.description.as_str(), item.shared_with))
+        .collect();
+    assert_eq!(shared_with, [("Steak", 1), ("Wine", 2), ("Soda", 2)]);
+    assert_eq!(alice_receipt.subtotal, split.shares[0].amount_owed);
+    let carol_receipt = PersonalReceipt::new(&bill, &split.shares[2]);
+    assert!(carol_receipt.your_items.is_empty());
+    assert_eq!(carol_receipt.tax, Money::ZERO);
+
+    let steps = explain_split(&bill, split.method, &split.shares);
+    let carol_step = steps
+        .iter()
+        .find(|step| step.description.starts_with("Carol"))
+        .unwrap();
+    assert!(
+        carol_step.description.contains("no items"),
+        "{carol_step:?}"
+    );
+}
+
+#[test]
+fn exempt_participants_owe_nothing_in_proportional_and_custom_splits() {
+    let (mut bill, participants) = dinner();
+    let (alice, bob, carol) = (&participants[0], &participants[1], &participants[2]);
+    bill.add_line_item(LineItem::new("Pizza", 1, money("30.00")));
+
+    let weights = HashMap::from([(alice.id, Decimal::from(2)), (bob.id, Decimal::ONE)]);
+    let proportional =
+        compute_split(&bill, &participants, &SplitSpec::Proportional { weights }).unwrap();
+    assert_eq!(
+        owed(&proportional.shares),
+        vec![money("20.00"), money("10.00"), Money::ZERO]
+    );
+
+    let amounts = HashMap::from([
+        (alice.id, money("10.00")),
+        (bob.id, money("10.00")),
+        (carol.id, money("10.00")),
+    ]);
+    let custom = compute_split(&bill, &participants, &SplitSpec::Custom { amounts }).unwrap();
+    assert_eq!(
+        owed(&custom.shares),
+        vec![money("15.00"), money("15.00"), Money::ZERO]
+    );
+}
+
+#[test]
+fn a_bill_where_everyone_is_exempt_cannot_be_split() {
+    let (mut bill, participants) = dinner();
+    bill.add_line_item(LineItem::new("Pizza", 1, money("30.00")));
+    for participant in &participants {
+        bill.exempt_participant(participant.id, "Ate nothing", Utc::now());
+    }
+    assert!(matches!(
+        compute_split(&bill, &participants, &SplitSpec::Equal),
+        Err(SplitError::AllExempt)
+    ));
+}
+
+#[actix_web::test]
+async fn exempt_zero_shares_do_not_trigger_the_inequality_warning() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let repo = state.repo();
+    let (mut bill, participants) = dinner();
+    bill.add_line_item(LineItem::new("Pizza", 1, money("30.00")));
+    for participant in &participants {
+        repo.put_participant(participant).await.unwrap();
+    }
+    repo.put_bill(&bill).await.unwrap();
+    let app = init_service(app(state.clone())).await;
+
+    // 15/15/0 would be 1.5 times the average with Carol's share counted.
+    let split: Value = call_and_read_body_json(
+        &app,
+        TestRequest::get()
+            .uri(&format!("/bills/{}/split?threshold=1.4", bill.id))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(split["inequality_warning"], Value::Null);
+    let warning: Value = call_and_read_body_json(
+        &app,
+        TestRequest::get()
+            .uri(&format!(
+                "/bills/{}/split-inequality-warning?threshold=1.4",
+                bill.id
+            ))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(warning, json!({ "warning": false }));
+}
+
+#[actix_web::test]
+async fn exemptions_can_be_set_shown_and_removed() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let repo = state.repo();
+    let alice = Participant::new("Alice", None);
+    let bob = Participant::new("Bob", None);
+    let mut bill = Bill::new("Dinner", None);
+    for participant in [&alice, &bob] {
+        repo.put_participant(participant).await.unwrap();
+        bill.add_participant(participant.id);
+    }
+    bill.add_line_item(LineItem::new("Pizza", 1, money("30.00")));
+    repo.put_bill(&bill).await.unwrap();
+    let app = init_service(app(state.clone())).await;
+    let exempt_uri = format!("/bills/{}/participants/{}/exempt", bill.id, bob.id);
+
+    let req = TestRequest::post()
+        .uri(&exempt_uri)
+        .set_json(json!({ "reason": "  " }))
+        .to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+
+    let req = TestRequest::post()
+        .uri(&exempt_uri)
+        .set_json(json!({ "reason": "Designated driver" }))
+        .to_request();
+    let body: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(body["exemption"]["reason"], "Designated driver");
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split", bill.id))
+        .to_request();
+    let split: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(split["shares"][0]["amount_owed"], "30.00");
+    assert_eq!(split["shares"][1]["amount_owed"], "0.00");
+    assert_eq!(split["exemptions"][0]["participant_id"], bob.id.to_string());
+    assert_eq!(split["exemptions"][0]["reason"], "Designated driver");
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split/breakdown/compact", bill.id))
+        .to_request();
+    let compact: Value = call_and_read_body_json(&app, req).await;
+    assert!(compact[0].get("exempt_reason").is_none());
+    assert_eq!(compact[1]["exempt_reason"], "Designated driver");
+
+    let req = TestRequest::delete().uri(&exempt_uri).to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::NO_CONTENT
+    );
+    let req = TestRequest::delete().uri(&exempt_uri).to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::NOT_FOUND
+    );
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split", bill.id))
+        .to_request();
+    let split: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(split["shares"][1]["amount_owed"], "15.00");
+    assert!(split.get("exemptions").is_none());
+
+    let changes: Vec<String> = repo
+        .get_changelog(bill.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .flat_map(|entry| entry.changes)
+        .collect();
+    assert!(changes.contains(&"Participant Bob exempted: Designated driver".to_string()));
+    assert!(changes.contains(&"Participant Bob no longer exempt".to_string()));
+}
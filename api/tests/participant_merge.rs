@@ -0,0 +1,155 @@
+use actix_web::{
+    http::StatusCode,
+    test::{call_and_read_body_json, call_service, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    models::{Bill, LineItem, Participant, Payment, SplitSnapshot},
+    split::{compute_split, SplitSpec},
+    state::AppState,
+    testing::money,
+};
+use serde_json::{json, Value};
+
+fn split_equally(bill: &mut Bill, participants: &[Participant]) {
+    let split = compute_split(bill, participants, &SplitSpec::Equal).unwrap();
+    bill.split_history.push(SplitSnapshot::from_result(&split));
+}
+
+#[test]
+fn merging_on_one_bill_combines_shares_and_items() {
+    let bob = Participant::new("Bob", None);
+    let robert = Participant::new("Robert", None);
+    let alice = Participant::new("Alice", None);
+    let mut bill = Bill::new("Dinner", None);
+    for participant in [&alice, &bob, &robert] {
+        bill.add_participant(participant.id);
+    }
+    let mut item = LineItem::new("Pizza", 1, money("30.00"));
+    item.participant_ids = vec![bob.id, robert.id];
+    bill.add_line_item(item);
+    bill.payer_id = Some(bob.id);
+    split_equally(&mut bill, &[alice.clone(), bob.clone(), robert.clone()]);
+    bill.payments
+        .push(Payment::new(bob.id, money("4.00"), None));
+
+    let transferred = bill.merge_participant(bob.id, &robert);
+
+    assert_eq!(transferred, 1);
+    assert_eq!(bill.participant_ids(), [alice.id, robert.id]);
+    assert_eq!(bill.line_items[0].participant_ids, [robert.id]);
+    assert_eq!(bill.payer_id, Some(robert.id));
+    let shares = &bill.latest_split().unwrap().shares;
+    assert_eq!(shares.len(), 2);
+    assert_eq!(
+        shares
+            .iter()
+            .find(|share| share.participant_id == robert.id)
+            .unwrap()
+            .amount_owed,
+        money("20.00")
+    );
+    assert_eq!(bill.outstanding(robert.id), Some(money("16.00")));
+}
+
+fn confirmed(uri: &str, merge_into: impl serde::Serialize) -> TestRequest {
+    TestRequest::post()
+        .uri(uri)
+        .insert_header(("X-Confirm", "merge"))
+        .set_json(json!({ "merge_into": merge_into }))
+}
+
+#[actix_web::test]
+async fn merges_across_every_bill_and_deletes_the_source() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let repo = state.repo();
+    let bob = Participant::new("Bob", None);
+    let robert = Participant::new("Robert", None);
+    let alice = Participant::new("Alice", None);
+    for participant in [&bob, &robert, &alice] {
+        repo.put_participant(participant).await.unwrap();
+    }
+
+    let mut shared = Bill::new("Dinner", None);
+    shared.add_participant(bob.id);
+    shared.add_participant(robert.id);
+    shared
+        .payments
+        .push(Payment::new(bob.id, money("5.00"), None));
+    let mut only_bob = Bill::new("Taxi", None);
+    only_bob.add_participant(alice.id);
+    only_bob.add_participant(bob.id);
+    only_bob
+        .payments
+        .push(Payment::new(bob.id, money("2.00"), None));
+    only_bob
+        .payments
+        .push(Payment::new(alice.id, money("2.00"), None));
+    let unrelated = Bill::new("Cinema", None);
+    for bill in [&shared, &only_bob, &unrelated] {
+        repo.put_bill(bill).await.unwrap();
+    }
+
+    let app = init_service(app(state.clone())).await;
+    let uri = format!("/participants/{}/merge", bob.id);
+    let result: Value =
+        call_and_read_body_json(&app, confirmed(&uri, robert.id).to_request()).await;
+
+    assert_eq!(
+        result,
+        json!({ "bills_updated": 2, "payments_transferred": 2 })
+    );
+    assert!(repo.get_participant(bob.id).await.unwrap().is_none());
+    let taxi = repo.get_bill(only_bob.id).await.unwrap().unwrap();
+    assert_eq!(taxi.participant_ids(), [alice.id, robert.id]);
+    assert_eq!(taxi.payments_by(robert.id).count(), 1);
+    let mut robert_bills = repo.participant_bill_ids(robert.id).await.unwrap();
+    robert_bills.sort();
+    let mut expected = vec![shared.id, only_bob.id];
+    expected.sort();
+    assert_eq!(robert_bills, expected);
+}
+
+#[actix_web::test]
+async fn merging_is_validated() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let repo = state.repo();
+    let bob = Participant::new("Bob", None);
+    let robert = Participant::new("Robert", None);
+    for participant in [&bob, &robert] {
+        repo.put_participant(participant).await.unwrap();
+    }
+    let mut bill = Bill::new("Taxi", None);
+    bill.add_participant(bob.id);
+    repo.put_bill(&bill).await.unwrap();
+
+    let app = init_service(app(state.clone())).await;
+    let uri = format!("/participants/{}/merge", bob.id);
+
+    let unconfirmed = TestRequest::post()
+        .uri(&uri)
+        .set_json(json!({ "merge_into": robert.id }))
+        .to_request();
+    assert_eq!(
+        call_service(&app, unconfirmed).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+    let no_common_bill = confirmed(&uri, robert.id).to_request();
+    assert_eq!(
+        call_service(&app, no_common_bill).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+    let unknown = confirmed(&uri, uuid::Uuid::new_v4()).to_request();
+    assert_eq!(
+        call_service(&app, unknown).await.status(),
+        StatusCode::NOT_FOUND
+    );
+    let itself = confirmed(&uri, bob.id).to_request();
+    assert_eq!(
+        call_service(&app, itself).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+    assert!(repo.get_participant(bob.id).await.unwrap().is_some());
+}
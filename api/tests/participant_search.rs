@@ -0,0 +1,103 @@
+use actix_web::{
+    http::StatusCode,
+    test::{call_and_read_body_json, call_service, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    models::Participant,
+    search::{name_score, MAX_SEARCH_RESULTS},
+    state::AppState,
+};
+use serde_json::Value;
+
+#[test]
+fn names_match_on_the_whole_name_or_any_word() {
+    assert!(name_score("ali", "Alice").unwrap() > 0.9);
+    assert!(name_score("SMITH", "Alice Smith").unwrap() == 1.0);
+    assert!(name_score("ali", "Bob").is_none());
+}
+
+#[actix_web::test]
+async fn name_index_follows_renames_and_deletes() {
+    let state = AppState::new(Config::default());
+    let repo = state.repo();
+    let mut alice = Participant::new("Alice", None);
+    repo.put_participant(&alice).await.unwrap();
+    assert_eq!(
+        repo.participant_ids_named(" ALICE ").await.unwrap(),
+        [alice.id]
+    );
+
+    alice.name = "Alicia".to_string();
+    repo.put_participant(&alice).await.unwrap();
+    assert!(repo
+        .participant_ids_named("alice")
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(repo.participant_names().await.unwrap(), ["alicia"]);
+
+    repo.delete_participant(alice.id).await.unwrap();
+    assert!(repo.participant_names().await.unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn searches_names_and_email_prefixes_by_relevance() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let repo = state.repo();
+    for participant in [
+        Participant::new("Alice", None),
+        Participant::new("Alison Brown", None),
+        Participant::new("Bob", Some("ali.b@example.com".to_string())),
+        Participant::new("Carol", Some("carol@example.com".to_string())),
+    ] {
+        repo.put_participant(&participant).await.unwrap();
+    }
+    let app = init_service(app(state.clone())).await;
+
+    let req = TestRequest::get()
+        .uri("/participants/search?q=ali")
+        .to_request();
+    let results: Value = call_and_read_body_json(&app, req).await;
+    let names: Vec<&str> = results
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|result| result["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["Bob", "Alice", "Alison Brown"]);
+    assert_eq!(results[0]["score"], 1.0);
+    assert_eq!(results[0]["email"], "ali.b@example.com");
+
+    let req = TestRequest::get()
+        .uri("/participants/search?q=ali&limit=1")
+        .to_request();
+    let results: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(results.as_array().unwrap().len(), 1);
+
+    let req = TestRequest::get()
+        .uri("/participants/search?q=%20")
+        .to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+}
+
+#[actix_web::test]
+async fn never_returns_more_than_twenty() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    for i in 0..MAX_SEARCH_RESULTS + 5 {
+        let participant = Participant::new(format!("Sam {i}"), None);
+        state.repo().put_participant(&participant).await.unwrap();
+    }
+    let app = init_service(app(state.clone())).await;
+
+    let req = TestRequest::get()
+        .uri("/participants/search?q=sam&limit=100")
+        .to_request();
+    let results: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(results.as_array().unwrap().len(), MAX_SEARCH_RESULTS);
+}
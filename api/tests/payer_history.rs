@@ -0,0 +1,90 @@
+use actix_web::{
+    http::StatusCode,
+    test::{call_and_read_body_json, call_service, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    models::{AuditAction, PayerChange},
+    state::AppState,
+    testing::BillBuilder,
+};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::json;
+use uuid::Uuid;
+
+const JWT_SECRET: &str = "jwt-secret";
+
+fn token(name: &str) -> String {
+    let claims = json!({
+        "sub": Uuid::new_v4(),
+        "name": name,
+        "exp": (Utc::now() + Duration::hours(1)).timestamp(),
+    });
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap()
+}
+
+#[actix_web::test]
+async fn every_payer_change_is_kept() {
+    let state = web::Data::new(AppState::new(Config {
+        jwt_secret: Some(JWT_SECRET.to_string()),
+        ..Config::default()
+    }));
+    let repo = state.repo();
+    let (bill, participants) = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_participant("Bob")
+        .with_item("Pizza", 1, 30.0)
+        .build()
+        .unwrap();
+    for participant in &participants {
+        repo.put_participant(participant).await.unwrap();
+    }
+    repo.put_bill(&bill).await.unwrap();
+    let (alice, bob) = (&participants[0], &participants[1]);
+    let app = init_service(app(state.clone())).await;
+    let history_uri = format!("/bills/{}/payer-history", bill.id);
+
+    let history: Vec<PayerChange> =
+        call_and_read_body_json(&app, TestRequest::get().uri(&history_uri).to_request()).await;
+    assert!(history.is_empty());
+
+    for payer in [alice, bob, bob] {
+        let req = TestRequest::put()
+            .uri(&format!("/bills/{}/payer", bill.id))
+            .insert_header(("Authorization", format!("Bearer {}", token("Dana"))))
+            .set_json(json!({ "participant_id": payer.id }))
+            .to_request();
+        assert_eq!(call_service(&app, req).await.status(), StatusCode::OK);
+    }
+
+    let history: Vec<PayerChange> =
+        call_and_read_body_json(&app, TestRequest::get().uri(&history_uri).to_request()).await;
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].previous_payer_id, None);
+    assert_eq!(history[0].new_payer_id, Some(alice.id));
+    assert_eq!(history[1].previous_payer_id, Some(alice.id));
+    assert_eq!(history[1].new_payer_id, Some(bob.id));
+    assert_eq!(history[1].changed_by.as_deref(), Some("Dana"));
+    assert!(history[0].changed_at <= history[1].changed_at);
+
+    let audit = repo.get_audit_log(bill.id).await.unwrap();
+    assert!(audit
+        .iter()
+        .all(|entry| entry.action == AuditAction::PayerChanged));
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/payer-history", Uuid::new_v4()))
+        .to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::NOT_FOUND
+    );
+}
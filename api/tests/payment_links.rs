@@ -0,0 +1,89 @@
+use bill_splitter_api::{
+    models::{Bill, Participant},
+    payment_links::{
+        cashapp_link, co_payment_link, suggest_payment_method, venmo_link, PaymentMethod,
+    },
+    testing::money,
+};
+
+#[test]
+fn venmo_links_encode_the_note() {
+    assert_eq!(
+        venmo_link("@alice-smith", money("12.5"), "Friday dinner & drinks"),
+        "venmo://paycharge?txn=pay&recipients=alice-smith&amount=12.50&note=Friday+dinner+%26+drinks"
+    );
+}
+
+#[test]
+fn cashapp_links_accept_tags_with_or_without_the_dollar() {
+    assert_eq!(
+        cashapp_link("$alice", money("3")),
+        "https://cash.app/$alice/3.00"
+    );
+    assert_eq!(
+        cashapp_link("alice", money("3")),
+        "https://cash.app/$alice/3.00"
+    );
+}
+
+#[test]
+fn only_apps_the_payee_uses_get_links() {
+    let bill = Bill::new("Dinner", None);
+    let mut alice = Participant::new("Alice", None);
+    alice.paypal_email = Some("alice@example.com".to_string());
+    let bob = Participant::new("Bob", None);
+
+    let link = co_payment_link(&bill, &bob, &alice, money("10"));
+
+    assert_eq!(link.participant_id, bob.id);
+    assert!(link.venmo_link.is_none());
+    assert!(link.cashapp_link.is_none());
+    assert!(link
+        .paypal_link
+        .unwrap()
+        .contains("business=alice%40example.com&amount=10.00&currency_code=USD"));
+}
+
+fn with_apps(phone: Option<&str>, venmo: bool, paypal: bool, cashapp: bool) -> Participant {
+    let mut participant = Participant::new("Alice", None);
+    participant.phone = phone.map(str::to_string);
+    participant.venmo_handle = venmo.then(|| "alice-smith".to_string());
+    participant.paypal_email = paypal.then(|| "alice@example.com".to_string());
+    participant.cashapp_tag = cashapp.then(|| "$alice".to_string());
+    participant
+}
+
+#[test]
+fn participants_without_payment_apps_are_pointed_to_cash() {
+    let suggestion = suggest_payment_method(&with_apps(Some("+15551234567"), false, false, false));
+
+    assert_eq!(suggestion.recommended_method, PaymentMethod::Cash);
+}
+
+#[test]
+fn the_phone_number_picks_between_registered_apps() {
+    let us = with_apps(Some("+15551234567"), true, true, true);
+    let uk = with_apps(Some("+447700900123"), false, true, true);
+    let unknown = with_apps(None, true, true, false);
+
+    assert_eq!(
+        suggest_payment_method(&us).recommended_method,
+        PaymentMethod::Venmo
+    );
+    assert_eq!(
+        suggest_payment_method(&uk).recommended_method,
+        PaymentMethod::Paypal
+    );
+    assert_eq!(
+        suggest_payment_method(&unknown).recommended_method,
+        PaymentMethod::Paypal
+    );
+}
+
+#[test]
+fn apps_unavailable_in_their_country_fall_back_to_a_bank_transfer() {
+    let suggestion = suggest_payment_method(&with_apps(Some("+33612345678"), true, false, false));
+
+    assert_eq!(suggestion.recommended_method, PaymentMethod::BankTransfer);
+    assert!(suggestion.reason.contains("Venmo"));
+}
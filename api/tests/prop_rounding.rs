@@ -0,0 +1,95 @@
+use bill_splitter_api::{
+    models::{Money, ParticipantShare},
+    split::distribute_rounding_remainder,
+};
+use proptest::prelude::*;
+use uuid::Uuid;
+
+fn share(name: &str, cents: i64) -> ParticipantShare {
+    ParticipantShare {
+        participant_id: Uuid::new_v4(),
+        name: name.to_string(),
+        amount_owed: Money::from_cents(cents),
+    }
+}
+
+fn shares_strategy() -> impl Strategy<Value = Vec<ParticipantShare>> {
+    prop::collection::vec(("[A-Za-z]{1,8}", 0i64..1_000_000), 1..12).prop_map(|rows| {
+        rows.into_iter()
+            .map(|(name, cents)| share(&name, cents))
+            .collect()
+    })
+}
+
+fn first_by_name(shares: &[ParticipantShare]) -> Uuid {
+    shares
+        .iter()
+        .min_by(|a, b| (&a.name, a.participant_id).cmp(&(&b.name, b.participant_id)))
+        .map(|share| share.participant_id)
+        .unwrap()
+}
+
+proptest! {
+    #[test]
+    fn sum_matches_total_exactly(shares in shares_strategy(), remainder in -20i64..=20) {
+        let allocated: Money = shares.iter().map(|share| share.amount_owed).sum();
+        let total = allocated + Money::from_cents(remainder);
+
+        let adjusted = distribute_rounding_remainder(shares, total);
+        let sum: Money = adjusted.iter().map(|share| share.amount_owed).sum();
+
+        prop_assert_eq!(sum, total);
+    }
+
+    #[test]
+    fn only_first_participant_alphabetically_changes(
+        shares in shares_strategy(),
+        remainder in -20i64..=20,
+    ) {
+        let allocated: Money = shares.iter().map(|share| share.amount_owed).sum();
+        let total = allocated + Money::from_cents(remainder);
+        let first = first_by_name(&shares);
+
+        let adjusted = distribute_rounding_remainder(shares.clone(), total);
+
+        prop_assert_eq!(adjusted.len(), shares.len());
+        for (before, after) in shares.iter().zip(&adjusted) {
+            prop_assert_eq!(before.participant_id, after.participant_id);
+            let expected = if before.participant_id == first {
+                before.amount_owed + Money::from_cents(remainder)
+            } else {
+                before.amount_owed
+            };
+            prop_assert_eq!(after.amount_owed, expected);
+        }
+    }
+
+    #[test]
+    fn result_does_not_depend_on_input_order(shares in shares_strategy(), remainder in -20i64..=20) {
+        let allocated: Money = shares.iter().map(|share| share.amount_owed).sum();
+        let total = allocated + Money::from_cents(remainder);
+
+        let mut reversed = shares.clone();
+        reversed.reverse();
+
+        let mut forward = distribute_rounding_remainder(shares, total);
+        let mut backward = distribute_rounding_remainder(reversed, total);
+        forward.sort_by_key(|share| share.participant_id);
+        backward.sort_by_key(|share| share.participant_id);
+
+        prop_assert_eq!(forward, backward);
+    }
+}
+
+#[test]
+fn ten_dollars_between_three_people() {
+    let shares = vec![share("Carol", 333), share("Alice", 333), share("Bob", 333)];
+
+    let adjusted = distribute_rounding_remainder(shares, Money::from_cents(1000));
+
+    let amounts: Vec<(&str, i64)> = adjusted
+        .iter()
+        .map(|share| (share.name.as_str(), share.amount_owed.cents()))
+        .collect();
+    assert_eq!(amounts, vec![("Carol", 333), ("Alice", 334), ("Bob", 333)]);
+}
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+
+use bill_splitter_api::{
+    models::{Bill, Money, Participant, ParticipantShare, Payment, SplitSnapshot},
+    split::{
+        minimise_settlements, naive_settlements, net_balances, Balance, SettlementSavings,
+        SplitMethod,
+    },
+};
+use chrono::Utc;
+use proptest::prelude::*;
+use uuid::Uuid;
+
+#[derive(Debug)]
+struct Scenario {
+    bill: Bill,
+    snapshot: SplitSnapshot,
+    participants: Vec<Participant>,
+}
+
+fn snapshot(shares: Vec<ParticipantShare>) -> SplitSnapshot {
+    SplitSnapshot {
+        id: Uuid::new_v4(),
+        method: SplitMethod::Custom,
+        computed_at: Utc::now(),
+        shares,
+        gini_coefficient: None,
+    }
+}
+
+/// Builds a bill whose payments add up to exactly the sum of the shares,
+/// spread over the chosen payers in proportion to their weights.
+fn scenario(owed: Vec<i64>, payments: Vec<(usize, u32)>) -> Scenario {
+    let participants: Vec<Participant> = (0..owed.len())
+        .map(|i| Participant::new(format!("P{i}"), None))
+        .collect();
+    let shares: Vec<ParticipantShare> = participants
+        .iter()
+        .zip(&owed)
+        .map(|(participant, cents)| ParticipantShare {
+            participant_id: participant.id,
+            name: participant.name.clone(),
+            amount_owed: Money::from_cents(*cents),
+        })
+        .collect();
+
+    let total: i64 = owed.iter().sum();
+    let total_weight: i64 = payments.iter().map(|(_, weight)| i64::from(*weight)).sum();
+    let mut bill = Bill::new("Dinner", None);
+    let mut remaining = total;
+    for (i, (payer, weight)) in payments.iter().enumerate() {
+        let amount = if i == payments.len() - 1 {
+            remaining
+        } else {
+            total * i64::from(*weight) / total_weight
+        };
+        remaining -= amount;
+        if amount > 0 {
+            let payer = &participants[payer % participants.len()];
+            bill.payments
+                .push(Payment::new(payer.id, Money::from_cents(amount), None));
+        }
+    }
+
+    Scenario {
+        bill,
+        snapshot: snapshot(shares),
+        participants,
+    }
+}
+
+fn scenario_strategy() -> impl Strategy<Value = Scenario> {
+    (
+        prop::collection::vec(0i64..100_000, 1..10),
+        prop::collection::vec((0usize..10, 1u32..1_000), 1..6),
+    )
+        .prop_map(|(owed, payments)| scenario(owed, payments))
+}
+
+fn balances(scenario: &Scenario) -> Vec<Balance> {
+    net_balances(&scenario.bill, &scenario.snapshot, &scenario.participants)
+}
+
+proptest! {
+    #[test]
+    fn transfers_clear_every_balance(scenario in scenario_strategy()) {
+        let balances = balances(&scenario);
+        let settlements = minimise_settlements(&balances);
+
+        let mut net: HashMap<Uuid, Money> = balances
+            .iter()
+            .map(|balance| (balance.participant_id, balance.net_balance))
+            .collect();
+        for settlement in &settlements {
+            *net.get_mut(&settlement.from).unwrap() += settlement.amount;
+            *net.get_mut(&settlement.to).unwrap() -= settlement.amount;
+        }
+
+        prop_assert!(net.values().all(|balance| balance.is_zero()), "left over: {:?}", net);
+    }
+
+    #[test]
+    fn at_most_n_minus_one_transfers(scenario in scenario_strategy()) {
+        let balances = balances(&scenario);
+        let settlements = minimise_settlements(&balances);
+
+        prop_assert!(settlements.len() < balances.len().max(1));
+    }
+
+    #[test]
+    fn no_transfer_is_negative_or_empty(scenario in scenario_strategy()) {
+        let settlements = minimise_settlements(&balances(&scenario));
+
+        prop_assert!(settlements
+            .iter()
+            .all(|settlement| settlement.amount > Money::ZERO));
+    }
+}
+
+#[test]
+fn participant_with_zero_balance_is_left_out() {
+    // A paid exactly their share, C paid for B.
+    let mut scenario = scenario(vec![1_000, 1_000, 0], vec![]);
+    let ids: Vec<Uuid> = scenario.participants.iter().map(|p| p.id).collect();
+    scenario.bill.payments = vec![
+        Payment::new(ids[0], Money::from_cents(1_000), None),
+        Payment::new(ids[2], Money::from_cents(1_000), None),
+    ];
+
+    let balances = balances(&scenario);
+    assert_eq!(balances[0].net_balance, Money::ZERO);
+
+    let settlements = minimise_settlements(&balances);
+    assert_eq!(settlements.len(), 1);
+    assert_eq!(settlements[0].from, ids[1]);
+    assert_eq!(settlements[0].to, ids[2]);
+    assert_eq!(settlements[0].amount, Money::from_cents(1_000));
+}
+
+proptest! {
+    #[test]
+    fn naive_transfers_clear_every_balance(scenario in scenario_strategy()) {
+        let balances = balances(&scenario);
+        let settlements = naive_settlements(&balances);
+
+        let mut net: HashMap<Uuid, Money> = balances
+            .iter()
+            .map(|balance| (balance.participant_id, balance.net_balance))
+            .collect();
+        for settlement in &settlements {
+            prop_assert!(settlement.amount > Money::ZERO);
+            *net.get_mut(&settlement.from).unwrap() += settlement.amount;
+            *net.get_mut(&settlement.to).unwrap() -= settlement.amount;
+        }
+
+        prop_assert!(net.values().all(|balance| balance.is_zero()), "left over: {:?}", net);
+    }
+
+    #[test]
+    fn minimising_never_needs_more_transfers(scenario in scenario_strategy()) {
+        let balances = balances(&scenario);
+        let savings = SettlementSavings::new(
+            &naive_settlements(&balances),
+            &minimise_settlements(&balances),
+        );
+
+        prop_assert!(savings.minimised_transfer_count <= savings.naive_transfer_count);
+        prop_assert_eq!(savings.minimised_total_amount, savings.naive_total_amount);
+    }
+}
+
+#[test]
+fn two_debtors_paying_two_creditors_need_four_naive_transfers() {
+    // A and B each owe 10.00; C paid 15.00 and D 5.00 of the others' shares.
+    let mut scenario = scenario(vec![1_000, 1_000, 0, 0], vec![]);
+    let ids: Vec<Uuid> = scenario.participants.iter().map(|p| p.id).collect();
+    scenario.bill.payments = vec![
+        Payment::new(ids[2], Money::from_cents(1_500), None),
+        Payment::new(ids[3], Money::from_cents(500), None),
+    ];
+    let balances = balances(&scenario);
+
+    let naive = naive_settlements(&balances);
+    let savings = SettlementSavings::new(&naive, &minimise_settlements(&balances));
+
+    assert_eq!(naive.len(), 4);
+    let from_a: Vec<Money> = naive
+        .iter()
+        .filter(|settlement| settlement.from == ids[0])
+        .map(|settlement| settlement.amount)
+        .collect();
+    assert_eq!(
+        from_a.iter().copied().sum::<Money>(),
+        Money::from_cents(1_000)
+    );
+    assert!(from_a.contains(&Money::from_cents(750)));
+    assert_eq!(savings.minimised_transfer_count, 3);
+    assert_eq!(savings.transfers_saved, 1);
+    assert_eq!(savings.naive_total_amount, Money::from_cents(2_000));
+}
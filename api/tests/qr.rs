@@ -0,0 +1,45 @@
+use bill_splitter_api::qr::{to_png, to_svg, QrCode, MAX_VERSION};
+
+#[test]
+fn picks_the_smallest_version_that_fits() {
+    let short = QrCode::encode(b"HELLO").unwrap();
+    assert_eq!((short.version(), short.size()), (1, 21));
+
+    let payment = QrCode::encode(
+        br#"{"payee_name":"Alice Smith","amount":"12.50","bill_reference":"INV-3F2A9C1B","currency":"USD"}"#,
+    )
+    .unwrap();
+    assert_eq!((payment.version(), payment.size()), (6, 41));
+}
+
+#[test]
+fn finder_patterns_sit_in_three_corners() {
+    let code = QrCode::encode(b"HELLO").unwrap();
+    let last = code.size() - 1;
+    for (x, y) in [(0, 0), (last - 6, 0), (0, last - 6)] {
+        // Dark outer ring, light ring, dark 3×3 centre.
+        assert!(code.is_dark(x, y) && code.is_dark(x + 6, y + 6));
+        assert!(!code.is_dark(x + 1, y + 1));
+        assert!(code.is_dark(x + 3, y + 3));
+    }
+}
+
+#[test]
+fn too_much_data_is_rejected() {
+    let err = QrCode::encode(&[b'x'; 214]).unwrap_err();
+    assert_eq!((err.len, err.max), (214, 213));
+    assert!(QrCode::encode(&[b'x'; 213]).unwrap().version() == MAX_VERSION);
+}
+
+#[test]
+fn renders_svg_and_png() {
+    let code = QrCode::encode(b"HELLO").unwrap();
+
+    let svg = to_svg(&code);
+    assert!(svg.starts_with("<svg") && svg.contains(r#"viewBox="0 0 29 29""#));
+
+    let png = to_png(&code, 2);
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    // IHDR width and height: (21 + 2 × 4) modules at 2 px each.
+    assert_eq!(&png[16..24], &[0, 0, 0, 58, 0, 0, 0, 58]);
+}
@@ -0,0 +1,212 @@
+use actix_web::{
+    http::StatusCode,
+    test::{call_and_read_body_json, call_service, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{
+    app,
+    config::{Config, EmailConfig, EmailProvider},
+    jobs::send_reminders,
+    models::{Bill, Participant, Payment},
+    notifications::{ScheduledReminder, MAX_PENDING_REMINDERS},
+    state::AppState,
+    testing::{money, BillBuilder},
+};
+use chrono::{Duration, Utc};
+use serde_json::Value;
+
+fn state() -> web::Data<AppState> {
+    web::Data::new(AppState::new(Config {
+        email: EmailConfig {
+            provider: Some(EmailProvider::Mailgun),
+            ..EmailConfig::default()
+        },
+        reminder_interval_days: 3,
+        ..Config::default()
+    }))
+}
+
+/// A bill with Alice and Bob splitting 30.00, split once so they owe.
+async fn seed(state: &AppState) -> (Bill, Participant, Participant) {
+    let repo = state.repo();
+    let (bill, participants) = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_participant("Bob")
+        .with_item("Pizza", 1, 30.0)
+        .build()
+        .unwrap();
+    for participant in &participants {
+        repo.put_participant(participant).await.unwrap();
+    }
+    repo.put_bill(&bill).await.unwrap();
+    let [alice, bob] = participants.try_into().unwrap();
+    (bill, alice, bob)
+}
+
+fn reminders_uri(bill: &Bill, participant: &Participant) -> String {
+    format!(
+        "/bills/{}/participants/{}/reminders",
+        bill.id, participant.id
+    )
+}
+
+#[actix_web::test]
+async fn notifying_schedules_a_reminder_that_can_be_cancelled() {
+    let state = state();
+    let (bill, alice, bob) = seed(&state).await;
+    let app = init_service(app(state.clone())).await;
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split", bill.id))
+        .to_request();
+    assert_eq!(call_service(&app, req).await.status(), StatusCode::OK);
+    let req = TestRequest::post()
+        .uri(&format!("/bills/{}/notify", bill.id))
+        .to_request();
+    let body: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(body["queued"], 2);
+    assert_eq!(body["reminders_scheduled"], 2);
+
+    let req = TestRequest::get()
+        .uri(&reminders_uri(&bill, &alice))
+        .to_request();
+    let reminders: Vec<ScheduledReminder> = call_and_read_body_json(&app, req).await;
+    assert_eq!(reminders.len(), 1);
+    let reminder = &reminders[0];
+    assert!(reminder.scheduled_for > Utc::now() + Duration::days(2));
+    assert!(reminder.message_preview.contains("Dinner"));
+    assert!(reminder.message_preview.contains("15.00"));
+
+    let req = TestRequest::delete()
+        .uri(&format!("{}/{}", reminders_uri(&bill, &alice), reminder.id))
+        .to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::NO_CONTENT
+    );
+    let req = TestRequest::get()
+        .uri(&reminders_uri(&bill, &alice))
+        .to_request();
+    let reminders: Vec<ScheduledReminder> = call_and_read_body_json(&app, req).await;
+    assert!(reminders.is_empty());
+
+    let req = TestRequest::delete()
+        .uri(&format!("{}/{}", reminders_uri(&bill, &alice), reminder.id))
+        .to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::NOT_FOUND
+    );
+
+    let req = TestRequest::get()
+        .uri(&reminders_uri(&bill, &bob))
+        .to_request();
+    let reminders: Vec<ScheduledReminder> = call_and_read_body_json(&app, req).await;
+    assert_eq!(reminders.len(), 1);
+}
+
+#[actix_web::test]
+async fn participants_have_at_most_five_pending_reminders() {
+    let state = state();
+    let (bill, alice, _) = seed(&state).await;
+    let app = init_service(app(state.clone())).await;
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split", bill.id))
+        .to_request();
+    call_service(&app, req).await;
+    for _ in 0..MAX_PENDING_REMINDERS + 1 {
+        let req = TestRequest::post()
+            .uri(&format!("/bills/{}/notify", bill.id))
+            .to_request();
+        call_service(&app, req).await;
+    }
+
+    let reminders = state.repo().get_reminders(bill.id, alice.id).await.unwrap();
+    assert_eq!(reminders.len(), MAX_PENDING_REMINDERS);
+}
+
+#[actix_web::test]
+async fn due_reminders_are_sent_only_to_those_who_still_owe() {
+    let state = state();
+    let mut queue = state.notification_queue.take_receiver().unwrap();
+    let (bill, alice, bob) = seed(&state).await;
+    let app = init_service(app(state.clone())).await;
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split", bill.id))
+        .to_request();
+    call_service(&app, req).await;
+    let req = TestRequest::post()
+        .uri(&format!("/bills/{}/notify", bill.id))
+        .to_request();
+    call_service(&app, req).await;
+    while queue.try_recv().is_ok() {}
+
+    let mut bill = state.repo().get_bill(bill.id).await.unwrap().unwrap();
+    bill.payments
+        .push(Payment::new(bob.id, money("15.00"), None));
+    state.repo().put_bill(&bill).await.unwrap();
+
+    let report = send_reminders(&state, Utc::now()).await.unwrap();
+    assert_eq!(report.sent_count, 0);
+
+    let later = Utc::now() + Duration::days(4);
+    let report = send_reminders(&state, later).await.unwrap();
+    assert_eq!(report.sent_count, 1);
+    assert_eq!(report.skipped_count, 1);
+    let task = queue.try_recv().unwrap();
+    assert_eq!(task.participant_id, alice.id);
+    assert!(queue.try_recv().is_err());
+
+    let repo = state.repo();
+    assert!(repo
+        .get_reminders(bill.id, alice.id)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(repo
+        .get_reminders(bill.id, bob.id)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[actix_web::test]
+async fn notify_skips_the_paid_up_and_quotes_what_is_left() {
+    let state = state();
+    let mut queue = state.notification_queue.take_receiver().unwrap();
+    let (bill, alice, bob) = seed(&state).await;
+    let app = init_service(app(state.clone())).await;
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split", bill.id))
+        .to_request();
+    call_service(&app, req).await;
+    let mut bill = state.repo().get_bill(bill.id).await.unwrap().unwrap();
+    bill.payments
+        .push(Payment::new(alice.id, money("5.00"), None));
+    bill.payments
+        .push(Payment::new(bob.id, money("15.00"), None));
+    state.repo().put_bill(&bill).await.unwrap();
+
+    let req = TestRequest::post()
+        .uri(&format!("/bills/{}/notify", bill.id))
+        .to_request();
+    let body: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(body["queued"], 1);
+    assert_eq!(body["reminders_scheduled"], 1);
+    assert_eq!(queue.try_recv().unwrap().participant_id, alice.id);
+    assert!(queue.try_recv().is_err());
+
+    let req = TestRequest::get()
+        .uri(&reminders_uri(&bill, &alice))
+        .to_request();
+    let reminders: Vec<ScheduledReminder> = call_and_read_body_json(&app, req).await;
+    assert!(reminders[0].message_preview.contains("10.00"));
+    let req = TestRequest::get()
+        .uri(&reminders_uri(&bill, &bob))
+        .to_request();
+    let reminders: Vec<ScheduledReminder> = call_and_read_body_json(&app, req).await;
+    assert!(reminders.is_empty());
+}
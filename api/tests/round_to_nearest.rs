@@ -0,0 +1,219 @@
+use actix_web::{
+    http::StatusCode,
+    test::{call_and_read_body_json, call_service, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    models::{Bill, CurrencyCode, Money, ParticipantShare},
+    split::{round_to_nearest, SplitError},
+    state::AppState,
+    testing::{money, BillBuilder},
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+fn share(name: &str, amount: &str) -> ParticipantShare {
+    ParticipantShare {
+        participant_id: Uuid::new_v4(),
+        name: name.to_string(),
+        amount_owed: money(amount),
+    }
+}
+
+#[test]
+fn shares_round_up_and_the_last_name_absorbs_the_excess() {
+    let shares = vec![
+        share("Carol", "3.34"),
+        share("Alice", "3.33"),
+        share("Bob", "3.33"),
+    ];
+    let carol = shares[0].participant_id;
+
+    let rounded = round_to_nearest(shares, money("10.00"), money("0.05")).unwrap();
+
+    let amounts: Vec<Money> = rounded
+        .shares
+        .iter()
+        .map(|share| share.amount_owed)
+        .collect();
+    assert_eq!(amounts, [money("3.30"), money("3.35"), money("3.35")]);
+    assert_eq!(rounded.remainder, money("-0.05"));
+    assert_eq!(rounded.remainder_applied_to, Some(carol));
+}
+
+#[test]
+fn exact_multiples_are_left_alone() {
+    let rounded = round_to_nearest(
+        vec![share("Alice", "5.00"), share("Bob", "5.00")],
+        money("10.00"),
+        money("0.05"),
+    )
+    .unwrap();
+    assert!(rounded.remainder.is_zero());
+    assert_eq!(rounded.remainder_applied_to, None);
+}
+
+#[test]
+fn zero_shares_never_absorb_the_remainder() {
+    let shares = vec![
+        share("Alice", "5.01"),
+        share("Bob", "4.99"),
+        share("Zed", "0.00"),
+    ];
+    let bob = shares[1].participant_id;
+
+    let rounded = round_to_nearest(shares, money("10.00"), money("1.00")).unwrap();
+
+    let amounts: Vec<Money> = rounded
+        .shares
+        .iter()
+        .map(|share| share.amount_owed)
+        .collect();
+    assert_eq!(amounts, [money("6.00"), money("4.00"), Money::ZERO]);
+    assert_eq!(rounded.remainder_applied_to, Some(bob));
+}
+
+#[test]
+fn overshooting_by_more_than_one_step_is_rejected() {
+    let shares: Vec<ParticipantShare> = (0..10).map(|i| share(&format!("P{i}"), "0.10")).collect();
+    assert_eq!(
+        round_to_nearest(shares, money("1.00"), money("1.00")).unwrap_err(),
+        SplitError::RoundingExceedsTotal {
+            round_to: money("1.00"),
+            excess: money("9.00"),
+        }
+    );
+}
+
+#[test]
+fn zero_decimal_currencies_round_to_whole_units() {
+    let yen = CurrencyCode::try_from("jpy".to_string()).unwrap();
+    assert_eq!(yen.decimal_places(), 0);
+    assert_eq!(yen.smallest_unit(), money("1.00"));
+    assert_eq!(CurrencyCode::default().smallest_unit(), money("0.01"));
+}
+
+async fn dinner(state: &web::Data<AppState>, currency: &str, total: f64) -> Bill {
+    let repo = state.repo();
+    let (mut bill, participants) = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_participant("Bob")
+        .with_participant("Carol")
+        .with_item("Feast", 1, total)
+        .build()
+        .unwrap();
+    bill.base_currency = CurrencyCode::try_from(currency.to_string()).unwrap();
+    for participant in &participants {
+        repo.put_participant(participant).await.unwrap();
+    }
+    repo.put_bill(&bill).await.unwrap();
+    bill
+}
+
+#[actix_web::test]
+async fn the_default_granularity_follows_the_currency() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let bill = dinner(&state, "JPY", 101.0).await;
+    let app = init_service(app(state.clone())).await;
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split/round-to-nearest", bill.id))
+        .to_request();
+    let body: Value = call_and_read_body_json(&app, req).await;
+
+    assert_eq!(body["round_to"], "1.00");
+    let amounts: Vec<&str> = body["shares"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|share| share["amount_owed"].as_str().unwrap())
+        .collect();
+    assert_eq!(amounts, ["34.00", "34.00", "33.00"]);
+    assert_eq!(body["remainder"], "-1.00");
+
+    // 100 split three ways rounds up to 102, two steps over.
+    let bill = dinner(&state, "JPY", 100.0).await;
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split/round-to-nearest", bill.id))
+        .to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+}
+
+#[actix_web::test]
+async fn the_split_endpoint_records_rounded_shares() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let bill = dinner(&state, "CHF", 100.0).await;
+    let app = init_service(app(state.clone())).await;
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split?round_to=0.05", bill.id))
+        .to_request();
+    let body: Value = call_and_read_body_json(&app, req).await;
+    let amounts: Vec<&str> = body["shares"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|share| share["amount_owed"].as_str().unwrap())
+        .collect();
+    assert_eq!(amounts, ["33.35", "33.35", "33.30"]);
+
+    let stored = state.repo().get_bill(bill.id).await.unwrap().unwrap();
+    assert_eq!(
+        stored.latest_split().unwrap().shares[2].amount_owed,
+        money("33.30")
+    );
+}
+
+#[actix_web::test]
+async fn round_to_is_validated() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let bill = dinner(&state, "USD", 100.0).await;
+    let app = init_service(app(state.clone())).await;
+
+    for round_to in ["0", "-0.05", "1.01", "0.005"] {
+        let req = TestRequest::get()
+            .uri(&format!(
+                "/bills/{}/split/round-to-nearest?round_to={round_to}",
+                bill.id
+            ))
+            .to_request();
+        let resp = call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST, "{round_to}");
+    }
+}
+
+#[actix_web::test]
+async fn exempt_participants_keep_a_zero_share_when_rounding() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let repo = state.repo();
+    let (mut bill, participants) = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_participant("Bob")
+        .with_participant("Zed")
+        .with_item("Feast", 1, 10.01)
+        .build()
+        .unwrap();
+    bill.exempt_participant(participants[2].id, "Designated driver", chrono::Utc::now());
+    for participant in &participants {
+        repo.put_participant(participant).await.unwrap();
+    }
+    repo.put_bill(&bill).await.unwrap();
+    let app = init_service(app(state.clone())).await;
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split?round_to=1.00", bill.id))
+        .to_request();
+    let body: Value = call_and_read_body_json(&app, req).await;
+    let amounts: Vec<&str> = body["shares"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|share| share["amount_owed"].as_str().unwrap())
+        .collect();
+    assert_eq!(amounts, ["6.00", "4.01", "0.00"]);
+}
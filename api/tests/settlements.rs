@@ -0,0 +1,96 @@
+use actix_web::{
+    http::StatusCode,
+    test::{call_and_read_body_json, call_service, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    models::{Bill, Money, Participant, Payment},
+    state::AppState,
+    testing::BillBuilder,
+};
+use serde_json::{json, Value};
+
+/// Alice, Bob and Carol owe 20.00 each of a 60.00 bill; Alice paid 45.00 of
+/// it and Bob 15.00.
+async fn seed(state: &AppState) -> (Bill, [Participant; 3]) {
+    let repo = state.repo();
+    let (mut bill, participants) = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_participant("Bob")
+        .with_participant("Carol")
+        .with_item("Feast", 1, 60.0)
+        .build()
+        .unwrap();
+    for participant in &participants {
+        repo.put_participant(participant).await.unwrap();
+    }
+    let [alice, bob, carol] = participants.try_into().unwrap();
+    bill.payments
+        .push(Payment::new(alice.id, Money::from_cents(4500), None));
+    bill.payments
+        .push(Payment::new(bob.id, Money::from_cents(1500), None));
+    repo.put_bill(&bill).await.unwrap();
+    (bill, [alice, bob, carol])
+}
+
+#[actix_web::test]
+async fn settlements_need_a_split_first() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let (bill, _) = seed(&state).await;
+    let app = init_service(app(state)).await;
+
+    for path in ["settlements", "split/graph"] {
+        let req = TestRequest::get()
+            .uri(&format!("/bills/{}/{path}", bill.id))
+            .to_request();
+        let res = call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        let body: Value = actix_web::test::read_body_json(res).await;
+        assert_eq!(body["error"], "InsufficientData");
+    }
+}
+
+#[actix_web::test]
+async fn debtors_pay_creditors_in_as_few_transfers_as_possible() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let (bill, [alice, bob, carol]) = seed(&state).await;
+    let app = init_service(app(state)).await;
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split", bill.id))
+        .to_request();
+    assert_eq!(call_service(&app, req).await.status(), StatusCode::OK);
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/settlements", bill.id))
+        .to_request();
+    let settlements: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(
+        settlements,
+        json!([
+            { "from": carol.id, "to": alice.id, "amount": "20.00" },
+            { "from": bob.id, "to": alice.id, "amount": "5.00" }
+        ])
+    );
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split/graph", bill.id))
+        .to_request();
+    let graph: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(
+        graph["nodes"],
+        json!([
+            { "id": alice.id, "name": "Alice", "net_balance": "25.00" },
+            { "id": bob.id, "name": "Bob", "net_balance": "-5.00" },
+            { "id": carol.id, "name": "Carol", "net_balance": "-20.00" }
+        ])
+    );
+    assert_eq!(
+        graph["edges"],
+        json!([
+            { "from": carol.id, "to": alice.id, "amount": "20.00", "weight": 1.0 },
+            { "from": bob.id, "to": alice.id, "amount": "5.00", "weight": 0.25 }
+        ])
+    );
+}
@@ -0,0 +1,130 @@
+use actix_web::{http::StatusCode, test, web};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    models::{Bill, Participant, Payment, SplitSnapshot},
+    split::{compute_split, SplitSpec},
+    state::AppState,
+    testing::{money, BillBuilder},
+};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+const JWT_SECRET: &str = "jwt-secret";
+
+fn token(user_id: Uuid) -> String {
+    let claims = json!({
+        "sub": user_id,
+        "exp": (Utc::now() + Duration::hours(1)).timestamp(),
+    });
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap()
+}
+
+/// `total` split equally between `participants`, created by the first.
+fn bill(title: &str, total: f64, participants: &[&Participant]) -> Bill {
+    let (mut bill, participants) = participants
+        .iter()
+        .fold(BillBuilder::new(title), |builder, participant| {
+            builder.with_existing_participant(participant)
+        })
+        .with_item("Food", 1, total)
+        .with_creator(participants[0].name.clone())
+        .build()
+        .unwrap();
+    let split = compute_split(&bill, &participants, &SplitSpec::Equal).unwrap();
+    bill.split_history.push(SplitSnapshot::from_result(&split));
+    bill
+}
+
+#[actix_web::test]
+async fn lists_bills_others_created_that_i_still_owe_on() {
+    let state = web::Data::new(AppState::new(Config {
+        jwt_secret: Some(JWT_SECRET.to_string()),
+        ..Config::default()
+    }));
+    let repo = state.repo();
+    let me = Participant::new("Me", None);
+    let alice = Participant::new("Alice", None);
+    let bob = Participant::new("Bob", None);
+    for participant in [&me, &alice, &bob] {
+        repo.put_participant(participant).await.unwrap();
+    }
+
+    let small = bill("Coffee", 10.0, &[&alice, &me]);
+    let large = bill("Dinner", 90.0, &[&bob, &me, &alice]);
+    let mine = bill("Lunch", 40.0, &[&me, &alice]);
+    let mut paid = bill("Taxi", 20.0, &[&alice, &me]);
+    paid.payments
+        .push(Payment::new(me.id, money("10.00"), None));
+    let not_mine = bill("Cinema", 30.0, &[&alice, &bob]);
+    for bill in [&small, &large, &mine, &paid, &not_mine] {
+        repo.put_bill(bill).await.unwrap();
+    }
+
+    let app = test::init_service(app(state.clone())).await;
+    let req = test::TestRequest::get()
+        .uri("/bills/shared-with-me")
+        .insert_header(("Authorization", format!("Bearer {}", token(me.id))))
+        .to_request();
+    let bills: Vec<Value> = test::call_and_read_body_json(&app, req).await;
+
+    let summary: Vec<(&str, &str, &str)> = bills
+        .iter()
+        .map(|bill| {
+            (
+                bill["title"].as_str().unwrap(),
+                bill["outstanding_amount"].as_str().unwrap(),
+                bill["creator_name"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [("Dinner", "30.00", "Bob"), ("Coffee", "5.00", "Alice")]
+    );
+    assert_eq!(bills[0]["id"], json!(large.id));
+}
+
+#[actix_web::test]
+async fn bills_i_am_removed_from_are_no_longer_listed() {
+    let state = web::Data::new(AppState::new(Config {
+        jwt_secret: Some(JWT_SECRET.to_string()),
+        ..Config::default()
+    }));
+    let repo = state.repo();
+    let me = Participant::new("Me", None);
+    let alice = Participant::new("Alice", None);
+    let mut bill = bill("Coffee", 10.0, &[&alice, &me]);
+    repo.put_bill(&bill).await.unwrap();
+    assert_eq!(repo.participant_bill_ids(me.id).await.unwrap(), [bill.id]);
+
+    bill.participants
+        .retain(|member| member.participant_id != me.id);
+    repo.put_bill(&bill).await.unwrap();
+    assert!(repo.participant_bill_ids(me.id).await.unwrap().is_empty());
+    assert_eq!(
+        repo.participant_bill_ids(alice.id).await.unwrap(),
+        [bill.id]
+    );
+}
+
+#[actix_web::test]
+async fn requires_a_token() {
+    let state = web::Data::new(AppState::new(Config {
+        jwt_secret: Some(JWT_SECRET.to_string()),
+        ..Config::default()
+    }));
+    let app = test::init_service(app(state)).await;
+    let req = test::TestRequest::get()
+        .uri("/bills/shared-with-me")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,68 @@
+use bill_splitter_api::crypto::hmac::{
+    decode_signed_token, encode_signed_token, sign, verify, TokenError,
+};
+use serde::{Deserialize, Serialize};
+
+const SECRET: &[u8] = b"share-secret";
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct ShareClaims {
+    bill_id: String,
+    expires_at: i64,
+}
+
+fn claims() -> ShareClaims {
+    ShareClaims {
+        bill_id: "3f2a9c1b".to_string(),
+        expires_at: 1_700_000_000,
+    }
+}
+
+#[test]
+fn signatures_only_verify_with_the_same_secret_and_payload() {
+    let signature = sign(b"payload", SECRET);
+
+    assert!(verify(b"payload", &signature, SECRET));
+    assert!(!verify(b"payload", &signature, b"other-secret"));
+    assert!(!verify(b"payloaD", &signature, SECRET));
+    assert!(!verify(b"payload", &signature[..31], SECRET));
+}
+
+#[test]
+fn tokens_round_trip_their_claims() {
+    let token = encode_signed_token(&claims(), SECRET);
+
+    assert!(!token.contains(['=', '+', '/']));
+    let decoded: ShareClaims = decode_signed_token(&token, SECRET).unwrap();
+    assert_eq!(decoded, claims());
+}
+
+#[test]
+fn tampered_or_foreign_tokens_are_rejected() {
+    let token = encode_signed_token(&claims(), SECRET);
+    let (_, signature) = token.split_once('.').unwrap();
+    let forged = format!(
+        "{}.{signature}",
+        encode_signed_token(&"someone else", SECRET)
+            .split_once('.')
+            .unwrap()
+            .0
+    );
+
+    assert!(matches!(
+        decode_signed_token::<ShareClaims>(&forged, SECRET),
+        Err(TokenError::BadSignature)
+    ));
+    assert!(matches!(
+        decode_signed_token::<ShareClaims>(&token, b"other-secret"),
+        Err(TokenError::BadSignature)
+    ));
+    assert!(matches!(
+        decode_signed_token::<ShareClaims>("no-dot", SECRET),
+        Err(TokenError::Malformed)
+    ));
+    assert!(matches!(
+        decode_signed_token::<ShareClaims>(&encode_signed_token(&42, SECRET), SECRET),
+        Err(TokenError::InvalidClaims(_))
+    ));
+}
@@ -0,0 +1,20 @@
+use bill_splitter_api::util::similarity::jaro_winkler;
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 0.001
+}
+
+#[test]
+fn matches_published_examples() {
+    assert!(close(jaro_winkler("martha", "marhta"), 0.961));
+    assert!(close(jaro_winkler("dwayne", "duane"), 0.840));
+    assert!(close(jaro_winkler("dixon", "dicksonx"), 0.813));
+}
+
+#[test]
+fn identical_and_disjoint_strings() {
+    assert_eq!(jaro_winkler("pizza", "pizza"), 1.0);
+    assert_eq!(jaro_winkler("", ""), 1.0);
+    assert_eq!(jaro_winkler("pizza", ""), 0.0);
+    assert_eq!(jaro_winkler("abc", "xyz"), 0.0);
+}
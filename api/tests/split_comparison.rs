@@ -0,0 +1,79 @@
+use bill_splitter_api::{
+    models::{Bill, Money, Participant, ParticipantShare},
+    split::{bar_chart, compare_methods, BAR_CHART_WIDTH},
+    testing::{money, BillBuilder},
+};
+
+fn lunch() -> (Bill, Vec<Participant>) {
+    BillBuilder::new("Lunch")
+        .with_participant("Alice")
+        .with_participant("Bob")
+        .with_item_shared_by("Steak", 1, 24.0, &["Alice"])
+        .with_item_shared_by("Fries", 1, 6.01, &["Alice", "Bob"])
+        .build()
+        .unwrap()
+}
+
+fn amounts(shares: &[ParticipantShare]) -> Vec<Money> {
+    shares.iter().map(|share| share.amount_owed).collect()
+}
+
+#[test]
+fn every_method_splits_the_whole_total() {
+    let (bill, participants) = lunch();
+
+    let comparison = compare_methods(&bill, &participants).unwrap();
+
+    let total = money("30.01");
+    assert_eq!(amounts(&comparison.equal).into_iter().sum::<Money>(), total);
+    assert_eq!(
+        amounts(&comparison.proportional_equal_weights)
+            .into_iter()
+            .sum::<Money>(),
+        total
+    );
+    let itemised = comparison.itemised.unwrap();
+    assert_eq!(amounts(&itemised), [money("27.01"), money("3.00")]);
+}
+
+#[test]
+fn itemised_is_left_out_while_an_item_is_unassigned() {
+    let (mut bill, participants) = lunch();
+    bill.line_items[1].participant_ids.clear();
+
+    let comparison = compare_methods(&bill, &participants).unwrap();
+
+    assert!(comparison.itemised.is_none());
+    assert_eq!(comparison.equal.len(), 2);
+}
+
+#[test]
+fn bar_chart_scales_the_largest_share_to_the_full_width() {
+    let (bill, participants) = lunch();
+    let comparison = compare_methods(&bill, &participants).unwrap();
+
+    let chart = bar_chart(&comparison.itemised.unwrap());
+
+    let rows: Vec<&str> = chart.lines().collect();
+    assert_eq!(
+        rows[0],
+        format!("Alice {} 27.01", "\u{2588}".repeat(BAR_CHART_WIDTH))
+    );
+    assert_eq!(rows[1], format!("Bob   {} 3.00", "\u{2588}".repeat(4)));
+    assert!(rows
+        .last()
+        .unwrap()
+        .starts_with("Scale: each \u{2588} is about 0.68"));
+}
+
+#[test]
+fn bar_chart_draws_no_bars_when_nobody_owes_anything() {
+    let (mut bill, participants) = lunch();
+    bill.line_items.clear();
+    let comparison = compare_methods(&bill, &participants).unwrap();
+
+    let chart = bar_chart(&comparison.equal);
+
+    assert!(!chart.contains('\u{2588}'));
+    assert!(chart.starts_with("Alice 0.00\nBob   0.00\n"));
+}
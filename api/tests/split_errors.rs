@@ -0,0 +1,159 @@
+use actix_web::{
+    body::to_bytes,
+    http::StatusCode,
+    test::{call_service, init_service, read_body_json, TestRequest},
+    web, ResponseError,
+};
+use std::collections::HashMap;
+
+use bill_splitter_api::{
+    app,
+    config::Config,
+    error::ApiError,
+    models::Money,
+    split::{compute_split, SplitError, SplitSpec},
+    state::AppState,
+    testing::BillBuilder,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+async fn body(err: SplitError) -> (StatusCode, Value) {
+    let err = ApiError::from(err);
+    let response = err.error_response();
+    let status = response.status();
+    let bytes = to_bytes(response.into_body()).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[actix_web::test]
+async fn problems_with_the_bill_are_insufficient_data() {
+    let id = Uuid::new_v4();
+    for err in [
+        SplitError::NoParticipants,
+        SplitError::AllExempt,
+        SplitError::NoLineItems,
+        SplitError::UnassignedLineItem { id },
+        SplitError::NegativeAmount { item_id: id },
+    ] {
+        let reason = err.to_string();
+        let (status, body) = body(err).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "InsufficientData");
+        assert_eq!(body["reason"], reason);
+    }
+}
+
+#[actix_web::test]
+async fn problems_with_the_parameters_are_bad_requests() {
+    let id = Uuid::new_v4();
+    for err in [
+        SplitError::WeightsMismatch {
+            missing: vec![id],
+            extra: vec![],
+        },
+        SplitError::NegativeWeight { participant_id: id },
+        SplitError::ZeroTotalWeight,
+        SplitError::AmountsMismatch {
+            missing: vec![],
+            extra: vec![id],
+        },
+        SplitError::AmountMismatch {
+            expected: Money::from_cents(1000),
+            got: Money::from_cents(900),
+        },
+        SplitError::NegativeCustomAmount { participant_id: id },
+        SplitError::CustomAmountsOverflow,
+        SplitError::RoundingExceedsTotal {
+            round_to: Money::from_cents(100),
+            excess: Money::from_cents(200),
+        },
+    ] {
+        let message = err.to_string();
+        let (status, body) = body(err).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "Bad Request");
+        assert_eq!(body["message"], message);
+    }
+}
+
+#[test]
+fn messages_name_what_is_wrong() {
+    let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+    assert_eq!(
+        SplitError::WeightsMismatch {
+            missing: vec![a],
+            extra: vec![b],
+        }
+        .to_string(),
+        format!("Weights missing for {a}; given for {b}, who are not part of this bill")
+    );
+    assert_eq!(
+        SplitError::AmountMismatch {
+            expected: Money::from_cents(1000),
+            got: Money::from_cents(900),
+        }
+        .to_string(),
+        "Custom amounts add up to 9.00 but the bill total is 10.00"
+    );
+    assert_eq!(
+        SplitError::NegativeCustomAmount { participant_id: a }.to_string(),
+        format!("Custom amount for {a} must not be negative")
+    );
+    assert_eq!(
+        ApiError::from(SplitError::ZeroTotalWeight).status_code(),
+        StatusCode::BAD_REQUEST
+    );
+}
+
+#[actix_web::test]
+async fn negative_custom_amounts_are_rejected_by_participant() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let repo = state.repo();
+    let (bill, participants) = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_participant("Bob")
+        .with_item("Pizza", 1, 10.0)
+        .build()
+        .unwrap();
+    for participant in &participants {
+        repo.put_participant(participant).await.unwrap();
+    }
+    repo.put_bill(&bill).await.unwrap();
+    let app = init_service(app(state)).await;
+
+    let (alice, bob) = (participants[0].id, participants[1].id);
+    let amounts = json!({ alice: "12.00", bob: "-2.00" }).to_string();
+    let query = serde_urlencoded::to_string([("method", "custom"), ("amounts", &amounts)]).unwrap();
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split?{query}", bill.id))
+        .to_request();
+    let res = call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let body: Value = read_body_json(res).await;
+    assert_eq!(
+        body["message"],
+        format!("Custom amount for {bob} must not be negative")
+    );
+}
+
+#[test]
+fn custom_amounts_that_overflow_are_rejected_rather_than_wrapped() {
+    let (bill, participants) = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_participant("Bob")
+        .with_participant("Carol")
+        .with_item("Pizza", 1, 10.0)
+        .build()
+        .unwrap();
+    // Wrapping, MAX + MAX + 10.02 would come back round to the 10.00 total.
+    let amounts = HashMap::from([
+        (participants[0].id, Money::from_cents(i64::MAX)),
+        (participants[1].id, Money::from_cents(i64::MAX)),
+        (participants[2].id, Money::from_cents(1002)),
+    ]);
+    assert_eq!(
+        compute_split(&bill, &participants, &SplitSpec::Custom { amounts }).unwrap_err(),
+        SplitError::CustomAmountsOverflow
+    );
+}
@@ -0,0 +1,184 @@
+use actix_web::{
+    http::StatusCode,
+    test::{call_and_read_body_json, call_service, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    models::{Bill, LineItem, Money, SplitSnapshot},
+    state::AppState,
+    testing::BillBuilder,
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Alice and Bob sharing a 30.00 pizza and a 10.00 salad.
+async fn seed(state: &AppState) -> (Bill, Uuid) {
+    let repo = state.repo();
+    let (bill, participants) = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_participant("Bob")
+        .with_item("Pizza", 1, 30.0)
+        .with_item("Salad", 1, 10.0)
+        .build()
+        .unwrap();
+    for participant in &participants {
+        repo.put_participant(participant).await.unwrap();
+    }
+    repo.put_bill(&bill).await.unwrap();
+    (bill, participants[0].id)
+}
+
+async fn history(state: &AppState, bill: &Bill) -> Vec<SplitSnapshot> {
+    state
+        .repo()
+        .get_bill(bill.id)
+        .await
+        .unwrap()
+        .unwrap()
+        .split_history
+}
+
+#[actix_web::test]
+async fn snapshots_can_be_listed_and_fetched_by_id() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let (bill, _) = seed(&state).await;
+    let app = init_service(app(state.clone())).await;
+
+    for _ in 0..2 {
+        let req = TestRequest::get()
+            .uri(&format!("/bills/{}/split", bill.id))
+            .to_request();
+        assert_eq!(call_service(&app, req).await.status(), StatusCode::OK);
+    }
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split-history", bill.id))
+        .to_request();
+    let snapshots: Vec<SplitSnapshot> = call_and_read_body_json(&app, req).await;
+    assert_eq!(snapshots.len(), 2);
+    assert_eq!(serde_json::to_value(snapshots[0].method).unwrap(), "equal");
+    assert_eq!(snapshots[0].shares[0].amount_owed.to_string(), "20.00");
+
+    let req = TestRequest::get()
+        .uri(&format!(
+            "/bills/{}/split-history/{}",
+            bill.id, snapshots[1].id
+        ))
+        .to_request();
+    let snapshot: SplitSnapshot = call_and_read_body_json(&app, req).await;
+    assert_eq!(snapshot, snapshots[1]);
+
+    let req = TestRequest::get()
+        .uri(&format!(
+            "/bills/{}/split-history/{}",
+            bill.id,
+            Uuid::new_v4()
+        ))
+        .to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[actix_web::test]
+async fn every_endpoint_computing_the_split_records_one() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let (bill, alice) = seed(&state).await;
+    let app = init_service(app(state.clone())).await;
+
+    let item = bill.line_items[1].id;
+    let uris = [
+        format!("/bills/{}/split/explain", bill.id),
+        format!("/bills/{}/split/preview-as/{alice}", bill.id),
+        format!("/bills/{}/split/rounding-report", bill.id),
+        format!("/bills/{}/split/round-to-nearest", bill.id),
+        format!("/bills/{}/split/tax-inclusive", bill.id),
+        format!("/bills/{}/split/simulate?remove_item={item}", bill.id),
+    ];
+    for (recorded, uri) in uris.iter().enumerate() {
+        let req = TestRequest::get().uri(uri).to_request();
+        assert_eq!(
+            call_service(&app, req).await.status(),
+            StatusCode::OK,
+            "{uri}"
+        );
+        let snapshots = history(&state, &bill).await;
+        assert_eq!(snapshots.len(), recorded + 1, "{uri}");
+        // The recorded split is the bill's own, not a simulated one.
+        assert_eq!(
+            snapshots[recorded].shares[0].amount_owed.to_string(),
+            "20.00",
+            "{uri}"
+        );
+    }
+}
+
+#[actix_web::test]
+async fn archived_bills_record_nothing() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let (bill, _) = seed(&state).await;
+    let app = init_service(app(state.clone())).await;
+
+    let req = TestRequest::post()
+        .uri(&format!("/bills/{}/archive", bill.id))
+        .to_request();
+    assert_eq!(call_service(&app, req).await.status(), StatusCode::OK);
+    for uri in [
+        format!("/bills/{}/split", bill.id),
+        format!("/bills/{}/split/explain", bill.id),
+    ] {
+        let req = TestRequest::get().uri(&uri).to_request();
+        assert_eq!(call_service(&app, req).await.status(), StatusCode::OK);
+    }
+    assert!(history(&state, &bill).await.is_empty());
+}
+
+#[actix_web::test]
+async fn snapshots_replay_against_the_bill_as_it_is_now() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let (bill, _) = seed(&state).await;
+    let app = init_service(app(state.clone())).await;
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split", bill.id))
+        .to_request();
+    assert_eq!(call_service(&app, req).await.status(), StatusCode::OK);
+    let mut bill = state.repo().get_bill(bill.id).await.unwrap().unwrap();
+    let snapshot_id = bill.split_history[0].id;
+    bill.line_items
+        .push(LineItem::new("Wine", 1, Money::from_cents(2000)));
+    state.repo().put_bill(&bill).await.unwrap();
+
+    let req = TestRequest::get()
+        .uri(&format!(
+            "/bills/{}/split/history/{snapshot_id}/replay",
+            bill.id
+        ))
+        .to_request();
+    let body: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(body["original_snapshot"]["id"], snapshot_id.to_string());
+    assert_eq!(
+        body["original_snapshot"]["shares"][0]["amount_owed"],
+        "20.00"
+    );
+    assert_eq!(body["replayed_snapshot"]["method"], "equal");
+    assert_eq!(
+        body["replayed_snapshot"]["shares"][0]["amount_owed"],
+        "30.00"
+    );
+
+    let req = TestRequest::get()
+        .uri(&format!(
+            "/bills/{}/split/history/{}/replay",
+            bill.id,
+            Uuid::new_v4()
+        ))
+        .to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::NOT_FOUND
+    );
+}
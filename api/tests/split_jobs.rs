@@ -0,0 +1,143 @@
+use actix_web::{
+    http::{header, StatusCode},
+    test::{call_and_read_body_json, call_service, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{
+    app, config::Config, models::Bill, queues::split_consumer, state::AppState,
+    testing::BillBuilder,
+};
+use serde_json::Value;
+
+async fn seed(state: &AppState) -> Bill {
+    let repo = state.repo();
+    let (bill, participants) = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_participant("Bob")
+        .with_item("Pizza", 1, 30.0)
+        .build()
+        .unwrap();
+    for participant in &participants {
+        repo.put_participant(participant).await.unwrap();
+    }
+    repo.put_bill(&bill).await.unwrap();
+    bill
+}
+
+#[actix_web::test]
+async fn queued_split_is_pending_until_processed() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let mut receiver = state.split_queue.take_receiver().unwrap();
+    let bill = seed(&state).await;
+    let app = init_service(app(state.clone())).await;
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split/async", bill.id))
+        .to_request();
+    let res = call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::ACCEPTED);
+    let location = res
+        .headers()
+        .get(header::LOCATION)
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    let body: Value = actix_web::test::read_body_json(res).await;
+    let poll_url = body["poll_url"].as_str().unwrap();
+    assert_eq!(poll_url, location);
+    assert_eq!(
+        poll_url,
+        format!(
+            "/bills/{}/split/jobs/{}",
+            bill.id,
+            body["job_id"].as_str().unwrap()
+        )
+    );
+
+    let status: Value =
+        call_and_read_body_json(&app, TestRequest::get().uri(poll_url).to_request()).await;
+    assert_eq!(status["status"], "pending");
+    assert!(status.get("result").is_none());
+
+    let job = receiver.recv().await.unwrap();
+    assert!(split_consumer::process(&state, job).await);
+
+    let status: Value =
+        call_and_read_body_json(&app, TestRequest::get().uri(poll_url).to_request()).await;
+    assert_eq!(status["status"], "done");
+    assert_eq!(status["result"]["method"], "equal");
+    let shares = status["result"]["shares"].as_array().unwrap();
+    assert_eq!(shares.len(), 2);
+    assert_eq!(shares[0]["amount_owed"], "15.00");
+
+    let bill = state.repo().get_bill(bill.id).await.unwrap().unwrap();
+    assert_eq!(bill.split_history.len(), 1);
+    assert_eq!(
+        bill.split_history[0].shares[0].amount_owed.to_string(),
+        "15.00"
+    );
+}
+
+#[actix_web::test]
+async fn job_fails_when_the_bill_is_gone() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let mut receiver = state.split_queue.take_receiver().unwrap();
+    let bill = seed(&state).await;
+    let app = init_service(app(state.clone())).await;
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split/async", bill.id))
+        .to_request();
+    let body: Value = call_and_read_body_json(&app, req).await;
+    state.repo().soft_delete_bill(&bill).await.unwrap();
+
+    let job = receiver.recv().await.unwrap();
+    assert!(!split_consumer::process(&state, job).await);
+
+    let req = TestRequest::get()
+        .uri(body["poll_url"].as_str().unwrap())
+        .to_request();
+    let status: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(status["status"], "failed");
+    assert!(status["error"]
+        .as_str()
+        .unwrap()
+        .contains("no longer exists"));
+}
+
+#[actix_web::test]
+async fn unknown_or_mismatched_jobs_are_not_found() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let bill = seed(&state).await;
+    let other = seed(&state).await;
+    let app = init_service(app(state.clone())).await;
+
+    let req = TestRequest::get()
+        .uri(&format!(
+            "/bills/{}/split/jobs/{}",
+            bill.id,
+            uuid::Uuid::new_v4()
+        ))
+        .to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::NOT_FOUND
+    );
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split/async", bill.id))
+        .to_request();
+    let body: Value = call_and_read_body_json(&app, req).await;
+    let req = TestRequest::get()
+        .uri(&format!(
+            "/bills/{}/split/jobs/{}",
+            other.id,
+            body["job_id"].as_str().unwrap()
+        ))
+        .to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::NOT_FOUND
+    );
+}
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use actix_web::{
+    http::StatusCode,
+    test::{call_and_read_body_json, call_service, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{app, config::Config, models::Bill, state::AppState, testing::BillBuilder};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+/// Carol, Alice and Bob: a 6.00 steak for Alice and 4.00 of pasta for Bob
+/// and Carol, 10.00 in all.
+async fn dinner(state: &AppState) -> (Bill, HashMap<String, Uuid>) {
+    let repo = state.repo();
+    let (bill, participants) = BillBuilder::new("Dinner")
+        .with_participant("Carol")
+        .with_participant("Alice")
+        .with_participant("Bob")
+        .with_item_shared_by("Steak", 1, 6.0, &["Alice"])
+        .with_item_shared_by("Pasta", 1, 4.0, &["Bob", "Carol"])
+        .build()
+        .unwrap();
+    for participant in &participants {
+        repo.put_participant(participant).await.unwrap();
+    }
+    repo.put_bill(&bill).await.unwrap();
+    let ids = participants
+        .into_iter()
+        .map(|participant| (participant.name, participant.id))
+        .collect();
+    (bill, ids)
+}
+
+fn split_uri(bill: &Bill, params: &[(&str, String)]) -> String {
+    format!(
+        "/bills/{}/split?{}",
+        bill.id,
+        serde_urlencoded::to_string(params).unwrap()
+    )
+}
+
+/// Each share as `name: amount`, in participant order.
+fn shares(body: &Value) -> Vec<String> {
+    body["shares"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|share| {
+            format!(
+                "{}: {}",
+                share["name"].as_str().unwrap(),
+                share["amount_owed"].as_str().unwrap()
+            )
+        })
+        .collect()
+}
+
+#[actix_web::test]
+async fn each_method_splits_the_bill_to_the_cent() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let (bill, ids) = dinner(&state).await;
+    let app = init_service(app(state)).await;
+
+    let weights = json!({ ids["Alice"]: 2, ids["Bob"]: 1, ids["Carol"]: 1 }).to_string();
+    let amounts =
+        json!({ ids["Alice"]: "1.00", ids["Bob"]: "4.00", ids["Carol"]: "5.00" }).to_string();
+    let cases = [
+        (
+            vec![("method", "equal".to_string())],
+            // The odd cent goes to whoever comes first by name.
+            ["Carol: 3.33", "Alice: 3.34", "Bob: 3.33"],
+        ),
+        (
+            vec![("method", "proportional".to_string()), ("weights", weights)],
+            ["Carol: 2.50", "Alice: 5.00", "Bob: 2.50"],
+        ),
+        (
+            vec![("method", "itemised".to_string())],
+            ["Carol: 2.00", "Alice: 6.00", "Bob: 2.00"],
+        ),
+        (
+            vec![("method", "custom".to_string()), ("amounts", amounts)],
+            ["Carol: 5.00", "Alice: 1.00", "Bob: 4.00"],
+        ),
+    ];
+    for (params, expected) in cases {
+        let req = TestRequest::get()
+            .uri(&split_uri(&bill, &params))
+            .to_request();
+        let body: Value = call_and_read_body_json(&app, req).await;
+        assert_eq!(body["method"], params[0].1);
+        assert_eq!(body["total"], "10.00");
+        assert_eq!(shares(&body), expected);
+    }
+}
+
+#[actix_web::test]
+async fn method_parameters_are_validated() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let (bill, ids) = dinner(&state).await;
+    let app = init_service(app(state)).await;
+
+    let bad = [
+        vec![("method", "equally".to_string())],
+        vec![("method", "proportional".to_string())],
+        vec![
+            ("method", "proportional".to_string()),
+            ("weights", "not json".to_string()),
+        ],
+        vec![
+            ("method", "proportional".to_string()),
+            ("weights", json!({ ids["Alice"]: 1 }).to_string()),
+        ],
+        vec![
+            ("method", "proportional".to_string()),
+            (
+                "weights",
+                json!({ ids["Alice"]: 0, ids["Bob"]: 0, ids["Carol"]: 0 }).to_string(),
+            ),
+        ],
+        vec![("method", "custom".to_string())],
+        vec![
+            ("method", "custom".to_string()),
+            (
+                "amounts",
+                json!({ ids["Alice"]: "1.00", ids["Bob"]: "1.00", ids["Carol"]: "1.00" })
+                    .to_string(),
+            ),
+        ],
+    ];
+    for params in bad {
+        let req = TestRequest::get()
+            .uri(&split_uri(&bill, &params))
+            .to_request();
+        let res = call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST, "{params:?}");
+    }
+}
+
+#[actix_web::test]
+async fn itemised_splits_need_every_item_assigned() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let repo = state.repo();
+    let (bill, participants) = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_item("Pizza", 1, 30.0)
+        .build()
+        .unwrap();
+    repo.put_participant(&participants[0]).await.unwrap();
+    repo.put_bill(&bill).await.unwrap();
+    let app = init_service(app(state.clone())).await;
+
+    let req = TestRequest::get()
+        .uri(&format!("/bills/{}/split?method=itemised", bill.id))
+        .to_request();
+    let res = call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let body: Value = actix_web::test::read_body_json(res).await;
+    assert_eq!(body["error"], "InsufficientData");
+    assert_eq!(
+        body["reason"],
+        format!(
+            "Line item {} has no participants assigned",
+            bill.line_items[0].id
+        )
+    );
+    assert!(repo
+        .get_bill(bill.id)
+        .await
+        .unwrap()
+        .unwrap()
+        .split_history
+        .is_empty());
+}
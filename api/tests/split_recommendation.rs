@@ -0,0 +1,86 @@
+use bill_splitter_api::{
+    models::{Bill, LineItem, Participant},
+    split::{recommend_method, SplitMethod},
+    testing::{money, BillBuilder},
+};
+
+fn people(names: &[&str]) -> (Bill, Vec<Participant>) {
+    names
+        .iter()
+        .fold(BillBuilder::new("Dinner"), |builder, name| {
+            builder.with_participant(*name)
+        })
+        .build()
+        .unwrap()
+}
+
+fn item(quantity: u32, price: &str, sharers: &[&Participant]) -> LineItem {
+    let mut item = LineItem::new("Item", quantity, money(price));
+    item.participant_ids = sharers.iter().map(|participant| participant.id).collect();
+    item
+}
+
+#[test]
+fn everyone_sharing_everything_suggests_equal() {
+    let (mut bill, p) = people(&["Alice", "Bob", "Carol"]);
+    bill.add_line_item(item(1, "30.00", &[&p[0], &p[1], &p[2]]));
+    bill.add_line_item(item(5, "2.00", &[&p[0], &p[1], &p[2]]));
+
+    let recommendation = recommend_method(&bill, &p).unwrap();
+
+    assert_eq!(recommendation.recommended, SplitMethod::Equal);
+    assert!(recommendation.confidence > 0.9);
+    assert_eq!(
+        recommendation.alternatives,
+        [SplitMethod::Itemised, SplitMethod::Proportional]
+    );
+}
+
+#[test]
+fn a_heavy_spender_suggests_proportional() {
+    let (mut bill, p) = people(&["Alice", "Bob", "Carol"]);
+    bill.add_line_item(item(1, "40.00", &[&p[0]]));
+    bill.add_line_item(item(1, "10.00", &[&p[1]]));
+    bill.add_line_item(item(1, "10.00", &[&p[2]]));
+
+    let recommendation = recommend_method(&bill, &p).unwrap();
+
+    assert_eq!(recommendation.recommended, SplitMethod::Proportional);
+    assert!(recommendation
+        .reasoning
+        .starts_with("Alice accounts for 4x"));
+    assert!(recommendation.confidence > 0.6 && recommendation.confidence <= 0.9);
+}
+
+#[test]
+fn differing_quantities_suggest_itemised() {
+    let (mut bill, p) = people(&["Alice", "Bob"]);
+    bill.add_line_item(item(4, "3.00", &[&p[0]]));
+    bill.add_line_item(item(1, "10.00", &[&p[1]]));
+
+    let recommendation = recommend_method(&bill, &p).unwrap();
+
+    assert_eq!(recommendation.recommended, SplitMethod::Itemised);
+    assert_eq!(
+        recommendation.alternatives,
+        [SplitMethod::Proportional, SplitMethod::Equal]
+    );
+}
+
+#[test]
+fn itemised_is_not_offered_while_items_are_unassigned() {
+    let (mut bill, p) = people(&["Alice", "Bob"]);
+    bill.add_line_item(item(1, "10.00", &[&p[0]]));
+    bill.add_line_item(item(1, "10.00", &[]));
+
+    let recommendation = recommend_method(&bill, &p).unwrap();
+
+    assert_eq!(recommendation.recommended, SplitMethod::Equal);
+    assert_eq!(recommendation.alternatives, [SplitMethod::Proportional]);
+}
+
+#[test]
+fn bills_without_items_get_no_recommendation() {
+    let (bill, p) = people(&["Alice"]);
+    assert!(recommend_method(&bill, &p).is_none());
+}
@@ -0,0 +1,66 @@
+use bill_splitter_api::{
+    export::spreadsheet::bill_to_formula_csv,
+    models::{Bill, LineItem},
+    testing::money,
+};
+use rust_decimal::Decimal;
+
+fn item(description: &str, quantity: u32, price: &str, tax_rate: Option<&str>) -> LineItem {
+    let mut item = LineItem::new(description, quantity, money(price));
+    item.tax_rate = tax_rate.map(|rate| rate.parse::<Decimal>().unwrap());
+    item
+}
+
+#[test]
+fn totals_and_taxes_are_formulas() {
+    let mut bill = Bill::new("Dinner", None);
+    bill.add_line_item(item("Pizza", 2, "12.50", Some("0.1")));
+    bill.add_line_item(item("Wine, red", 1, "30.00", Some("0.2")));
+    bill.add_line_item(item("Water", 3, "1.00", None));
+
+    let csv = bill_to_formula_csv(&bill);
+    let rows: Vec<&str> = csv.split_terminator("\r\n").collect();
+
+    assert_eq!(
+        rows,
+        [
+            "Description,Quantity,Unit price,Total,Tax rate",
+            "Pizza,2,12.50,=B2*C2,0.1",
+            "\"Wine, red\",1,30.00,=B3*C3,0.2",
+            "Water,3,1.00,=B4*C4,",
+            "Subtotal,,,=SUM(D2:D4),",
+            "Tax included at 10%,,,\"=SUMIF(E2:E4,0.1,D2:D4)*0.1/(1+0.1)\",",
+            "Tax included at 20%,,,\"=SUMIF(E2:E4,0.2,D2:D4)*0.2/(1+0.2)\",",
+        ]
+    );
+}
+
+#[test]
+fn discounts_get_their_own_rows() {
+    let mut bill = Bill::new("Dinner", None);
+    bill.add_line_item(item("Pizza", 1, "20.00", Some("0.1")));
+    bill.discount = money("5.00");
+
+    let csv = bill_to_formula_csv(&bill);
+    let rows: Vec<&str> = csv.split_terminator("\r\n").skip(2).collect();
+
+    assert_eq!(
+        rows,
+        [
+            "Subtotal,,,=SUM(D2:D2),",
+            "Discount,,,5.00,",
+            "Total,,,=D3-D4,",
+            "Tax included at 10%,,,\"=SUMIF(E2:E2,0.1,D2:D2)*D5/D3*0.1/(1+0.1)\",",
+        ]
+    );
+}
+
+#[test]
+fn descriptions_cannot_inject_formulas() {
+    let mut bill = Bill::new("Dinner", None);
+    bill.add_line_item(item("=HYPERLINK(\"x\")", 1, "1.00", None));
+
+    let csv = bill_to_formula_csv(&bill);
+
+    assert!(csv.contains("\"'=HYPERLINK(\"\"x\"\")\",1,1.00,=B2*C2,"));
+}
@@ -0,0 +1,148 @@
+use actix_web::{
+    http::StatusCode,
+    test::{call_and_read_body_json, call_service, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    models::{Bill, MAX_TAGS_PER_BILL},
+    state::AppState,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+fn titles(body: &Value) -> Vec<&str> {
+    let mut titles: Vec<&str> = body["bills"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|bill| bill["title"].as_str().unwrap())
+        .collect();
+    titles.sort();
+    titles
+}
+
+#[actix_web::test]
+async fn tagged_bills_are_indexed_for_filtering() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let (beach, ski, rent) = (
+        Bill::new("Beach", None),
+        Bill::new("Ski", None),
+        Bill::new("Rent", None),
+    );
+    for bill in [&beach, &ski, &rent] {
+        state.repo().put_bill(bill).await.unwrap();
+    }
+    let app = init_service(app(state.clone())).await;
+
+    let req = TestRequest::post()
+        .uri(&format!("/bills/{}/tags", beach.id))
+        .set_json(json!({ "name": " Vacation ", "color": "#ff8800" }))
+        .to_request();
+    let tagged: Value = call_and_read_body_json(&app, req).await;
+    let tag = &tagged["tags"][0];
+    assert_eq!(tag["name"], "vacation");
+    assert_eq!(tag["color"], "#ff8800");
+
+    // The same name, in any case, or the id reuses the tag.
+    for body in [
+        json!({ "name": "VACATION" }),
+        json!({ "tag_id": tag["id"] }),
+    ] {
+        let req = TestRequest::post()
+            .uri(&format!("/bills/{}/tags", ski.id))
+            .set_json(body)
+            .to_request();
+        let tagged: Value = call_and_read_body_json(&app, req).await;
+        assert_eq!(tagged["tags"].as_array().unwrap().len(), 1);
+        assert_eq!(tagged["tags"][0]["id"], tag["id"]);
+    }
+
+    let repo = state.repo();
+    assert_eq!(
+        repo.tagged_bill_ids("vacation").await.unwrap(),
+        [beach.id, ski.id]
+    );
+    let req = TestRequest::get().uri("/bills?tag=Vacation").to_request();
+    let body: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(titles(&body), ["Beach", "Ski"]);
+
+    let req = TestRequest::delete()
+        .uri(&format!(
+            "/bills/{}/tags/{}",
+            ski.id,
+            tag["id"].as_str().unwrap()
+        ))
+        .to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::NO_CONTENT
+    );
+    assert_eq!(repo.tagged_bill_ids("vacation").await.unwrap(), [beach.id]);
+    let req = TestRequest::get().uri("/bills?tag=vacation").to_request();
+    let body: Value = call_and_read_body_json(&app, req).await;
+    assert_eq!(titles(&body), ["Beach"]);
+}
+
+#[actix_web::test]
+async fn tag_requests_are_validated() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let bill = Bill::new("Beach", None);
+    state.repo().put_bill(&bill).await.unwrap();
+    let app = init_service(app(state)).await;
+    let uri = format!("/bills/{}/tags", bill.id);
+
+    for (body, status) in [
+        (json!({}), StatusCode::BAD_REQUEST),
+        (
+            json!({ "name": "trip", "tag_id": Uuid::new_v4() }),
+            StatusCode::BAD_REQUEST,
+        ),
+        (json!({ "name": "road trip" }), StatusCode::BAD_REQUEST),
+        (
+            json!({ "name": "trip", "color": "orange" }),
+            StatusCode::BAD_REQUEST,
+        ),
+        (json!({ "tag_id": Uuid::new_v4() }), StatusCode::NOT_FOUND),
+    ] {
+        let req = TestRequest::post().uri(&uri).set_json(&body).to_request();
+        assert_eq!(call_service(&app, req).await.status(), status, "{body}");
+    }
+
+    let req = TestRequest::delete()
+        .uri(&format!("{uri}/{}", Uuid::new_v4()))
+        .to_request();
+    assert_eq!(
+        call_service(&app, req).await.status(),
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[actix_web::test]
+async fn bills_carry_at_most_twenty_tags() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let bill = Bill::new("Beach", None);
+    state.repo().put_bill(&bill).await.unwrap();
+    let app = init_service(app(state)).await;
+    let uri = format!("/bills/{}/tags", bill.id);
+
+    for i in 0..MAX_TAGS_PER_BILL {
+        let req = TestRequest::post()
+            .uri(&uri)
+            .set_json(json!({ "name": format!("tag-{i}") }))
+            .to_request();
+        assert_eq!(call_service(&app, req).await.status(), StatusCode::OK);
+    }
+    let req = TestRequest::post()
+        .uri(&uri)
+        .set_json(json!({ "name": "one-too-many" }))
+        .to_request();
+    assert_eq!(call_service(&app, req).await.status(), StatusCode::CONFLICT);
+    // Re-attaching a tag the bill already has is still fine.
+    let req = TestRequest::post()
+        .uri(&uri)
+        .set_json(json!({ "name": "tag-0" }))
+        .to_request();
+    assert_eq!(call_service(&app, req).await.status(), StatusCode::OK);
+}
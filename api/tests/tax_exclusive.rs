@@ -0,0 +1,77 @@
+use bill_splitter_api::{
+    models::{Bill, Money, Participant},
+    split::{compute_split, split_tax_exclusive, split_tax_inclusive, SplitSpec},
+    testing::{money, BillBuilder},
+};
+
+fn dinner() -> (Bill, Vec<Participant>) {
+    let (mut bill, participants) = BillBuilder::new("Dinner")
+        .with_participant("Alice")
+        .with_participant("Bob")
+        .with_item_shared_by("Wine", 1, 21.40, &["Alice"])
+        .with_item_shared_by("Bread", 1, 5.0, &["Alice", "Bob"])
+        .build()
+        .unwrap();
+    bill.line_items[0].tax_rate = Some("0.07".parse().unwrap());
+    (bill, participants)
+}
+
+#[test]
+fn itemised_shares_carry_the_tax_of_their_items() {
+    let (bill, participants) = dinner();
+    let split = compute_split(&bill, &participants, &SplitSpec::Itemised).unwrap();
+
+    let result = split_tax_exclusive(&bill, &split);
+
+    assert_eq!(result.total_tax, money("1.40"));
+    assert_eq!(result.total_pre_tax, money("25.00"));
+    assert_eq!(result.shares[0].tax_amount, money("1.40"));
+    assert_eq!(result.shares[0].pre_tax_amount, money("22.50"));
+    assert_eq!(result.shares[1].tax_amount, Money::ZERO);
+    assert_eq!(result.shares[1].total_amount, money("2.50"));
+}
+
+#[test]
+fn other_methods_spread_tax_with_the_shares() {
+    let (bill, participants) = dinner();
+    let split = compute_split(&bill, &participants, &SplitSpec::Equal).unwrap();
+
+    let result = split_tax_exclusive(&bill, &split);
+
+    let taxes: Vec<Money> = result.shares.iter().map(|share| share.tax_amount).collect();
+    assert_eq!(taxes, [money("0.70"), money("0.70")]);
+    for (share, split) in result.shares.iter().zip(&split.shares) {
+        assert_eq!(share.total_amount, split.amount_owed);
+        assert_eq!(share.pre_tax_amount + share.tax_amount, share.total_amount);
+    }
+}
+
+#[test]
+fn untaxed_bills_split_the_same_as_usual() {
+    let (mut bill, participants) = dinner();
+    bill.line_items[0].tax_rate = None;
+    let split = compute_split(&bill, &participants, &SplitSpec::Equal).unwrap();
+
+    let result = split_tax_exclusive(&bill, &split);
+
+    assert_eq!(result.total_tax, Money::ZERO);
+    for (share, split) in result.shares.iter().zip(&split.shares) {
+        assert_eq!(share.pre_tax_amount, split.amount_owed);
+    }
+}
+
+#[test]
+fn tax_inclusive_shares_break_the_gross_into_net_and_tax() {
+    let (bill, participants) = dinner();
+    let split = compute_split(&bill, &participants, &SplitSpec::Itemised).unwrap();
+
+    let result = split_tax_inclusive(&bill, &split);
+
+    assert_eq!(result.total_gross, money("26.40"));
+    assert_eq!(result.total_net, money("25.00"));
+    assert_eq!(result.total_tax, money("1.40"));
+    assert_eq!(result.shares[0].gross_amount, money("23.90"));
+    assert_eq!(result.shares[0].net_amount, money("22.50"));
+    assert_eq!(result.shares[0].tax_component, money("1.40"));
+    assert_eq!(result.shares[1].net_amount, result.shares[1].gross_amount);
+}
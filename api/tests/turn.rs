@@ -0,0 +1,45 @@
+use bill_splitter_api::{
+    analytics::turn::suggest_payer,
+    models::{Bill, Participant},
+};
+
+fn paid_by(payer: &Participant) -> Bill {
+    let mut bill = Bill::new("Dinner", None);
+    bill.payer_id = Some(payer.id);
+    bill
+}
+
+#[test]
+fn suggests_whoever_paid_least() {
+    let alice = Participant::new("Alice", None);
+    let bob = Participant::new("Bob", None);
+    let carol = Participant::new("Carol", None);
+    let bills = [paid_by(&alice), paid_by(&bob), paid_by(&alice)];
+
+    let suggestion = suggest_payer(&[alice, bob, carol.clone()], &bills).unwrap();
+
+    assert_eq!(suggestion.participant_id, carol.id);
+    assert_eq!(suggestion.times_paid_recently, 0);
+    assert_eq!(
+        suggestion.reason,
+        "Carol hasn't paid in the last 3 bills, less than anyone else"
+    );
+}
+
+#[test]
+fn ties_go_to_the_first_name_alphabetically() {
+    let zoe = Participant::new("Zoe", None);
+    let bob = Participant::new("bob", None);
+    let bills = [paid_by(&zoe), paid_by(&bob)];
+
+    let suggestion = suggest_payer(&[zoe, bob.clone()], &bills).unwrap();
+
+    assert_eq!(suggestion.participant_id, bob.id);
+    assert_eq!(suggestion.times_paid_recently, 1);
+    assert!(suggestion.reason.contains("tied with 1 other"));
+}
+
+#[test]
+fn nobody_to_suggest() {
+    assert!(suggest_payer(&[], &[]).is_none());
+}
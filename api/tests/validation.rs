@@ -0,0 +1,81 @@
+use bill_splitter_api::{
+    models::{Bill, LineItem},
+    split::{SplitConfig, SplitMethod},
+    testing::money,
+    validation::{validate, Severity},
+};
+use uuid::Uuid;
+
+fn itemised() -> Option<SplitConfig> {
+    Some(SplitConfig {
+        method: SplitMethod::Itemised,
+        weights: None,
+        amounts: None,
+        assignments: None,
+    })
+}
+
+fn codes(bill: &Bill) -> Vec<String> {
+    validate(bill).into_iter().map(|issue| issue.code).collect()
+}
+
+#[test]
+fn a_complete_bill_has_no_issues() {
+    let mut bill = Bill::new("Dinner", None);
+    let alice = Uuid::new_v4();
+    bill.add_participant(alice);
+    let mut pizza = LineItem::new("Pizza", 1, money("9"));
+    pizza.participant_ids = vec![alice];
+    bill.line_items.push(pizza);
+    bill.split_config = itemised();
+
+    assert!(validate(&bill).is_empty());
+}
+
+#[test]
+fn reports_every_problem_with_errors_first() {
+    let mut bill = Bill::new("Dinner", None);
+    let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+    bill.add_participant(alice);
+    bill.add_participant(bob);
+    let mut pizza = LineItem::new("Pizza", 1, money("9"));
+    pizza.participant_ids = vec![alice, Uuid::new_v4()];
+    bill.line_items.push(pizza);
+    bill.line_items
+        .push(LineItem::new(" pizza", 1, money("-2")));
+    bill.split_config = itemised();
+
+    let issues = validate(&bill);
+
+    assert_eq!(
+        codes(&bill),
+        [
+            "negative_amount",
+            "unassigned_line_item",
+            "share_total_mismatch",
+            "participant_without_items",
+            "duplicate_description",
+        ]
+    );
+    assert!(issues[..3]
+        .iter()
+        .all(|issue| issue.severity == Severity::Error));
+    assert_eq!(issues[3].affected_id, Some(bob));
+    assert!(issues[2]
+        .message
+        .contains("add up to 4.50 but the bill total is 7.00"));
+}
+
+#[test]
+fn assignments_are_only_checked_for_itemised_splits() {
+    let mut bill = Bill::new("Dinner", None);
+    bill.add_participant(Uuid::new_v4());
+    bill.line_items.push(LineItem::new("Pizza", 1, money("9")));
+
+    assert!(validate(&bill).is_empty());
+    bill.split_config = itemised();
+    assert_eq!(
+        codes(&bill),
+        ["unassigned_line_item", "participant_without_items"]
+    );
+}
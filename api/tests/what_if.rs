@@ -0,0 +1,98 @@
+use actix_web::{
+    http::StatusCode,
+    test::{call_and_read_body_json, call_service, init_service, TestRequest},
+    web,
+};
+use bill_splitter_api::{
+    app,
+    config::Config,
+    models::{Bill, Participant, ParticipantShare},
+    split::WhatIfPriceResult,
+    state::AppState,
+    testing::{money, BillBuilder},
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+async fn lunch(state: &AppState) -> (Bill, Participant, Participant) {
+    let repo = state.repo();
+    let (bill, participants) = BillBuilder::new("Lunch")
+        .with_participant("Alice")
+        .with_participant("Bob")
+        .with_item_shared_by("Steak", 1, 20.0, &["Alice"])
+        .with_item_shared_by("Fries", 1, 6.0, &["Alice", "Bob"])
+        .build()
+        .unwrap();
+    for participant in &participants {
+        repo.put_participant(participant).await.unwrap();
+    }
+    repo.put_bill(&bill).await.unwrap();
+    let [alice, bob] = participants.try_into().unwrap();
+    (bill, alice, bob)
+}
+
+#[actix_web::test]
+async fn a_price_change_only_moves_the_sharers_of_that_item() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let (bill, alice, bob) = lunch(&state).await;
+    let app = init_service(app(state)).await;
+    let steak = bill.line_items[0].id;
+
+    let req = TestRequest::get()
+        .uri(&format!(
+            "/bills/{}/split/what-if?method=itemised&change_price={steak}&to=25.00",
+            bill.id
+        ))
+        .to_request();
+    let result: Value = call_and_read_body_json(&app, req).await;
+
+    assert_eq!(result["original_price"], "20.00");
+    assert_eq!(result["hypothetical_price"], "25.00");
+    let deltas = &result["delta_per_participant"];
+    assert_eq!(deltas[0]["participant_id"], alice.id.to_string());
+    assert_eq!(deltas[0]["delta"], "5.00");
+    assert_eq!(deltas[1]["participant_id"], bob.id.to_string());
+    assert_eq!(deltas[1]["delta"], "0.00");
+}
+
+#[actix_web::test]
+async fn the_new_price_must_be_a_positive_amount_in_cents() {
+    let state = web::Data::new(AppState::new(Config::default()));
+    let (bill, _, _) = lunch(&state).await;
+    let app = init_service(app(state)).await;
+    let steak = bill.line_items[0].id;
+
+    for to in ["0.00", "-1.00", "25.001"] {
+        let req = TestRequest::get()
+            .uri(&format!(
+                "/bills/{}/split/what-if?change_price={steak}&to={to}",
+                bill.id
+            ))
+            .to_request();
+        assert_eq!(
+            call_service(&app, req).await.status(),
+            StatusCode::BAD_REQUEST,
+            "{to}"
+        );
+    }
+}
+
+#[test]
+fn deltas_compare_hypothetical_with_original_shares() {
+    let alice = Participant::new("Alice", None);
+    let share = |amount| ParticipantShare {
+        participant_id: alice.id,
+        name: alice.name.clone(),
+        amount_owed: money(amount),
+    };
+
+    let result = WhatIfPriceResult::new(
+        Uuid::new_v4(),
+        money("10.00"),
+        money("4.00"),
+        vec![share("10.00")],
+        vec![share("4.00")],
+    );
+
+    assert_eq!(result.delta_per_participant[0].delta, money("-6.00"));
+}